@@ -1,11 +1,11 @@
 use std::{f32::consts::TAU, sync::Arc};
 
-use criterion::{Criterion, criterion_group, criterion_main};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 use kira::{
 	AudioManager, AudioManagerSettings, Frame,
 	backend::mock::{MockBackend, MockBackendSettings},
 	sound::static_sound::{StaticSoundData, StaticSoundSettings},
-	track::MainTrackBuilder,
+	track::{MainTrackBuilder, TrackBuilder},
 };
 
 fn create_test_sound(num_samples: usize) -> StaticSoundData {
@@ -70,5 +70,44 @@ fn sounds(c: &mut Criterion) {
 	});
 }
 
-criterion_group!(benches, sounds);
+fn parallel_mixing(c: &mut Criterion) {
+	// many sounds spread across many sub-tracks, mixed with
+	// different numbers of worker threads
+	const SAMPLE_RATE: u32 = 48_000;
+	const NUM_TRACKS: usize = 16;
+	const NUM_SOUNDS_PER_TRACK: usize = 300;
+	let mut group = c.benchmark_group("parallel mixing");
+	for num_mixer_threads in [0, 1, 3, 7] {
+		group.bench_with_input(
+			BenchmarkId::from_parameter(num_mixer_threads),
+			&num_mixer_threads,
+			|b, &num_mixer_threads| {
+				let mut manager = AudioManager::<MockBackend>::new(AudioManagerSettings {
+					backend_settings: MockBackendSettings {
+						sample_rate: SAMPLE_RATE,
+					},
+					num_mixer_threads,
+					..Default::default()
+				})
+				.unwrap();
+				let sound_data = create_test_sound(SAMPLE_RATE as usize);
+				let mut tracks = vec![];
+				for _ in 0..NUM_TRACKS {
+					let mut track = manager
+						.add_sub_track(TrackBuilder::new().sound_capacity(NUM_SOUNDS_PER_TRACK))
+						.unwrap();
+					for _ in 0..NUM_SOUNDS_PER_TRACK {
+						track.play(sound_data.clone()).unwrap();
+					}
+					tracks.push(track);
+				}
+				manager.backend_mut().on_start_processing();
+				b.iter(|| manager.backend_mut().process());
+			},
+		);
+	}
+	group.finish();
+}
+
+criterion_group!(benches, sounds, parallel_mixing);
 criterion_main!(benches);
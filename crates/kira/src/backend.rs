@@ -1,11 +1,19 @@
 //! Communication between Kira and a low-level audio API.
 
+mod channel_layout;
 #[cfg(feature = "cpal")]
 pub mod cpal;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod headless;
 pub mod mock;
+pub mod offline;
+mod output_stage;
 mod renderer;
 pub(crate) mod resources;
 
+pub use channel_layout::*;
+pub(crate) use channel_layout::{ChannelFrame, mix_down_bus, with_bus_mixed_down};
+pub use output_stage::OutputStage;
 pub use renderer::*;
 
 #[cfg(feature = "cpal")]
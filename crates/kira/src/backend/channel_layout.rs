@@ -0,0 +1,470 @@
+#[cfg(test)]
+mod test;
+
+use std::{
+	f32::consts::{FRAC_1_SQRT_2, FRAC_PI_2, TAU},
+	ops::{Add, AddAssign, Mul, MulAssign, Sub},
+};
+
+use crate::Frame;
+
+/// The most channels a [`ChannelLayout`] can have.
+pub(crate) const MAX_CHANNELS: usize = 8;
+
+/// A speaker in a [`ChannelLayout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Speaker {
+	/// The only speaker of a mono output.
+	Mono,
+	/// The front left speaker.
+	FrontLeft,
+	/// The front right speaker.
+	FrontRight,
+	/// The front center speaker.
+	FrontCenter,
+	/// The subwoofer.
+	LowFrequency,
+	/// The back left speaker.
+	BackLeft,
+	/// The back right speaker.
+	BackRight,
+	/// The side left speaker.
+	SideLeft,
+	/// The side right speaker.
+	SideRight,
+}
+
+/**
+An arrangement of output channels.
+
+Channels are expected in the standard WAV/SMPTE order, which is
+the order used by WASAPI and Core Audio.
+
+The mixer mixes audio in the layout set in
+[`AudioManagerSettings::channel_layout`](crate::AudioManagerSettings::channel_layout).
+With [`Quad`](ChannelLayout::Quad) and larger layouts, spatial tracks
+are panned across every speaker around the listener instead of
+between the left and right speakers. Effects only process stereo
+audio, so a track with effects (including the main track) mixes the
+surround audio of its sub-tracks down to stereo before applying them.
+To keep spatial tracks panned across every speaker, put effects on
+the spatial tracks themselves or on send tracks.
+
+If the audio device has a different number of channels, the mix is
+downmixed or upmixed to the device's layout:
+
+- Speakers the device doesn't have are folded into the nearest
+  speakers it does have at -3dB. The center speaker goes to the front
+  left and right speakers, the side speakers go to the front and back
+  speakers on the same side, and the back speakers go to the front
+  speakers on the same side if the device has no back speakers.
+- Mono devices receive the average of the stereo downmix.
+- The subwoofer channel is dropped when downmixing, since nothing in
+  the mix is sent to it.
+- Speakers the mix doesn't have receive silence.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChannelLayout {
+	/// A single speaker.
+	Mono,
+	/// Left and right speakers.
+	#[default]
+	Stereo,
+	/// Front left, front right, back left, and back right speakers.
+	Quad,
+	/// Front left, front right, front center, subwoofer, back left,
+	/// and back right speakers.
+	Surround51,
+	/// Front left, front right, front center, subwoofer, back left,
+	/// back right, side left, and side right speakers.
+	Surround71,
+}
+
+impl ChannelLayout {
+	/// Returns the standard layout for the given number of channels,
+	/// or `None` if there isn't one.
+	#[must_use]
+	pub fn from_num_channels(num_channels: u16) -> Option<Self> {
+		match num_channels {
+			1 => Some(Self::Mono),
+			2 => Some(Self::Stereo),
+			4 => Some(Self::Quad),
+			6 => Some(Self::Surround51),
+			8 => Some(Self::Surround71),
+			_ => None,
+		}
+	}
+
+	/// Returns the number of channels in this layout.
+	#[must_use]
+	pub fn num_channels(self) -> u16 {
+		self.speakers().len() as u16
+	}
+
+	/// Returns the speakers in this layout in channel order.
+	#[must_use]
+	pub fn speakers(self) -> &'static [Speaker] {
+		match self {
+			Self::Mono => &[Speaker::Mono],
+			Self::Stereo => &[Speaker::FrontLeft, Speaker::FrontRight],
+			Self::Quad => &[
+				Speaker::FrontLeft,
+				Speaker::FrontRight,
+				Speaker::BackLeft,
+				Speaker::BackRight,
+			],
+			Self::Surround51 => &[
+				Speaker::FrontLeft,
+				Speaker::FrontRight,
+				Speaker::FrontCenter,
+				Speaker::LowFrequency,
+				Speaker::BackLeft,
+				Speaker::BackRight,
+			],
+			Self::Surround71 => &[
+				Speaker::FrontLeft,
+				Speaker::FrontRight,
+				Speaker::FrontCenter,
+				Speaker::LowFrequency,
+				Speaker::BackLeft,
+				Speaker::BackRight,
+				Speaker::SideLeft,
+				Speaker::SideRight,
+			],
+		}
+	}
+
+	/// Returns `true` if the layout has speakers behind or beside
+	/// the listener.
+	#[must_use]
+	pub(crate) fn is_surround(self) -> bool {
+		self.num_channels() > 2
+	}
+
+	/// Returns the channel index and angle (in degrees clockwise from
+	/// straight ahead) of each speaker that can be panned to, in
+	/// clockwise order.
+	///
+	/// The angles follow the ITU-R BS.775 and BS.2051 recommendations.
+	#[must_use]
+	fn speaker_ring(self) -> &'static [(usize, f32)] {
+		match self {
+			Self::Mono => &[(0, 0.0)],
+			Self::Stereo => &[(0, -30.0), (1, 30.0)],
+			Self::Quad => &[(0, -45.0), (1, 45.0), (3, 135.0), (2, -135.0)],
+			Self::Surround51 => &[(0, -30.0), (2, 0.0), (1, 30.0), (5, 110.0), (4, -110.0)],
+			Self::Surround71 => &[
+				(0, -30.0),
+				(2, 0.0),
+				(1, 30.0),
+				(7, 90.0),
+				(5, 150.0),
+				(4, -150.0),
+				(6, -90.0),
+			],
+		}
+	}
+
+	/**
+	Returns the gain of each speaker for a sound coming from the given
+	direction, in radians clockwise from straight ahead.
+
+	The sound is panned between the two speakers on either side of it
+	with constant power. `spread` is between `0.0` and `1.0`, where
+	`0.0` pans the sound to those two speakers, and `1.0` spreads it
+	evenly across every speaker. The subwoofer is never panned to.
+	*/
+	#[must_use]
+	pub(crate) fn pan(self, azimuth: f32, spread: f32) -> ChannelFrame {
+		let ring = self.speaker_ring();
+		let mut gains = ChannelFrame::ZERO;
+		if let [(index, _)] = ring {
+			gains.0[*index] = 1.0;
+			return gains;
+		}
+		let azimuth = azimuth.rem_euclid(TAU);
+		for (i, (index, angle)) in ring.iter().enumerate() {
+			let (next_index, next_angle) = ring[(i + 1) % ring.len()];
+			let angle = angle.to_radians();
+			let span = (next_angle.to_radians() - angle).rem_euclid(TAU);
+			let offset = (azimuth - angle).rem_euclid(TAU);
+			if offset <= span {
+				let fraction = offset / span;
+				gains.0[*index] = (fraction * FRAC_PI_2).cos();
+				gains.0[next_index] = (fraction * FRAC_PI_2).sin();
+				break;
+			}
+		}
+		let spread = spread.clamp(0.0, 1.0);
+		if spread > 0.0 {
+			let spread_power = spread / ring.len() as f32;
+			for (index, _) in ring {
+				let gain = &mut gains.0[*index];
+				*gain = ((1.0 - spread) * *gain * *gain + spread_power).sqrt();
+			}
+		}
+		gains
+	}
+
+	/// Maps a stereo frame to the speakers of this layout.
+	#[must_use]
+	pub(crate) fn upmix_stereo(self, frame: Frame, upmix: StereoUpmix) -> ChannelFrame {
+		let mut output = ChannelFrame::ZERO;
+		for (channel, speaker) in output.0.iter_mut().zip(self.speakers()) {
+			*channel = match (speaker, upmix) {
+				(Speaker::Mono, _) => (frame.left + frame.right) / 2.0,
+				(Speaker::FrontLeft, _) => frame.left,
+				(Speaker::FrontRight, _) => frame.right,
+				(Speaker::LowFrequency, _) | (_, StereoUpmix::FrontOnly) => 0.0,
+				(Speaker::FrontCenter, StereoUpmix::Surround) => {
+					frame.as_mono().left * FRAC_1_SQRT_2
+				}
+				(Speaker::BackLeft | Speaker::SideLeft, StereoUpmix::Surround) => {
+					frame.left * FRAC_1_SQRT_2
+				}
+				(Speaker::BackRight | Speaker::SideRight, StereoUpmix::Surround) => {
+					frame.right * FRAC_1_SQRT_2
+				}
+			};
+		}
+		output
+	}
+
+	/// Converts a frame in this layout to the given layout.
+	#[must_use]
+	pub(crate) fn remix(self, frame: ChannelFrame, layout: ChannelLayout) -> ChannelFrame {
+		if self == layout {
+			return frame;
+		}
+		if layout == Self::Mono {
+			let stereo = self.remix(frame, Self::Stereo);
+			let mut output = ChannelFrame::ZERO;
+			output.0[0] = (stereo.0[0] + stereo.0[1]) / 2.0;
+			return output;
+		}
+		let mut output = ChannelFrame::ZERO;
+		for (input, from) in frame.0.iter().zip(self.speakers()) {
+			for (channel, to) in output.0.iter_mut().zip(layout.speakers()) {
+				*channel += input * remix_gain(*from, *to, layout);
+			}
+		}
+		output
+	}
+}
+
+/// Mixes audio that's been panned across the speakers of a surround
+/// layout down to stereo and adds it to `out`, leaving `bus` silent.
+///
+/// This is used before audio goes through anything that only works
+/// with stereo audio, like effects.
+pub(crate) fn mix_down_bus(out: &mut [Frame], bus: &mut [ChannelFrame], layout: ChannelLayout) {
+	if !layout.is_surround() {
+		return;
+	}
+	for (out, bus) in out.iter_mut().zip(bus.iter_mut()) {
+		*out += layout.remix(*bus, ChannelLayout::Stereo).as_stereo();
+		*bus = ChannelFrame::ZERO;
+	}
+}
+
+/// Returns `out` with the stereo downmix of `bus` mixed in, using
+/// `temp_buffer` to hold the result if `bus` can contain any audio.
+///
+/// Everything that can only observe stereo audio, like taps and
+/// send tracks, receives this version of a track's output.
+#[must_use]
+pub(crate) fn with_bus_mixed_down<'a>(
+	out: &'a [Frame],
+	bus: &[ChannelFrame],
+	layout: ChannelLayout,
+	temp_buffer: &'a mut [Frame],
+) -> &'a [Frame] {
+	if !layout.is_surround() {
+		return out;
+	}
+	let temp_buffer = &mut temp_buffer[..out.len()];
+	for ((mixed, out), bus) in temp_buffer.iter_mut().zip(out).zip(bus) {
+		*mixed = *out + layout.remix(*bus, ChannelLayout::Stereo).as_stereo();
+	}
+	temp_buffer
+}
+
+/// Returns how much of the signal for the speaker `from` should be
+/// sent to the speaker `to` of `layout`.
+#[must_use]
+fn remix_gain(from: Speaker, to: Speaker, layout: ChannelLayout) -> f32 {
+	if layout.speakers().contains(&from) {
+		return if from == to { 1.0 } else { 0.0 };
+	}
+	match (from, to) {
+		(Speaker::FrontCenter, Speaker::FrontLeft | Speaker::FrontRight)
+		| (Speaker::SideLeft, Speaker::FrontLeft | Speaker::BackLeft)
+		| (Speaker::SideRight, Speaker::FrontRight | Speaker::BackRight)
+		| (Speaker::BackLeft, Speaker::FrontLeft)
+		| (Speaker::BackRight, Speaker::FrontRight) => FRAC_1_SQRT_2,
+		_ => 0.0,
+	}
+}
+
+/**
+How stereo audio is spread across layouts with more than two
+speakers.
+
+This applies to the whole mix when the mixer's
+[`ChannelLayout`] is [`Stereo`](ChannelLayout::Stereo) and the
+audio device has more channels. With larger layouts, it applies to
+everything except spatial tracks, which are panned across the
+speakers themselves.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StereoUpmix {
+	/// Only the front left and right speakers are used. Every other
+	/// speaker receives silence.
+	#[default]
+	FrontOnly,
+	/// The left and right channels are also sent to the back and
+	/// side speakers on the same side, and the center speaker
+	/// receives a mono mix. Each extra speaker is attenuated by 3dB.
+	/// The subwoofer receives silence.
+	Surround,
+}
+
+/**
+A frame of audio with one sample for each speaker of a
+[`ChannelLayout`], in channel order.
+
+Channels past the end of the layout are always silent. When the
+layout is [`Mono`](ChannelLayout::Mono) or
+[`Stereo`](ChannelLayout::Stereo), the first two channels hold the
+left and right channels of the stereo mix.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct ChannelFrame(pub [f32; MAX_CHANNELS]);
+
+impl ChannelFrame {
+	/// A frame with every channel set to `0.0`.
+	pub const ZERO: Self = Self([0.0; MAX_CHANNELS]);
+
+	/// Creates a frame from the left and right channels
+	/// of a stereo frame.
+	#[must_use]
+	pub fn from_stereo(frame: Frame) -> Self {
+		let mut output = Self::ZERO;
+		output.0[0] = frame.left;
+		output.0[1] = frame.right;
+		output
+	}
+
+	/// Returns the first two channels as a stereo frame.
+	#[must_use]
+	pub fn as_stereo(self) -> Frame {
+		Frame::new(self.0[0], self.0[1])
+	}
+
+	/// Applies a function to each channel.
+	#[must_use]
+	pub fn map(self, mut f: impl FnMut(f32) -> f32) -> Self {
+		Self(self.0.map(&mut f))
+	}
+}
+
+impl Add for ChannelFrame {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		let mut output = self;
+		output += rhs;
+		output
+	}
+}
+
+impl AddAssign for ChannelFrame {
+	fn add_assign(&mut self, rhs: Self) {
+		for (channel, added) in self.0.iter_mut().zip(rhs.0) {
+			*channel += added;
+		}
+	}
+}
+
+impl Sub for ChannelFrame {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		let mut output = self;
+		for (channel, subtracted) in output.0.iter_mut().zip(rhs.0) {
+			*channel -= subtracted;
+		}
+		output
+	}
+}
+
+impl Mul<f32> for ChannelFrame {
+	type Output = Self;
+
+	fn mul(self, rhs: f32) -> Self::Output {
+		self.map(|channel| channel * rhs)
+	}
+}
+
+impl MulAssign<f32> for ChannelFrame {
+	fn mul_assign(&mut self, rhs: f32) {
+		*self = *self * rhs;
+	}
+}
+
+/// Writes a frame of the mix, which is in the given layout, to an
+/// interleaved output frame with the given number of channels.
+pub(crate) fn write_frame(
+	frame: ChannelFrame,
+	layout: ChannelLayout,
+	channels: &mut [f32],
+	upmix: StereoUpmix,
+) {
+	let output_layout = ChannelLayout::from_num_channels(channels.len() as u16);
+	match layout {
+		ChannelLayout::Mono => write_stereo_frame(frame.as_stereo().as_mono(), channels, upmix),
+		ChannelLayout::Stereo => write_stereo_frame(frame.as_stereo(), channels, upmix),
+		_ => match output_layout {
+			Some(output_layout) => {
+				let output = layout.remix(frame, output_layout);
+				channels.copy_from_slice(&output.0[..channels.len()]);
+			}
+			// for non-standard layouts with enough channels, keep the speakers
+			// in order and send silence to the rest
+			None if channels.len() >= layout.num_channels().into() => {
+				let (used, unused) = channels.split_at_mut(layout.num_channels().into());
+				used.copy_from_slice(&frame.0[..used.len()]);
+				unused.fill(0.0);
+			}
+			None => write_stereo_frame(
+				layout.remix(frame, ChannelLayout::Stereo).as_stereo(),
+				channels,
+				upmix,
+			),
+		},
+	}
+}
+
+/// Writes a stereo frame to an interleaved output frame with
+/// the given number of channels.
+fn write_stereo_frame(frame: Frame, channels: &mut [f32], upmix: StereoUpmix) {
+	let Some(layout) = ChannelLayout::from_num_channels(channels.len() as u16) else {
+		/*
+			for non-standard layouts, send the stereo mix to the first
+			two channels and silence to the rest. if we don't,
+			we might get bad sounds outputted to those channels.
+			(https://github.com/tesselode/kira/issues/50)
+		*/
+		channels[0] = frame.left;
+		if let Some((right, rest)) = channels[1..].split_first_mut() {
+			*right = frame.right;
+			rest.fill(0.0);
+		}
+		return;
+	};
+	let output = layout.upmix_stereo(frame, upmix);
+	channels.copy_from_slice(&output.0[..channels.len()]);
+}
@@ -0,0 +1,140 @@
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+use approx::assert_relative_eq;
+
+use crate::Frame;
+
+use super::{ChannelFrame, ChannelLayout, StereoUpmix, write_frame};
+
+/// Tests that a sound straight ahead of the listener is panned
+/// to the center speaker, or split evenly between the front
+/// speakers if there isn't one.
+#[test]
+fn pans_front_sounds_to_the_front_speakers() {
+	let gains = ChannelLayout::Surround51.pan(0.0, 0.0);
+	for (i, expected) in [0.0, 0.0, 1.0, 0.0, 0.0, 0.0].into_iter().enumerate() {
+		assert_relative_eq!(gains.0[i], expected, epsilon = 0.0001);
+	}
+
+	let gains = ChannelLayout::Quad.pan(0.0, 0.0);
+	assert_relative_eq!(gains.0[0], FRAC_1_SQRT_2);
+	assert_relative_eq!(gains.0[1], FRAC_1_SQRT_2);
+	assert_relative_eq!(gains.0[2], 0.0, epsilon = 0.0001);
+	assert_relative_eq!(gains.0[3], 0.0, epsilon = 0.0001);
+}
+
+/// Tests that a sound behind the listener is panned to the
+/// back speakers.
+#[test]
+fn pans_back_sounds_to_the_back_speakers() {
+	let gains = ChannelLayout::Surround51.pan(PI, 0.0);
+	for gain in &gains.0[..4] {
+		assert_relative_eq!(*gain, 0.0, epsilon = 0.0001);
+	}
+	assert_relative_eq!(gains.0[4], FRAC_1_SQRT_2);
+	assert_relative_eq!(gains.0[5], FRAC_1_SQRT_2);
+
+	// directly to the right of the listener
+	let gains = ChannelLayout::Surround71.pan(PI / 2.0, 0.0);
+	assert_relative_eq!(gains.0[7], 1.0, epsilon = 0.0001);
+	assert_relative_eq!(gains.0.iter().sum::<f32>(), 1.0, epsilon = 0.0001);
+}
+
+/// Tests that panning keeps the total power constant regardless
+/// of the direction and spread.
+#[test]
+fn pans_with_constant_power() {
+	for layout in [
+		ChannelLayout::Quad,
+		ChannelLayout::Surround51,
+		ChannelLayout::Surround71,
+	] {
+		for i in 0..16 {
+			let azimuth = i as f32 / 16.0 * 2.0 * PI;
+			for spread in [0.0, 0.5, 1.0] {
+				let power: f32 = layout
+					.pan(azimuth, spread)
+					.0
+					.iter()
+					.map(|gain| gain * gain)
+					.sum();
+				assert_relative_eq!(power, 1.0, epsilon = 0.0001);
+			}
+		}
+		// the subwoofer is never panned to
+		if layout.num_channels() > 4 {
+			assert_eq!(layout.pan(0.0, 1.0).0[3], 0.0);
+		}
+	}
+}
+
+/// Tests that a 5.1 mix is folded down to stereo at -3dB and
+/// the subwoofer channel is dropped.
+#[test]
+fn downmixes_surround_to_stereo() {
+	let frame = ChannelFrame([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 0.0, 0.0]);
+	let stereo = ChannelLayout::Surround51.remix(frame, ChannelLayout::Stereo);
+	assert_relative_eq!(stereo.0[0], 1.0 + (3.0 + 5.0) * FRAC_1_SQRT_2);
+	assert_relative_eq!(stereo.0[1], 2.0 + (3.0 + 6.0) * FRAC_1_SQRT_2);
+	assert_eq!(stereo.0[2..], [0.0; 6]);
+
+	let mono = ChannelLayout::Surround51.remix(frame, ChannelLayout::Mono);
+	assert_relative_eq!(mono.0[0], (stereo.0[0] + stereo.0[1]) / 2.0);
+}
+
+/// Tests that the side speakers of a 7.1 mix are folded into the
+/// front and back speakers of a 5.1 device.
+#[test]
+fn downmixes_7_1_to_5_1() {
+	let frame = ChannelFrame([0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+	let output = ChannelLayout::Surround71.remix(frame, ChannelLayout::Surround51);
+	assert_relative_eq!(output.0[0], FRAC_1_SQRT_2);
+	assert_relative_eq!(output.0[4], FRAC_1_SQRT_2);
+	assert_eq!(output.0[1], 0.0);
+	assert_eq!(output.0[5], 0.0);
+}
+
+/// Tests that a surround mix is written to a device with
+/// a non-standard number of channels.
+#[test]
+fn writes_surround_frames_to_non_standard_devices() {
+	let frame = ChannelFrame([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 0.0, 0.0]);
+
+	// enough channels: the speakers are kept in order
+	let mut channels = [-1.0; 7];
+	write_frame(
+		frame,
+		ChannelLayout::Surround51,
+		&mut channels,
+		StereoUpmix::FrontOnly,
+	);
+	assert_eq!(channels, [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 0.0]);
+
+	// too few channels: the stereo downmix is used
+	let mut channels = [-1.0; 3];
+	write_frame(
+		frame,
+		ChannelLayout::Surround51,
+		&mut channels,
+		StereoUpmix::FrontOnly,
+	);
+	let stereo = ChannelLayout::Surround51
+		.remix(frame, ChannelLayout::Stereo)
+		.as_stereo();
+	assert_eq!(channels, [stereo.left, stereo.right, 0.0]);
+}
+
+/// Tests that a stereo mix is written the same way regardless of
+/// whether it's stored in a frame with more channels.
+#[test]
+fn writes_stereo_frames_to_surround_devices() {
+	let frame = ChannelFrame::from_stereo(Frame::new(1.0, 2.0));
+	let mut channels = [-1.0; 6];
+	write_frame(
+		frame,
+		ChannelLayout::Stereo,
+		&mut channels,
+		StereoUpmix::FrontOnly,
+	);
+	assert_eq!(channels, [1.0, 2.0, 0.0, 0.0, 0.0, 0.0]);
+}
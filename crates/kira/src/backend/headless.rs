@@ -0,0 +1,248 @@
+//! Plays audio at realtime speed without an audio device.
+
+use std::{
+	fmt::{Debug, Formatter},
+	io,
+	sync::{
+		Arc, Mutex,
+		atomic::{AtomicBool, AtomicU64, Ordering},
+	},
+	thread::{self, JoinHandle},
+	time::{Duration, Instant},
+};
+
+use super::{Backend, Renderer};
+
+/// If the audio thread falls this many buffers behind, it stops
+/// trying to catch up and continues from the current time.
+const MAX_BUFFERS_BEHIND: u32 = 8;
+
+/// A function that receives rendered audio.
+pub type OutputCallback = Box<dyn FnMut(&[f32]) + Send>;
+
+/// Settings for the headless backend.
+pub struct HeadlessBackendSettings {
+	/// The sample rate that the [`Renderer`] should run at.
+	///
+	/// `0` is treated the same as `1`.
+	pub sample_rate: u32,
+	/// The number of channels to render.
+	///
+	/// `0` is treated the same as `1`.
+	pub num_channels: u16,
+	/// The number of frames to render at a time.
+	///
+	/// This plays the same role as the buffer size of an audio device.
+	pub buffer_size: usize,
+	/// A function that receives each buffer of interleaved samples
+	/// after it's rendered.
+	///
+	/// If [`None`], the audio is discarded.
+	pub output: Option<OutputCallback>,
+}
+
+impl Default for HeadlessBackendSettings {
+	fn default() -> Self {
+		Self {
+			sample_rate: 48_000,
+			num_channels: 2,
+			buffer_size: 512,
+			output: None,
+		}
+	}
+}
+
+impl Debug for HeadlessBackendSettings {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("HeadlessBackendSettings")
+			.field("sample_rate", &self.sample_rate)
+			.field("num_channels", &self.num_channels)
+			.field("buffer_size", &self.buffer_size)
+			.field("output", &self.output.as_ref().map(|_| OutputDebug))
+			.finish()
+	}
+}
+
+struct OutputDebug;
+
+impl Debug for OutputDebug {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str("FnMut(&[f32])")
+	}
+}
+
+enum State {
+	Uninitialized {
+		// wrapped in a mutex so the backend is `Sync` even though
+		// the output callback doesn't have to be
+		output: Mutex<Option<OutputCallback>>,
+	},
+	Initialized {
+		thread: JoinHandle<()>,
+	},
+	Empty,
+}
+
+/**
+A backend that renders audio on its own thread at realtime speed,
+but doesn't send it to an audio device.
+
+This is useful for dedicated servers and CI machines that don't
+have an audio device, but still need clocks, start times, and
+sound states to advance just like they would on a player's machine.
+The audio can optionally be forwarded to a function, for example
+to stream it over the network.
+
+# Examples
+
+```no_run
+use kira::{
+	AudioManager, AudioManagerSettings,
+	backend::headless::{HeadlessBackend, HeadlessBackendSettings},
+	clock::ClockSpeed,
+};
+
+let mut manager = AudioManager::<HeadlessBackend>::new(AudioManagerSettings {
+	backend_settings: HeadlessBackendSettings::default(),
+	..Default::default()
+})?;
+let mut clock = manager.add_clock(ClockSpeed::TicksPerSecond(1.0))?;
+clock.start();
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+*/
+pub struct HeadlessBackend {
+	sample_rate: u32,
+	num_channels: u16,
+	buffer_size: usize,
+	state: State,
+	shared: Arc<Shared>,
+}
+
+impl HeadlessBackend {
+	/// Returns the sample rate audio is rendered at.
+	#[must_use]
+	pub fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+
+	/// Returns the number of channels audio is rendered with.
+	#[must_use]
+	pub fn num_channels(&self) -> u16 {
+		self.num_channels
+	}
+
+	/// Returns the number of frames that have been rendered so far.
+	#[must_use]
+	pub fn num_frames_rendered(&self) -> u64 {
+		self.shared.num_frames_rendered.load(Ordering::SeqCst)
+	}
+}
+
+impl Backend for HeadlessBackend {
+	type Settings = HeadlessBackendSettings;
+
+	type Error = io::Error;
+
+	fn setup(
+		settings: Self::Settings,
+		_internal_buffer_size: usize,
+	) -> Result<(Self, u32), Self::Error> {
+		let sample_rate = settings.sample_rate.max(1);
+		Ok((
+			Self {
+				sample_rate,
+				num_channels: settings.num_channels.max(1),
+				buffer_size: settings.buffer_size.max(1),
+				state: State::Uninitialized {
+					output: Mutex::new(settings.output),
+				},
+				shared: Arc::new(Shared {
+					stopped: AtomicBool::new(false),
+					num_frames_rendered: AtomicU64::new(0),
+				}),
+			},
+			sample_rate,
+		))
+	}
+
+	fn start(&mut self, renderer: Renderer) -> Result<(), Self::Error> {
+		let State::Uninitialized { output } = std::mem::replace(&mut self.state, State::Empty)
+		else {
+			panic!("Cannot initialize the backend multiple times")
+		};
+		let audio_thread = AudioThread {
+			renderer,
+			output: output.into_inner().expect("output mutex poisoned"),
+			buffer: vec![0.0; self.buffer_size * self.num_channels as usize],
+			num_channels: self.num_channels,
+			buffer_duration: Duration::from_secs_f64(
+				self.buffer_size as f64 / self.sample_rate as f64,
+			),
+			shared: self.shared.clone(),
+		};
+		let thread = thread::Builder::new()
+			.name("kira headless audio".into())
+			.spawn(move || audio_thread.run())?;
+		self.state = State::Initialized { thread };
+		Ok(())
+	}
+}
+
+impl Drop for HeadlessBackend {
+	fn drop(&mut self) {
+		self.shared.stopped.store(true, Ordering::SeqCst);
+		if let State::Initialized { thread } = std::mem::replace(&mut self.state, State::Empty) {
+			thread.thread().unpark();
+			thread.join().ok();
+		}
+	}
+}
+
+struct Shared {
+	stopped: AtomicBool,
+	num_frames_rendered: AtomicU64,
+}
+
+struct AudioThread {
+	renderer: Renderer,
+	output: Option<OutputCallback>,
+	buffer: Vec<f32>,
+	num_channels: u16,
+	buffer_duration: Duration,
+	shared: Arc<Shared>,
+}
+
+impl AudioThread {
+	fn run(mut self) {
+		let num_frames = (self.buffer.len() / self.num_channels as usize) as u64;
+		let mut next_buffer_time = Instant::now();
+		while !self.shared.stopped.load(Ordering::SeqCst) {
+			self.renderer.on_start_processing();
+			self.renderer.process(&mut self.buffer, self.num_channels);
+			if let Some(output) = &mut self.output {
+				output(&self.buffer);
+			}
+			self.shared
+				.num_frames_rendered
+				.fetch_add(num_frames, Ordering::SeqCst);
+
+			/*
+				schedule buffers relative to when the first one was rendered
+				rather than when the previous one finished, so oversleeping
+				doesn't cause the audio to drift behind realtime.
+			*/
+			next_buffer_time += self.buffer_duration;
+			let mut now = Instant::now();
+			if now - next_buffer_time.min(now) > self.buffer_duration * MAX_BUFFERS_BEHIND {
+				next_buffer_time = now;
+			}
+			// parking can end early, so keep waiting until it's time
+			// for the next buffer or the backend is dropped
+			while now < next_buffer_time && !self.shared.stopped.load(Ordering::SeqCst) {
+				thread::park_timeout(next_buffer_time - now);
+				now = Instant::now();
+			}
+		}
+	}
+}
@@ -1,6 +1,8 @@
 //! Useful for testing and benchmarking.
 
-use std::sync::Mutex;
+use std::{sync::Mutex, time::Duration};
+
+use crate::Frame;
 
 use super::{Backend, Renderer};
 
@@ -22,15 +24,45 @@ impl Default for MockBackendSettings {
 	}
 }
 
-/// A backend that does not connect to any lower-level
-/// audio APIs, but allows manually calling
-/// [`Renderer::on_start_processing`] and [`Renderer::process`].
-///
-/// This is useful for testing and benchmarking.
+/**
+A backend that does not connect to any lower-level
+audio APIs, but allows manually calling
+[`Renderer::on_start_processing`] and [`Renderer::process`].
+
+This is useful for testing and benchmarking. Since audio is only
+produced when you ask for it, the output is deterministic, so it
+can be compared against known-good output in tests.
+
+# Examples
+
+```
+use kira::{
+	AudioManager, AudioManagerSettings, Frame,
+	backend::mock::{MockBackend, MockBackendSettings},
+	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+};
+
+let mut manager = AudioManager::<MockBackend>::new(AudioManagerSettings {
+	backend_settings: MockBackendSettings { sample_rate: 4 },
+	..Default::default()
+})
+.unwrap();
+let sound = StaticSoundData {
+	sample_rate: 4,
+	frames: vec![Frame::from_mono(0.5); 4].into(),
+	settings: StaticSoundSettings::new(),
+	slice: None,
+};
+manager.play(sound).unwrap();
+let frames = manager.backend_mut().render(8);
+assert_eq!(frames.len(), 8);
+```
+*/
 pub struct MockBackend {
 	sample_rate: u32,
 	state: State,
 	frames: Vec<f32>,
+	chunk_size: usize,
 }
 
 impl MockBackend {
@@ -45,6 +77,64 @@ impl MockBackend {
 		}
 	}
 
+	/// Sets the number of frames the renderer produces after
+	/// each call to [`on_start_processing`](Renderer::on_start_processing).
+	///
+	/// This defaults to the manager's
+	/// [`internal_buffer_size`](crate::AudioManagerSettings::internal_buffer_size).
+	/// Setting it to a different value is useful for testing how
+	/// audio behaves when the audio device asks for different
+	/// amounts of audio at a time.
+	///
+	/// # Panics
+	///
+	/// Panics if `chunk_size` is `0`.
+	pub fn set_chunk_size(&mut self, chunk_size: usize) {
+		assert!(chunk_size > 0, "chunk size must be greater than 0");
+		self.chunk_size = chunk_size;
+		self.frames.resize(chunk_size * 2, 0.0);
+	}
+
+	/// Returns the number of frames the renderer produces after
+	/// each call to [`on_start_processing`](Renderer::on_start_processing).
+	#[must_use]
+	pub fn chunk_size(&self) -> usize {
+		self.chunk_size
+	}
+
+	/// Renders the next `num_frames` frames of audio and returns them.
+	///
+	/// [`on_start_processing`](Renderer::on_start_processing) is called
+	/// before each chunk of audio is rendered, so commands sent from
+	/// handles are applied.
+	pub fn render(&mut self, num_frames: usize) -> Vec<Frame> {
+		let mut frames = Vec::with_capacity(num_frames);
+		let mut num_remaining_frames = num_frames;
+		while num_remaining_frames > 0 {
+			let chunk_size = num_remaining_frames.min(self.chunk_size);
+			self.render_chunk(chunk_size);
+			frames.extend(
+				self.frames[..chunk_size * 2]
+					.chunks_exact(2)
+					.map(|samples| Frame::new(samples[0], samples[1])),
+			);
+			num_remaining_frames -= chunk_size;
+		}
+		frames
+	}
+
+	/// Renders and discards the amount of audio needed to move
+	/// time forward by `duration`, rounded to the nearest frame.
+	pub fn advance(&mut self, duration: Duration) {
+		let mut num_remaining_frames =
+			(duration.as_secs_f64() * self.sample_rate as f64).round() as usize;
+		while num_remaining_frames > 0 {
+			let chunk_size = num_remaining_frames.min(self.chunk_size);
+			self.render_chunk(chunk_size);
+			num_remaining_frames -= chunk_size;
+		}
+	}
+
 	/// Calls the [`on_start_processing`](Renderer::on_start_processing)
 	/// callback of the [`Renderer`].
 	pub fn on_start_processing(&mut self) {
@@ -59,6 +149,8 @@ impl MockBackend {
 	}
 
 	/// Calls the [`process`](Renderer::process) callback of the [`Renderer`].
+	///
+	/// This renders [`chunk_size`](MockBackend::chunk_size) frames of audio.
 	pub fn process(&mut self) {
 		if let State::Initialized { renderer } = &mut self.state {
 			renderer
@@ -69,6 +161,15 @@ impl MockBackend {
 			panic!("backend is not initialized")
 		}
 	}
+
+	fn render_chunk(&mut self, num_frames: usize) {
+		let State::Initialized { renderer } = &mut self.state else {
+			panic!("backend is not initialized")
+		};
+		let renderer = renderer.get_mut().expect("mutex poisoned");
+		renderer.on_start_processing();
+		renderer.process(&mut self.frames[..num_frames * 2], 2);
+	}
 }
 
 impl Backend for MockBackend {
@@ -85,6 +186,7 @@ impl Backend for MockBackend {
 				sample_rate: settings.sample_rate,
 				state: State::Uninitialized,
 				frames: vec![0.0; internal_buffer_size * 2],
+				chunk_size: internal_buffer_size,
 			},
 			settings.sample_rate,
 		))
@@ -0,0 +1,217 @@
+//! Renders audio faster than realtime without an audio device.
+
+use std::{
+	fs::File,
+	io::{self, BufWriter, Write},
+	path::Path,
+	sync::Mutex,
+	time::Duration,
+};
+
+use super::{Backend, Renderer};
+
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const BYTES_PER_SAMPLE: u16 = 4;
+
+enum State {
+	Uninitialized,
+	Initialized { renderer: Mutex<Renderer> },
+}
+
+/// Settings for the offline backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OfflineBackendSettings {
+	/// The sample rate that the [`Renderer`] should run at.
+	pub sample_rate: u32,
+	/// The number of channels to render.
+	///
+	/// `0` is treated the same as `1`.
+	pub num_channels: u16,
+}
+
+impl Default for OfflineBackendSettings {
+	fn default() -> Self {
+		Self {
+			sample_rate: 48_000,
+			num_channels: 2,
+		}
+	}
+}
+
+/**
+A backend that renders audio on demand instead of sending it
+to an audio device.
+
+Audio is only produced when [`render`](OfflineBackend::render) or
+[`render_frames`](OfflineBackend::render_frames) is called, so
+it can be rendered much faster than realtime. The rendered
+samples are kept until they're [cleared](OfflineBackend::clear),
+and they can be exported with
+[`write_wav`](OfflineBackend::write_wav) or
+[`write_wav_file`](OfflineBackend::write_wav_file).
+
+# Examples
+
+```no_run
+use std::time::Duration;
+
+use kira::{
+	AudioManager, AudioManagerSettings,
+	backend::offline::OfflineBackend,
+	sound::static_sound::StaticSoundData,
+};
+
+let mut manager = AudioManager::<OfflineBackend>::new(AudioManagerSettings::default())?;
+manager.play(StaticSoundData::from_file("cutscene.ogg")?)?;
+let backend = manager.backend_mut();
+backend.render(Duration::from_secs(30));
+backend.write_wav_file("cutscene.wav")?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+*/
+pub struct OfflineBackend {
+	sample_rate: u32,
+	num_channels: u16,
+	internal_buffer_size: usize,
+	state: State,
+	buffer: Vec<f32>,
+	samples: Vec<f32>,
+}
+
+impl OfflineBackend {
+	/// Returns the sample rate audio is rendered at.
+	#[must_use]
+	pub fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+
+	/// Returns the number of channels audio is rendered with.
+	#[must_use]
+	pub fn num_channels(&self) -> u16 {
+		self.num_channels
+	}
+
+	/// Renders audio for the given amount of time.
+	///
+	/// The duration is rounded to the nearest number of frames.
+	pub fn render(&mut self, duration: Duration) {
+		let num_frames = (duration.as_secs_f64() * self.sample_rate as f64).round() as usize;
+		self.render_frames(num_frames);
+	}
+
+	/// Renders the given number of frames of audio.
+	///
+	/// The [`Renderer`] is driven in batches of the internal
+	/// buffer size, just like it would be by a realtime backend.
+	pub fn render_frames(&mut self, mut num_frames: usize) {
+		let State::Initialized { renderer } = &mut self.state else {
+			panic!("backend is not initialized")
+		};
+		let renderer = renderer.get_mut().expect("mutex poisoned");
+		let num_channels = self.num_channels as usize;
+		self.samples.reserve(num_frames * num_channels);
+		while num_frames > 0 {
+			let batch_size = num_frames.min(self.internal_buffer_size);
+			let buffer = &mut self.buffer[..batch_size * num_channels];
+			renderer.on_start_processing();
+			renderer.process(buffer, self.num_channels);
+			self.samples.extend_from_slice(buffer);
+			num_frames -= batch_size;
+		}
+	}
+
+	/// Returns the interleaved samples that have been rendered so far.
+	#[must_use]
+	pub fn samples(&self) -> &[f32] {
+		&self.samples
+	}
+
+	/// Returns the number of frames that have been rendered so far.
+	#[must_use]
+	pub fn num_frames(&self) -> usize {
+		self.samples.len() / self.num_channels as usize
+	}
+
+	/// Discards all of the audio that has been rendered so far.
+	pub fn clear(&mut self) {
+		self.samples.clear();
+	}
+
+	/// Writes the audio rendered so far to a 32-bit float WAV file.
+	pub fn write_wav_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+		let mut writer = BufWriter::new(File::create(path)?);
+		self.write_wav(&mut writer)?;
+		writer.flush()
+	}
+
+	/// Writes the audio rendered so far as a 32-bit float WAV file
+	/// to any [`Write`] implementor.
+	pub fn write_wav(&self, mut writer: impl Write) -> io::Result<()> {
+		let data_size = u32::try_from(self.samples.len() * BYTES_PER_SAMPLE as usize)
+			.ok()
+			.filter(|size| *size <= u32::MAX - 50)
+			.ok_or_else(|| {
+				io::Error::new(
+					io::ErrorKind::InvalidInput,
+					"too much audio has been rendered to fit in a WAV file",
+				)
+			})?;
+		let block_align = self.num_channels * BYTES_PER_SAMPLE;
+		// RIFF header
+		writer.write_all(b"RIFF")?;
+		writer.write_all(&(data_size + 50).to_le_bytes())?;
+		writer.write_all(b"WAVE")?;
+		// format chunk
+		writer.write_all(b"fmt ")?;
+		writer.write_all(&18u32.to_le_bytes())?;
+		writer.write_all(&WAVE_FORMAT_IEEE_FLOAT.to_le_bytes())?;
+		writer.write_all(&self.num_channels.to_le_bytes())?;
+		writer.write_all(&self.sample_rate.to_le_bytes())?;
+		writer.write_all(&(self.sample_rate * block_align as u32).to_le_bytes())?;
+		writer.write_all(&block_align.to_le_bytes())?;
+		writer.write_all(&(BYTES_PER_SAMPLE * 8).to_le_bytes())?;
+		writer.write_all(&0u16.to_le_bytes())?;
+		// fact chunk (required for non-PCM formats)
+		writer.write_all(b"fact")?;
+		writer.write_all(&4u32.to_le_bytes())?;
+		writer.write_all(&(self.num_frames() as u32).to_le_bytes())?;
+		// data chunk
+		writer.write_all(b"data")?;
+		writer.write_all(&data_size.to_le_bytes())?;
+		for sample in &self.samples {
+			writer.write_all(&sample.to_le_bytes())?;
+		}
+		Ok(())
+	}
+}
+
+impl Backend for OfflineBackend {
+	type Settings = OfflineBackendSettings;
+
+	type Error = ();
+
+	fn setup(
+		settings: Self::Settings,
+		internal_buffer_size: usize,
+	) -> Result<(Self, u32), Self::Error> {
+		let num_channels = settings.num_channels.max(1);
+		Ok((
+			Self {
+				sample_rate: settings.sample_rate,
+				num_channels,
+				internal_buffer_size,
+				state: State::Uninitialized,
+				buffer: vec![0.0; internal_buffer_size * num_channels as usize],
+				samples: vec![],
+			},
+			settings.sample_rate,
+		))
+	}
+
+	fn start(&mut self, renderer: Renderer) -> Result<(), Self::Error> {
+		self.state = State::Initialized {
+			renderer: Mutex::new(renderer),
+		};
+		Ok(())
+	}
+}
@@ -0,0 +1,298 @@
+#[cfg(test)]
+mod test;
+
+use std::{
+	collections::VecDeque,
+	ops::{Add, Mul, Sub},
+	time::Duration,
+};
+
+use crate::{
+	Decibels,
+	frame::{Frame, interpolate},
+};
+
+use super::channel_layout::ChannelFrame;
+
+/// How far ahead the [`OutputStage::Limiter`] looks for peaks.
+const LIMITER_LOOKAHEAD: Duration = Duration::from_millis(5);
+/// The level above which [`OutputStage::SoftClip`] starts
+/// saturating the signal.
+const SOFT_CLIP_THRESHOLD: f32 = 0.5;
+
+/**
+The final processing applied to the mix before it's sent to the
+audio device.
+
+Audio devices can't output samples outside of the range `-1.0`
+to `1.0`, so when many loud sounds play at once, the output stage
+decides how to keep the mix within that range.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutputStage {
+	/// Every sample is clamped to the range `-1.0` to `1.0`. This is
+	/// cheap, but it causes harsh distortion when the mix clips.
+	#[default]
+	Clamp,
+	/// Samples louder than -6dBFS are smoothly saturated so that
+	/// they never exceed `1.0`. Quieter samples pass through unchanged.
+	SoftClip,
+	/// The volume of the mix is turned down ahead of time whenever
+	/// it would exceed the ceiling, including peaks that occur
+	/// between samples.
+	///
+	/// The limiter looks 5 milliseconds ahead, which delays the
+	/// output by about the same amount.
+	Limiter {
+		/// The maximum level of the output.
+		ceiling: Decibels,
+		/// How long the limiter takes to return to full volume
+		/// after a peak.
+		release: Duration,
+	},
+}
+
+/// A frame of audio that can be processed by the output stage.
+pub(crate) trait OutputFrame:
+	Copy + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self>
+{
+	/// A frame with every channel set to `0.0`.
+	const ZERO: Self;
+
+	/// Applies a function to each channel.
+	#[must_use]
+	fn map(self, f: impl FnMut(f32) -> f32) -> Self;
+
+	/// Returns the highest absolute value of any channel.
+	#[must_use]
+	fn peak(self) -> f32;
+}
+
+impl OutputFrame for Frame {
+	const ZERO: Self = Frame::ZERO;
+
+	fn map(self, mut f: impl FnMut(f32) -> f32) -> Self {
+		Frame::new(f(self.left), f(self.right))
+	}
+
+	fn peak(self) -> f32 {
+		self.left.abs().max(self.right.abs())
+	}
+}
+
+impl OutputFrame for ChannelFrame {
+	const ZERO: Self = ChannelFrame::ZERO;
+
+	fn map(self, f: impl FnMut(f32) -> f32) -> Self {
+		ChannelFrame::map(self, f)
+	}
+
+	fn peak(self) -> f32 {
+		self.0
+			.iter()
+			.fold(0.0, |peak, sample| f32::max(peak, sample.abs()))
+	}
+}
+
+pub(crate) enum OutputProcessor<F> {
+	Clamp,
+	SoftClip,
+	Limiter(Limiter<F>),
+}
+
+impl<F: OutputFrame> OutputProcessor<F> {
+	#[must_use]
+	pub fn new(output_stage: OutputStage, sample_rate: u32) -> Self {
+		match output_stage {
+			OutputStage::Clamp => Self::Clamp,
+			OutputStage::SoftClip => Self::SoftClip,
+			OutputStage::Limiter { ceiling, release } => {
+				Self::Limiter(Limiter::new(ceiling, release, sample_rate))
+			}
+		}
+	}
+
+	pub fn on_change_sample_rate(&mut self, sample_rate: u32) {
+		if let Self::Limiter(limiter) = self {
+			*limiter = Limiter::new(limiter.ceiling, limiter.release, sample_rate);
+		}
+	}
+
+	/// Processes the frames in place and returns the lowest
+	/// gain that was applied to any of them.
+	pub fn process(&mut self, frames: &mut [F]) -> f32 {
+		let mut min_gain = 1.0f32;
+		for frame in frames {
+			let input = *frame;
+			let (output, gain) = match self {
+				Self::Clamp => {
+					let output = input.map(|sample| sample.clamp(-1.0, 1.0));
+					(output, applied_gain(input, output))
+				}
+				Self::SoftClip => {
+					let output = input.map(soft_clip);
+					(output, applied_gain(input, output))
+				}
+				Self::Limiter(limiter) => limiter.process(input),
+			};
+			*frame = output;
+			min_gain = min_gain.min(gain);
+		}
+		min_gain
+	}
+}
+
+pub(crate) struct Limiter<F> {
+	ceiling: Decibels,
+	release: Duration,
+	ceiling_amplitude: f32,
+	release_speed: f32,
+	/// The last four input frames, used to estimate peaks
+	/// between samples.
+	history: [F; 4],
+	/// Input frames that haven't been output yet.
+	delay_line: VecDeque<F>,
+	/// The gain needed to keep each recent input frame under the
+	/// ceiling, stored so that the minimum of the window can be
+	/// found quickly.
+	required_gains: VecDeque<(usize, f32)>,
+	required_gain_window_size: usize,
+	frame_index: usize,
+	released_gain: f32,
+	/// Recent released gains, which are averaged to smooth out
+	/// the gain changes.
+	smoothing_buffer: VecDeque<f32>,
+	smoothing_sum: f64,
+}
+
+impl<F: OutputFrame> Limiter<F> {
+	#[must_use]
+	fn new(ceiling: Decibels, release: Duration, sample_rate: u32) -> Self {
+		let lookahead_frames =
+			((LIMITER_LOOKAHEAD.as_secs_f64() * sample_rate as f64).round() as usize).max(1);
+		/*
+			the peak estimate for a frame covers the two frames before it,
+			so the audio is delayed by one extra frame, and the minimum is
+			taken over one extra frame, to make sure the gain reduction is
+			fully applied by the time the peak is output.
+		*/
+		let delay = lookahead_frames + 1;
+		let release_frames = release.as_secs_f64() * sample_rate as f64;
+		Self {
+			ceiling,
+			release,
+			ceiling_amplitude: ceiling.as_amplitude(),
+			release_speed: if release_frames > 0.0 {
+				(-1.0 / release_frames).exp() as f32
+			} else {
+				0.0
+			},
+			history: [F::ZERO; 4],
+			delay_line: VecDeque::from(vec![F::ZERO; delay]),
+			// a new gain is added before old gains are removed from
+			// the window, so the window can briefly hold one extra gain
+			required_gains: VecDeque::with_capacity(delay + 1),
+			required_gain_window_size: delay,
+			frame_index: 0,
+			released_gain: 1.0,
+			smoothing_buffer: VecDeque::from(vec![1.0; lookahead_frames]),
+			smoothing_sum: lookahead_frames as f64,
+		}
+	}
+
+	fn process(&mut self, input: F) -> (F, f32) {
+		self.history.copy_within(1.., 0);
+		self.history[3] = input;
+		let required_gain = self.required_gain(self.true_peak());
+
+		// keep track of the minimum required gain over the window
+		while self
+			.required_gains
+			.back()
+			.is_some_and(|(_, gain)| *gain >= required_gain)
+		{
+			self.required_gains.pop_back();
+		}
+		self.required_gains
+			.push_back((self.frame_index, required_gain));
+		while self
+			.required_gains
+			.front()
+			.is_some_and(|(index, _)| *index + self.required_gain_window_size <= self.frame_index)
+		{
+			self.required_gains.pop_front();
+		}
+		self.frame_index = self.frame_index.wrapping_add(1);
+		let held_gain = self.required_gains.front().map_or(1.0, |(_, gain)| *gain);
+
+		// gradually return to full volume after a peak
+		self.released_gain = if held_gain < self.released_gain {
+			held_gain
+		} else {
+			held_gain + self.release_speed * (self.released_gain - held_gain)
+		};
+
+		// smooth out the gain changes
+		self.smoothing_sum -= self.smoothing_buffer.pop_front().unwrap_or(1.0) as f64;
+		self.smoothing_buffer.push_back(self.released_gain);
+		self.smoothing_sum += self.released_gain as f64;
+		let gain = ((self.smoothing_sum / self.smoothing_buffer.len() as f64) as f32).min(1.0);
+
+		// pop before pushing so the delay line never grows
+		let output = self.delay_line.pop_front().unwrap_or(F::ZERO) * gain;
+		self.delay_line.push_back(input);
+		(
+			output.map(|sample| sample.clamp(-self.ceiling_amplitude, self.ceiling_amplitude)),
+			gain,
+		)
+	}
+
+	/// Estimates the highest level of the signal between the
+	/// second and third most recent frames, including both frames.
+	fn true_peak(&self) -> f32 {
+		let [previous, current, next_1, next_2] = self.history;
+		[0.25, 0.5, 0.75]
+			.into_iter()
+			.map(|fraction| interpolate(previous, current, next_1, next_2, fraction).peak())
+			.fold(current.peak().max(next_1.peak()), f32::max)
+	}
+
+	fn required_gain(&self, peak: f32) -> f32 {
+		if peak > self.ceiling_amplitude {
+			self.ceiling_amplitude / peak
+		} else {
+			1.0
+		}
+	}
+}
+
+fn applied_gain<F: OutputFrame>(input: F, output: F) -> f32 {
+	let input_peak = input.peak();
+	if input_peak > 0.0 {
+		(output.peak() / input_peak).min(1.0)
+	} else {
+		1.0
+	}
+}
+
+fn soft_clip(sample: f32) -> f32 {
+	let magnitude = sample.abs();
+	if magnitude <= SOFT_CLIP_THRESHOLD {
+		return sample;
+	}
+	let headroom = 1.0 - SOFT_CLIP_THRESHOLD;
+	let saturated =
+		SOFT_CLIP_THRESHOLD + headroom * ((magnitude - SOFT_CLIP_THRESHOLD) / headroom).tanh();
+	saturated.copysign(sample)
+}
+
+/// Converts the lowest gain applied by the output stage
+/// to an amount of gain reduction.
+#[must_use]
+pub(crate) fn gain_reduction(min_gain: f32) -> Decibels {
+	if min_gain >= 1.0 {
+		return Decibels::IDENTITY;
+	}
+	Decibels(-20.0 * min_gain.max(f32::MIN_POSITIVE).log10())
+}
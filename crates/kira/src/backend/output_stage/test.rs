@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use crate::{Decibels, Frame};
+
+use super::{OutputProcessor, OutputStage, gain_reduction};
+
+/// Tests that the clamp output stage limits samples to the range
+/// -1.0 to 1.0 and reports the resulting gain reduction.
+#[test]
+fn clamp() {
+	let mut processor = OutputProcessor::new(OutputStage::Clamp, 1);
+	let mut frames = [
+		Frame::new(0.5, -0.5),
+		Frame::new(2.0, 0.5),
+		Frame::new(0.25, -4.0),
+	];
+	let min_gain = processor.process(&mut frames);
+	assert_eq!(
+		frames,
+		[
+			Frame::new(0.5, -0.5),
+			Frame::new(1.0, 0.5),
+			Frame::new(0.25, -1.0),
+		]
+	);
+	assert_eq!(min_gain, 0.25);
+}
+
+/// Tests that the soft clip output stage leaves quiet samples
+/// unchanged and smoothly saturates loud samples.
+#[test]
+fn soft_clip() {
+	let mut processor = OutputProcessor::new(OutputStage::SoftClip, 1);
+	let mut frames = [Frame::new(0.5, -0.25)];
+	assert_eq!(processor.process(&mut frames), 1.0);
+	assert_eq!(frames, [Frame::new(0.5, -0.25)]);
+
+	let inputs = [0.6, 0.9, 1.5, 4.0];
+	let mut frames = inputs.map(Frame::from_mono);
+	let min_gain = processor.process(&mut frames);
+	let mut previous = 0.5;
+	for (input, frame) in inputs.into_iter().zip(frames) {
+		assert!(frame.left > previous);
+		assert!(frame.left < 1.0);
+		assert!(frame.left < input);
+		previous = frame.left;
+	}
+	assert!(min_gain < 0.25);
+	let mut frames = [Frame::from_mono(100.0)];
+	processor.process(&mut frames);
+	assert!(frames[0].left <= 1.0);
+
+	let mut frames = [Frame::new(4.0, -4.0)];
+	processor.process(&mut frames);
+	assert_eq!(frames[0].right, -frames[0].left);
+}
+
+/// Tests that the limiter delays the signal and leaves it
+/// unchanged when it stays under the ceiling.
+#[test]
+fn limiter_passes_quiet_audio() {
+	// 5ms of lookahead at 1000hz is 5 frames, plus one frame
+	// for the peak estimate
+	const DELAY: usize = 6;
+	let mut processor = OutputProcessor::new(
+		OutputStage::Limiter {
+			ceiling: Decibels::IDENTITY,
+			release: Duration::from_millis(100),
+		},
+		1000,
+	);
+	let inputs = (0..20)
+		.map(|i| Frame::from_mono((i as f32 * 0.5).sin() * 0.5))
+		.collect::<Vec<_>>();
+	let mut frames = inputs.clone();
+	assert_eq!(processor.process(&mut frames), 1.0);
+	assert!(frames[..DELAY].iter().all(|frame| *frame == Frame::ZERO));
+	assert_eq!(frames[DELAY..], inputs[..inputs.len() - DELAY]);
+}
+
+/// Tests that the limiter turns down loud audio before it
+/// exceeds the ceiling and then returns to full volume.
+#[test]
+fn limiter_reduces_peaks() {
+	const DELAY: usize = 6;
+	let ceiling = Decibels(-6.0);
+	let mut processor = OutputProcessor::new(
+		OutputStage::Limiter {
+			ceiling,
+			release: Duration::from_millis(10),
+		},
+		1000,
+	);
+	let mut inputs = vec![Frame::from_mono(0.1); 200];
+	for (i, frame) in inputs[20..40].iter_mut().enumerate() {
+		*frame = Frame::from_mono((i as f32 * 1.3).sin() * 2.0);
+	}
+	let mut min_gain = 1.0f32;
+	for (i, input) in inputs.iter().copied().enumerate() {
+		let mut frames = [input];
+		let gain = processor.process(&mut frames);
+		min_gain = min_gain.min(gain);
+		let output = frames[0];
+		assert!(output.left.abs() <= ceiling.as_amplitude() + 0.000001);
+		// the gain reduction should already be applied, so the
+		// output should be the delayed input times the gain
+		if i >= DELAY {
+			let expected = inputs[i - DELAY] * gain;
+			assert!((output.left - expected.left).abs() < 0.000001);
+		}
+	}
+	assert!(min_gain < 0.26);
+	// by the end, the limiter should have released
+	let mut frames = [Frame::from_mono(0.1)];
+	assert!(processor.process(&mut frames) > 0.999);
+	assert!((frames[0].left - 0.1).abs() < 0.001);
+}
+
+/// Tests that the limiter's buffers never grow, so it
+/// doesn't allocate on the audio thread.
+#[test]
+fn limiter_does_not_allocate() {
+	let mut processor = OutputProcessor::new(
+		OutputStage::Limiter {
+			ceiling: Decibels(-6.0),
+			release: Duration::from_millis(10),
+		},
+		1000,
+	);
+	let OutputProcessor::Limiter(limiter) = &processor else {
+		panic!("expected a limiter");
+	};
+	let delay_line_capacity = limiter.delay_line.capacity();
+	let required_gains_capacity = limiter.required_gains.capacity();
+	let smoothing_buffer_capacity = limiter.smoothing_buffer.capacity();
+	// a falling signal keeps every gain in the window
+	let mut frames = (0..100)
+		.map(|i| Frame::from_mono(10.0 - i as f32 * 0.05))
+		.collect::<Vec<_>>();
+	processor.process(&mut frames);
+	let OutputProcessor::Limiter(limiter) = &processor else {
+		panic!("expected a limiter");
+	};
+	assert_eq!(limiter.delay_line.capacity(), delay_line_capacity);
+	assert_eq!(limiter.required_gains.capacity(), required_gains_capacity);
+	assert_eq!(
+		limiter.smoothing_buffer.capacity(),
+		smoothing_buffer_capacity
+	);
+}
+
+#[test]
+fn gain_reduction_in_decibels() {
+	assert_eq!(gain_reduction(1.0), Decibels::IDENTITY);
+	assert!((gain_reduction(0.5).0 - 6.0206).abs() < 0.001);
+	assert!((gain_reduction(0.1).0 - 20.0).abs() < 0.001);
+}
@@ -1,24 +1,61 @@
 use std::sync::{
 	Arc,
-	atomic::{AtomicU32, Ordering},
+	atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
 };
 
-use crate::Frame;
+use crate::{Decibels, Frame};
 
-use super::resources::Resources;
+use super::{
+	ChannelLayout, OutputStage, StereoUpmix,
+	channel_layout::{ChannelFrame, write_frame},
+	output_stage::{OutputProcessor, gain_reduction},
+	resources::Resources,
+};
 
 #[derive(Debug)]
 pub(crate) struct RendererShared {
 	pub(crate) sample_rate: AtomicU32,
+	/// The layout the mixer mixes audio in.
+	pub(crate) channel_layout: ChannelLayout,
+	output_gain_reduction: AtomicU32,
+	profiling_enabled: AtomicBool,
+	/// How much audio (in seconds) has been produced before the
+	/// batch that's currently being processed.
+	time: AtomicU64,
 }
 
 impl RendererShared {
 	#[must_use]
-	pub fn new(sample_rate: u32) -> Self {
+	pub fn new(sample_rate: u32, channel_layout: ChannelLayout) -> Self {
 		Self {
 			sample_rate: AtomicU32::new(sample_rate),
+			channel_layout,
+			output_gain_reduction: AtomicU32::new(Decibels::IDENTITY.0.to_bits()),
+			profiling_enabled: AtomicBool::new(false),
+			time: AtomicU64::new(0.0f64.to_bits()),
 		}
 	}
+
+	#[must_use]
+	pub fn profiling_enabled(&self) -> bool {
+		self.profiling_enabled.load(Ordering::Relaxed)
+	}
+
+	pub fn set_profiling_enabled(&self, enabled: bool) {
+		self.profiling_enabled.store(enabled, Ordering::Relaxed);
+	}
+
+	#[must_use]
+	pub fn time(&self) -> f64 {
+		f64::from_bits(self.time.load(Ordering::Relaxed))
+	}
+
+	#[must_use]
+	pub fn output_gain_reduction(&self) -> Decibels {
+		Decibels(f32::from_bits(
+			self.output_gain_reduction.load(Ordering::SeqCst),
+		))
+	}
 }
 
 /// Produces [`Frame`]s of audio data to be consumed by a
@@ -28,10 +65,14 @@ impl RendererShared {
 /// directly unless you're writing a [`Backend`](super::Backend).
 pub struct Renderer {
 	dt: f64,
+	time: f64,
 	shared: Arc<RendererShared>,
 	resources: Resources,
 	internal_buffer_size: usize,
+	stereo_upmix: StereoUpmix,
+	output_processor: OutputProcessor<ChannelFrame>,
 	temp_buffer: Vec<Frame>,
+	bus_buffer: Vec<ChannelFrame>,
 }
 
 impl Renderer {
@@ -39,14 +80,21 @@ impl Renderer {
 	pub(crate) fn new(
 		shared: Arc<RendererShared>,
 		internal_buffer_size: usize,
+		stereo_upmix: StereoUpmix,
+		output_stage: OutputStage,
 		resources: Resources,
 	) -> Self {
+		let sample_rate = shared.sample_rate.load(Ordering::SeqCst);
 		Self {
-			dt: 1.0 / shared.sample_rate.load(Ordering::SeqCst) as f64,
+			dt: 1.0 / sample_rate as f64,
+			time: 0.0,
 			shared,
 			resources,
 			internal_buffer_size,
+			stereo_upmix,
+			output_processor: OutputProcessor::new(output_stage, sample_rate),
 			temp_buffer: vec![Frame::ZERO; internal_buffer_size],
+			bus_buffer: vec![ChannelFrame::ZERO; internal_buffer_size],
 		}
 	}
 
@@ -56,6 +104,7 @@ impl Renderer {
 		self.dt = 1.0 / sample_rate as f64;
 		self.shared.sample_rate.store(sample_rate, Ordering::SeqCst);
 		self.resources.mixer.on_change_sample_rate(sample_rate);
+		self.output_processor.on_change_sample_rate(sample_rate);
 	}
 
 	/// Called by the backend when it's time to process
@@ -68,6 +117,13 @@ impl Renderer {
 	}
 
 	/// Produces the next [`Frame`]s of audio.
+	///
+	/// `out` is filled with interleaved samples for `num_channels`
+	/// channels. If the number of channels matches a standard
+	/// [`ChannelLayout`], the mix is converted to that layout's speakers.
+	/// Otherwise, the mix is written to the first channels, and the rest
+	/// receive silence. If there aren't enough channels for every speaker
+	/// of the mix, the mix is downmixed to stereo first.
 	pub fn process(&mut self, out: &mut [f32], num_channels: u16) {
 		for chunk in out.chunks_mut(self.internal_buffer_size * num_channels as usize) {
 			self.process_chunk(chunk, num_channels);
@@ -76,51 +132,76 @@ impl Renderer {
 
 	fn process_chunk(&mut self, chunk: &mut [f32], num_channels: u16) {
 		let num_frames = chunk.len() / num_channels as usize;
+		self.shared
+			.time
+			.store(self.time.to_bits(), Ordering::Relaxed);
 
-		self.resources.modulators.process(
+		/*
+			clocks are updated first so start times in modulators see the
+			clock state at the end of this batch, just like sounds do. clocks
+			whose speed is controlled by a modulator use the modulator's
+			value from the previous batch.
+		*/
+		self.resources.clocks.update(
 			self.dt * num_frames as f64,
-			&self.resources.clocks,
+			&self.resources.modulators,
 			&self.resources.listeners,
+			self.time,
 		);
-		self.resources.clocks.update(
+		self.resources.modulators.process(
 			self.dt * num_frames as f64,
-			&self.resources.modulators,
+			&self.resources.clocks,
 			&self.resources.listeners,
+			self.time,
 		);
 		self.resources.listeners.update(
 			self.dt * num_frames as f64,
 			&self.resources.clocks,
 			&self.resources.modulators,
+			self.time,
 		);
 
 		self.resources.mixer.process(
 			&mut self.temp_buffer[..num_frames],
+			&mut self.bus_buffer[..num_frames],
 			self.dt,
 			&self.resources.clocks,
 			&self.resources.modulators,
 			&self.resources.listeners,
+			self.time,
 		);
 
+		// add the stereo mix to the surround bus
+		let channel_layout = self.shared.channel_layout;
+		for (bus_frame, frame) in self.bus_buffer[..num_frames]
+			.iter_mut()
+			.zip(&self.temp_buffer)
+		{
+			*bus_frame += if channel_layout.is_surround() {
+				channel_layout.upmix_stereo(*frame, self.stereo_upmix)
+			} else {
+				ChannelFrame::from_stereo(*frame)
+			};
+		}
+
+		let min_gain = self
+			.output_processor
+			.process(&mut self.bus_buffer[..num_frames]);
+		self.shared
+			.output_gain_reduction
+			.store(gain_reduction(min_gain).0.to_bits(), Ordering::SeqCst);
+
 		// convert from frames to requested number of channels
 		for (i, channels) in chunk.chunks_mut(num_channels.into()).enumerate() {
-			let mut frame = self.temp_buffer[i];
-			frame.left = frame.left.clamp(-1.0, 1.0);
-			frame.right = frame.right.clamp(-1.0, 1.0);
-			if num_channels == 1 {
-				channels[0] = (frame.left + frame.right) / 2.0;
-			} else {
-				channels[0] = frame.left;
-				channels[1] = frame.right;
-				/*
-					if there's more channels, send silence to them. if we don't,
-					we might get bad sounds outputted to those channels.
-					(https://github.com/tesselode/kira/issues/50)
-				*/
-				for channel in channels.iter_mut().skip(2) {
-					*channel = 0.0;
-				}
-			}
+			write_frame(
+				self.bus_buffer[i],
+				channel_layout,
+				channels,
+				self.stereo_upmix,
+			);
 		}
 		self.temp_buffer.fill(Frame::ZERO);
+		self.bus_buffer.fill(ChannelFrame::ZERO);
+		self.time += self.dt * num_frames as f64;
 	}
 }
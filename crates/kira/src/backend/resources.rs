@@ -8,7 +8,7 @@ mod test;
 
 use std::{
 	fmt::{Debug, Formatter},
-	sync::Mutex,
+	sync::{Arc, Mutex},
 };
 
 use crate::{
@@ -24,6 +24,8 @@ use crate::{clock::Clock, manager::Capacities, modulator::Modulator};
 
 use self::{clocks::Clocks, mixer::Mixer, modulators::Modulators};
 
+use super::RendererShared;
+
 pub(crate) struct ResourceStorage<T> {
 	pub(crate) resources: Arena<T>,
 	new_resource_consumer: Consumer<(Key, T)>,
@@ -83,6 +85,11 @@ impl<T> ResourceStorage<T> {
 	pub fn is_empty(&self) -> bool {
 		self.resources.is_empty()
 	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.resources.len()
+	}
 }
 
 impl<'a, T> IntoIterator for &'a mut ResourceStorage<T> {
@@ -277,14 +284,17 @@ pub(crate) struct ResourceControllers {
 pub(crate) fn create_resources(
 	capacities: Capacities,
 	main_track_builder: MainTrackBuilder,
-	sample_rate: u32,
+	renderer_shared: Arc<RendererShared>,
 	internal_buffer_size: usize,
+	num_mixer_threads: usize,
 ) -> (Resources, ResourceControllers) {
 	let (mixer, sub_track_controller, send_track_controller, main_track_handle) = Mixer::new(
 		capacities.sub_track_capacity,
 		capacities.send_track_capacity,
-		sample_rate,
+		capacities.modulator_capacity,
+		renderer_shared,
 		internal_buffer_size,
+		num_mixer_threads,
 		main_track_builder,
 	);
 	let (clocks, clock_controller) = Clocks::new(capacities.clock_capacity);
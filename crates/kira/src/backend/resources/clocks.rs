@@ -22,7 +22,13 @@ impl Clocks {
 		}
 	}
 
-	pub(crate) fn update(&mut self, dt: f64, modulators: &Modulators, listeners: &Listeners) {
+	pub(crate) fn update(
+		&mut self,
+		dt: f64,
+		modulators: &Modulators,
+		listeners: &Listeners,
+		time: f64,
+	) {
 		self.0.for_each(|clock, others| {
 			clock.update(
 				dt,
@@ -31,6 +37,7 @@ impl Clocks {
 					&modulators.0.resources,
 					&listeners.0.resources,
 					None,
+					time,
 				),
 			);
 		});
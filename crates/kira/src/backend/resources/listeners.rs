@@ -21,11 +21,17 @@ impl Listeners {
 		}
 	}
 
-	pub(crate) fn update(&mut self, dt: f64, clocks: &Clocks, modulators: &Modulators) {
+	pub(crate) fn update(&mut self, dt: f64, clocks: &Clocks, modulators: &Modulators, time: f64) {
 		self.0.for_each(|listener, others| {
 			listener.update(
 				dt,
-				&Info::new(&clocks.0.resources, &modulators.0.resources, others, None),
+				&Info::new(
+					&clocks.0.resources,
+					&modulators.0.resources,
+					others,
+					None,
+					time,
+				),
 			);
 		});
 	}
@@ -1,4 +1,10 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod parallel;
+
+use std::sync::{Arc, atomic::Ordering};
+
 use crate::{
+	backend::{ChannelFrame, RendererShared},
 	frame::Frame,
 	info::Info,
 	track::{MainTrack, MainTrackBuilder, MainTrackHandle, SendTrack, Track},
@@ -14,15 +20,21 @@ pub(crate) struct Mixer {
 	sub_tracks: ResourceStorage<Track>,
 	send_tracks: ResourceStorage<SendTrack>,
 	temp_buffer: Vec<Frame>,
+	temp_bus_buffer: Vec<ChannelFrame>,
+	#[cfg(not(target_arch = "wasm32"))]
+	parallel_mixer: Option<parallel::ParallelMixer>,
 }
 
 impl Mixer {
 	#[must_use]
+	#[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
 	pub fn new(
 		sub_track_capacity: usize,
 		send_track_capacity: usize,
-		sample_rate: u32,
+		modulator_capacity: usize,
+		renderer_shared: Arc<RendererShared>,
 		internal_buffer_size: usize,
+		num_mixer_threads: usize,
 		main_track_builder: MainTrackBuilder,
 	) -> (
 		Self,
@@ -30,7 +42,9 @@ impl Mixer {
 		ResourceController<SendTrack>,
 		MainTrackHandle,
 	) {
-		let (mut main_track, main_track_handle) = main_track_builder.build(internal_buffer_size);
+		let sample_rate = renderer_shared.sample_rate.load(Ordering::SeqCst);
+		let (mut main_track, main_track_handle) =
+			main_track_builder.build(renderer_shared, internal_buffer_size);
 		main_track.init_effects(sample_rate);
 		let (sub_tracks, sub_track_controller) = ResourceStorage::new(sub_track_capacity);
 		let (send_tracks, send_track_controller) = ResourceStorage::new(send_track_capacity);
@@ -40,6 +54,17 @@ impl Mixer {
 				sub_tracks,
 				send_tracks,
 				temp_buffer: vec![Frame::ZERO; internal_buffer_size],
+				temp_bus_buffer: vec![ChannelFrame::ZERO; internal_buffer_size],
+				#[cfg(not(target_arch = "wasm32"))]
+				parallel_mixer: (num_mixer_threads > 0).then(|| {
+					parallel::ParallelMixer::new(
+						num_mixer_threads,
+						sub_track_capacity,
+						send_track_capacity,
+						modulator_capacity,
+						internal_buffer_size,
+					)
+				}),
 			},
 			sub_track_controller,
 			send_track_controller,
@@ -71,34 +96,51 @@ impl Mixer {
 		self.main_track.on_start_processing();
 	}
 
+	/// Mixes the output of every track into `out`. Audio that's
+	/// already been panned across the speakers of a surround layout
+	/// is mixed into `bus` instead.
+	#[allow(clippy::too_many_arguments)]
 	pub fn process(
 		&mut self,
 		out: &mut [Frame],
+		bus: &mut [ChannelFrame],
 		dt: f64,
 		clocks: &Clocks,
 		modulators: &Modulators,
 		listeners: &Listeners,
+		time: f64,
 	) {
-		for (_, track) in &mut self.sub_tracks {
-			track.process(
-				&mut self.temp_buffer[..out.len()],
-				dt,
-				clocks,
-				modulators,
-				listeners,
-				None,
-				&mut self.send_tracks,
-			);
-			for (summed_out, sound_out) in out.iter_mut().zip(self.temp_buffer.iter().copied()) {
-				*summed_out += sound_out;
+		if !self.process_sub_tracks_in_parallel(out, bus, dt, clocks, modulators, listeners) {
+			for (_, track) in &mut self.sub_tracks {
+				track.process(
+					&mut self.temp_buffer[..out.len()],
+					&mut self.temp_bus_buffer[..out.len()],
+					dt,
+					&clocks.0.resources,
+					(&modulators.0.resources).into(),
+					&listeners.0.resources,
+					None,
+					&mut self.send_tracks,
+				);
+				for (summed_out, sound_out) in out.iter_mut().zip(self.temp_buffer.iter().copied())
+				{
+					*summed_out += sound_out;
+				}
+				for (summed_bus, track_bus) in
+					bus.iter_mut().zip(self.temp_bus_buffer.iter().copied())
+				{
+					*summed_bus += track_bus;
+				}
+				self.temp_buffer.fill(Frame::ZERO);
+				self.temp_bus_buffer.fill(ChannelFrame::ZERO);
 			}
-			self.temp_buffer.fill(Frame::ZERO);
 		}
 		let info = Info::new(
 			&clocks.0.resources,
 			&modulators.0.resources,
 			&listeners.0.resources,
 			None,
+			time,
 		);
 		for (_, track) in &mut self.send_tracks {
 			track.process(&mut self.temp_buffer[..out.len()], dt, &info);
@@ -107,6 +149,51 @@ impl Mixer {
 			}
 			self.temp_buffer.fill(Frame::ZERO);
 		}
-		self.main_track.process(out, dt, &info);
+		self.main_track.process(out, bus, dt, &info);
+	}
+
+	/// Processes the sub-tracks on the worker pool if parallel mixing
+	/// is enabled and there's more than one sub-track to process.
+	/// Returns `false` if the sub-tracks should be processed serially.
+	#[cfg(not(target_arch = "wasm32"))]
+	fn process_sub_tracks_in_parallel(
+		&mut self,
+		out: &mut [Frame],
+		bus: &mut [ChannelFrame],
+		dt: f64,
+		clocks: &Clocks,
+		modulators: &Modulators,
+		listeners: &Listeners,
+	) -> bool {
+		let Some(parallel_mixer) = &mut self.parallel_mixer else {
+			return false;
+		};
+		if self.sub_tracks.len() < 2 {
+			return false;
+		}
+		parallel_mixer.process(
+			out,
+			bus,
+			dt,
+			&mut self.sub_tracks,
+			&mut self.send_tracks,
+			clocks,
+			modulators,
+			listeners,
+		);
+		true
+	}
+
+	#[cfg(target_arch = "wasm32")]
+	fn process_sub_tracks_in_parallel(
+		&mut self,
+		_out: &mut [Frame],
+		_bus: &mut [ChannelFrame],
+		_dt: f64,
+		_clocks: &Clocks,
+		_modulators: &Modulators,
+		_listeners: &Listeners,
+	) -> bool {
+		false
 	}
 }
@@ -0,0 +1,401 @@
+use std::{
+	cell::UnsafeCell,
+	hint::spin_loop,
+	ptr::null,
+	slice,
+	sync::{
+		Arc,
+		atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+	},
+	thread::{self, Thread},
+};
+
+use atomic_arena::Arena;
+
+use crate::{
+	Decibels,
+	backend::{
+		ChannelFrame,
+		resources::{
+			ResourceStorage,
+			clocks::Clocks,
+			listeners::Listeners,
+			modulators::{ModulatorSnapshot, Modulators},
+		},
+	},
+	clock::Clock,
+	frame::Frame,
+	listener::Listener,
+	track::{SendTrack, SendTrackId, SendTrackInputs, Track},
+};
+
+/// How many times an idle worker thread checks for new work
+/// before going to sleep.
+const NUM_SPINS_BEFORE_PARKING: usize = 10_000;
+/// How many groups of tracks to create per thread. Having more groups
+/// than threads lets threads that finish early take over work
+/// from slower threads.
+const GROUPS_PER_THREAD: usize = 2;
+
+/**
+Processes the mixer's sub-tracks on a pool of worker threads.
+
+Tracks are split into a fixed number of groups, and each group has its
+own output buffers and send track buffers. Whichever thread is free
+claims the next group, so the audio thread can do all of the work
+itself if the worker threads are slow to wake up. Once every group is
+finished, the audio thread sums the groups in order, so the output
+doesn't depend on which thread processed which group.
+*/
+pub(crate) struct ParallelMixer {
+	shared: Arc<PoolShared>,
+	worker_threads: Vec<Thread>,
+	track_pointers: Vec<TrackPointer>,
+	send_track_ids: Vec<SendTrackId>,
+	/// The values of the modulators for the current batch. Modulators
+	/// aren't required to be `Sync`, so the worker threads read their
+	/// values from here instead.
+	modulator_snapshot: ModulatorSnapshot,
+}
+
+impl ParallelMixer {
+	#[must_use]
+	pub fn new(
+		num_worker_threads: usize,
+		sub_track_capacity: usize,
+		send_track_capacity: usize,
+		modulator_capacity: usize,
+		internal_buffer_size: usize,
+	) -> Self {
+		let num_groups = (num_worker_threads + 1) * GROUPS_PER_THREAD;
+		let shared = Arc::new(PoolShared {
+			generation: AtomicU64::new(0),
+			next_group: AtomicUsize::new(num_groups),
+			num_finished_groups: AtomicUsize::new(num_groups),
+			shutting_down: AtomicBool::new(false),
+			job: UnsafeCell::new(Job::default()),
+			groups: (0..num_groups)
+				.map(|_| {
+					UnsafeCell::new(Group {
+						out: vec![Frame::ZERO; internal_buffer_size],
+						temp_buffer: vec![Frame::ZERO; internal_buffer_size],
+						bus: vec![ChannelFrame::ZERO; internal_buffer_size],
+						temp_bus_buffer: vec![ChannelFrame::ZERO; internal_buffer_size],
+						sends: vec![vec![Frame::ZERO; internal_buffer_size]; send_track_capacity],
+						used_sends: vec![false; send_track_capacity],
+					})
+				})
+				.collect(),
+		});
+		/*
+			if a thread can't be spawned, we just end up with fewer workers.
+			the audio thread will pick up any groups the workers don't get to.
+		*/
+		let worker_threads = (0..num_worker_threads)
+			.filter_map(|i| {
+				let shared = shared.clone();
+				thread::Builder::new()
+					.name(format!("kira mixer worker {i}"))
+					.spawn(move || run_worker(shared))
+					.ok()
+			})
+			.map(|join_handle| join_handle.thread().clone())
+			.collect();
+		Self {
+			shared,
+			worker_threads,
+			track_pointers: Vec::with_capacity(sub_track_capacity),
+			send_track_ids: Vec::with_capacity(send_track_capacity),
+			modulator_snapshot: ModulatorSnapshot::new(modulator_capacity),
+		}
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	pub fn process(
+		&mut self,
+		out: &mut [Frame],
+		bus: &mut [ChannelFrame],
+		dt: f64,
+		sub_tracks: &mut ResourceStorage<Track>,
+		send_tracks: &mut ResourceStorage<SendTrack>,
+		clocks: &Clocks,
+		modulators: &Modulators,
+		listeners: &Listeners,
+	) {
+		let num_frames = out.len();
+		self.track_pointers.extend(
+			sub_tracks
+				.iter_mut()
+				.map(|(_, track)| TrackPointer(track as *mut Track)),
+		);
+		self.send_track_ids
+			.extend(send_tracks.iter().map(|(key, _)| SendTrackId(key)));
+		self.modulator_snapshot.update(modulators);
+
+		// start the job
+		// SAFETY: every group from the previous job is finished, so
+		// no other thread is reading the job.
+		unsafe {
+			*self.shared.job.get() = Job {
+				tracks: self.track_pointers.as_ptr(),
+				num_tracks: self.track_pointers.len(),
+				send_track_ids: self.send_track_ids.as_ptr(),
+				num_send_tracks: self.send_track_ids.len(),
+				num_frames,
+				dt,
+				clocks: &clocks.0.resources,
+				modulators: &self.modulator_snapshot,
+				listeners: &listeners.0.resources,
+			};
+		}
+		self.shared.num_finished_groups.store(0, Ordering::Relaxed);
+		self.shared.next_group.store(0, Ordering::Release);
+		self.shared.generation.fetch_add(1, Ordering::Release);
+		for thread in &self.worker_threads {
+			thread.unpark();
+		}
+
+		// help out, then wait for the worker threads to finish
+		self.shared.process_groups();
+		while self.shared.num_finished_groups.load(Ordering::Acquire) < self.shared.groups.len() {
+			spin_loop();
+		}
+
+		// sum the output of each group in order
+		for group in &self.shared.groups {
+			// SAFETY: every group is finished, so no other thread
+			// is accessing it.
+			let group = unsafe { &mut *group.get() };
+			for (summed_out, group_out) in out.iter_mut().zip(group.out.iter_mut()) {
+				*summed_out += *group_out;
+				*group_out = Frame::ZERO;
+			}
+			for (summed_bus, group_bus) in bus.iter_mut().zip(group.bus.iter_mut()) {
+				*summed_bus += *group_bus;
+				*group_bus = ChannelFrame::ZERO;
+			}
+			for ((id, send), used) in self
+				.send_track_ids
+				.iter()
+				.zip(group.sends.iter_mut())
+				.zip(group.used_sends.iter_mut())
+			{
+				if !*used {
+					continue;
+				}
+				send_tracks.add_input(*id, &send[..num_frames], Decibels::IDENTITY);
+				send.fill(Frame::ZERO);
+				*used = false;
+			}
+		}
+
+		self.track_pointers.clear();
+		self.send_track_ids.clear();
+	}
+}
+
+impl Drop for ParallelMixer {
+	fn drop(&mut self) {
+		self.shared.shutting_down.store(true, Ordering::Release);
+		for thread in &self.worker_threads {
+			thread.unpark();
+		}
+	}
+}
+
+struct PoolShared {
+	/// Incremented whenever there's a new job.
+	generation: AtomicU64,
+	/// The index of the next group that hasn't been claimed by a thread.
+	next_group: AtomicUsize,
+	num_finished_groups: AtomicUsize,
+	shutting_down: AtomicBool,
+	job: UnsafeCell<Job>,
+	groups: Box<[UnsafeCell<Group>]>,
+}
+
+impl PoolShared {
+	/// Claims and processes groups until there are none left.
+	fn process_groups(&self) {
+		loop {
+			let index = self.next_group.fetch_add(1, Ordering::AcqRel);
+			if index >= self.groups.len() {
+				return;
+			}
+			// SAFETY: this thread is the only one that claimed the group,
+			// and the job won't change until every group is finished.
+			unsafe {
+				self.process_group(index);
+			}
+			self.num_finished_groups.fetch_add(1, Ordering::Release);
+		}
+	}
+
+	/// # Safety
+	///
+	/// The group must be claimed by the calling thread, and the
+	/// current job must not be finished.
+	unsafe fn process_group(&self, index: usize) {
+		// SAFETY: the job only changes when every group is finished,
+		// and the claimed group is only accessed by this thread.
+		let (job, group) = unsafe { (&*self.job.get(), &mut *self.groups[index].get()) };
+		// SAFETY: the pointers in the job stay valid until every
+		// group is finished, and each track is only in one group.
+		let (tracks, send_track_ids, clocks, modulators, listeners) = unsafe {
+			(
+				slice::from_raw_parts(job.tracks, job.num_tracks),
+				slice::from_raw_parts(job.send_track_ids, job.num_send_tracks),
+				&*job.clocks,
+				&*job.modulators,
+				&*job.listeners,
+			)
+		};
+		let mut sends = DeferredSends {
+			send_track_ids,
+			sends: &mut group.sends,
+			used_sends: &mut group.used_sends,
+		};
+		for track in tracks.iter().skip(index).step_by(self.groups.len()) {
+			// SAFETY: see above
+			let track = unsafe { &mut *track.0 };
+			track.process(
+				&mut group.temp_buffer[..job.num_frames],
+				&mut group.temp_bus_buffer[..job.num_frames],
+				job.dt,
+				clocks,
+				modulators.into(),
+				listeners,
+				None,
+				&mut sends,
+			);
+			for (summed_out, track_out) in
+				group.out.iter_mut().zip(group.temp_buffer.iter().copied())
+			{
+				*summed_out += track_out;
+			}
+			for (summed_bus, track_bus) in group
+				.bus
+				.iter_mut()
+				.zip(group.temp_bus_buffer.iter().copied())
+			{
+				*summed_bus += track_bus;
+			}
+			group.temp_buffer.fill(Frame::ZERO);
+			group.temp_bus_buffer.fill(ChannelFrame::ZERO);
+		}
+	}
+}
+
+// SAFETY: the job and groups are only accessed according to the
+// rules described in `process_group`. The tracks the job points to are
+// `Send`, and the clocks, modulator values, and listeners are `Sync`.
+unsafe impl Send for PoolShared {}
+unsafe impl Sync for PoolShared {}
+
+/// The data the worker threads need to process a batch of tracks.
+struct Job {
+	tracks: *const TrackPointer,
+	num_tracks: usize,
+	send_track_ids: *const SendTrackId,
+	num_send_tracks: usize,
+	num_frames: usize,
+	dt: f64,
+	clocks: *const Arena<Clock>,
+	modulators: *const ModulatorSnapshot,
+	listeners: *const Arena<Listener>,
+}
+
+impl Default for Job {
+	fn default() -> Self {
+		Self {
+			tracks: null(),
+			num_tracks: 0,
+			send_track_ids: null(),
+			num_send_tracks: 0,
+			num_frames: 0,
+			dt: 0.0,
+			clocks: null(),
+			modulators: null(),
+			listeners: null(),
+		}
+	}
+}
+
+struct Group {
+	out: Vec<Frame>,
+	temp_buffer: Vec<Frame>,
+	/// Audio that's been panned across the speakers of a
+	/// surround layout.
+	bus: Vec<ChannelFrame>,
+	temp_bus_buffer: Vec<ChannelFrame>,
+	/// The audio sent to each send track, in the same order
+	/// as the job's send track IDs.
+	sends: Vec<Vec<Frame>>,
+	used_sends: Vec<bool>,
+}
+
+struct TrackPointer(*mut Track);
+
+// SAFETY: track pointers are only stored between the start and end
+// of a call to `process`, while the tracks they point to are
+// mutably borrowed by the mixer, and `Track` is `Send`.
+unsafe impl Send for TrackPointer {}
+
+/// Collects the audio a group of tracks sends to send tracks
+/// so it can be added to the send tracks on the audio thread.
+struct DeferredSends<'a> {
+	send_track_ids: &'a [SendTrackId],
+	sends: &'a mut [Vec<Frame>],
+	used_sends: &'a mut [bool],
+}
+
+impl SendTrackInputs for DeferredSends<'_> {
+	fn add_input(&mut self, id: SendTrackId, input: &[Frame], volume: Decibels) {
+		let Some(index) = self
+			.send_track_ids
+			.iter()
+			.position(|send_id| *send_id == id)
+		else {
+			return;
+		};
+		let amplitude = volume.as_amplitude();
+		for (send, added) in self.sends[index].iter_mut().zip(input.iter().copied()) {
+			*send += added * amplitude;
+		}
+		self.used_sends[index] = true;
+	}
+}
+
+fn run_worker(shared: Arc<PoolShared>) {
+	let mut generation = shared.generation.load(Ordering::Acquire);
+	loop {
+		let mut num_spins = 0;
+		loop {
+			if shared.shutting_down.load(Ordering::Acquire) {
+				return;
+			}
+			let current_generation = shared.generation.load(Ordering::Acquire);
+			if current_generation != generation {
+				generation = current_generation;
+				break;
+			}
+			if num_spins < NUM_SPINS_BEFORE_PARKING {
+				num_spins += 1;
+				spin_loop();
+			} else {
+				thread::park();
+			}
+		}
+		shared.process_groups();
+	}
+}
+
+#[allow(dead_code)]
+fn assert_thread_safe() {
+	fn assert_send<T: Send>() {}
+	fn assert_sync<T: Sync>() {}
+	assert_send::<Track>();
+	assert_sync::<Arena<Clock>>();
+	assert_sync::<ModulatorSnapshot>();
+	assert_sync::<Arena<Listener>>();
+}
@@ -1,4 +1,9 @@
-use crate::{info::Info, modulator::Modulator};
+use std::collections::HashMap;
+
+use crate::{
+	info::Info,
+	modulator::{Modulator, ModulatorId},
+};
 
 use super::{
 	ResourceController, SelfReferentialResourceStorage, clocks::Clocks, listeners::Listeners,
@@ -20,16 +25,51 @@ impl Modulators {
 		}
 	}
 
-	pub fn process(&mut self, dt: f64, clocks: &Clocks, listeners: &Listeners) {
+	pub fn process(&mut self, dt: f64, clocks: &Clocks, listeners: &Listeners, time: f64) {
 		self.0.for_each(|modulator, others| {
 			modulator.update(
 				dt,
-				&Info::new(&clocks.0.resources, others, &listeners.0.resources, None),
+				&Info::new(
+					&clocks.0.resources,
+					&*others,
+					&listeners.0.resources,
+					None,
+					time,
+				),
 			);
 		});
 	}
 }
 
+/// The values of every modulator at one point in time.
+pub(crate) struct ModulatorSnapshot(HashMap<ModulatorId, f64>);
+
+impl ModulatorSnapshot {
+	/// Creates an empty snapshot with room for `capacity` modulators,
+	/// so updating it doesn't allocate.
+	#[must_use]
+	pub fn new(capacity: usize) -> Self {
+		Self(HashMap::with_capacity(capacity))
+	}
+
+	/// Replaces the snapshot with the current values of the modulators.
+	pub fn update(&mut self, modulators: &Modulators) {
+		self.0.clear();
+		self.0.extend(
+			modulators
+				.0
+				.resources
+				.iter()
+				.map(|(key, modulator)| (ModulatorId(key), modulator.value())),
+		);
+	}
+
+	#[must_use]
+	pub fn get(&self, id: ModulatorId) -> Option<f64> {
+		self.0.get(&id).copied()
+	}
+}
+
 struct DummyModulator;
 
 impl Modulator for DummyModulator {
@@ -102,6 +102,34 @@ manager.play(
 # Result::<(), Box<dyn std::error::Error>>::Ok(())
 ```
 
+## Snapping to the clock's grid
+
+Reading [`ClockHandle::time`] on the game thread and adding to it can miss
+a tick if the clock moves on before the audio thread sees the start time.
+A [`QuantizedClockTime`] names a tick relative to whatever the clock's time
+is when the audio thread first uses it, like "the next tick" or "the next
+multiple of 4 ticks", so it always lands on the grid:
+
+```no_run
+use kira::{
+	AudioManager, AudioManagerSettings, DefaultBackend,
+	sound::static_sound::StaticSoundData,
+	clock::{ClockSpeed, QuantizedClockTime},
+};
+
+# let mut manager = AudioManager::<DefaultBackend>::new(
+# 	AudioManagerSettings::default(),
+# )?;
+# let mut clock = manager.add_clock(ClockSpeed::TicksPerMinute(120.0))?;
+# clock.start();
+// play on the second beat of the next bar of 4 beats
+manager.play(
+	StaticSoundData::from_file("sound.ogg")?
+		.start_time(QuantizedClockTime::next_multiple(&clock, 4).offset(1))
+)?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+
 ## Starting tweens on clock ticks
 
 You can also use clocks to set the start time of tweens. In this example, we set
@@ -137,6 +165,7 @@ clock.start();
 
 mod clock_speed;
 mod handle;
+mod quantized_time;
 mod time;
 
 #[cfg(test)]
@@ -145,6 +174,7 @@ mod test;
 use atomic_arena::Key;
 pub use clock_speed::*;
 pub use handle::*;
+pub use quantized_time::*;
 pub use time::*;
 
 use std::sync::{
@@ -156,6 +186,7 @@ use crate::{
 	Parameter, Value,
 	command::{ValueChangeCommand, read_commands_into_parameters},
 	command_writers_and_readers,
+	event::{EventProducer, event_queue},
 	info::Info,
 };
 
@@ -163,6 +194,19 @@ use crate::{
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ClockId(pub(crate) Key);
 
+/// Something that happened to a clock on the audio thread.
+///
+/// Events can be retrieved with [`ClockHandle::pop_event`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClockEvent {
+	/// The clock's tick count changed. This is also emitted with
+	/// a tick count of `0` when the clock first starts.
+	Ticked {
+		/// The new tick count.
+		ticks: u64,
+	},
+}
+
 #[derive(Debug)]
 pub(crate) struct ClockShared {
 	ticking: AtomicBool,
@@ -222,12 +266,14 @@ pub(crate) struct Clock {
 	ticking: bool,
 	speed: Parameter<ClockSpeed>,
 	state: State,
+	event_producer: EventProducer<ClockEvent>,
 }
 
 impl Clock {
 	#[must_use]
 	pub(crate) fn new(speed: Value<ClockSpeed>, id: ClockId) -> (Self, ClockHandle) {
 		let (command_writers, command_readers) = command_writers_and_readers();
+		let (event_producer, event_consumer) = event_queue();
 		let shared = Arc::new(ClockShared::new());
 		(
 			Self {
@@ -236,11 +282,13 @@ impl Clock {
 				ticking: false,
 				speed: Parameter::new(speed, ClockSpeed::TicksPerMinute(120.0)),
 				state: State::NotStarted,
+				event_producer,
 			},
 			ClockHandle {
 				id,
 				shared,
 				command_writers,
+				event_consumer,
 			},
 		)
 	}
@@ -248,12 +296,14 @@ impl Clock {
 	#[must_use]
 	pub(crate) fn without_handle(speed: Value<ClockSpeed>) -> Self {
 		let (_, command_readers) = command_writers_and_readers();
+		let (event_producer, _) = event_queue();
 		Self {
 			command_readers,
 			shared: Arc::new(ClockShared::new()),
 			ticking: false,
 			speed: Parameter::new(speed, ClockSpeed::TicksPerMinute(120.0)),
 			state: State::NotStarted,
+			event_producer,
 		}
 	}
 
@@ -272,6 +322,11 @@ impl Clock {
 		self.ticking
 	}
 
+	#[must_use]
+	pub(crate) fn ticks_per_second(&self) -> f64 {
+		self.speed.value().as_ticks_per_second()
+	}
+
 	pub(crate) fn on_start_processing(&mut self) {
 		read_commands_into_parameters!(self, speed);
 		if let Some(ticking) = self.command_readers.set_ticking.read() {
@@ -323,17 +378,29 @@ impl Clock {
 				fractional_position: 0.0,
 			};
 			new_tick_count = Some(0);
+			self.event_producer
+				.push(ClockEvent::Ticked { ticks: 0 }, info.time());
 		}
 		if let State::Started {
 			ticks,
 			fractional_position: tick_timer,
 		} = &mut self.state
 		{
-			*tick_timer += self.speed.value().as_ticks_per_second() * dt;
+			let ticks_per_second = self.speed.value().as_ticks_per_second();
+			let start_position = *tick_timer;
+			*tick_timer += ticks_per_second * dt;
+			let mut num_ticks = 0;
 			while *tick_timer >= 1.0 {
 				*tick_timer -= 1.0;
 				*ticks += 1;
+				num_ticks += 1;
 				new_tick_count = Some(*ticks);
+				// find when in this update the clock reached the next tick
+				let time_in_update = (num_ticks as f64 - start_position) / ticks_per_second;
+				self.event_producer.push(
+					ClockEvent::Ticked { ticks: *ticks },
+					info.time() + time_in_update,
+				);
 			}
 		} else {
 			panic!("clock state should be Started by now");
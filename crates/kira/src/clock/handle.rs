@@ -1,8 +1,8 @@
 use std::sync::{Arc, atomic::Ordering};
 
-use crate::command::handle_param_setters;
+use crate::{Event, command::handle_param_setters, event::EventConsumer};
 
-use super::{ClockId, ClockShared, ClockSpeed, ClockTime, CommandWriters};
+use super::{ClockEvent, ClockId, ClockShared, ClockSpeed, ClockTime, CommandWriters};
 
 /// Controls a clock.
 ///
@@ -13,6 +13,7 @@ pub struct ClockHandle {
 	pub(crate) id: ClockId,
 	pub(crate) shared: Arc<ClockShared>,
 	pub(crate) command_writers: CommandWriters,
+	pub(crate) event_consumer: EventConsumer<ClockEvent>,
 }
 
 impl ClockHandle {
@@ -66,6 +67,14 @@ impl ClockHandle {
 			.fractional_position
 			.store(0.0f64.to_bits(), Ordering::SeqCst);
 	}
+
+	/// Returns the next event that happened to the clock, if any.
+	///
+	/// Events are returned in the order they happened.
+	#[must_use]
+	pub fn pop_event(&mut self) -> Option<Event<ClockEvent>> {
+		self.event_consumer.pop()
+	}
 }
 
 impl Drop for ClockHandle {
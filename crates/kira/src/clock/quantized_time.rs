@@ -0,0 +1,115 @@
+use crate::info::Info;
+
+use super::{ClockId, ClockTime};
+
+/**
+A time on a clock's grid of ticks that's chosen on the audio thread
+when the time is first used.
+
+Unlike a [`ClockTime`], which names an exact tick, a [`QuantizedClockTime`]
+names a tick relative to the clock's current time, like "the next tick"
+or "the next multiple of 4 ticks". Because the audio thread looks up the
+clock's time itself, the result always lines up with the grid, even if
+the clock ticks while the command is on its way to the audio thread.
+
+`QuantizedClockTime`s can be used anywhere a [`StartTime`](crate::StartTime)
+is accepted.
+
+# Examples
+
+Start a sound on the next beat of a bar of 4 beats:
+
+```no_run
+use kira::{
+	AudioManager, AudioManagerSettings, DefaultBackend,
+	clock::{ClockSpeed, QuantizedClockTime},
+	sound::static_sound::StaticSoundData,
+};
+
+let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+let mut clock = manager.add_clock(ClockSpeed::TicksPerMinute(120.0))?;
+clock.start();
+manager.play(
+	StaticSoundData::from_file("stinger.ogg")?
+		.start_time(QuantizedClockTime::next_multiple(&clock, 4)),
+)?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QuantizedClockTime {
+	/// The clock this time is associated with.
+	pub clock: ClockId,
+	/// The time lands on the next tick that's a multiple of this
+	/// number of ticks.
+	///
+	/// `0` is treated the same as `1`.
+	pub multiple: u64,
+	/// The tick the multiples are counted from.
+	///
+	/// If the clock hasn't reached this tick yet, the time lands on
+	/// this tick. This is `0` by default.
+	pub anchor: u64,
+	/// The number of ticks to wait after reaching the multiple.
+	pub offset: u64,
+}
+
+impl QuantizedClockTime {
+	/// Creates a [`QuantizedClockTime`] for the next tick of a clock.
+	#[must_use]
+	pub fn next_tick(clock: impl Into<ClockId>) -> Self {
+		Self::next_multiple(clock, 1)
+	}
+
+	/// Creates a [`QuantizedClockTime`] for the next tick of a clock
+	/// that's a multiple of the given number of ticks.
+	#[must_use]
+	pub fn next_multiple(clock: impl Into<ClockId>, multiple: u64) -> Self {
+		Self {
+			clock: clock.into(),
+			multiple,
+			anchor: 0,
+			offset: 0,
+		}
+	}
+
+	/**
+	Sets the tick the multiples are counted from.
+
+	For example, a time for the next multiple of 4 ticks anchored at
+	tick 2 lands on tick 2, 6, 10, and so on.
+	*/
+	#[must_use = "This method consumes self and returns a modified QuantizedClockTime, so the return value should be used"]
+	pub fn anchor(self, anchor: u64) -> Self {
+		Self { anchor, ..self }
+	}
+
+	/// Sets the number of ticks to wait after reaching the multiple.
+	#[must_use = "This method consumes self and returns a modified QuantizedClockTime, so the return value should be used"]
+	pub fn offset(self, offset: u64) -> Self {
+		Self { offset, ..self }
+	}
+
+	/// Returns the exact tick this time refers to at the start of a
+	/// batch of audio lasting `dt` seconds, or `None` if the clock
+	/// no longer exists.
+	#[must_use]
+	pub(crate) fn resolve(&self, dt: f64, info: &Info) -> Option<ClockTime> {
+		let ticks = info.clock_ticks_before_batch(self.clock, dt)?;
+		Some(ClockTime::from_ticks_u64(
+			self.clock,
+			self.resolve_ticks(ticks),
+		))
+	}
+
+	/// Returns the tick this time refers to if the clock's current
+	/// time is `ticks`.
+	#[must_use]
+	pub(crate) fn resolve_ticks(&self, ticks: f64) -> u64 {
+		// allow for rounding error when the batch starts right on a tick
+		const EPSILON: f64 = 1.0e-9;
+		let next_tick = (ticks - EPSILON).ceil().max(0.0) as u64;
+		let ticks_after_anchor = next_tick.saturating_sub(self.anchor);
+		self.anchor + ticks_after_anchor.next_multiple_of(self.multiple.max(1)) + self.offset
+	}
+}
@@ -4,7 +4,7 @@ use atomic_arena::Arena;
 
 use crate::{StartTime, Tween, Value, clock::ClockTime, info::MockInfoBuilder};
 
-use super::{Clock, ClockId, ClockSpeed};
+use super::{Clock, ClockEvent, ClockId, ClockSpeed, QuantizedClockTime};
 
 /// Tests that a `Clock` is stopped when it's first created.
 #[test]
@@ -41,6 +41,30 @@ fn basic_behavior() {
 	}
 }
 
+/// Tests that a `Clock` reports each tick along with the
+/// time it happened.
+#[test]
+fn reports_tick_events() {
+	let (mut clock, mut handle) = Clock::new(
+		Value::Fixed(ClockSpeed::SecondsPerTick(1.0)),
+		fake_clock_id(),
+	);
+	handle.start();
+	clock.on_start_processing();
+	clock.update(2.5, &MockInfoBuilder::new().build());
+	for (ticks, time) in [(0, 0.0), (1, 1.0), (2, 2.0)] {
+		let event = handle.pop_event().unwrap();
+		assert_eq!(event.kind, ClockEvent::Ticked { ticks });
+		assert_eq!(event.time, Duration::from_secs_f64(time));
+	}
+	assert!(handle.pop_event().is_none());
+	// the next tick happens half a second into the next update
+	clock.update(1.0, &MockInfoBuilder::new().build());
+	let event = handle.pop_event().unwrap();
+	assert_eq!(event.kind, ClockEvent::Ticked { ticks: 3 });
+	assert_eq!(event.time, Duration::from_secs_f64(0.5));
+}
+
 /// Tests that a `Clock` can be paused.
 #[test]
 fn pause() {
@@ -202,6 +226,27 @@ fn fractional_position() {
 	assert_eq!(handle.time().fraction, 0.25);
 }
 
+/// Tests that a `QuantizedClockTime` lands on the next multiple
+/// counted from its anchor.
+#[test]
+fn quantized_time_resolves_to_next_multiple() {
+	let time = QuantizedClockTime::next_multiple(fake_clock_id(), 4);
+	assert_eq!(time.resolve_ticks(0.0), 0);
+	assert_eq!(time.resolve_ticks(0.5), 4);
+	assert_eq!(time.resolve_ticks(4.0), 4);
+	assert_eq!(time.offset(1).resolve_ticks(4.5), 9);
+	// bars of 4 ticks starting at tick 2
+	let time = time.anchor(2);
+	assert_eq!(time.resolve_ticks(0.0), 2);
+	assert_eq!(time.resolve_ticks(2.5), 6);
+	assert_eq!(time.resolve_ticks(6.0), 6);
+	assert_eq!(time.resolve_ticks(6.5), 10);
+	// a multiple of 0 is treated as 1
+	let time = QuantizedClockTime::next_multiple(fake_clock_id(), 0).anchor(5);
+	assert_eq!(time.resolve_ticks(3.0), 5);
+	assert_eq!(time.resolve_ticks(6.5), 7);
+}
+
 fn fake_clock_id() -> ClockId {
 	let mut arena = Arena::new(1);
 	let key = arena.insert(()).unwrap();
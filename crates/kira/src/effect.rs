@@ -2,8 +2,10 @@
 Modifies audio signals.
 
 Any type that implements [`EffectBuilder`] can be added to a mixer track by
-using [`TrackBuilder::add_effect`](crate::track::TrackBuilder::add_effect). Kira
-comes with a number of commonly used effects.
+using [`TrackBuilder::add_effect`](crate::track::TrackBuilder::add_effect).
+Effects can also be applied to a single instance of a sound by adding them to
+the sound's [`EffectChain`](crate::sound::EffectChain). Kira comes with a number
+of commonly used effects.
 
 If needed, you can create custom effects by implementing the [`EffectBuilder`]
 and [`Effect`] traits.
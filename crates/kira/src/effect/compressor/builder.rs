@@ -8,6 +8,7 @@ use crate::{
 use super::{Compressor, CompressorHandle, command_writers_and_readers};
 
 /// Configures a compressor.
+#[derive(Clone)]
 pub struct CompressorBuilder {
 	/// The volume above which volume will start to be decreased (in decibels).
 	pub threshold: Value<f64>,
@@ -31,14 +31,18 @@ struct Delay {
 impl Delay {
 	/// Creates a new delay effect.
 	#[must_use]
-	fn new(builder: DelayBuilder, command_readers: CommandReaders) -> Self {
+	fn new(
+		builder: DelayBuilder,
+		feedback_effects: Vec<Box<dyn Effect>>,
+		command_readers: CommandReaders,
+	) -> Self {
 		Self {
 			command_readers,
 			delay_time: builder.delay_time,
 			feedback: Parameter::new(builder.feedback, Decibels(-6.0)),
 			mix: Parameter::new(builder.mix, Mix(0.5)),
 			buffer: Vec::with_capacity(0),
-			feedback_effects: builder.feedback_effects,
+			feedback_effects,
 			temp_buffer: vec![],
 		}
 	}
@@ -3,18 +3,20 @@ use std::time::Duration;
 use crate::{
 	Decibels, Mix, Value,
 	effect::{Effect, EffectBuilder},
+	sound::EffectChain,
 };
 
 use super::{Delay, DelayHandle, command_writers_and_readers};
 
 /// Configures a delay effect.
+#[derive(Clone)]
 pub struct DelayBuilder {
 	/// The amount of time the input audio is delayed by.
 	pub(super) delay_time: Duration,
 	/// The amount of feedback.
 	pub(super) feedback: Value<Decibels>,
 	/// Effects that should be applied in the feedback loop.
-	pub(super) feedback_effects: Vec<Box<dyn Effect>>,
+	pub(super) feedback_effects: EffectChain,
 	/// How much dry (unprocessed) signal should be blended
 	/// with the wet (processed) signal.
 	pub(super) mix: Value<Mix>,
@@ -43,18 +45,20 @@ impl DelayBuilder {
 	}
 
 	/// Adds an effect to the feedback loop.
-	pub fn add_feedback_effect<B: EffectBuilder>(&mut self, builder: B) -> B::Handle {
-		let (effect, handle) = builder.build();
-		self.feedback_effects.push(effect);
-		handle
-	}
-
-	/// Adds an effect to the feedback loop and returns the [`DelayBuilder`].
 	///
-	/// If you need a handle to the newly added effect, use [`DelayBuilder::add_feedback_effect`].
-	pub fn with_feedback_effect<B: EffectBuilder>(mut self, builder: B) -> Self {
-		self.add_feedback_effect(builder);
-		self
+	/// The handle of the effect can be retrieved from the [`DelayHandle`]
+	/// using [`DelayHandle::feedback_effect_mut`] and the index the effect
+	/// was added at.
+	#[must_use = "This method consumes self and returns a modified DelayBuilder, so the return value should be used"]
+	pub fn with_feedback_effect<B>(self, builder: B) -> Self
+	where
+		B: EffectBuilder + Clone + Send + Sync + 'static,
+		B::Handle: Send + Sync + 'static,
+	{
+		Self {
+			feedback_effects: self.feedback_effects.with_effect(builder),
+			..self
+		}
 	}
 
 	/// Sets how much dry (unprocessed) signal should be blended
@@ -75,7 +79,7 @@ impl Default for DelayBuilder {
 		Self {
 			delay_time: Duration::from_millis(500),
 			feedback: Value::Fixed(Decibels(-6.0)),
-			feedback_effects: vec![],
+			feedback_effects: EffectChain::new(),
 			mix: Value::Fixed(Mix(0.5)),
 		}
 	}
@@ -86,9 +90,13 @@ impl EffectBuilder for DelayBuilder {
 
 	fn build(self) -> (Box<dyn Effect>, Self::Handle) {
 		let (command_writers, command_readers) = command_writers_and_readers();
+		let (feedback_effects, feedback_effect_handles) = self.feedback_effects.build();
 		(
-			Box::new(Delay::new(self, command_readers)),
-			DelayHandle { command_writers },
+			Box::new(Delay::new(self, feedback_effects, command_readers)),
+			DelayHandle {
+				command_writers,
+				feedback_effect_handles,
+			},
 		)
 	}
 }
@@ -1,4 +1,4 @@
-use crate::{Decibels, Mix, command::handle_param_setters};
+use crate::{Decibels, Mix, command::handle_param_setters, sound::EffectHandles};
 
 use super::CommandWriters;
 
@@ -6,9 +6,30 @@ use super::CommandWriters;
 #[derive(Debug)]
 pub struct DelayHandle {
 	pub(super) command_writers: CommandWriters,
+	pub(super) feedback_effect_handles: EffectHandles,
 }
 
 impl DelayHandle {
+	/// Returns the handle of the effect at the given index in the
+	/// delay's feedback loop.
+	///
+	/// Returns `None` if there's no effect at that index or its
+	/// handle isn't an `H`.
+	#[must_use]
+	pub fn feedback_effect<H: 'static>(&self, index: usize) -> Option<&H> {
+		self.feedback_effect_handles.get(index)
+	}
+
+	/// Returns a mutable reference to the handle of the effect at the
+	/// given index in the delay's feedback loop.
+	///
+	/// Returns `None` if there's no effect at that index or its
+	/// handle isn't an `H`.
+	#[must_use]
+	pub fn feedback_effect_mut<H: 'static>(&mut self, index: usize) -> Option<&mut H> {
+		self.feedback_effect_handles.get_mut(index)
+	}
+
 	handle_param_setters! {
 		/// Sets the amount of feedback.
 		feedback: Decibels,
@@ -3,6 +3,7 @@ use crate::{Decibels, Value, effect::EffectBuilder};
 use super::{EqFilter, EqFilterHandle, EqFilterKind, command_writers_and_readers};
 
 /// Configures an EQ filter.
+#[derive(Clone)]
 pub struct EqFilterBuilder {
 	/// The shape of the frequency adjustment curve.
 	pub kind: EqFilterKind,
@@ -0,0 +1,72 @@
+use std::{
+	fmt::{Debug, Formatter},
+	sync::Mutex,
+	time::Duration,
+};
+
+use rtrb::{Consumer, Producer, RingBuffer};
+
+/// The maximum number of events that can be waiting to be
+/// popped from a handle. Newer events are discarded when
+/// the queue is full.
+const EVENT_QUEUE_CAPACITY: usize = 64;
+
+/// Something that happened on the audio thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Event<T> {
+	/// What happened.
+	pub kind: T,
+	/// How much audio the renderer had produced when the
+	/// event happened.
+	///
+	/// Since this is measured in audio time, it can be used to compare
+	/// when events happened, even if they were popped at different
+	/// times on the gameplay thread.
+	pub time: Duration,
+}
+
+pub(crate) struct EventProducer<T>(Producer<Event<T>>);
+
+impl<T> EventProducer<T> {
+	/// Adds an event that happened `time` seconds into the audio
+	/// stream to the queue. If the queue is full, the event is
+	/// discarded.
+	pub fn push(&mut self, kind: T, time: f64) {
+		self.0
+			.push(Event {
+				kind,
+				time: Duration::from_secs_f64(time.max(0.0)),
+			})
+			.ok();
+	}
+}
+
+// SAFETY: the producer can only be used through a mutable reference,
+// so sharing an immutable reference between threads doesn't allow
+// any access to the underlying ringbuffer.
+unsafe impl<T: Send> Sync for EventProducer<T> {}
+
+pub(crate) struct EventConsumer<T>(Mutex<Consumer<Event<T>>>);
+
+impl<T> EventConsumer<T> {
+	#[must_use]
+	pub fn pop(&self) -> Option<Event<T>> {
+		self.0
+			.lock()
+			.expect("event consumer mutex poisoned")
+			.pop()
+			.ok()
+	}
+}
+
+impl<T> Debug for EventConsumer<T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("EventConsumer").finish()
+	}
+}
+
+#[must_use]
+pub(crate) fn event_queue<T>() -> (EventProducer<T>, EventConsumer<T>) {
+	let (producer, consumer) = RingBuffer::new(EVENT_QUEUE_CAPACITY);
+	(EventProducer(producer), EventConsumer(Mutex::new(consumer)))
+}
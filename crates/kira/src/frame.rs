@@ -147,6 +147,15 @@ pub fn interpolate_frame(
 	next_2: Frame,
 	fraction: f32,
 ) -> Frame {
+	interpolate(previous, current, next_1, next_2, fraction)
+}
+
+/// Like [`interpolate_frame`], but for any type of frame.
+#[must_use]
+pub(crate) fn interpolate<T>(previous: T, current: T, next_1: T, next_2: T, fraction: f32) -> T
+where
+	T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>,
+{
 	let c0 = current;
 	let c1 = (next_1 - previous) * 0.5;
 	let c2 = previous - current * 2.5 + next_1 * 2.0 - next_2 * 0.5;
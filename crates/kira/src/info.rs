@@ -9,6 +9,7 @@ use atomic_arena::Arena;
 use glam::{Quat, Vec3};
 
 use crate::{
+	backend::resources::modulators::ModulatorSnapshot,
 	clock::{Clock, ClockId, ClockTime, State as ClockState},
 	listener::{Listener, ListenerId},
 	modulator::{Modulator, ModulatorId},
@@ -19,48 +20,94 @@ use crate::{
 /// You'll only need this if you're implementing one of Kira's traits,
 /// like [`Sound`](crate::sound::Sound) or [`Effect`](crate::effect::Effect).
 pub struct Info<'a> {
-	kind: InfoKind<'a>,
+	kind: InfoSource<'a>,
 	spatial_track_info: Option<SpatialTrackInfo>,
+	time: f64,
+	/// How much time (in seconds) passes between the end of the audio
+	/// being processed and the end of the batch the clocks were
+	/// updated for.
+	time_until_batch_end: f64,
 }
 
 impl<'a> Info<'a> {
 	pub(crate) fn new(
 		clocks: &'a Arena<Clock>,
-		modulators: &'a Arena<Box<dyn Modulator>>,
+		modulators: impl Into<ModulatorValues<'a>>,
 		listeners: &'a Arena<Listener>,
 		spatial_track_info: Option<SpatialTrackInfo>,
+		time: f64,
 	) -> Self {
 		Self {
-			kind: InfoKind::Real {
+			kind: InfoSource::Owned(InfoKind::Real {
 				clocks,
-				modulators,
+				modulators: modulators.into(),
 				listeners,
-			},
+			}),
 			spatial_track_info,
+			time,
+			time_until_batch_end: 0.0,
+		}
+	}
+
+	/// Returns info for processing part of the current batch of audio
+	/// that starts `offset` seconds into the batch and ends
+	/// `time_until_end` seconds before the end of the batch.
+	#[must_use]
+	pub(crate) fn for_sub_chunk(&self, offset: f64, time_until_end: f64) -> Info<'_> {
+		Info {
+			kind: InfoSource::Borrowed(self.kind()),
+			spatial_track_info: self.spatial_track_info,
+			time: self.time + offset,
+			time_until_batch_end: self.time_until_batch_end + time_until_end,
 		}
 	}
 
+	#[must_use]
+	fn kind(&self) -> &InfoKind<'a> {
+		match &self.kind {
+			InfoSource::Owned(kind) => kind,
+			InfoSource::Borrowed(kind) => kind,
+		}
+	}
+
+	/// Returns how much audio (in seconds) the renderer had produced
+	/// before the current batch of audio (or the current part of it).
+	#[must_use]
+	pub(crate) fn time(&self) -> f64 {
+		self.time
+	}
+
 	/// Gets information about the clock with the given ID if it
 	/// exists, returns `None` otherwise.
 	#[must_use]
 	pub fn clock_info(&self, id: ClockId) -> Option<ClockInfo> {
-		match &self.kind {
-			InfoKind::Real { clocks, .. } => clocks.get(id.0).map(|clock| ClockInfo {
-				ticking: clock.ticking(),
-				time: ClockTime {
-					clock: id,
-					ticks: match clock.state() {
-						ClockState::NotStarted => 0,
-						ClockState::Started { ticks, .. } => ticks,
-					},
-					fraction: match clock.state() {
-						ClockState::NotStarted => 0.0,
-						ClockState::Started {
-							fractional_position,
-							..
-						} => fractional_position,
+		match self.kind() {
+			InfoKind::Real { clocks, .. } => clocks.get(id.0).map(|clock| {
+				let (ticks, fraction) = match clock.state() {
+					ClockState::NotStarted => (0, 0.0),
+					ClockState::Started {
+						ticks,
+						fractional_position,
+					} if clock.ticking() => {
+						// rewind the clock to the end of the audio being processed
+						let ticks = (ticks as f64 + fractional_position
+							- clock.ticks_per_second() * self.time_until_batch_end)
+							.max(0.0);
+						(ticks as u64, ticks.fract())
+					}
+					ClockState::Started {
+						ticks,
+						fractional_position,
+					} => (ticks, fractional_position),
+				};
+				ClockInfo {
+					ticking: clock.ticking(),
+					time: ClockTime {
+						clock: id,
+						ticks,
+						fraction,
 					},
-				},
+				}
 			}),
 			InfoKind::Mock { clock_info, .. } => clock_info.get(id.0).copied(),
 		}
@@ -81,14 +128,64 @@ impl<'a> Info<'a> {
 		}
 	}
 
+	/// Returns how many seconds into a batch of audio lasting `dt`
+	/// seconds the clock reached the given time.
+	///
+	/// Clocks are updated before tracks and sounds are processed, so
+	/// the clock's current time is its time at the end of the batch
+	/// (or the end of the part of the batch being processed).
+	#[must_use]
+	pub(crate) fn clock_time_delay(&self, time: ClockTime, dt: f64) -> f64 {
+		let InfoKind::Real { clocks, .. } = self.kind() else {
+			return 0.0;
+		};
+		let Some(clock) = clocks.get(time.clock.0) else {
+			return 0.0;
+		};
+		let ClockState::Started {
+			ticks,
+			fractional_position,
+		} = clock.state()
+		else {
+			return 0.0;
+		};
+		let ticks_per_second = clock.ticks_per_second();
+		if ticks_per_second <= 0.0 {
+			return 0.0;
+		}
+		let ticks_since_time =
+			(ticks as f64 - time.ticks as f64) + (fractional_position - time.fraction);
+		let ticks_since_time = if clock.ticking() {
+			ticks_since_time - ticks_per_second * self.time_until_batch_end
+		} else {
+			ticks_since_time
+		};
+		(dt - ticks_since_time / ticks_per_second).clamp(0.0, dt)
+	}
+
+	/// Returns the time of the given clock in ticks at the start of
+	/// a batch of audio lasting `dt` seconds, or `None` if the clock
+	/// doesn't exist.
+	#[must_use]
+	pub(crate) fn clock_ticks_before_batch(&self, id: ClockId, dt: f64) -> Option<f64> {
+		let clock_info = self.clock_info(id)?;
+		let ticks = clock_info.time.ticks as f64 + clock_info.time.fraction;
+		// mock clocks don't have a speed, so their time is used as-is
+		let ticks_during_batch = match self.kind() {
+			InfoKind::Real { clocks, .. } if clock_info.ticking => clocks
+				.get(id.0)
+				.map_or(0.0, |clock| clock.ticks_per_second() * dt),
+			_ => 0.0,
+		};
+		Some((ticks - ticks_during_batch).max(0.0))
+	}
+
 	/// Gets the value of the modulator with the given ID if it
 	/// exists, returns `None` otherwise.
 	#[must_use]
 	pub fn modulator_value(&self, id: ModulatorId) -> Option<f64> {
-		match &self.kind {
-			InfoKind::Real { modulators, .. } => {
-				modulators.get(id.0).map(|modulator| modulator.value())
-			}
+		match self.kind() {
+			InfoKind::Real { modulators, .. } => modulators.get(id),
 			InfoKind::Mock {
 				modulator_values, ..
 			} => modulator_values.get(id.0).copied(),
@@ -101,7 +198,7 @@ impl<'a> Info<'a> {
 	pub fn listener_info(&self) -> Option<ListenerInfo> {
 		self.spatial_track_info.and_then(|spatial_track_info| {
 			let listener_id = spatial_track_info.listener_id;
-			match &self.kind {
+			match self.kind() {
 				InfoKind::Real { listeners, .. } => {
 					listeners.get(listener_id.0).map(|listener| ListenerInfo {
 						position: listener.position.value().into(),
@@ -258,12 +355,14 @@ impl MockInfoBuilder {
 	/// Consumes the `MockInfoProvider` and returns a fake `Info`.
 	pub fn build(self) -> Info<'static> {
 		Info {
-			kind: InfoKind::Mock {
+			kind: InfoSource::Owned(InfoKind::Mock {
 				clock_info: self.clock_info,
 				modulator_values: self.modulator_values,
 				listener_info: self.listener_info,
-			},
+			}),
 			spatial_track_info: self.spatial_track_info,
+			time: 0.0,
+			time_until_batch_end: 0.0,
 		}
 	}
 }
@@ -274,10 +373,17 @@ impl Default for MockInfoBuilder {
 	}
 }
 
+enum InfoSource<'a> {
+	Owned(InfoKind<'a>),
+	/// The resources of the [`Info`] for the whole batch, used when
+	/// processing part of the batch.
+	Borrowed(&'a InfoKind<'a>),
+}
+
 enum InfoKind<'a> {
 	Real {
 		clocks: &'a Arena<Clock>,
-		modulators: &'a Arena<Box<dyn Modulator>>,
+		modulators: ModulatorValues<'a>,
 		listeners: &'a Arena<Listener>,
 	},
 	Mock {
@@ -287,6 +393,38 @@ enum InfoKind<'a> {
 	},
 }
 
+/// Where [`Info`] reads the values of modulators from.
+#[derive(Clone, Copy)]
+pub(crate) enum ModulatorValues<'a> {
+	Modulators(&'a Arena<Box<dyn Modulator>>),
+	/// The values of the modulators at the start of the batch. This
+	/// is used on threads other than the audio thread, since modulators
+	/// aren't required to be [`Sync`].
+	Snapshot(&'a ModulatorSnapshot),
+}
+
+impl ModulatorValues<'_> {
+	#[must_use]
+	fn get(self, id: ModulatorId) -> Option<f64> {
+		match self {
+			Self::Modulators(modulators) => modulators.get(id.0).map(|modulator| modulator.value()),
+			Self::Snapshot(snapshot) => snapshot.get(id),
+		}
+	}
+}
+
+impl<'a> From<&'a Arena<Box<dyn Modulator>>> for ModulatorValues<'a> {
+	fn from(modulators: &'a Arena<Box<dyn Modulator>>) -> Self {
+		Self::Modulators(modulators)
+	}
+}
+
+impl<'a> From<&'a ModulatorSnapshot> for ModulatorValues<'a> {
+	fn from(snapshot: &'a ModulatorSnapshot) -> Self {
+		Self::Snapshot(snapshot)
+	}
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) struct SpatialTrackInfo {
 	pub position: Vec3,
@@ -224,16 +224,19 @@ pub mod command;
 mod decibels;
 pub mod effect;
 mod error;
+mod event;
 mod frame;
 pub mod info;
 pub mod listener;
 mod manager;
 mod mix;
 pub mod modulator;
+pub mod music;
 mod panning;
 mod parameter;
 mod playback_rate;
 mod playback_state_manager;
+mod random;
 mod semitones;
 pub mod sound;
 mod start_time;
@@ -246,6 +249,7 @@ mod value;
 pub use backend::DefaultBackend;
 pub use decibels::*;
 pub use error::*;
+pub use event::Event;
 pub use frame::*;
 pub use manager::*;
 pub use mix::*;
@@ -12,7 +12,7 @@ pub use settings::*;
 use std::sync::{Arc, atomic::Ordering};
 
 use crate::{
-	PlaySoundError, ResourceLimitReached, Value,
+	Decibels, PlaySoundError, ResourceLimitReached, Value,
 	backend::{
 		Backend, DefaultBackend, Renderer, RendererShared,
 		resources::{ResourceControllers, create_resources},
@@ -70,19 +70,25 @@ impl<B: Backend> AudioManager<B> {
 	pub fn new(settings: AudioManagerSettings<B>) -> Result<Self, B::Error> {
 		let (mut backend, sample_rate) =
 			B::setup(settings.backend_settings, settings.internal_buffer_size)?;
-		let renderer_shared = Arc::new(RendererShared::new(sample_rate));
+		let renderer_shared = Arc::new(RendererShared::new(sample_rate, settings.channel_layout));
 		let (resources, resource_controllers) = create_resources(
 			settings.capacities,
 			settings.main_track_builder,
-			sample_rate,
+			renderer_shared.clone(),
 			settings.internal_buffer_size,
+			settings.num_mixer_threads,
 		);
 		let renderer = Renderer::new(
 			renderer_shared.clone(),
 			settings.internal_buffer_size,
+			settings.stereo_upmix,
+			settings.output_stage,
 			resources,
 		);
 		backend.start(renderer)?;
+		#[cfg(not(target_arch = "wasm32"))]
+		crate::sound::streaming::DecodePool::global()
+			.request_max_threads(settings.num_decode_threads);
 		Ok(Self {
 			backend,
 			resource_controllers,
@@ -157,7 +163,8 @@ impl<B: Backend> AudioManager<B> {
 			.send_track_controller
 			.try_reserve()?;
 		let id = SendTrackId(key);
-		let (mut track, handle) = builder.build(id, self.internal_buffer_size);
+		let (mut track, handle) =
+			builder.build(id, self.renderer_shared.clone(), self.internal_buffer_size);
 		track.init_effects(self.renderer_shared.sample_rate.load(Ordering::SeqCst));
 		self.resource_controllers
 			.send_track_controller
@@ -322,6 +329,36 @@ impl<B: Backend> AudioManager<B> {
 		self.resource_controllers.modulator_controller.len()
 	}
 
+	/// Returns how much the [output stage](crate::backend::OutputStage)
+	/// turned down the mix during the most recently processed batch
+	/// of audio.
+	///
+	/// For example, `Decibels(3.0)` means the loudest moment of the
+	/// batch was turned down by 3dB, and [`Decibels::IDENTITY`] means
+	/// the mix was left unchanged.
+	#[must_use]
+	pub fn output_gain_reduction(&self) -> Decibels {
+		self.renderer_shared.output_gain_reduction()
+	}
+
+	/**
+	Sets whether the audio thread should record how long it spends
+	processing each mixer track and effect.
+
+	Profiling is disabled by default. The timings can be read with
+	the `pop_profile` method on each kind of track handle.
+	*/
+	pub fn set_profiling_enabled(&mut self, enabled: bool) {
+		self.renderer_shared.set_profiling_enabled(enabled);
+	}
+
+	/// Returns whether the audio thread is recording how long it
+	/// spends processing each mixer track and effect.
+	#[must_use]
+	pub fn profiling_enabled(&self) -> bool {
+		self.renderer_shared.profiling_enabled()
+	}
+
 	/// Returns a mutable reference to this manager's backend.
 	#[must_use]
 	pub fn backend_mut(&mut self) -> &mut B {
@@ -1,6 +1,6 @@
 use crate::track::MainTrackBuilder;
 
-use crate::backend::Backend;
+use crate::backend::{Backend, ChannelLayout, OutputStage, StereoUpmix};
 
 /// Specifies how many of each resource type an audio context
 /// can have.
@@ -46,6 +46,48 @@ pub struct AudioManagerSettings<B: Backend> {
 	/// Decreasing this value increases the precision of clocks and modulators
 	/// at the expense of higher CPU usage.
 	pub internal_buffer_size: usize,
+	/// The speakers the mixer mixes audio for.
+	///
+	/// With [`Quad`](ChannelLayout::Quad) and larger layouts, spatial
+	/// tracks are panned across every speaker around the listener. The
+	/// mix is converted to the audio device's layout if it's different.
+	pub channel_layout: ChannelLayout,
+	/// How stereo audio is spread across layouts with more than
+	/// two speakers.
+	pub stereo_upmix: StereoUpmix,
+	/// How the mix is kept within the range the audio device
+	/// can output.
+	pub output_stage: OutputStage,
+	/// The number of extra threads used to process mixer sub-tracks
+	/// in parallel.
+	///
+	/// When this is `0`, all tracks are processed on the audio thread.
+	/// Otherwise, the sub-tracks of the main track are split between the
+	/// audio thread and this many worker threads. A sub-track's own
+	/// sub-tracks are processed on the same thread as it. Because the
+	/// tracks are summed in a different order, the output may differ
+	/// very slightly from serial mixing, but it's always the same for
+	/// a given number of threads.
+	///
+	/// This has no effect on WebAssembly.
+	pub num_mixer_threads: usize,
+	/**
+	The maximum number of threads used to decode audio for
+	[streaming sounds](crate::sound::streaming).
+
+	The threads are shared by every streaming sound and are only
+	spawned once there are streams to decode. When there are more
+	streams than threads, the streams with the least audio buffered
+	are decoded first. At least one thread is always used.
+
+	The pool of decode threads is shared by every [`AudioManager`](super::AudioManager).
+	It uses the largest number of threads requested by any of them, so
+	creating an [`AudioManager`](super::AudioManager) never reduces the
+	number of threads available to the others.
+
+	This has no effect on WebAssembly.
+	*/
+	pub num_decode_threads: usize,
 	/// Configures the backend.
 	pub backend_settings: B::Settings,
 }
@@ -59,6 +101,11 @@ where
 			capacities: Capacities::default(),
 			main_track_builder: MainTrackBuilder::default(),
 			internal_buffer_size: 128,
+			channel_layout: ChannelLayout::default(),
+			stereo_upmix: StereoUpmix::default(),
+			output_stage: OutputStage::default(),
+			num_mixer_threads: 0,
+			num_decode_threads: 2,
 			backend_settings: B::Settings::default(),
 		}
 	}
@@ -6,20 +6,16 @@ mod handle;
 #[cfg(test)]
 mod test;
 
-use std::{
-	sync::{
-		Arc,
-		atomic::{AtomicBool, Ordering},
-	},
-	time::Duration,
+use std::sync::{
+	Arc,
+	atomic::{AtomicBool, Ordering},
 };
 
 pub use builder::*;
 pub use handle::*;
 
 use crate::{
-	StartTime, Tween, Tweenable, command_writers_and_readers,
-	info::{Info, WhenToStart},
+	Tween, Tweenable, command_writers_and_readers, info::Info, start_time::StartTimeProgress,
 };
 
 use super::Modulator;
@@ -69,25 +65,13 @@ impl Modulator for Tweener {
 			tween,
 		} = &mut self.state
 		{
-			let started = match &mut tween.start_time {
-				StartTime::Immediate => true,
-				StartTime::Delayed(time_remaining) => {
-					if time_remaining.is_zero() {
-						true
-					} else {
-						*time_remaining =
-							time_remaining.saturating_sub(Duration::from_secs_f64(dt));
-						false
-					}
-				}
-				StartTime::ClockTime(clock_time) => {
-					info.when_to_start(*clock_time) == WhenToStart::Now
+			let delay = match tween.start_time.update(dt, info) {
+				StartTimeProgress::Started { delay } => delay,
+				StartTimeProgress::Waiting | StartTimeProgress::WillNeverStart => {
+					return;
 				}
 			};
-			if !started {
-				return;
-			}
-			*time += dt;
+			*time += dt - delay;
 			if *time >= tween.duration.as_secs_f64() {
 				self.value = values.1;
 				self.state = State::Idle;
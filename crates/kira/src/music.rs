@@ -0,0 +1,276 @@
+/*!
+Interactive music made of segments that transition into each other
+in time with a clock.
+
+A [`MusicController`] plays one named [`MusicSegment`] at a time on its
+own mixer track. When you ask it to [transition](MusicController::transition_to)
+to another segment, it waits for the next beat, bar, or end of the
+current segment (as measured by its clock) and then fades out the
+current segment, starts the new one, and optionally plays a stinger.
+How each pair of segments transitions is set by a [`MusicTransition`].
+
+Each tick of the controller's clock is one beat, so a clock created
+with [`ClockSpeed::TicksPerMinute`](crate::clock::ClockSpeed::TicksPerMinute)
+runs at the tempo of the music.
+
+Segments can be [static sounds](crate::sound::static_sound) or, with the
+`symphonia` feature enabled, [streamed](MusicSegment::streaming) from
+audio files, which uses less memory for long pieces of music.
+
+```no_run
+use std::time::Duration;
+
+use kira::{
+	AudioManager, AudioManagerSettings, DefaultBackend,
+	clock::ClockSpeed,
+	music::{MusicController, MusicSegment, MusicTransition, Quantization},
+	sound::static_sound::StaticSoundData,
+	track::TrackBuilder,
+};
+
+let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+let track = manager.add_sub_track(TrackBuilder::new())?;
+let clock = manager.add_clock(ClockSpeed::TicksPerMinute(120.0))?;
+let mut music = MusicController::new(track, clock)
+	.with_segment(
+		"explore",
+		MusicSegment::new(StaticSoundData::from_file("explore.ogg")?.loop_region(..))
+			.num_beats(32),
+	)
+	.with_segment(
+		"combat",
+		MusicSegment::new(StaticSoundData::from_file("combat.ogg")?.loop_region(..))
+			.num_beats(16),
+	)
+	.with_transition(
+		"explore",
+		"combat",
+		MusicTransition::new(Quantization::NextBar)
+			.stinger(StaticSoundData::from_file("combat_stinger.ogg")?)
+			.crossfade(Duration::from_millis(500)),
+	);
+music.play("explore")?;
+music.clock_mut().start();
+// later...
+music.transition_to("combat")?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+*/
+
+mod controller;
+mod segment_sound;
+
+pub use controller::*;
+
+use std::{
+	error::Error,
+	fmt::{Display, Formatter},
+	time::Duration,
+};
+
+#[cfg(all(feature = "symphonia", not(target_arch = "wasm32")))]
+use std::path::PathBuf;
+
+#[cfg(all(feature = "symphonia", not(target_arch = "wasm32")))]
+use crate::sound::{FromFileError, streaming::StreamingSoundSettings};
+use crate::{PlaySoundError, sound::static_sound::StaticSoundData};
+
+/// A piece of music that a [`MusicController`] can play.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MusicSegment {
+	/// The audio of the segment.
+	///
+	/// Set a loop region on the sound to make the segment loop
+	/// until the next transition.
+	pub data: MusicSegmentData,
+	/// The number of beats in each bar of the segment.
+	pub beats_per_bar: u64,
+	/// The length of the segment (or of its loop) in beats, if known.
+	///
+	/// This is used by [`Quantization::EndOfSegment`].
+	pub num_beats: Option<u64>,
+}
+
+impl MusicSegment {
+	/// Creates a new [`MusicSegment`] in 4/4 time with an unknown length.
+	#[must_use]
+	pub fn new(data: impl Into<MusicSegmentData>) -> Self {
+		Self {
+			data: data.into(),
+			beats_per_bar: 4,
+			num_beats: None,
+		}
+	}
+
+	/**
+	Creates a new [`MusicSegment`] in 4/4 time with an unknown length
+	that's streamed from an audio file.
+
+	The file is opened each time the segment starts playing.
+	*/
+	#[cfg(all(feature = "symphonia", not(target_arch = "wasm32")))]
+	#[cfg_attr(docsrs, doc(cfg(all(feature = "symphonia", not(wasm32)))))]
+	#[must_use]
+	pub fn streaming(path: impl Into<PathBuf>, settings: StreamingSoundSettings) -> Self {
+		Self::new(MusicSegmentData::Streaming {
+			path: path.into(),
+			settings,
+		})
+	}
+
+	/// Sets the number of beats in each bar of the segment.
+	#[must_use = "This method consumes self and returns a modified MusicSegment, so the return value should be used"]
+	pub fn beats_per_bar(self, beats_per_bar: u64) -> Self {
+		Self {
+			beats_per_bar,
+			..self
+		}
+	}
+
+	/// Sets the length of the segment (or of its loop) in beats.
+	#[must_use = "This method consumes self and returns a modified MusicSegment, so the return value should be used"]
+	pub fn num_beats(self, num_beats: impl Into<Option<u64>>) -> Self {
+		Self {
+			num_beats: num_beats.into(),
+			..self
+		}
+	}
+}
+
+/// The audio of a [`MusicSegment`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MusicSegmentData {
+	/// Audio that's loaded into memory.
+	Static(StaticSoundData),
+	/// Audio that's streamed from a file each time the segment plays.
+	#[cfg(all(feature = "symphonia", not(target_arch = "wasm32")))]
+	#[cfg_attr(docsrs, doc(cfg(all(feature = "symphonia", not(wasm32)))))]
+	Streaming {
+		/// The path to the audio file.
+		path: PathBuf,
+		/// The settings used to play the file.
+		///
+		/// The start time and fade-in tween are set by the
+		/// [`MusicController`].
+		settings: StreamingSoundSettings,
+	},
+}
+
+impl From<StaticSoundData> for MusicSegmentData {
+	fn from(data: StaticSoundData) -> Self {
+		Self::Static(data)
+	}
+}
+
+/// When a transition between segments happens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Quantization {
+	/// The transition happens on the next beat.
+	NextBeat,
+	/// The transition happens at the start of the next bar
+	/// of the current segment.
+	#[default]
+	NextBar,
+	/// The transition happens when the current segment (or the
+	/// current iteration of its loop) ends.
+	///
+	/// If the current segment's length isn't known, this behaves
+	/// like [`Quantization::NextBar`].
+	EndOfSegment,
+}
+
+/// How a [`MusicController`] moves from one segment to another.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MusicTransition {
+	/// When the transition happens.
+	pub quantization: Quantization,
+	/// How long the current segment fades out and the new segment
+	/// fades in for, starting at the transition.
+	///
+	/// If this is zero, the segments cut from one to the other.
+	pub crossfade: Duration,
+	/// A sound to play once at the moment of the transition.
+	pub stinger: Option<StaticSoundData>,
+}
+
+impl MusicTransition {
+	/// Creates a new [`MusicTransition`] that cuts from one segment
+	/// to the next at the given boundary.
+	#[must_use]
+	pub fn new(quantization: Quantization) -> Self {
+		Self {
+			quantization,
+			crossfade: Duration::ZERO,
+			stinger: None,
+		}
+	}
+
+	/// Sets how long the segments crossfade for.
+	#[must_use = "This method consumes self and returns a modified MusicTransition, so the return value should be used"]
+	pub fn crossfade(self, crossfade: Duration) -> Self {
+		Self { crossfade, ..self }
+	}
+
+	/// Sets a sound to play once at the moment of the transition.
+	#[must_use = "This method consumes self and returns a modified MusicTransition, so the return value should be used"]
+	pub fn stinger(self, stinger: impl Into<Option<StaticSoundData>>) -> Self {
+		Self {
+			stinger: stinger.into(),
+			..self
+		}
+	}
+}
+
+/// Errors that can occur when playing music with a [`MusicController`].
+#[derive(Debug)]
+pub enum MusicError {
+	/// The controller doesn't have a segment with the given name.
+	UnknownSegment(String),
+	/// An error occurred when playing a segment or stinger.
+	PlaySoundError(PlaySoundError<()>),
+	/// An error occurred when opening the file of a streaming segment.
+	#[cfg(all(feature = "symphonia", not(target_arch = "wasm32")))]
+	#[cfg_attr(docsrs, doc(cfg(all(feature = "symphonia", not(wasm32)))))]
+	FromFileError(FromFileError),
+}
+
+impl Display for MusicError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			MusicError::UnknownSegment(name) => {
+				write!(f, "The controller doesn't have a segment named \"{name}\".")
+			}
+			MusicError::PlaySoundError(error) => error.fmt(f),
+			#[cfg(all(feature = "symphonia", not(target_arch = "wasm32")))]
+			MusicError::FromFileError(error) => error.fmt(f),
+		}
+	}
+}
+
+impl Error for MusicError {}
+
+impl From<PlaySoundError<()>> for MusicError {
+	fn from(error: PlaySoundError<()>) -> Self {
+		Self::PlaySoundError(error)
+	}
+}
+
+#[cfg(all(feature = "symphonia", not(target_arch = "wasm32")))]
+impl From<FromFileError> for MusicError {
+	fn from(error: FromFileError) -> Self {
+		Self::FromFileError(error)
+	}
+}
+
+#[cfg(all(feature = "symphonia", not(target_arch = "wasm32")))]
+impl From<PlaySoundError<FromFileError>> for MusicError {
+	fn from(error: PlaySoundError<FromFileError>) -> Self {
+		match error {
+			PlaySoundError::SoundLimitReached => {
+				Self::PlaySoundError(PlaySoundError::SoundLimitReached)
+			}
+			PlaySoundError::IntoSoundError(error) => Self::FromFileError(error),
+		}
+	}
+}
@@ -0,0 +1,296 @@
+use std::{
+	collections::HashMap,
+	sync::{
+		Arc,
+		atomic::{AtomicU64, Ordering},
+	},
+};
+
+#[cfg(all(feature = "symphonia", not(target_arch = "wasm32")))]
+use crate::sound::streaming::StreamingSoundData;
+use crate::{
+	Easing, StartTime, Tween,
+	clock::{ClockHandle, ClockTime, QuantizedClockTime},
+	track::TrackHandle,
+};
+
+use super::{
+	MusicError, MusicSegment, MusicSegmentData, MusicTransition, Quantization,
+	segment_sound::{SegmentHandle, SegmentSoundData},
+};
+
+/**
+Plays [`MusicSegment`]s on a mixer track and transitions between
+them in time with a clock.
+
+The controller owns the track and clock it uses. They can be
+accessed with [`track_mut`](MusicController::track_mut) and
+[`clock_mut`](MusicController::clock_mut), for example to start the
+clock or change the volume of the music.
+*/
+#[derive(Debug)]
+pub struct MusicController {
+	track: TrackHandle,
+	clock: ClockHandle,
+	segments: HashMap<String, MusicSegment>,
+	transitions: HashMap<(String, String), MusicTransition>,
+	transitions_to: HashMap<String, MusicTransition>,
+	default_transition: MusicTransition,
+	current: Option<CurrentSegment>,
+}
+
+impl MusicController {
+	/// Creates a new [`MusicController`] with no segments that plays
+	/// music on the given track, using the given clock for timing.
+	#[must_use]
+	pub fn new(track: TrackHandle, clock: ClockHandle) -> Self {
+		Self {
+			track,
+			clock,
+			segments: HashMap::new(),
+			transitions: HashMap::new(),
+			transitions_to: HashMap::new(),
+			default_transition: MusicTransition::default(),
+			current: None,
+		}
+	}
+
+	/// Adds a segment with the given name.
+	#[must_use = "This method consumes self and returns a modified MusicController, so the return value should be used"]
+	pub fn with_segment(mut self, name: impl Into<String>, segment: MusicSegment) -> Self {
+		self.segments.insert(name.into(), segment);
+		self
+	}
+
+	/// Sets how the controller transitions from the segment named `from`
+	/// to the segment named `to`.
+	#[must_use = "This method consumes self and returns a modified MusicController, so the return value should be used"]
+	pub fn with_transition(
+		mut self,
+		from: impl Into<String>,
+		to: impl Into<String>,
+		transition: MusicTransition,
+	) -> Self {
+		self.transitions
+			.insert((from.into(), to.into()), transition);
+		self
+	}
+
+	/// Sets how the controller transitions from any segment to the
+	/// segment named `to` when there's no transition specifically
+	/// for the current segment.
+	#[must_use = "This method consumes self and returns a modified MusicController, so the return value should be used"]
+	pub fn with_transition_to(
+		mut self,
+		to: impl Into<String>,
+		transition: MusicTransition,
+	) -> Self {
+		self.transitions_to.insert(to.into(), transition);
+		self
+	}
+
+	/// Sets the transition used when no other transition applies.
+	///
+	/// By default, segments cut from one to the other at the next bar.
+	#[must_use = "This method consumes self and returns a modified MusicController, so the return value should be used"]
+	pub fn default_transition(self, default_transition: MusicTransition) -> Self {
+		Self {
+			default_transition,
+			..self
+		}
+	}
+
+	/// Returns the track the music plays on.
+	#[must_use]
+	pub fn track(&self) -> &TrackHandle {
+		&self.track
+	}
+
+	/// Returns a mutable reference to the track the music plays on.
+	#[must_use]
+	pub fn track_mut(&mut self) -> &mut TrackHandle {
+		&mut self.track
+	}
+
+	/// Returns the clock used to time transitions.
+	#[must_use]
+	pub fn clock(&self) -> &ClockHandle {
+		&self.clock
+	}
+
+	/// Returns a mutable reference to the clock used to time transitions.
+	#[must_use]
+	pub fn clock_mut(&mut self) -> &mut ClockHandle {
+		&mut self.clock
+	}
+
+	/// Returns the name of the segment that's playing, or that will
+	/// play once the pending transition happens.
+	#[must_use]
+	pub fn current_segment(&self) -> Option<&str> {
+		self.current.as_ref().map(|current| current.name.as_str())
+	}
+
+	/**
+	Returns the clock time the current segment started (or will start)
+	playing.
+
+	Until the audio thread receives the transition, this is the time
+	returned by [`transition_to`](MusicController::transition_to).
+	*/
+	#[must_use]
+	pub fn current_segment_start_time(&self) -> Option<ClockTime> {
+		self.current
+			.as_ref()
+			.map(|current| ClockTime::from_ticks_u64(&self.clock, current.start_tick()))
+	}
+
+	/**
+	Starts playing the segment with the given name on the next beat.
+
+	If a segment is already playing, this transitions to the new
+	segment like [`transition_to`](MusicController::transition_to).
+	*/
+	pub fn play(&mut self, name: &str) -> Result<ClockTime, MusicError> {
+		self.transition_to(name)
+	}
+
+	/**
+	Transitions from the current segment to the segment with the
+	given name and returns the clock time when the transition is
+	scheduled to happen.
+
+	The transition time is checked again on the audio thread, so if the
+	clock passes it before the audio thread receives the transition, the
+	transition happens on the next beat, bar, or end of the current
+	segment instead of off the beat.
+
+	The transition is chosen from the ones added with
+	[`with_transition`](MusicController::with_transition), then
+	[`with_transition_to`](MusicController::with_transition_to), then
+	the [default transition](MusicController::default_transition).
+
+	If the segment is already playing (or about to play), nothing
+	changes.
+	*/
+	pub fn transition_to(&mut self, name: &str) -> Result<ClockTime, MusicError> {
+		if !self.segments.contains_key(name) {
+			return Err(MusicError::UnknownSegment(name.to_string()));
+		}
+		if let Some(current) = &self.current {
+			if current.name == name {
+				return Ok(ClockTime::from_ticks_u64(&self.clock, current.start_tick()));
+			}
+		}
+		let transition = self.transition(name).clone();
+		let (quantized_time, start_tick) = self.transition_time(transition.quantization);
+		let start_time = StartTime::Quantized(quantized_time);
+		let fade_tween = Tween {
+			start_time,
+			duration: transition.crossfade,
+			easing: Easing::Linear,
+		};
+		let fade_in_tween = (!transition.crossfade.is_zero()).then_some(fade_tween);
+		let resolved_start_tick = Arc::new(AtomicU64::new(start_tick));
+
+		let handle = match &self.segments[name].data {
+			MusicSegmentData::Static(data) => self.track.play(SegmentSoundData {
+				data: data.start_time(start_time).fade_in_tween(fade_in_tween),
+				start_time: quantized_time,
+				start_tick: resolved_start_tick.clone(),
+			})?,
+			#[cfg(all(feature = "symphonia", not(target_arch = "wasm32")))]
+			MusicSegmentData::Streaming { path, settings } => self.track.play(SegmentSoundData {
+				data: StreamingSoundData::from_file(path)?
+					.with_settings(settings.clone())
+					.start_time(start_time)
+					.fade_in_tween(fade_in_tween),
+				start_time: quantized_time,
+				start_tick: resolved_start_tick.clone(),
+			})?,
+		};
+		if let Some(stinger) = &transition.stinger {
+			self.track.play(stinger.start_time(start_time))?;
+		}
+		if let Some(mut current) = self.current.take() {
+			current.handle.stop(fade_tween);
+		}
+		self.current = Some(CurrentSegment {
+			name: name.to_string(),
+			handle,
+			start_tick: resolved_start_tick,
+		});
+		Ok(ClockTime::from_ticks_u64(&self.clock, start_tick))
+	}
+
+	/// Fades out the current segment with the given tween and stops it.
+	pub fn stop(&mut self, tween: Tween) {
+		if let Some(mut current) = self.current.take() {
+			current.handle.stop(tween);
+		}
+	}
+
+	/// Returns the transition to use when moving from the current
+	/// segment to the segment named `to`.
+	#[must_use]
+	fn transition(&self, to: &str) -> &MusicTransition {
+		self.current
+			.as_ref()
+			.and_then(|current| {
+				self.transitions
+					.get(&(current.name.clone(), to.to_string()))
+			})
+			.or_else(|| self.transitions_to.get(to))
+			.unwrap_or(&self.default_transition)
+	}
+
+	/**
+	Returns the time a transition with the given quantization should
+	happen, along with the tick it's expected to resolve to.
+
+	Beats and bars are counted from the tick the current segment started
+	on. The time is anchored at the first boundary after the clock's time
+	as seen from this thread, so the new segment, the stinger, and the
+	fade-out of the current segment all resolve to that tick unless the
+	clock passes it before the audio thread sees the transition, in which
+	case they move to the next boundary.
+	*/
+	#[must_use]
+	fn transition_time(&self, quantization: Quantization) -> (QuantizedClockTime, u64) {
+		let time = self.clock.time();
+		let ticks = time.ticks as f64 + time.fraction;
+		let boundaries = match &self.current {
+			Some(current) => {
+				let current_segment = &self.segments[&current.name];
+				let interval = match quantization {
+					Quantization::NextBeat => 1,
+					Quantization::NextBar => current_segment.beats_per_bar,
+					Quantization::EndOfSegment => current_segment
+						.num_beats
+						.unwrap_or(current_segment.beats_per_bar),
+				};
+				QuantizedClockTime::next_multiple(&self.clock, interval)
+					.anchor(current.start_tick())
+			}
+			None => QuantizedClockTime::next_tick(&self.clock),
+		};
+		let start_tick = boundaries.resolve_ticks(ticks);
+		(boundaries.anchor(start_tick), start_tick)
+	}
+}
+
+#[derive(Debug)]
+struct CurrentSegment {
+	name: String,
+	handle: SegmentHandle,
+	/// The tick the segment is expected to start on, which is updated
+	/// once the audio thread resolves the start time.
+	start_tick: Arc<AtomicU64>,
+}
+
+impl CurrentSegment {
+	#[must_use]
+	fn start_tick(&self) -> u64 {
+		self.start_tick.load(Ordering::SeqCst)
+	}
+}
@@ -0,0 +1,165 @@
+use std::sync::{
+	Arc,
+	atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+	Decibels, StartTime, Tween,
+	clock::QuantizedClockTime,
+	command::{CommandReader, CommandWriter, command_writer_and_reader},
+	frame::Frame,
+	info::Info,
+	sound::{Sound, SoundData},
+	start_time::{StartTimeProgress, num_frames_before_start},
+	tween::Tweenable,
+};
+
+/// The sound of a [`MusicSegment`](super::MusicSegment), which reports
+/// the tick its start time resolves to on the audio thread.
+pub(super) struct SegmentSoundData<D> {
+	pub data: D,
+	/// The start time of the segment, which should also be
+	/// the start time of `data`.
+	pub start_time: QuantizedClockTime,
+	/// Set to the tick the segment starts on once the start time
+	/// is resolved.
+	pub start_tick: Arc<AtomicU64>,
+}
+
+impl<D: SoundData> SoundData for SegmentSoundData<D> {
+	type Error = D::Error;
+
+	type Handle = SegmentHandle;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+		let (sound, _) = self.data.into_sound()?;
+		let (stop_command_writer, stop_command_reader) = command_writer_and_reader();
+		Ok((
+			Box::new(SegmentSound {
+				sound,
+				start_time: Some(self.start_time),
+				start_tick: self.start_tick,
+				stop_command_reader,
+				fade_out: None,
+			}),
+			SegmentHandle {
+				stop_command_writer,
+			},
+		))
+	}
+}
+
+/// Controls a playing [`MusicSegment`](super::MusicSegment).
+#[derive(Debug)]
+pub(super) struct SegmentHandle {
+	stop_command_writer: CommandWriter<Tween>,
+}
+
+impl SegmentHandle {
+	/**
+	Fades out and stops the segment.
+
+	Unlike stopping a sound, the fade starts on the exact frame the
+	tween's start time is reached, so a segment stopped with a
+	zero-length tween plays right up until the next one starts.
+	*/
+	pub fn stop(&mut self, tween: Tween) {
+		self.stop_command_writer.write(tween);
+	}
+}
+
+struct SegmentSound {
+	sound: Box<dyn Sound>,
+	/// The start time of the segment, or `None` if it's
+	/// already been resolved.
+	start_time: Option<QuantizedClockTime>,
+	start_tick: Arc<AtomicU64>,
+	stop_command_reader: CommandReader<Tween>,
+	fade_out: Option<FadeOut>,
+}
+
+struct FadeOut {
+	tween: Tween,
+	/// How many seconds the fade has been running for.
+	time: f64,
+}
+
+impl FadeOut {
+	/// Returns `true` if the fade has started and reached silence.
+	#[must_use]
+	fn finished(&self) -> bool {
+		matches!(self.tween.start_time, StartTime::Immediate)
+			&& self.time >= self.tween.duration.as_secs_f64()
+	}
+
+	/// Advances the fade by one frame and returns the
+	/// volume of that frame.
+	fn next_volume(&mut self, dt: f64) -> f32 {
+		if self.time >= self.tween.duration.as_secs_f64() {
+			return 0.0;
+		}
+		self.time += dt;
+		let amount = self
+			.tween
+			.easing
+			.apply((self.time / self.tween.duration.as_secs_f64()).min(1.0));
+		Decibels::interpolate(Decibels::IDENTITY, Decibels::SILENCE, amount).as_amplitude()
+	}
+}
+
+impl Sound for SegmentSound {
+	fn init(&mut self, sample_rate: u32, internal_buffer_size: usize) {
+		self.sound.init(sample_rate, internal_buffer_size);
+	}
+
+	fn on_change_sample_rate(&mut self, sample_rate: u32) {
+		self.sound.on_change_sample_rate(sample_rate);
+	}
+
+	fn on_start_processing(&mut self) {
+		if let Some(tween) = self.stop_command_reader.read() {
+			self.fade_out = Some(FadeOut { tween, time: 0.0 });
+		}
+		self.sound.on_start_processing();
+	}
+
+	fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
+		// the inner sound resolves the same start time from the same
+		// batch, so it starts on the same tick
+		if let Some(start_time) = self.start_time.take() {
+			if let Some(clock_time) = start_time.resolve(dt * out.len() as f64, info) {
+				self.start_tick.store(clock_time.ticks, Ordering::SeqCst);
+			}
+		}
+		self.sound.process(out, dt, info);
+
+		let Some(fade_out) = &mut self.fade_out else {
+			return;
+		};
+		let delay = match fade_out
+			.tween
+			.start_time
+			.update(dt * out.len() as f64, info)
+		{
+			StartTimeProgress::Waiting => return,
+			StartTimeProgress::Started { delay } => delay,
+			StartTimeProgress::WillNeverStart => {
+				fade_out.tween.start_time = StartTime::Immediate;
+				0.0
+			}
+		};
+		let start_frame = num_frames_before_start(delay, dt, out.len());
+		for frame in &mut out[start_frame..] {
+			*frame *= fade_out.next_volume(dt);
+		}
+	}
+
+	fn remaining_duration(&self) -> Option<f64> {
+		self.sound.remaining_duration()
+	}
+
+	fn finished(&self) -> bool {
+		self.sound.finished() || self.fade_out.as_ref().is_some_and(FadeOut::finished)
+	}
+}
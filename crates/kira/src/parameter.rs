@@ -1,12 +1,11 @@
 #[cfg(test)]
 mod test;
 
-use std::time::Duration;
-
 use crate::{
-	StartTime, Tween, Tweenable, Value,
+	Tween, Tweenable, Value,
 	command::{CommandReader, ValueChangeCommand},
-	info::{Info, WhenToStart},
+	info::Info,
+	start_time::StartTimeProgress,
 };
 
 /// Manages and updates a value that can be smoothly transitioned
@@ -113,25 +112,13 @@ impl<T: Tweenable> Parameter<T> {
 			..
 		} = &mut self.state
 		{
-			let started = match &mut tween.start_time {
-				StartTime::Immediate => true,
-				StartTime::Delayed(time_remaining) => {
-					if time_remaining.is_zero() {
-						true
-					} else {
-						*time_remaining =
-							time_remaining.saturating_sub(Duration::from_secs_f64(dt));
-						false
-					}
-				}
-				StartTime::ClockTime(clock_time) => {
-					info.when_to_start(*clock_time) == WhenToStart::Now
+			let delay = match tween.start_time.update(dt, info) {
+				StartTimeProgress::Started { delay } => delay,
+				StartTimeProgress::Waiting | StartTimeProgress::WillNeverStart => {
+					return false;
 				}
 			};
-			if !started {
-				return false;
-			}
-			*time += dt;
+			*time += dt - delay;
 			if *time >= tween.duration.as_secs_f64() {
 				if matches!(target, Value::Fixed(_)) {
 					self.stagnant = true;
@@ -1,10 +1,12 @@
 use crate::{
 	Decibels, StartTime, Tween, Value, info::Info, parameter::Parameter, sound::PlaybackState,
+	start_time::StartTimeProgress,
 };
 
 pub(crate) struct PlaybackStateManager {
 	state: State,
 	volume_fade: Parameter<Decibels>,
+	start_delay: f64,
 }
 
 impl PlaybackStateManager {
@@ -21,6 +23,7 @@ impl PlaybackStateManager {
 				.unwrap_or_else(|| {
 					Parameter::new(Value::Fixed(Decibels::IDENTITY), Decibels::IDENTITY)
 				}),
+			start_delay: 0.0,
 		}
 	}
 
@@ -28,6 +31,12 @@ impl PlaybackStateManager {
 		self.volume_fade.interpolated_value(amount)
 	}
 
+	/// Returns how many seconds into the most recent batch of audio
+	/// playback resumed, or 0 if it didn't resume partway through.
+	pub fn start_delay(&self) -> f64 {
+		self.start_delay
+	}
+
 	pub fn playback_state(&self) -> PlaybackState {
 		match self.state {
 			State::Playing => PlaybackState::Playing,
@@ -79,6 +88,7 @@ impl PlaybackStateManager {
 	}
 
 	pub fn update(&mut self, dt: f64, info: &Info) -> ChangedPlaybackState {
+		self.start_delay = 0.0;
 		let finished = self.volume_fade.update(dt, info);
 		match &mut self.state {
 			State::Playing => {}
@@ -93,15 +103,22 @@ impl PlaybackStateManager {
 				start_time,
 				fade_in_tween,
 			} => {
-				let will_never_start = start_time.update(dt, info);
-				if will_never_start {
-					self.state = State::Stopped;
-					return true;
-				}
-				if *start_time == StartTime::Immediate {
-					let fade_in_tween = *fade_in_tween;
-					self.resume(StartTime::Immediate, fade_in_tween);
-					return true;
+				match start_time.update(dt, info) {
+					StartTimeProgress::Waiting => {}
+					StartTimeProgress::Started { delay } => {
+						let fade_in_tween = *fade_in_tween;
+						self.resume(StartTime::Immediate, fade_in_tween);
+						// fade in for the part of the batch after playback resumed
+						if self.volume_fade.update(dt - delay, info) {
+							self.state = State::Playing;
+						}
+						self.start_delay = delay;
+						return true;
+					}
+					StartTimeProgress::WillNeverStart => {
+						self.state = State::Stopped;
+						return true;
+					}
 				}
 			}
 			State::Resuming => {
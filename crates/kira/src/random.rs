@@ -0,0 +1,46 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Used to give each [`Random`] a different seed.
+static NEXT_SEED: AtomicU64 = AtomicU64::new(0);
+
+/// A small, fast random number generator that's safe to use
+/// on the audio thread.
+///
+/// This uses the xorshift64* algorithm, so it isn't suitable
+/// for anything that needs unpredictable numbers.
+#[derive(Debug)]
+pub(crate) struct Random {
+	state: u64,
+}
+
+impl Random {
+	/// Creates a new [`Random`] with a different seed than
+	/// the previously created ones.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::with_seed(NEXT_SEED.fetch_add(1, Ordering::Relaxed))
+	}
+
+	#[must_use]
+	pub fn with_seed(seed: u64) -> Self {
+		Self {
+			// xorshift gets stuck at 0, so make sure the state
+			// has some bits set
+			state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+		}
+	}
+
+	/// Returns a random number from 0.0 to 1.0.
+	pub fn unipolar(&mut self) -> f64 {
+		self.state ^= self.state >> 12;
+		self.state ^= self.state << 25;
+		self.state ^= self.state >> 27;
+		let random = self.state.wrapping_mul(0x2545_F491_4F6C_DD1D);
+		(random >> 11) as f64 / (1u64 << 53) as f64
+	}
+
+	/// Returns a random number from -1.0 to 1.0.
+	pub fn bipolar(&mut self) -> f64 {
+		self.unipolar() * 2.0 - 1.0
+	}
+}
@@ -2,7 +2,7 @@
 Sources of audio.
 
 Any type that implements [`SoundData`] can be played using
-[`AudioManager::play`](crate::AudioManager::play). Kira comes with two
+[`AudioManager::play`](crate::AudioManager::play). Kira comes with these
 [`SoundData`] implementations:
 
 - [`StaticSoundData`](static_sound::StaticSoundData), which loads an entire chunk of audio
@@ -11,29 +11,75 @@ Any type that implements [`SoundData`] can be played using
 - [`StreamingSoundData`](streaming::StreamingSoundData), which streams audio from a file or cursor
   (only available on desktop platforms). This is more appropriate for long sounds that you only
   play once at a time, like background music. Streaming sounds use less memory than static sounds.
+- [`PushSoundData`](push::PushSoundData), which plays audio that's pushed to it while it's playing.
+  This is useful for audio generated outside of Kira, like decoded voice chat or emulator output.
+- [`OscillatorSoundData`](oscillator::OscillatorSoundData), which synthesizes simple tones and
+  noise. This is useful for UI beeps and debug sounds.
+- [`GranularSoundData`](granular::GranularSoundData), which plays many short, overlapping pieces
+  of a static sound. This is useful for engine sounds and ambience.
+- The [containers](container), which choose between several other sounds, either randomly,
+  in order, or based on a key that can be changed while the sound is playing.
 
-These two sound types should cover most use cases, but if you need something else, you can
+These sound types should cover most use cases, but if you need something else, you can
 create your own types that implement the [`SoundData`] and [`Sound`] traits.
 */
 
+pub mod container;
+mod downmix;
+mod effect_chain;
 #[cfg(feature = "symphonia")]
 mod error;
+pub mod granular;
+mod marker;
+pub mod oscillator;
 mod playback_position;
+pub mod push;
 pub mod static_sound;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod streaming;
 #[cfg(feature = "symphonia")]
 mod symphonia;
+mod time_stretcher;
 mod transport;
 
 use std::ops::{Range, RangeFrom, RangeFull, RangeTo};
 
+pub use downmix::*;
+pub use effect_chain::*;
 #[cfg(feature = "symphonia")]
 pub use error::*;
+pub use marker::*;
 pub use playback_position::*;
 
 use crate::{frame::Frame, info::Info};
 
+/// Something that happened to a sound on the audio thread.
+///
+/// Events can be retrieved with the `pop_event` method on
+/// a sound's handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundEvent {
+	/// The sound started producing audio, either immediately or
+	/// once its start time was reached.
+	Started,
+	/// The sound played to the end of its audio.
+	Finished,
+	/// The sound was stopped before it reached the end of its audio,
+	/// either because it was told to stop or because its start time
+	/// will never be reached.
+	Stopped,
+	/// The sound jumped back to the start of its loop region.
+	Looped,
+	/// Playback reached a [`Marker`]. This contains the index of the
+	/// marker in the list returned by the `markers` method on the
+	/// sound's handle.
+	Marker(usize),
+	/// The sound stopped because an error occurred while
+	/// decoding audio. The error can be retrieved with
+	/// [`StreamingSoundHandle::pop_error`](streaming::StreamingSoundHandle::pop_error).
+	Error,
+}
+
 /// A source of audio that is loaded, but not yet playing.
 pub trait SoundData {
 	/// Errors that can occur when starting the sound.
@@ -55,10 +101,17 @@ pub trait SoundData {
 
 /// An actively playing sound.
 ///
-/// For performance reasons, the methods of this trait should not allocate
-/// or deallocate memory.
+/// For performance reasons, avoid allocating and deallocating memory in any methods
+/// of this trait besides [`init`](Sound::init) and
+/// [`on_change_sample_rate`](Sound::on_change_sample_rate).
 #[allow(unused_variables)]
 pub trait Sound: Send {
+	/// Called when the sound is first sent to the renderer.
+	fn init(&mut self, sample_rate: u32, internal_buffer_size: usize) {}
+
+	/// Called when the sample rate of the renderer is changed.
+	fn on_change_sample_rate(&mut self, sample_rate: u32) {}
+
 	/// Called whenever a new batch of audio samples is requested by the backend.
 	///
 	/// This is a good place to put code that needs to run fairly frequently,
@@ -71,6 +124,16 @@ pub trait Sound: Send {
 	/// `dt` is the time between each frame (in seconds).
 	fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info);
 
+	/// Returns how many seconds of audio the sound has left to play
+	/// at its current speed, or [`None`] if that isn't known (for
+	/// example, because the sound loops forever).
+	///
+	/// [`QueueSoundData`](container::QueueSoundData) uses this to start
+	/// the next sound in the queue right as the current one ends.
+	fn remaining_duration(&self) -> Option<f64> {
+		None
+	}
+
 	/// Processes a single [`Frame`]. Mostly useful for testing.
 	///
 	/// `dt` is the time elapsed since the previous frame (in seconds).
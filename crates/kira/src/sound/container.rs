@@ -0,0 +1,88 @@
+/*!
+Sounds that choose between several other sounds.
+
+Containers wrap several entries of any type that implements
+[`SoundData`](super::SoundData), like [`StaticSoundData`](super::static_sound::StaticSoundData)
+or [`StreamingSoundData`](super::streaming::StreamingSoundData):
+
+- [`RandomSoundData`] plays a randomly chosen entry each time it's played.
+- [`SequenceSoundData`] plays its entries in order, one per play.
+- [`SwitchSoundData`] plays the entry that matches a key, which can be
+  changed from its [`SwitchSoundHandle`] while the sound is playing.
+- [`BlendSoundData`] plays all of its layers at once and sets the
+  volume of each one from a blend value, which can be tweened from
+  its [`BlendSoundHandle`].
+- [`QueueSoundData`] plays its entries back to back with no gap, like
+  a playlist. Entries can be appended, skipped, and cleared from its
+  [`QueueSoundHandle`].
+
+```no_run
+use kira::{
+	AudioManager, AudioManagerSettings, DefaultBackend,
+	sound::{container::RandomSoundData, static_sound::StaticSoundData},
+};
+
+let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+let footsteps = RandomSoundData::new()
+	.with_entry(StaticSoundData::from_file("footstep_1.ogg")?, 1.0)
+	.with_entry(StaticSoundData::from_file("footstep_2.ogg")?, 1.0)
+	.with_entry(StaticSoundData::from_file("footstep_3.ogg")?, 1.0)
+	// never play the same footstep twice in a row
+	.no_repeat_window(1);
+// clones remember which entries the other clones played
+manager.play(footsteps.clone())?;
+manager.play(footsteps.clone())?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+
+Random and sequence containers return the handle of the entry they
+chose, so playing a container of [`StaticSoundData`](super::static_sound::StaticSoundData)s
+gives you a [`StaticSoundHandle`](super::static_sound::StaticSoundHandle).
+*/
+
+#[cfg(test)]
+mod test;
+
+mod blend;
+mod queue;
+mod random;
+mod sequence;
+mod shared_playback_state;
+mod switch;
+
+pub use blend::*;
+pub use queue::*;
+pub use random::*;
+pub use sequence::*;
+pub use switch::*;
+
+pub(crate) use shared_playback_state::SharedPlaybackState;
+
+use std::{
+	error::Error,
+	fmt::{Display, Formatter},
+};
+
+/// Errors that can occur when playing a container.
+#[derive(Debug)]
+pub enum ContainerError<E> {
+	/// The container doesn't have any entries to play.
+	NoEntries,
+	/// An error occurred when initializing one of the container's entries.
+	IntoSoundError(E),
+}
+
+impl<E> Display for ContainerError<E> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ContainerError::NoEntries => {
+				f.write_str("The container doesn't have any entries to play.")
+			}
+			ContainerError::IntoSoundError(_) => {
+				f.write_str("An error occurred when initializing one of the container's entries.")
+			}
+		}
+	}
+}
+
+impl<E: std::fmt::Debug> Error for ContainerError<E> {}
@@ -0,0 +1,128 @@
+mod data;
+mod handle;
+mod sound;
+
+pub use data::*;
+pub use handle::*;
+
+use crate::{
+	Decibels, Easing, Panning, StartTime, command::ValueChangeCommand, command_writers_and_readers,
+	tween::Tween,
+};
+
+/**
+How loud a layer of a [`BlendSoundData`] is at each blend value.
+
+The layer fades in from silence to full volume as the blend value
+goes from the start to the end of `fade_in`, and fades back out to
+silence as the blend value goes from the start to the end of `fade_out`.
+
+# Examples
+
+Crossfade between two layers as the blend value goes from 0.0 to 1.0:
+
+```
+use kira::sound::container::BlendCurve;
+
+let low = BlendCurve::fade_out(0.0, 1.0);
+let high = BlendCurve::fade_in(0.0, 1.0);
+assert_eq!(low.amplitude(0.5), 0.5);
+assert_eq!(high.amplitude(0.5), 0.5);
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlendCurve {
+	/// The range of blend values over which the layer fades in.
+	pub fade_in: (f64, f64),
+	/// The range of blend values over which the layer fades out.
+	pub fade_out: (f64, f64),
+	/// The curve applied to both fades.
+	///
+	/// [`Easing::OutPowi(2)`](Easing::OutPowi) keeps the combined loudness
+	/// of two crossfading layers closer to constant than
+	/// [`Easing::Linear`] does.
+	pub easing: Easing,
+}
+
+impl BlendCurve {
+	/// A curve that plays the layer at full volume at every blend value.
+	pub const FULL: Self = Self {
+		fade_in: (f64::NEG_INFINITY, f64::NEG_INFINITY),
+		fade_out: (f64::INFINITY, f64::INFINITY),
+		easing: Easing::Linear,
+	};
+
+	/// Creates a new [`BlendCurve`] that fades in and then fades out
+	/// over the given ranges of blend values.
+	#[must_use]
+	pub fn new(fade_in: (f64, f64), fade_out: (f64, f64)) -> Self {
+		Self {
+			fade_in,
+			fade_out,
+			easing: Easing::Linear,
+		}
+	}
+
+	/// Creates a new [`BlendCurve`] that fades in as the blend value
+	/// goes from `start` to `end` and stays at full volume after that.
+	#[must_use]
+	pub fn fade_in(start: f64, end: f64) -> Self {
+		Self {
+			fade_in: (start, end),
+			..Self::FULL
+		}
+	}
+
+	/// Creates a new [`BlendCurve`] that starts at full volume and fades
+	/// out as the blend value goes from `start` to `end`.
+	#[must_use]
+	pub fn fade_out(start: f64, end: f64) -> Self {
+		Self {
+			fade_out: (start, end),
+			..Self::FULL
+		}
+	}
+
+	/// Sets the curve applied to both fades.
+	#[must_use = "This method consumes self and returns a modified BlendCurve, so the return value should be used"]
+	pub fn easing(self, easing: Easing) -> Self {
+		Self { easing, ..self }
+	}
+
+	/// Returns the amplitude of the layer, from 0.0 to 1.0, at the
+	/// given blend value.
+	#[must_use]
+	pub fn amplitude(&self, blend: f64) -> f64 {
+		let fade_in = self.easing.apply(fade_progress(blend, self.fade_in));
+		let fade_out = self.easing.apply(1.0 - fade_progress(blend, self.fade_out));
+		fade_in * fade_out
+	}
+}
+
+impl Default for BlendCurve {
+	fn default() -> Self {
+		Self::FULL
+	}
+}
+
+/// Returns how far through `range` the blend value is, from 0.0 to 1.0.
+#[must_use]
+fn fade_progress(blend: f64, (start, end): (f64, f64)) -> f64 {
+	if blend >= end {
+		1.0
+	} else if blend <= start {
+		0.0
+	} else {
+		(blend - start) / (end - start)
+	}
+}
+
+command_writers_and_readers! {
+	set_blend: ValueChangeCommand<f64>,
+	set_volume: ValueChangeCommand<Decibels>,
+	set_panning: ValueChangeCommand<Panning>,
+	pause: Tween,
+	resume: (StartTime, Tween),
+	stop: Tween,
+}
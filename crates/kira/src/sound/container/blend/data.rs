@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use crate::{
+	Decibels, Panning, Tween, Value,
+	event::event_queue,
+	sound::{Sound, SoundData},
+};
+
+use super::{
+	super::{ContainerError, SharedPlaybackState},
+	BlendCurve, BlendSoundHandle, command_writers_and_readers,
+	sound::BlendSound,
+};
+
+/**
+A container that plays several layers at the same time and sets the
+volume of each layer from a single blend value.
+
+This is useful for engine sounds and music with layers of intensity.
+Each layer has a [`BlendCurve`] that decides how loud it is at each
+blend value. The blend value is a [`Value`], so it can be tweened with
+[`BlendSoundHandle::set_blend`] or linked to a [modulator](crate::modulator).
+
+All of the layers start on the same sample and keep playing while
+they're silent, so layers with the same length and loop region stay
+in sync.
+
+# Examples
+
+```no_run
+use kira::{
+	AudioManager, AudioManagerSettings, DefaultBackend,
+	sound::{
+		container::{BlendCurve, BlendSoundData},
+		static_sound::StaticSoundData,
+	},
+};
+
+let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+let idle = StaticSoundData::from_file("engine_idle.ogg")?.loop_region(..);
+let revving = StaticSoundData::from_file("engine_revving.ogg")?.loop_region(..);
+let mut engine = manager.play(
+	BlendSoundData::new(0.0)
+		.with_layer(idle, BlendCurve::fade_out(0.0, 1.0))
+		.with_layer(revving, BlendCurve::fade_in(0.0, 1.0)),
+)?;
+engine.set_blend(0.75, Default::default());
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+*/
+#[derive(Debug, Clone)]
+pub struct BlendSoundData<D> {
+	blend: Value<f64>,
+	layers: Vec<(D, BlendCurve)>,
+	volume: Value<Decibels>,
+	panning: Value<Panning>,
+	fade_in_tween: Option<Tween>,
+}
+
+impl<D> BlendSoundData<D> {
+	/// Creates a new [`BlendSoundData`] with no layers and the
+	/// given blend value.
+	#[must_use]
+	pub fn new(blend: impl Into<Value<f64>>) -> Self {
+		Self {
+			blend: blend.into(),
+			layers: vec![],
+			volume: Value::Fixed(Decibels::IDENTITY),
+			panning: Value::Fixed(Panning::CENTER),
+			fade_in_tween: None,
+		}
+	}
+
+	/// Adds a layer whose volume follows the given curve.
+	#[must_use = "This method consumes self and returns a modified BlendSoundData, so the return value should be used"]
+	pub fn with_layer(mut self, data: D, curve: BlendCurve) -> Self {
+		self.layers.push((data, curve));
+		self
+	}
+
+	/// Sets the volume of the container.
+	#[must_use = "This method consumes self and returns a modified BlendSoundData, so the return value should be used"]
+	pub fn volume(self, volume: impl Into<Value<Decibels>>) -> Self {
+		Self {
+			volume: volume.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets the panning of the container, where -1.0 is hard left
+	and 1.0 is hard right.
+	*/
+	#[must_use = "This method consumes self and returns a modified BlendSoundData, so the return value should be used"]
+	pub fn panning(self, panning: impl Into<Value<Panning>>) -> Self {
+		Self {
+			panning: panning.into(),
+			..self
+		}
+	}
+
+	/// Sets the tween used to fade in the container from silence.
+	#[must_use = "This method consumes self and returns a modified BlendSoundData, so the return value should be used"]
+	pub fn fade_in_tween(self, fade_in_tween: impl Into<Option<Tween>>) -> Self {
+		Self {
+			fade_in_tween: fade_in_tween.into(),
+			..self
+		}
+	}
+
+	/// Returns the number of layers in the container.
+	#[must_use]
+	pub fn num_layers(&self) -> usize {
+		self.layers.len()
+	}
+}
+
+impl<D: SoundData> SoundData for BlendSoundData<D> {
+	type Error = ContainerError<D::Error>;
+
+	type Handle = BlendSoundHandle<D::Handle>;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+		if self.layers.is_empty() {
+			return Err(ContainerError::NoEntries);
+		}
+		let mut sounds = Vec::with_capacity(self.layers.len());
+		let mut handles = Vec::with_capacity(self.layers.len());
+		for (data, curve) in self.layers {
+			let (sound, handle) = data.into_sound().map_err(ContainerError::IntoSoundError)?;
+			sounds.push((sound, curve));
+			handles.push(handle);
+		}
+		let (command_writers, command_readers) = command_writers_and_readers();
+		let (event_producer, event_consumer) = event_queue();
+		let shared = Arc::new(SharedPlaybackState::new());
+		let sound = BlendSound::new(
+			sounds,
+			self.blend,
+			self.volume,
+			self.panning,
+			self.fade_in_tween,
+			command_readers,
+			event_producer,
+			shared.clone(),
+		);
+		Ok((
+			Box::new(sound),
+			BlendSoundHandle {
+				layers: handles,
+				command_writers,
+				shared,
+				event_consumer,
+			},
+		))
+	}
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use crate::{
+	Decibels, Event, Panning, StartTime, Tween,
+	command::handle_param_setters,
+	event::EventConsumer,
+	sound::{PlaybackState, SoundEvent},
+};
+
+use super::{super::SharedPlaybackState, CommandWriters};
+
+/// Controls a blend container.
+#[derive(Debug)]
+pub struct BlendSoundHandle<H> {
+	pub(super) layers: Vec<H>,
+	pub(super) command_writers: CommandWriters,
+	pub(super) shared: Arc<SharedPlaybackState>,
+	pub(super) event_consumer: EventConsumer<SoundEvent>,
+}
+
+impl<H> BlendSoundHandle<H> {
+	/// Returns the current playback state of the container.
+	#[must_use]
+	pub fn state(&self) -> PlaybackState {
+		self.shared.state()
+	}
+
+	/// Returns the next event that happened to the container, if any.
+	///
+	/// Events are returned in the order they happened.
+	#[must_use]
+	pub fn pop_event(&mut self) -> Option<Event<SoundEvent>> {
+		self.event_consumer.pop()
+	}
+
+	/// Returns the handle of the layer at the given index, in the
+	/// order the layers were added.
+	#[must_use]
+	pub fn layer(&self, index: usize) -> Option<&H> {
+		self.layers.get(index)
+	}
+
+	/// Returns a mutable reference to the handle of the layer at the
+	/// given index, in the order the layers were added.
+	#[must_use]
+	pub fn layer_mut(&mut self, index: usize) -> Option<&mut H> {
+		self.layers.get_mut(index)
+	}
+
+	handle_param_setters! {
+		/// Sets the value that decides how loud each layer is.
+		blend: f64,
+
+		/// Sets the volume of the container.
+		volume: Decibels,
+
+		/// Sets the panning of the container, where `-1.0` is hard left,
+		/// `0.0` is center, and `1.0` is hard right.
+		panning: Panning,
+	}
+
+	/// Fades out the container to silence with the given tween and then
+	/// pauses playback.
+	pub fn pause(&mut self, tween: Tween) {
+		self.command_writers.pause.write(tween)
+	}
+
+	/// Resumes playback and fades in the container from silence
+	/// with the given tween.
+	pub fn resume(&mut self, tween: Tween) {
+		self.resume_at(StartTime::Immediate, tween)
+	}
+
+	/// Resumes playback at the given start time and fades in
+	/// the container from silence with the given tween.
+	pub fn resume_at(&mut self, start_time: StartTime, tween: Tween) {
+		self.command_writers.resume.write((start_time, tween))
+	}
+
+	/// Fades out the container to silence with the given tween and then
+	/// stops playback.
+	///
+	/// Once the container is stopped, it cannot be restarted.
+	pub fn stop(&mut self, tween: Tween) {
+		self.command_writers.stop.write(tween)
+	}
+}
@@ -0,0 +1,211 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::Arc;
+
+use crate::{
+	Decibels, Panning, Parameter, StartTime, Tween, Value,
+	command::read_commands_into_parameters,
+	event::EventProducer,
+	frame::Frame,
+	info::Info,
+	playback_state_manager::PlaybackStateManager,
+	sound::{PlaybackState, Sound, SoundEvent},
+	start_time::num_frames_before_start,
+};
+
+use super::{super::SharedPlaybackState, BlendCurve, CommandReaders};
+
+/// The number of frames of each layer that are rendered at a time
+/// before being mixed into the output.
+const LAYER_BUFFER_LENGTH: usize = 512;
+
+pub(super) struct BlendSound {
+	command_readers: CommandReaders,
+	layers: Vec<(Box<dyn Sound>, BlendCurve)>,
+	/// Space to render each layer into before mixing it with
+	/// the other layers.
+	layer_buffer: Vec<Frame>,
+	playback_state_manager: PlaybackStateManager,
+	started: bool,
+	blend: Parameter<f64>,
+	volume: Parameter<Decibels>,
+	panning: Parameter<Panning>,
+	shared: Arc<SharedPlaybackState>,
+	event_producer: EventProducer<SoundEvent>,
+}
+
+impl BlendSound {
+	#[allow(clippy::too_many_arguments)]
+	#[must_use]
+	pub fn new(
+		layers: Vec<(Box<dyn Sound>, BlendCurve)>,
+		blend: Value<f64>,
+		volume: Value<Decibels>,
+		panning: Value<Panning>,
+		fade_in_tween: Option<Tween>,
+		command_readers: CommandReaders,
+		event_producer: EventProducer<SoundEvent>,
+		shared: Arc<SharedPlaybackState>,
+	) -> Self {
+		Self {
+			command_readers,
+			layers,
+			layer_buffer: vec![Frame::ZERO; LAYER_BUFFER_LENGTH],
+			playback_state_manager: PlaybackStateManager::new(fade_in_tween),
+			started: false,
+			blend: Parameter::new(blend, 0.0),
+			volume: Parameter::new(volume, Decibels::IDENTITY),
+			panning: Parameter::new(panning, Panning::CENTER),
+			shared,
+			event_producer,
+		}
+	}
+
+	fn update_shared_playback_state(&mut self) {
+		self.shared
+			.set_state(self.playback_state_manager.playback_state());
+	}
+
+	fn pause(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.pause(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn resume(&mut self, start_time: StartTime, fade_in_tween: Tween) {
+		self.playback_state_manager
+			.resume(start_time, fade_in_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn stop(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.stop(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn read_commands(&mut self) {
+		read_commands_into_parameters!(self, blend, volume, panning);
+		if let Some(tween) = self.command_readers.pause.read() {
+			self.pause(tween);
+		}
+		if let Some((start_time, tween)) = self.command_readers.resume.read() {
+			self.resume(start_time, tween);
+		}
+		if let Some(tween) = self.command_readers.stop.read() {
+			self.stop(tween);
+		}
+	}
+}
+
+impl Sound for BlendSound {
+	fn init(&mut self, sample_rate: u32, internal_buffer_size: usize) {
+		for (sound, _) in &mut self.layers {
+			sound.init(sample_rate, internal_buffer_size);
+		}
+	}
+
+	fn on_change_sample_rate(&mut self, sample_rate: u32) {
+		for (sound, _) in &mut self.layers {
+			sound.on_change_sample_rate(sample_rate);
+		}
+	}
+
+	fn on_start_processing(&mut self) {
+		self.read_commands();
+		for (sound, _) in &mut self.layers {
+			sound.on_start_processing();
+		}
+	}
+
+	fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
+		// update parameters
+		self.blend.update(dt * out.len() as f64, info);
+		self.volume.update(dt * out.len() as f64, info);
+		self.panning.update(dt * out.len() as f64, info);
+		let changed_playback_state = self
+			.playback_state_manager
+			.update(dt * out.len() as f64, info);
+		if changed_playback_state {
+			self.update_shared_playback_state();
+			// the fade out finishes at the end of this batch
+			if self.playback_state_manager.playback_state() == PlaybackState::Stopped {
+				self.event_producer
+					.push(SoundEvent::Stopped, info.time() + dt * out.len() as f64);
+			}
+		}
+
+		if !self.started {
+			self.started = true;
+			self.event_producer.push(SoundEvent::Started, info.time());
+		}
+
+		out.fill(Frame::ZERO);
+		if !self.playback_state_manager.playback_state().is_advancing() {
+			return;
+		}
+
+		// if playback resumed partway through this batch, the frames
+		// before that point stay silent
+		let num_frames = out.len();
+		let start_frame =
+			num_frames_before_start(self.playback_state_manager.start_delay(), dt, num_frames);
+
+		// mix the layers. every layer keeps playing, even when it's
+		// silent, so the layers stay in sync
+		for (sound, curve) in &mut self.layers {
+			if sound.finished() {
+				continue;
+			}
+			let mut chunk_start = start_frame;
+			while chunk_start < num_frames {
+				let chunk_length = (num_frames - chunk_start).min(LAYER_BUFFER_LENGTH);
+				let layer_buffer = &mut self.layer_buffer[..chunk_length];
+				sound.process(
+					layer_buffer,
+					dt,
+					&info.for_sub_chunk(
+						chunk_start as f64 * dt,
+						(num_frames - chunk_start - chunk_length) as f64 * dt,
+					),
+				);
+				for (i, frame) in layer_buffer.iter().enumerate() {
+					let time_in_chunk = (chunk_start + i + 1) as f64 / num_frames as f64;
+					let amplitude = curve.amplitude(self.blend.interpolated_value(time_in_chunk));
+					out[chunk_start + i] += *frame * amplitude as f32;
+				}
+				chunk_start += chunk_length;
+			}
+		}
+		if self.layers.iter().all(|(sound, _)| sound.finished()) {
+			self.playback_state_manager.mark_as_stopped();
+			self.update_shared_playback_state();
+			self.event_producer
+				.push(SoundEvent::Finished, info.time() + dt * num_frames as f64);
+		}
+
+		for (i, frame) in out.iter_mut().enumerate().skip(start_frame) {
+			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
+			let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
+			let fade_volume = self
+				.playback_state_manager
+				.interpolated_fade_volume(time_in_chunk)
+				.as_amplitude();
+			let panning = self.panning.interpolated_value(time_in_chunk);
+			*frame = (*frame * fade_volume * volume).panned(panning);
+		}
+	}
+
+	fn remaining_duration(&self) -> Option<f64> {
+		// the container plays until its longest layer finishes
+		self.layers
+			.iter()
+			.filter(|(sound, _)| !sound.finished())
+			.try_fold(0.0f64, |remaining_duration, (sound, _)| {
+				Some(remaining_duration.max(sound.remaining_duration()?))
+			})
+	}
+
+	fn finished(&self) -> bool {
+		self.playback_state_manager.playback_state() == PlaybackState::Stopped
+	}
+}
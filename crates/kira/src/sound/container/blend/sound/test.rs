@@ -0,0 +1,104 @@
+use crate::{
+	Panning,
+	frame::Frame,
+	info::MockInfoBuilder,
+	sound::{
+		PlaybackState, SoundData, SoundEvent,
+		container::{BlendCurve, BlendSoundData},
+		static_sound::{StaticSoundData, StaticSoundSettings},
+	},
+};
+
+/// Creates a layer whose frames count up from 1 in steps of `step`.
+fn layer(step: f32, num_frames: usize) -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 1,
+		frames: (1..=num_frames)
+			.map(|i| Frame::from_mono(i as f32 * step))
+			.collect(),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+}
+
+fn expected(value: f32) -> Frame {
+	Frame::from_mono(value).panned(Panning::CENTER)
+}
+
+/// Tests that a `BlendSound` sets the volume of each layer
+/// based on its blend value.
+#[test]
+fn crossfades_layers() {
+	let data = BlendSoundData::new(0.0)
+		.with_layer(layer(1.0, 10), BlendCurve::fade_out(0.0, 1.0))
+		.with_layer(layer(10.0, 10), BlendCurve::fade_in(0.0, 1.0));
+	let (mut sound, mut handle) = data.into_sound().unwrap();
+	let info = MockInfoBuilder::new().build();
+
+	assert_eq!(sound.process_one(1.0, &info), expected(1.0));
+
+	handle.set_blend(1.0, Default::default());
+	sound.on_start_processing();
+	assert_eq!(sound.process_one(1.0, &info), expected(20.0));
+
+	handle.set_blend(0.5, Default::default());
+	sound.on_start_processing();
+	assert_eq!(
+		sound.process_one(1.0, &info),
+		expected(0.5 * 3.0 + 0.5 * 30.0)
+	);
+}
+
+/// Tests that silent layers of a `BlendSound` keep playing,
+/// so the layers stay in sync.
+#[test]
+fn silent_layers_stay_in_sync() {
+	let data = BlendSoundData::new(0.0)
+		.with_layer(layer(1.0, 10), BlendCurve::fade_out(0.0, 1.0))
+		.with_layer(layer(10.0, 10), BlendCurve::fade_in(0.0, 1.0));
+	let (mut sound, mut handle) = data.into_sound().unwrap();
+	let info = MockInfoBuilder::new().build();
+
+	for i in 1..=5 {
+		assert_eq!(sound.process_one(1.0, &info), expected(i as f32));
+	}
+	handle.set_blend(1.0, Default::default());
+	sound.on_start_processing();
+	assert_eq!(sound.process_one(1.0, &info), expected(60.0));
+}
+
+/// Tests that a `BlendSound` finishes when all of its layers finish.
+#[test]
+fn finishes_when_all_layers_finish() {
+	let data = BlendSoundData::new(0.0)
+		.with_layer(layer(1.0, 2), BlendCurve::FULL)
+		.with_layer(layer(1.0, 5), BlendCurve::FULL);
+	let (mut sound, mut handle) = data.into_sound().unwrap();
+	let info = MockInfoBuilder::new().build();
+
+	for _ in 0..3 {
+		sound.process_one(1.0, &info);
+	}
+	assert!(!sound.finished());
+	assert_eq!(handle.layer(0).unwrap().state(), PlaybackState::Stopped);
+	assert_eq!(handle.layer(1).unwrap().state(), PlaybackState::Playing);
+	for _ in 0..3 {
+		sound.process_one(1.0, &info);
+	}
+	assert!(sound.finished());
+	assert_eq!(handle.state(), PlaybackState::Stopped);
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.map(|event| event.kind)
+		.collect::<Vec<_>>();
+	assert_eq!(events, [SoundEvent::Started, SoundEvent::Finished]);
+}
+
+/// Tests that a `BlendSoundData` with no layers can't be played.
+#[test]
+fn requires_layers() {
+	assert!(
+		BlendSoundData::<StaticSoundData>::new(0.0)
+			.into_sound()
+			.is_err()
+	);
+}
@@ -0,0 +1,50 @@
+mod data;
+mod handle;
+mod sound;
+
+pub use data::*;
+pub use handle::*;
+
+use std::{
+	error::Error,
+	fmt::{Display, Formatter},
+};
+
+use crate::{
+	Decibels, Panning, StartTime, command::ValueChangeCommand, command_writers_and_readers,
+	tween::Tween,
+};
+
+/// Errors that can occur when adding an entry to a queue.
+#[derive(Debug)]
+pub enum QueueError<E> {
+	/// The queue can't hold any more entries that are waiting to play.
+	QueueFull,
+	/// An error occurred when initializing the entry.
+	IntoSoundError(E),
+}
+
+impl<E> Display for QueueError<E> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			QueueError::QueueFull => {
+				f.write_str("The queue can't hold any more entries that are waiting to play.")
+			}
+			QueueError::IntoSoundError(_) => {
+				f.write_str("An error occurred when initializing the entry.")
+			}
+		}
+	}
+}
+
+impl<E: std::fmt::Debug> Error for QueueError<E> {}
+
+command_writers_and_readers! {
+	set_volume: ValueChangeCommand<Decibels>,
+	set_panning: ValueChangeCommand<Panning>,
+	pause: Tween,
+	resume: (StartTime, Tween),
+	stop: Tween,
+	skip: usize,
+	clear: usize,
+}
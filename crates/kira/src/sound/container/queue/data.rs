@@ -0,0 +1,208 @@
+use std::{
+	marker::PhantomData,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use rtrb::RingBuffer;
+
+use crate::{
+	Decibels, Panning, Tween, Value,
+	event::event_queue,
+	sound::{Sound, SoundData},
+};
+
+use super::{
+	QueueSoundHandle, command_writers_and_readers,
+	sound::{QueueSound, Shared},
+};
+
+/// The number of entries that can be waiting in a queue
+/// if no capacity is set.
+const DEFAULT_CAPACITY: usize = 16;
+
+/**
+A container that plays its entries one after another with no gap
+between them, like a playlist.
+
+More entries can be added from the [`QueueSoundHandle`] while the
+queue is playing, and the handle can skip the current entry or clear
+the queue. Each entry can optionally crossfade into the next one.
+
+The next entry starts on the exact frame the current one ends as long
+as the current entry knows how much audio it has left, which is true
+for [static](crate::sound::static_sound) and [streaming](crate::sound::streaming)
+sounds that aren't looping forever. Other entries are followed by the
+next entry once they finish.
+
+When the queue runs out of entries, it plays silence until more are
+added. Once the [`QueueSoundHandle`] is dropped, the queue finishes after
+playing the entries it already has.
+
+# Examples
+
+```no_run
+use kira::{
+	AudioManager, AudioManagerSettings, DefaultBackend,
+	sound::{container::QueueSoundData, streaming::StreamingSoundData},
+};
+use std::time::Duration;
+
+let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+let mut radio = manager.play(
+	QueueSoundData::new()
+		.with_entry(StreamingSoundData::from_file("song_1.ogg")?)
+		.with_entry(StreamingSoundData::from_file("song_2.ogg")?)
+		.crossfade(Duration::from_secs(3)),
+)?;
+let song_3 = radio.append(StreamingSoundData::from_file("song_3.ogg")?)?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+*/
+#[derive(Debug, Clone)]
+pub struct QueueSoundData<D> {
+	entries: Vec<D>,
+	capacity: usize,
+	crossfade: Duration,
+	volume: Value<Decibels>,
+	panning: Value<Panning>,
+	fade_in_tween: Option<Tween>,
+}
+
+impl<D> QueueSoundData<D> {
+	/// Creates a new [`QueueSoundData`] with no entries.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			entries: vec![],
+			capacity: DEFAULT_CAPACITY,
+			crossfade: Duration::ZERO,
+			volume: Value::Fixed(Decibels::IDENTITY),
+			panning: Value::Fixed(Panning::CENTER),
+			fade_in_tween: None,
+		}
+	}
+
+	/// Adds an entry to the end of the queue.
+	#[must_use = "This method consumes self and returns a modified QueueSoundData, so the return value should be used"]
+	pub fn with_entry(mut self, data: D) -> Self {
+		self.entries.push(data);
+		self
+	}
+
+	/**
+	Sets the maximum number of entries that can be added with
+	[`QueueSoundHandle::append`] before the queue gets to them.
+
+	The queue can always hold all of the entries added with
+	[`QueueSoundData::with_entry`].
+	*/
+	#[must_use = "This method consumes self and returns a modified QueueSoundData, so the return value should be used"]
+	pub fn capacity(self, capacity: usize) -> Self {
+		Self { capacity, ..self }
+	}
+
+	/**
+	Sets how long each entry crossfades into the next one.
+
+	The next entry starts this long before the current one ends. When
+	an entry is skipped or the queue is cleared, the current entry fades
+	out over the same amount of time.
+	*/
+	#[must_use = "This method consumes self and returns a modified QueueSoundData, so the return value should be used"]
+	pub fn crossfade(self, crossfade: Duration) -> Self {
+		Self { crossfade, ..self }
+	}
+
+	/// Sets the volume of the container.
+	#[must_use = "This method consumes self and returns a modified QueueSoundData, so the return value should be used"]
+	pub fn volume(self, volume: impl Into<Value<Decibels>>) -> Self {
+		Self {
+			volume: volume.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets the panning of the container, where -1.0 is hard left
+	and 1.0 is hard right.
+	*/
+	#[must_use = "This method consumes self and returns a modified QueueSoundData, so the return value should be used"]
+	pub fn panning(self, panning: impl Into<Value<Panning>>) -> Self {
+		Self {
+			panning: panning.into(),
+			..self
+		}
+	}
+
+	/// Sets the tween used to fade in the container from silence.
+	#[must_use = "This method consumes self and returns a modified QueueSoundData, so the return value should be used"]
+	pub fn fade_in_tween(self, fade_in_tween: impl Into<Option<Tween>>) -> Self {
+		Self {
+			fade_in_tween: fade_in_tween.into(),
+			..self
+		}
+	}
+
+	/// Returns the number of entries in the container.
+	#[must_use]
+	pub fn num_entries(&self) -> usize {
+		self.entries.len()
+	}
+}
+
+impl<D> Default for QueueSoundData<D> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<D: SoundData> SoundData for QueueSoundData<D> {
+	type Error = D::Error;
+
+	type Handle = QueueSoundHandle<D>;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+		let num_entries = self.entries.len();
+		let mut sounds = Vec::with_capacity(self.capacity.max(num_entries));
+		for data in self.entries {
+			let (sound, _) = data.into_sound()?;
+			sounds.push(sound);
+		}
+		let (new_entry_producer, new_entry_consumer) = RingBuffer::new(self.capacity);
+		// every entry ends up here once it's done playing, so this
+		// needs room for all of the entries the queue can hold, plus
+		// the entry that's fading out
+		let (unused_entry_producer, unused_entry_consumer) =
+			RingBuffer::new(sounds.capacity() + self.capacity + 1);
+		let (command_writers, command_readers) = command_writers_and_readers();
+		let (event_producer, event_consumer) = event_queue();
+		let shared = Arc::new(Shared::new());
+		let sound = QueueSound::new(
+			sounds,
+			self.crossfade,
+			self.volume,
+			self.panning,
+			self.fade_in_tween,
+			command_readers,
+			new_entry_consumer,
+			unused_entry_producer,
+			event_producer,
+			shared.clone(),
+		);
+		Ok((
+			Box::new(sound),
+			QueueSoundHandle {
+				command_writers,
+				shared,
+				new_entry_producer: Mutex::new(new_entry_producer),
+				unused_entry_consumer: Mutex::new(unused_entry_consumer),
+				event_consumer,
+				num_entries_added: num_entries,
+				num_skips: 0,
+				data: PhantomData,
+			},
+		))
+	}
+}
@@ -0,0 +1,181 @@
+use std::{
+	fmt::{Debug, Formatter},
+	marker::PhantomData,
+	sync::{Arc, Mutex},
+};
+
+use rtrb::{Consumer, Producer};
+
+use crate::{
+	Decibels, Event, Panning, StartTime, Tween,
+	command::handle_param_setters,
+	event::EventConsumer,
+	sound::{PlaybackState, Sound, SoundData, SoundEvent},
+};
+
+use super::{CommandWriters, QueueError, sound::Shared};
+
+/// Controls a queue container.
+pub struct QueueSoundHandle<D> {
+	pub(super) command_writers: CommandWriters,
+	pub(super) shared: Arc<Shared>,
+	pub(super) new_entry_producer: Mutex<Producer<Box<dyn Sound>>>,
+	pub(super) unused_entry_consumer: Mutex<Consumer<Box<dyn Sound>>>,
+	pub(super) event_consumer: EventConsumer<SoundEvent>,
+	/// The total number of entries that have been added to the queue,
+	/// including the ones it started with.
+	pub(super) num_entries_added: usize,
+	/// The total number of times [`QueueSoundHandle::skip`] has been called.
+	pub(super) num_skips: usize,
+	pub(super) data: PhantomData<fn(D)>,
+}
+
+impl<D> QueueSoundHandle<D> {
+	/// Returns the current playback state of the container.
+	#[must_use]
+	pub fn state(&self) -> PlaybackState {
+		self.shared.state()
+	}
+
+	/// Returns the next event that happened to the container, if any.
+	///
+	/// Events are returned in the order they happened.
+	#[must_use]
+	pub fn pop_event(&mut self) -> Option<Event<SoundEvent>> {
+		self.event_consumer.pop()
+	}
+
+	/**
+	Returns the index of the entry that's currently playing, counting
+	every entry that has been added to the queue in order.
+
+	Once every entry has played, this is equal to the total number of
+	entries that have been added.
+	*/
+	#[must_use]
+	pub fn current_index(&self) -> usize {
+		self.shared.current_index()
+	}
+
+	/// Returns the number of entries that haven't finished playing yet,
+	/// including the one that's currently playing.
+	#[must_use]
+	pub fn num_entries(&self) -> usize {
+		self.num_entries_added.saturating_sub(self.current_index())
+	}
+
+	/**
+	Adds an entry to the end of the queue and returns the entry's handle.
+
+	If the queue is already holding as many waiting entries as its
+	[capacity](super::QueueSoundData::capacity) allows, this returns
+	[`QueueError::QueueFull`].
+	*/
+	pub fn append(&mut self, data: D) -> Result<D::Handle, QueueError<D::Error>>
+	where
+		D: SoundData,
+	{
+		self.drop_unused_entries();
+		let new_entry_producer = self
+			.new_entry_producer
+			.get_mut()
+			.expect("new entry producer mutex poisoned");
+		if new_entry_producer.is_full() {
+			return Err(QueueError::QueueFull);
+		}
+		let (sound, handle) = data.into_sound().map_err(QueueError::IntoSoundError)?;
+		new_entry_producer
+			.push(sound)
+			.unwrap_or_else(|_| panic!("new entry producer is full"));
+		self.num_entries_added += 1;
+		Ok(handle)
+	}
+
+	/// Stops the current entry and starts the next one, crossfading
+	/// between them if the queue has a crossfade duration.
+	pub fn skip(&mut self) {
+		self.drop_unused_entries();
+		self.num_skips += 1;
+		self.command_writers.skip.write(self.num_skips)
+	}
+
+	/**
+	Removes every entry that has been added to the queue so far,
+	including the current entry, which fades out if the queue has
+	a crossfade duration.
+
+	The queue keeps playing and will play entries that are appended
+	afterward.
+	*/
+	pub fn clear(&mut self) {
+		self.drop_unused_entries();
+		self.command_writers.clear.write(self.num_entries_added)
+	}
+
+	handle_param_setters! {
+		/// Sets the volume of the container.
+		volume: Decibels,
+
+		/// Sets the panning of the container, where `-1.0` is hard left,
+		/// `0.0` is center, and `1.0` is hard right.
+		panning: Panning,
+	}
+
+	/// Fades out the container to silence with the given tween and then
+	/// pauses playback.
+	pub fn pause(&mut self, tween: Tween) {
+		self.command_writers.pause.write(tween)
+	}
+
+	/// Resumes playback and fades in the container from silence
+	/// with the given tween.
+	pub fn resume(&mut self, tween: Tween) {
+		self.resume_at(StartTime::Immediate, tween)
+	}
+
+	/// Resumes playback at the given start time and fades in
+	/// the container from silence with the given tween.
+	pub fn resume_at(&mut self, start_time: StartTime, tween: Tween) {
+		self.command_writers.resume.write((start_time, tween))
+	}
+
+	/// Fades out the container to silence with the given tween and then
+	/// stops playback.
+	///
+	/// Once the container is stopped, it cannot be restarted.
+	pub fn stop(&mut self, tween: Tween) {
+		self.command_writers.stop.write(tween)
+	}
+
+	/// Drops entries the queue is done with on this thread, since
+	/// dropping them on the audio thread could be slow.
+	fn drop_unused_entries(&mut self) {
+		let unused_entry_consumer = self
+			.unused_entry_consumer
+			.get_mut()
+			.expect("unused entry consumer mutex poisoned");
+		while unused_entry_consumer.pop().is_ok() {}
+	}
+}
+
+impl<D> Debug for QueueSoundHandle<D> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("QueueSoundHandle")
+			.field("command_writers", &self.command_writers)
+			.field("shared", &self.shared)
+			.field("new_entry_producer", &EntryRingBufferDebug)
+			.field("unused_entry_consumer", &EntryRingBufferDebug)
+			.field("event_consumer", &self.event_consumer)
+			.field("num_entries_added", &self.num_entries_added)
+			.field("num_skips", &self.num_skips)
+			.finish()
+	}
+}
+
+struct EntryRingBufferDebug;
+
+impl Debug for EntryRingBufferDebug {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("RingBuffer").finish()
+	}
+}
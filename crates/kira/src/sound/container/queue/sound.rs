@@ -0,0 +1,433 @@
+#[cfg(test)]
+mod test;
+
+use std::{
+	collections::VecDeque,
+	f64::consts::FRAC_PI_2,
+	sync::{
+		Arc,
+		atomic::{AtomicUsize, Ordering},
+	},
+	time::Duration,
+};
+
+use rtrb::{Consumer, Producer};
+
+use crate::{
+	Decibels, Panning, Parameter, StartTime, Tween, Value,
+	command::read_commands_into_parameters,
+	event::EventProducer,
+	frame::Frame,
+	info::Info,
+	playback_state_manager::PlaybackStateManager,
+	sound::{PlaybackState, Sound, SoundEvent},
+	start_time::num_frames_before_start,
+};
+
+use super::{super::SharedPlaybackState, CommandReaders};
+
+/// The number of frames of each entry that are rendered at a time
+/// before being mixed into the output.
+const ENTRY_BUFFER_LENGTH: usize = 512;
+
+pub(super) struct QueueSound {
+	command_readers: CommandReaders,
+	/// The entries that haven't finished playing. The first entry
+	/// is the one that's currently playing.
+	entries: VecDeque<Box<dyn Sound>>,
+	/// The maximum number of entries that can be held in `entries`.
+	max_entries: usize,
+	/// The entry that's fading out while the current entry fades in.
+	outgoing: Option<Outgoing>,
+	new_entry_consumer: Consumer<Box<dyn Sound>>,
+	unused_entry_producer: Producer<Box<dyn Sound>>,
+	/// Space to render the current entry into before mixing it with
+	/// the outgoing entry.
+	entry_buffer: Vec<Frame>,
+	/// Space to render the outgoing entry into.
+	outgoing_buffer: Vec<Frame>,
+	crossfade: Duration,
+	sample_rate: u32,
+	internal_buffer_size: usize,
+	current_index: usize,
+	num_skips_handled: usize,
+	playback_state_manager: PlaybackStateManager,
+	started: bool,
+	volume: Parameter<Decibels>,
+	panning: Parameter<Panning>,
+	shared: Arc<Shared>,
+	event_producer: EventProducer<SoundEvent>,
+}
+
+impl QueueSound {
+	#[allow(clippy::too_many_arguments)]
+	#[must_use]
+	pub fn new(
+		entries: Vec<Box<dyn Sound>>,
+		crossfade: Duration,
+		volume: Value<Decibels>,
+		panning: Value<Panning>,
+		fade_in_tween: Option<Tween>,
+		command_readers: CommandReaders,
+		new_entry_consumer: Consumer<Box<dyn Sound>>,
+		unused_entry_producer: Producer<Box<dyn Sound>>,
+		event_producer: EventProducer<SoundEvent>,
+		shared: Arc<Shared>,
+	) -> Self {
+		let max_entries = entries.capacity();
+		let mut entries_deque = VecDeque::with_capacity(max_entries);
+		entries_deque.extend(entries);
+		Self {
+			command_readers,
+			entries: entries_deque,
+			max_entries,
+			outgoing: None,
+			new_entry_consumer,
+			unused_entry_producer,
+			entry_buffer: vec![Frame::ZERO; ENTRY_BUFFER_LENGTH],
+			outgoing_buffer: vec![Frame::ZERO; ENTRY_BUFFER_LENGTH],
+			crossfade,
+			sample_rate: 0,
+			internal_buffer_size: 0,
+			current_index: 0,
+			num_skips_handled: 0,
+			playback_state_manager: PlaybackStateManager::new(fade_in_tween),
+			started: false,
+			volume: Parameter::new(volume, Decibels::IDENTITY),
+			panning: Parameter::new(panning, Panning::CENTER),
+			shared,
+			event_producer,
+		}
+	}
+
+	fn update_shared_playback_state(&mut self) {
+		self.shared
+			.set_state(self.playback_state_manager.playback_state());
+	}
+
+	fn pause(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.pause(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn resume(&mut self, start_time: StartTime, fade_in_tween: Tween) {
+		self.playback_state_manager
+			.resume(start_time, fade_in_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn stop(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.stop(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn read_commands(&mut self) {
+		read_commands_into_parameters!(self, volume, panning);
+		if let Some(tween) = self.command_readers.pause.read() {
+			self.pause(tween);
+		}
+		if let Some((start_time, tween)) = self.command_readers.resume.read() {
+			self.resume(start_time, tween);
+		}
+		if let Some(tween) = self.command_readers.stop.read() {
+			self.stop(tween);
+		}
+		if let Some(num_skips) = self.command_readers.skip.read() {
+			for _ in self.num_skips_handled..num_skips {
+				self.end_current_entry(true);
+			}
+			self.num_skips_handled = num_skips;
+		}
+		if let Some(num_entries_added) = self.command_readers.clear.read() {
+			let mut fade_out = true;
+			while self.current_index < num_entries_added {
+				if !self.end_current_entry(fade_out) {
+					break;
+				}
+				fade_out = false;
+			}
+		}
+	}
+
+	/// Moves entries that were appended from the handle into the queue.
+	fn receive_new_entries(&mut self) {
+		while self.entries.len() < self.max_entries {
+			let Ok(mut sound) = self.new_entry_consumer.pop() else {
+				break;
+			};
+			sound.init(self.sample_rate, self.internal_buffer_size);
+			self.entries.push_back(sound);
+		}
+	}
+
+	/**
+	Removes the current entry from the queue and moves on to the next one.
+
+	If `fade_out` is `true` and the queue has a crossfade duration,
+	the entry crossfades into the next one. Otherwise, it stops
+	immediately.
+
+	Returns `false` if there was no entry to remove.
+	*/
+	fn end_current_entry(&mut self, fade_out: bool) -> bool {
+		if self.entries.is_empty() {
+			self.receive_new_entries();
+		}
+		let Some(sound) = self.entries.pop_front() else {
+			return false;
+		};
+		self.current_index += 1;
+		self.shared.set_current_index(self.current_index);
+		let crossfade_length = self.crossfade_length();
+		if fade_out && crossfade_length > 0 {
+			self.start_crossfade(sound, crossfade_length);
+		} else {
+			self.discard(sound);
+		}
+		self.receive_new_entries();
+		true
+	}
+
+	/// Fades out the given entry over the given number of frames
+	/// while the next entry fades in.
+	fn start_crossfade(&mut self, sound: Box<dyn Sound>, length: usize) {
+		if let Some(Outgoing { sound, .. }) = self.outgoing.take() {
+			self.discard(sound);
+		}
+		self.outgoing = Some(Outgoing {
+			sound,
+			elapsed: 0,
+			length,
+		});
+	}
+
+	/// Sends an entry back to the handle to be dropped.
+	fn discard(&mut self, sound: Box<dyn Sound>) {
+		self.unused_entry_producer
+			.push(sound)
+			.unwrap_or_else(|_| panic!("unused entry producer is full"));
+	}
+
+	/// Returns the length of the crossfade in frames.
+	#[must_use]
+	fn crossfade_length(&self) -> usize {
+		(self.crossfade.as_secs_f64() * self.sample_rate as f64).round() as usize
+	}
+
+	/// Returns the number of frames that can be rendered from the current
+	/// entry before it needs to start crossfading into the next one, or
+	/// `None` if the current entry doesn't know when it will end.
+	#[must_use]
+	fn num_frames_until_transition(&self, dt: f64) -> Option<usize> {
+		if self.outgoing.is_some() || self.entries.len() < 2 {
+			return None;
+		}
+		let remaining_duration = self.entries.front()?.remaining_duration()?;
+		let remaining_frames = (remaining_duration / dt).round() as usize;
+		Some(remaining_frames.saturating_sub(self.crossfade_length()))
+	}
+
+	/// Renders the current entry and the outgoing entry into `out`.
+	fn render_entries(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
+		let mut chunk_start = 0;
+		while chunk_start < out.len() {
+			// move on from entries that have finished on their own
+			if let Some(outgoing) = &self.outgoing {
+				if outgoing.sound.finished() || outgoing.elapsed >= outgoing.length {
+					let Outgoing { sound, .. } = self.outgoing.take().unwrap();
+					self.discard(sound);
+				}
+			}
+			if self.entries.front().is_some_and(|sound| sound.finished()) {
+				self.end_current_entry(false);
+				continue;
+			}
+			let mut chunk_length = (out.len() - chunk_start).min(ENTRY_BUFFER_LENGTH);
+			match self.num_frames_until_transition(dt) {
+				// the next entry should start right now
+				Some(0) => {
+					let sound = self.entries.pop_front().unwrap();
+					self.current_index += 1;
+					self.shared.set_current_index(self.current_index);
+					let remaining_frames =
+						(sound.remaining_duration().unwrap_or_default() / dt).round() as usize;
+					self.start_crossfade(sound, remaining_frames);
+					continue;
+				}
+				Some(num_frames) => chunk_length = chunk_length.min(num_frames),
+				None => {}
+			}
+			if let Some(outgoing) = &self.outgoing {
+				chunk_length = chunk_length.min(outgoing.length - outgoing.elapsed);
+			}
+			let info = info.for_sub_chunk(
+				chunk_start as f64 * dt,
+				(out.len() - chunk_start - chunk_length) as f64 * dt,
+			);
+			let out = &mut out[chunk_start..chunk_start + chunk_length];
+			if let Some(sound) = self.entries.front_mut() {
+				let entry_buffer = &mut self.entry_buffer[..chunk_length];
+				sound.process(entry_buffer, dt, &info);
+				out.copy_from_slice(entry_buffer);
+			}
+			if let Some(outgoing) = &mut self.outgoing {
+				let outgoing_buffer = &mut self.outgoing_buffer[..chunk_length];
+				outgoing.sound.process(outgoing_buffer, dt, &info);
+				// equal-power crossfade
+				for (i, (frame, outgoing_frame)) in
+					out.iter_mut().zip(outgoing_buffer.iter()).enumerate()
+				{
+					let progress = (outgoing.elapsed + i + 1) as f64 / outgoing.length as f64;
+					let fade_in = (progress * FRAC_PI_2).sin() as f32;
+					let fade_out = (progress * FRAC_PI_2).cos() as f32;
+					*frame = *frame * fade_in + *outgoing_frame * fade_out;
+				}
+				outgoing.elapsed += chunk_length;
+			}
+			chunk_start += chunk_length;
+		}
+	}
+}
+
+impl Sound for QueueSound {
+	fn init(&mut self, sample_rate: u32, internal_buffer_size: usize) {
+		self.sample_rate = sample_rate;
+		self.internal_buffer_size = internal_buffer_size;
+		for sound in &mut self.entries {
+			sound.init(sample_rate, internal_buffer_size);
+		}
+	}
+
+	fn on_change_sample_rate(&mut self, sample_rate: u32) {
+		self.sample_rate = sample_rate;
+		for sound in &mut self.entries {
+			sound.on_change_sample_rate(sample_rate);
+		}
+		if let Some(outgoing) = &mut self.outgoing {
+			outgoing.sound.on_change_sample_rate(sample_rate);
+		}
+	}
+
+	fn on_start_processing(&mut self) {
+		self.receive_new_entries();
+		self.read_commands();
+		for sound in &mut self.entries {
+			sound.on_start_processing();
+		}
+		if let Some(outgoing) = &mut self.outgoing {
+			outgoing.sound.on_start_processing();
+		}
+	}
+
+	fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
+		// update parameters
+		self.volume.update(dt * out.len() as f64, info);
+		self.panning.update(dt * out.len() as f64, info);
+		let changed_playback_state = self
+			.playback_state_manager
+			.update(dt * out.len() as f64, info);
+		if changed_playback_state {
+			self.update_shared_playback_state();
+			// the fade out finishes at the end of this batch
+			if self.playback_state_manager.playback_state() == PlaybackState::Stopped {
+				self.event_producer
+					.push(SoundEvent::Stopped, info.time() + dt * out.len() as f64);
+			}
+		}
+
+		if !self.started {
+			self.started = true;
+			self.event_producer.push(SoundEvent::Started, info.time());
+		}
+
+		out.fill(Frame::ZERO);
+		if !self.playback_state_manager.playback_state().is_advancing() {
+			return;
+		}
+
+		// if playback resumed partway through this batch, the frames
+		// before that point stay silent
+		let num_frames = out.len();
+		let start_frame =
+			num_frames_before_start(self.playback_state_manager.start_delay(), dt, num_frames);
+
+		self.render_entries(
+			&mut out[start_frame..],
+			dt,
+			&info.for_sub_chunk(start_frame as f64 * dt, 0.0),
+		);
+		if self.entries.front().is_some_and(|sound| sound.finished()) {
+			self.end_current_entry(false);
+		}
+		// once the handle is dropped, no more entries can be added, so the
+		// queue is done after it plays the ones it has
+		if self.entries.is_empty()
+			&& self.outgoing.is_none()
+			&& self.new_entry_consumer.is_abandoned()
+			&& self.new_entry_consumer.is_empty()
+		{
+			self.playback_state_manager.mark_as_stopped();
+			self.update_shared_playback_state();
+			self.event_producer
+				.push(SoundEvent::Finished, info.time() + dt * num_frames as f64);
+		}
+
+		for (i, frame) in out.iter_mut().enumerate().skip(start_frame) {
+			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
+			let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
+			let fade_volume = self
+				.playback_state_manager
+				.interpolated_fade_volume(time_in_chunk)
+				.as_amplitude();
+			let panning = self.panning.interpolated_value(time_in_chunk);
+			*frame = (*frame * fade_volume * volume).panned(panning);
+		}
+	}
+
+	fn finished(&self) -> bool {
+		self.playback_state_manager.playback_state() == PlaybackState::Stopped
+	}
+}
+
+/// An entry that's fading out.
+struct Outgoing {
+	sound: Box<dyn Sound>,
+	/// How many frames of the crossfade have been rendered.
+	elapsed: usize,
+	/// The length of the crossfade in frames.
+	length: usize,
+}
+
+#[derive(Debug)]
+pub(super) struct Shared {
+	state: SharedPlaybackState,
+	current_index: AtomicUsize,
+}
+
+impl Shared {
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			state: SharedPlaybackState::new(),
+			current_index: AtomicUsize::new(0),
+		}
+	}
+
+	#[must_use]
+	pub fn state(&self) -> PlaybackState {
+		self.state.state()
+	}
+
+	pub fn set_state(&self, state: PlaybackState) {
+		self.state.set_state(state);
+	}
+
+	#[must_use]
+	pub fn current_index(&self) -> usize {
+		self.current_index.load(Ordering::SeqCst)
+	}
+
+	pub fn set_current_index(&self, current_index: usize) {
+		self.current_index.store(current_index, Ordering::SeqCst);
+	}
+}
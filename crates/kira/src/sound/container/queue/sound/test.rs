@@ -0,0 +1,151 @@
+use std::{f32::consts::FRAC_1_SQRT_2, time::Duration};
+
+use crate::{
+	Panning,
+	frame::Frame,
+	info::MockInfoBuilder,
+	sound::{
+		PlaybackState, SoundData, SoundEvent,
+		container::QueueSoundData,
+		static_sound::{StaticSoundData, StaticSoundSettings},
+	},
+};
+
+fn entry(value: f32, num_frames: usize) -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 1,
+		frames: vec![Frame::from_mono(value); num_frames].into(),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+}
+
+fn expected(values: &[f32]) -> Vec<Frame> {
+	values
+		.iter()
+		.map(|value| Frame::from_mono(*value).panned(Panning::CENTER))
+		.collect()
+}
+
+/// Tests that a `QueueSound` plays each entry right after
+/// the previous one ends.
+#[test]
+fn plays_entries_without_gaps() {
+	let data = QueueSoundData::new()
+		.with_entry(entry(1.0, 3))
+		.with_entry(entry(2.0, 3));
+	let (mut sound, _handle) = data.into_sound().unwrap();
+	sound.init(1, 8);
+	let info = MockInfoBuilder::new().build();
+
+	let mut out = vec![Frame::ZERO; 8];
+	sound.process(&mut out, 1.0, &info);
+	assert_eq!(out, expected(&[1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 0.0, 0.0]));
+}
+
+/// Tests that a `QueueSound` crossfades between entries
+/// with an equal-power curve.
+#[test]
+fn crossfades_between_entries() {
+	let data = QueueSoundData::new()
+		.with_entry(entry(1.0, 4))
+		.with_entry(entry(2.0, 4))
+		.crossfade(Duration::from_secs(2));
+	let (mut sound, _handle) = data.into_sound().unwrap();
+	sound.init(1, 8);
+	let info = MockInfoBuilder::new().build();
+
+	let mut out = vec![Frame::ZERO; 6];
+	sound.process(&mut out, 1.0, &info);
+	assert_eq!(
+		out,
+		expected(&[1.0, 1.0, 3.0 * FRAC_1_SQRT_2, 2.0, 2.0, 2.0])
+	);
+}
+
+/// Tests that entries can be appended, skipped, and cleared
+/// from a `QueueSoundHandle`.
+#[test]
+fn entries_can_be_appended_skipped_and_cleared() {
+	let data = QueueSoundData::new().with_entry(entry(1.0, 10));
+	let (mut sound, mut handle) = data.into_sound().unwrap();
+	sound.init(1, 8);
+	let info = MockInfoBuilder::new().build();
+
+	assert_eq!(sound.process_one(1.0, &info), expected(&[1.0])[0]);
+	handle.append(entry(2.0, 10)).unwrap();
+	handle.append(entry(3.0, 10)).unwrap();
+	handle.append(entry(4.0, 10)).unwrap();
+	sound.on_start_processing();
+	assert_eq!(handle.num_entries(), 4);
+	assert_eq!(sound.process_one(1.0, &info), expected(&[1.0])[0]);
+
+	handle.skip();
+	sound.on_start_processing();
+	assert_eq!(handle.current_index(), 1);
+	assert_eq!(sound.process_one(1.0, &info), expected(&[2.0])[0]);
+
+	// skipping more than once between batches skips every time
+	handle.skip();
+	handle.skip();
+	sound.on_start_processing();
+	assert_eq!(handle.current_index(), 3);
+	assert_eq!(sound.process_one(1.0, &info), expected(&[4.0])[0]);
+
+	handle.clear();
+	handle.append(entry(5.0, 10)).unwrap();
+	sound.on_start_processing();
+	assert_eq!(handle.current_index(), 4);
+	assert_eq!(handle.num_entries(), 1);
+	assert_eq!(sound.process_one(1.0, &info), expected(&[5.0])[0]);
+}
+
+/// Tests that a `QueueSoundHandle` refuses new entries when
+/// the queue is at capacity.
+#[test]
+fn append_fails_when_full() {
+	let data = QueueSoundData::new().capacity(1);
+	let (_sound, mut handle) = data.into_sound().unwrap();
+	handle.append(entry(1.0, 1)).unwrap();
+	assert!(handle.append(entry(1.0, 1)).is_err());
+}
+
+/// Tests that a `QueueSound` keeps waiting for new entries until
+/// its handle is dropped.
+#[test]
+fn finishes_after_handle_is_dropped() {
+	let data = QueueSoundData::new().with_entry(entry(1.0, 2));
+	let (mut sound, mut handle) = data.into_sound().unwrap();
+	sound.init(1, 8);
+	let info = MockInfoBuilder::new().build();
+
+	for _ in 0..5 {
+		sound.process_one(1.0, &info);
+	}
+	assert!(!sound.finished());
+	assert_eq!(handle.current_index(), 1);
+	assert_eq!(handle.num_entries(), 0);
+
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.map(|event| event.kind)
+		.collect::<Vec<_>>();
+	assert_eq!(events, [SoundEvent::Started]);
+	drop(handle);
+	sound.process_one(1.0, &info);
+	assert!(sound.finished());
+}
+
+/// Tests that a `QueueSound` can be paused and resumed.
+#[test]
+fn pauses_and_resumes() {
+	let data = QueueSoundData::new().with_entry(entry(1.0, 10));
+	let (mut sound, mut handle) = data.into_sound().unwrap();
+	sound.init(1, 8);
+	let info = MockInfoBuilder::new().build();
+
+	handle.pause(Default::default());
+	sound.on_start_processing();
+	sound.process_one(1.0, &info);
+	assert_eq!(handle.state(), PlaybackState::Paused);
+	assert_eq!(sound.process_one(1.0, &info), Frame::ZERO);
+}
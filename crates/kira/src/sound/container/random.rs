@@ -0,0 +1,168 @@
+use std::{
+	collections::VecDeque,
+	sync::{Arc, Mutex},
+};
+
+use crate::{
+	random::Random,
+	sound::{Sound, SoundData},
+};
+
+use super::ContainerError;
+
+/**
+A container that plays a randomly chosen entry each time it's played.
+
+Each entry has a weight, and entries with higher weights are chosen
+more often. Clones of a [`RandomSoundData`] share their random number
+generator and their history of which entries were played, so the
+no-repeat window works across clones.
+*/
+#[derive(Debug, Clone)]
+pub struct RandomSoundData<D> {
+	entries: Vec<(D, f64)>,
+	no_repeat_window: usize,
+	state: Arc<Mutex<RandomState>>,
+}
+
+impl<D> RandomSoundData<D> {
+	/// Creates a new [`RandomSoundData`] with no entries.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			entries: vec![],
+			no_repeat_window: 0,
+			state: Arc::new(Mutex::new(RandomState::new(Random::new()))),
+		}
+	}
+
+	/// Adds an entry that's chosen with the given weight relative
+	/// to the other entries.
+	#[must_use = "This method consumes self and returns a modified RandomSoundData, so the return value should be used"]
+	pub fn with_entry(mut self, data: D, weight: f64) -> Self {
+		self.entries.push((data, weight));
+		self
+	}
+
+	/**
+	Sets how many of the most recently played entries can't be
+	chosen again.
+
+	For example, a window of `1` means the same entry is never
+	played twice in a row. The window is limited to one less than
+	the number of entries, so there's always an entry to choose.
+	*/
+	#[must_use = "This method consumes self and returns a modified RandomSoundData, so the return value should be used"]
+	pub fn no_repeat_window(self, no_repeat_window: usize) -> Self {
+		Self {
+			no_repeat_window,
+			..self
+		}
+	}
+
+	/**
+	Sets the seed for the random number generator, so the same
+	entries are chosen in the same order every time.
+
+	This also forgets which entries were played before, and stops
+	sharing that history with previous clones.
+	*/
+	#[must_use = "This method consumes self and returns a modified RandomSoundData, so the return value should be used"]
+	pub fn seed(self, seed: u64) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(RandomState::new(Random::with_seed(seed)))),
+			..self
+		}
+	}
+
+	/// Returns the number of entries in the container.
+	#[must_use]
+	pub fn num_entries(&self) -> usize {
+		self.entries.len()
+	}
+}
+
+impl<D> Default for RandomSoundData<D> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<D: SoundData> SoundData for RandomSoundData<D> {
+	type Error = ContainerError<D::Error>;
+
+	type Handle = D::Handle;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(mut self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+		if self.entries.is_empty() {
+			return Err(ContainerError::NoEntries);
+		}
+		let index = self
+			.state
+			.lock()
+			.expect("random container state mutex poisoned")
+			.choose(&self.entries, self.no_repeat_window);
+		let (data, _) = self.entries.swap_remove(index);
+		data.into_sound().map_err(ContainerError::IntoSoundError)
+	}
+}
+
+#[derive(Debug)]
+struct RandomState {
+	random: Random,
+	/// The indices of the most recently played entries,
+	/// from oldest to newest.
+	history: VecDeque<usize>,
+}
+
+impl RandomState {
+	#[must_use]
+	fn new(random: Random) -> Self {
+		Self {
+			random,
+			history: VecDeque::new(),
+		}
+	}
+
+	/// Picks the index of the next entry to play and adds
+	/// it to the history.
+	#[must_use]
+	fn choose<D>(&mut self, entries: &[(D, f64)], no_repeat_window: usize) -> usize {
+		let no_repeat_window = no_repeat_window.min(entries.len() - 1);
+		while self.history.len() > no_repeat_window {
+			self.history.pop_front();
+		}
+		let candidates = || {
+			entries
+				.iter()
+				.enumerate()
+				.filter(|(index, _)| !self.history.contains(index))
+				.map(|(index, (_, weight))| (index, weight.max(0.0)))
+		};
+		let total_weight: f64 = candidates().map(|(_, weight)| weight).sum();
+		let chosen = if total_weight > 0.0 {
+			let mut target = self.random.unipolar() * total_weight;
+			let mut chosen = None;
+			for (index, weight) in candidates().filter(|(_, weight)| *weight > 0.0) {
+				chosen = Some(index);
+				if target < weight {
+					break;
+				}
+				target -= weight;
+			}
+			chosen
+		} else {
+			// if every weight is 0, fall back to the first candidate
+			candidates().next().map(|(index, _)| index)
+		};
+		let chosen = chosen.expect("the no-repeat window always leaves a candidate");
+		if no_repeat_window > 0 {
+			if self.history.len() == no_repeat_window {
+				self.history.pop_front();
+			}
+			self.history.push_back(chosen);
+		}
+		chosen
+	}
+}
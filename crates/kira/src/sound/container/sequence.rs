@@ -0,0 +1,80 @@
+use std::sync::{
+	Arc,
+	atomic::{AtomicUsize, Ordering},
+};
+
+use crate::sound::{Sound, SoundData};
+
+use super::ContainerError;
+
+/**
+A container that plays its entries in order, one per play, and
+starts over from the first entry after playing the last one.
+
+Clones of a [`SequenceSoundData`] share their position in the
+sequence, so playing a clone each time steps through the entries.
+*/
+#[derive(Debug, Clone)]
+pub struct SequenceSoundData<D> {
+	entries: Vec<D>,
+	next_index: Arc<AtomicUsize>,
+}
+
+impl<D> SequenceSoundData<D> {
+	/// Creates a new [`SequenceSoundData`] with no entries.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			entries: vec![],
+			next_index: Arc::new(AtomicUsize::new(0)),
+		}
+	}
+
+	/// Adds an entry to the end of the sequence.
+	#[must_use = "This method consumes self and returns a modified SequenceSoundData, so the return value should be used"]
+	pub fn with_entry(mut self, data: D) -> Self {
+		self.entries.push(data);
+		self
+	}
+
+	/// Returns the number of entries in the container.
+	#[must_use]
+	pub fn num_entries(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Returns the index of the entry that will be played next.
+	#[must_use]
+	pub fn next_index(&self) -> usize {
+		self.next_index.load(Ordering::SeqCst) % self.entries.len().max(1)
+	}
+}
+
+impl<D> Default for SequenceSoundData<D> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<D: SoundData> SoundData for SequenceSoundData<D> {
+	type Error = ContainerError<D::Error>;
+
+	type Handle = D::Handle;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(mut self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+		if self.entries.is_empty() {
+			return Err(ContainerError::NoEntries);
+		}
+		let num_entries = self.entries.len();
+		let index = self
+			.next_index
+			.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |index| {
+				Some((index % num_entries + 1) % num_entries)
+			})
+			.expect("the update function always returns Some")
+			% num_entries;
+		let data = self.entries.swap_remove(index);
+		data.into_sound().map_err(ContainerError::IntoSoundError)
+	}
+}
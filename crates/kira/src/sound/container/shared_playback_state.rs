@@ -0,0 +1,37 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::sound::PlaybackState;
+
+/// The playback state of a container, shared between the sound
+/// on the audio thread and its handle.
+#[derive(Debug)]
+pub(crate) struct SharedPlaybackState {
+	state: AtomicU8,
+}
+
+impl SharedPlaybackState {
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			state: AtomicU8::new(PlaybackState::Playing as u8),
+		}
+	}
+
+	#[must_use]
+	pub fn state(&self) -> PlaybackState {
+		match self.state.load(Ordering::SeqCst) {
+			0 => PlaybackState::Playing,
+			1 => PlaybackState::Pausing,
+			2 => PlaybackState::Paused,
+			3 => PlaybackState::WaitingToResume,
+			4 => PlaybackState::Resuming,
+			5 => PlaybackState::Stopping,
+			6 => PlaybackState::Stopped,
+			_ => panic!("Invalid playback state"),
+		}
+	}
+
+	pub fn set_state(&self, state: PlaybackState) {
+		self.state.store(state as u8, Ordering::SeqCst);
+	}
+}
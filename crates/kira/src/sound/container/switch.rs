@@ -0,0 +1,19 @@
+mod data;
+mod handle;
+mod sound;
+
+pub use data::*;
+pub use handle::*;
+
+use crate::{
+	Decibels, Panning, StartTime, command::ValueChangeCommand, command_writers_and_readers,
+	tween::Tween,
+};
+
+command_writers_and_readers! {
+	set_volume: ValueChangeCommand<Decibels>,
+	set_panning: ValueChangeCommand<Panning>,
+	pause: Tween,
+	resume: (StartTime, Tween),
+	stop: Tween,
+}
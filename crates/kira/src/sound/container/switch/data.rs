@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use crate::{
+	Decibels, Panning, Tween, Value,
+	command::command_writer_and_reader,
+	event::event_queue,
+	sound::{Sound, SoundData},
+};
+
+use super::{
+	super::{ContainerError, SharedPlaybackState},
+	SwitchSoundHandle, command_writers_and_readers,
+	sound::SwitchSound,
+};
+
+/**
+A container that plays the entry that matches a key.
+
+The key can be changed from the [`SwitchSoundHandle`] while the sound
+is playing, for example to change footstep sounds when the player walks
+onto a different surface. Only the entry for the current key is played;
+the other entries are paused where they are, and they pick up from there
+if their key is chosen again.
+
+Every entry is started when the container is played, so each one can be
+controlled from [`SwitchSoundHandle::entry_mut`].
+*/
+#[derive(Debug, Clone)]
+pub struct SwitchSoundData<K, D> {
+	key: K,
+	entries: Vec<(K, D)>,
+	volume: Value<Decibels>,
+	panning: Value<Panning>,
+	fade_in_tween: Option<Tween>,
+}
+
+impl<K, D> SwitchSoundData<K, D> {
+	/// Creates a new [`SwitchSoundData`] with no entries that
+	/// starts out playing the entry for the given key.
+	#[must_use]
+	pub fn new(key: K) -> Self {
+		Self {
+			key,
+			entries: vec![],
+			volume: Value::Fixed(Decibels::IDENTITY),
+			panning: Value::Fixed(Panning::CENTER),
+			fade_in_tween: None,
+		}
+	}
+
+	/// Adds an entry that's played when the container's key
+	/// is set to `key`.
+	#[must_use = "This method consumes self and returns a modified SwitchSoundData, so the return value should be used"]
+	pub fn with_entry(mut self, key: K, data: D) -> Self {
+		self.entries.push((key, data));
+		self
+	}
+
+	/// Sets the volume of the container.
+	#[must_use = "This method consumes self and returns a modified SwitchSoundData, so the return value should be used"]
+	pub fn volume(self, volume: impl Into<Value<Decibels>>) -> Self {
+		Self {
+			volume: volume.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets the panning of the container, where -1.0 is hard left
+	and 1.0 is hard right.
+	*/
+	#[must_use = "This method consumes self and returns a modified SwitchSoundData, so the return value should be used"]
+	pub fn panning(self, panning: impl Into<Value<Panning>>) -> Self {
+		Self {
+			panning: panning.into(),
+			..self
+		}
+	}
+
+	/// Sets the tween used to fade in the container from silence.
+	#[must_use = "This method consumes self and returns a modified SwitchSoundData, so the return value should be used"]
+	pub fn fade_in_tween(self, fade_in_tween: impl Into<Option<Tween>>) -> Self {
+		Self {
+			fade_in_tween: fade_in_tween.into(),
+			..self
+		}
+	}
+
+	/// Returns the number of entries in the container.
+	#[must_use]
+	pub fn num_entries(&self) -> usize {
+		self.entries.len()
+	}
+}
+
+impl<K, D> SoundData for SwitchSoundData<K, D>
+where
+	K: Copy + PartialEq + Send + Sync + 'static,
+	D: SoundData,
+{
+	type Error = ContainerError<D::Error>;
+
+	type Handle = SwitchSoundHandle<K, D::Handle>;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+		if self.entries.is_empty() {
+			return Err(ContainerError::NoEntries);
+		}
+		let mut sounds = Vec::with_capacity(self.entries.len());
+		let mut handles = Vec::with_capacity(self.entries.len());
+		for (key, data) in self.entries {
+			let (sound, handle) = data.into_sound().map_err(ContainerError::IntoSoundError)?;
+			sounds.push((key, sound));
+			handles.push((key, handle));
+		}
+		let (command_writers, command_readers) = command_writers_and_readers();
+		let (set_key_writer, set_key_reader) = command_writer_and_reader();
+		let (event_producer, event_consumer) = event_queue();
+		let shared = Arc::new(SharedPlaybackState::new());
+		let sound = SwitchSound::new(
+			self.key,
+			sounds,
+			self.volume,
+			self.panning,
+			self.fade_in_tween,
+			command_readers,
+			set_key_reader,
+			event_producer,
+			shared.clone(),
+		);
+		Ok((
+			Box::new(sound),
+			SwitchSoundHandle {
+				key: self.key,
+				entries: handles,
+				command_writers,
+				set_key: set_key_writer,
+				shared,
+				event_consumer,
+			},
+		))
+	}
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::sound::streaming::StreamingSoundHandle;
+use crate::{
+	Decibels, Event, Panning, PlaybackRate, StartTime, Tween, Value,
+	command::{CommandWriter, handle_param_setters},
+	event::EventConsumer,
+	sound::{PlaybackState, SoundEvent, static_sound::StaticSoundHandle},
+};
+
+use super::{super::SharedPlaybackState, CommandWriters};
+
+/// Controls a switch container.
+#[derive(Debug)]
+pub struct SwitchSoundHandle<K: Send + Copy, H> {
+	pub(super) key: K,
+	pub(super) entries: Vec<(K, H)>,
+	pub(super) command_writers: CommandWriters,
+	pub(super) set_key: CommandWriter<K>,
+	pub(super) shared: Arc<SharedPlaybackState>,
+	pub(super) event_consumer: EventConsumer<SoundEvent>,
+}
+
+impl<K: Send + Copy + PartialEq, H> SwitchSoundHandle<K, H> {
+	/// Returns the current playback state of the container.
+	#[must_use]
+	pub fn state(&self) -> PlaybackState {
+		self.shared.state()
+	}
+
+	/// Returns the next event that happened to the container, if any.
+	///
+	/// Events are returned in the order they happened.
+	#[must_use]
+	pub fn pop_event(&mut self) -> Option<Event<SoundEvent>> {
+		self.event_consumer.pop()
+	}
+
+	/// Returns the key of the entry that's playing.
+	#[must_use]
+	pub fn key(&self) -> K {
+		self.key
+	}
+
+	/**
+	Switches to the entry for the given key.
+
+	The previous entry is paused where it is. If no entry
+	has the given key, the container plays silence until
+	the key is changed again.
+	*/
+	pub fn set_key(&mut self, key: K) {
+		self.key = key;
+		self.set_key.write(key)
+	}
+
+	/// Returns the handle of the entry with the given key.
+	#[must_use]
+	pub fn entry(&self, key: K) -> Option<&H> {
+		self.entries
+			.iter()
+			.find(|(entry_key, _)| *entry_key == key)
+			.map(|(_, handle)| handle)
+	}
+
+	/// Returns a mutable reference to the handle of the entry
+	/// with the given key.
+	#[must_use]
+	pub fn entry_mut(&mut self, key: K) -> Option<&mut H> {
+		self.entries
+			.iter_mut()
+			.find(|(entry_key, _)| *entry_key == key)
+			.map(|(_, handle)| handle)
+	}
+
+	handle_param_setters! {
+		/// Sets the volume of the container.
+		volume: Decibels,
+
+		/// Sets the panning of the container, where `-1.0` is hard left,
+		/// `0.0` is center, and `1.0` is hard right.
+		panning: Panning,
+	}
+
+	/// Fades out the container to silence with the given tween and then
+	/// pauses playback.
+	pub fn pause(&mut self, tween: Tween) {
+		self.command_writers.pause.write(tween)
+	}
+
+	/// Resumes playback and fades in the container from silence
+	/// with the given tween.
+	pub fn resume(&mut self, tween: Tween) {
+		self.resume_at(StartTime::Immediate, tween)
+	}
+
+	/// Resumes playback at the given start time and fades in
+	/// the container from silence with the given tween.
+	pub fn resume_at(&mut self, start_time: StartTime, tween: Tween) {
+		self.command_writers.resume.write((start_time, tween))
+	}
+
+	/// Fades out the container to silence with the given tween and then
+	/// stops playback.
+	///
+	/// Once the container is stopped, it cannot be restarted.
+	pub fn stop(&mut self, tween: Tween) {
+		self.command_writers.stop.write(tween)
+	}
+}
+
+impl<K: Send + Copy + PartialEq> SwitchSoundHandle<K, StaticSoundHandle> {
+	/**
+	Sets the playback rate of the entry that's playing.
+
+	The other entries keep their own playback rates, so switching
+	to another key plays that entry at the rate it was last set to.
+	*/
+	pub fn set_playback_rate(
+		&mut self,
+		playback_rate: impl Into<Value<PlaybackRate>>,
+		tween: Tween,
+	) {
+		if let Some(handle) = self.entry_mut(self.key) {
+			handle.set_playback_rate(playback_rate, tween);
+		}
+	}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<K: Send + Copy + PartialEq, E> SwitchSoundHandle<K, StreamingSoundHandle<E>> {
+	/**
+	Sets the playback rate of the entry that's playing.
+
+	The other entries keep their own playback rates, so switching
+	to another key plays that entry at the rate it was last set to.
+	*/
+	pub fn set_playback_rate(
+		&mut self,
+		playback_rate: impl Into<Value<PlaybackRate>>,
+		tween: Tween,
+	) {
+		if let Some(handle) = self.entry_mut(self.key) {
+			handle.set_playback_rate(playback_rate, tween);
+		}
+	}
+}
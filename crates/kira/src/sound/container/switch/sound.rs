@@ -0,0 +1,206 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::Arc;
+
+use crate::{
+	Decibels, Panning, Parameter, StartTime, Tween, Value,
+	command::{CommandReader, read_commands_into_parameters},
+	event::EventProducer,
+	frame::Frame,
+	info::Info,
+	playback_state_manager::PlaybackStateManager,
+	sound::{PlaybackState, Sound, SoundEvent},
+	start_time::num_frames_before_start,
+};
+
+use super::{super::SharedPlaybackState, CommandReaders};
+
+pub(super) struct SwitchSound<K: Send + Copy> {
+	command_readers: CommandReaders,
+	set_key: CommandReader<K>,
+	entries: Vec<(K, Box<dyn Sound>)>,
+	/// The index of the entry for the current key, or `None`
+	/// if no entry has that key.
+	current_entry: Option<usize>,
+	playback_state_manager: PlaybackStateManager,
+	started: bool,
+	volume: Parameter<Decibels>,
+	panning: Parameter<Panning>,
+	shared: Arc<SharedPlaybackState>,
+	event_producer: EventProducer<SoundEvent>,
+}
+
+impl<K: Send + Copy + PartialEq> SwitchSound<K> {
+	#[allow(clippy::too_many_arguments)]
+	#[must_use]
+	pub fn new(
+		key: K,
+		entries: Vec<(K, Box<dyn Sound>)>,
+		volume: Value<Decibels>,
+		panning: Value<Panning>,
+		fade_in_tween: Option<Tween>,
+		command_readers: CommandReaders,
+		set_key: CommandReader<K>,
+		event_producer: EventProducer<SoundEvent>,
+		shared: Arc<SharedPlaybackState>,
+	) -> Self {
+		let mut sound = Self {
+			command_readers,
+			set_key,
+			entries,
+			current_entry: None,
+			playback_state_manager: PlaybackStateManager::new(fade_in_tween),
+			started: false,
+			volume: Parameter::new(volume, Decibels::IDENTITY),
+			panning: Parameter::new(panning, Panning::CENTER),
+			shared,
+			event_producer,
+		};
+		sound.switch_to(key);
+		sound
+	}
+
+	fn switch_to(&mut self, key: K) {
+		self.current_entry = self
+			.entries
+			.iter()
+			.position(|(entry_key, _)| *entry_key == key);
+	}
+
+	fn update_shared_playback_state(&mut self) {
+		self.shared
+			.set_state(self.playback_state_manager.playback_state());
+	}
+
+	fn pause(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.pause(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn resume(&mut self, start_time: StartTime, fade_in_tween: Tween) {
+		self.playback_state_manager
+			.resume(start_time, fade_in_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn stop(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.stop(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn read_commands(&mut self) {
+		read_commands_into_parameters!(self, volume, panning);
+		if let Some(key) = self.set_key.read() {
+			self.switch_to(key);
+		}
+		if let Some(tween) = self.command_readers.pause.read() {
+			self.pause(tween);
+		}
+		if let Some((start_time, tween)) = self.command_readers.resume.read() {
+			self.resume(start_time, tween);
+		}
+		if let Some(tween) = self.command_readers.stop.read() {
+			self.stop(tween);
+		}
+	}
+}
+
+impl<K: Send + Copy + PartialEq> Sound for SwitchSound<K> {
+	fn init(&mut self, sample_rate: u32, internal_buffer_size: usize) {
+		for (_, sound) in &mut self.entries {
+			sound.init(sample_rate, internal_buffer_size);
+		}
+	}
+
+	fn on_change_sample_rate(&mut self, sample_rate: u32) {
+		for (_, sound) in &mut self.entries {
+			sound.on_change_sample_rate(sample_rate);
+		}
+	}
+
+	fn on_start_processing(&mut self) {
+		self.read_commands();
+		// every entry reads its commands, even if it isn't playing,
+		// so its handle still works
+		for (_, sound) in &mut self.entries {
+			sound.on_start_processing();
+		}
+	}
+
+	fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
+		// update parameters
+		self.volume.update(dt * out.len() as f64, info);
+		self.panning.update(dt * out.len() as f64, info);
+		let changed_playback_state = self
+			.playback_state_manager
+			.update(dt * out.len() as f64, info);
+		if changed_playback_state {
+			self.update_shared_playback_state();
+			// the fade out finishes at the end of this batch
+			if self.playback_state_manager.playback_state() == PlaybackState::Stopped {
+				self.event_producer
+					.push(SoundEvent::Stopped, info.time() + dt * out.len() as f64);
+			}
+		}
+
+		if !self.started {
+			self.started = true;
+			self.event_producer.push(SoundEvent::Started, info.time());
+		}
+
+		if !self.playback_state_manager.playback_state().is_advancing() {
+			out.fill(Frame::ZERO);
+			return;
+		}
+
+		// if playback resumed partway through this batch, the frames
+		// before that point stay silent
+		let num_frames = out.len();
+		let start_frame =
+			num_frames_before_start(self.playback_state_manager.start_delay(), dt, num_frames);
+		out[..start_frame].fill(Frame::ZERO);
+
+		// play the current entry
+		let Some(current_entry) = self.current_entry else {
+			out.fill(Frame::ZERO);
+			return;
+		};
+		let sound = &mut self.entries[current_entry].1;
+		if sound.finished() {
+			out[start_frame..].fill(Frame::ZERO);
+		} else {
+			sound.process(
+				&mut out[start_frame..],
+				dt,
+				&info.for_sub_chunk(start_frame as f64 * dt, 0.0),
+			);
+		}
+		if sound.finished() {
+			self.playback_state_manager.mark_as_stopped();
+			self.update_shared_playback_state();
+			self.event_producer
+				.push(SoundEvent::Finished, info.time() + dt * num_frames as f64);
+		}
+
+		for (i, frame) in out.iter_mut().enumerate().skip(start_frame) {
+			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
+			let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
+			let fade_volume = self
+				.playback_state_manager
+				.interpolated_fade_volume(time_in_chunk)
+				.as_amplitude();
+			let panning = self.panning.interpolated_value(time_in_chunk);
+			*frame = (*frame * fade_volume * volume).panned(panning);
+		}
+	}
+
+	fn remaining_duration(&self) -> Option<f64> {
+		let (_, sound) = &self.entries[self.current_entry?];
+		sound.remaining_duration()
+	}
+
+	fn finished(&self) -> bool {
+		self.playback_state_manager.playback_state() == PlaybackState::Stopped
+	}
+}
@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use crate::{
+	Panning, Tween,
+	frame::Frame,
+	info::MockInfoBuilder,
+	sound::{
+		PlaybackState, SoundData, SoundEvent,
+		container::SwitchSoundData,
+		static_sound::{StaticSoundData, StaticSoundSettings},
+	},
+};
+
+fn entry(value: f32, num_frames: usize) -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 1,
+		frames: vec![Frame::from_mono(value); num_frames].into(),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+}
+
+/// Tests that a `SwitchSound` plays the entry for the key
+/// set on its handle.
+#[test]
+fn plays_entry_for_key() {
+	let data = SwitchSoundData::new('a')
+		.with_entry('a', entry(1.0, 10))
+		.with_entry('b', entry(2.0, 10));
+	let (mut sound, mut handle) = data.into_sound().unwrap();
+	let info = MockInfoBuilder::new().build();
+
+	assert_eq!(
+		sound.process_one(1.0, &info),
+		Frame::from_mono(1.0).panned(Panning::CENTER)
+	);
+
+	handle.set_key('b');
+	sound.on_start_processing();
+	assert_eq!(handle.key(), 'b');
+	assert_eq!(
+		sound.process_one(1.0, &info),
+		Frame::from_mono(2.0).panned(Panning::CENTER)
+	);
+
+	// keys without an entry play silence
+	handle.set_key('c');
+	sound.on_start_processing();
+	assert_eq!(sound.process_one(1.0, &info), Frame::ZERO);
+	assert_eq!(handle.state(), PlaybackState::Playing);
+
+	handle.set_key('a');
+	sound.on_start_processing();
+	assert_eq!(
+		sound.process_one(1.0, &info),
+		Frame::from_mono(1.0).panned(Panning::CENTER)
+	);
+}
+
+/// Tests that a `SwitchSound` finishes when its current entry finishes.
+#[test]
+fn finishes_when_entry_finishes() {
+	let data = SwitchSoundData::new('a')
+		.with_entry('a', entry(1.0, 2))
+		.with_entry('b', entry(2.0, 10));
+	let (mut sound, mut handle) = data.into_sound().unwrap();
+	let info = MockInfoBuilder::new().build();
+
+	for _ in 0..10 {
+		sound.process_one(1.0, &info);
+	}
+	assert!(sound.finished());
+	assert_eq!(handle.state(), PlaybackState::Stopped);
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.map(|event| event.kind)
+		.collect::<Vec<_>>();
+	assert_eq!(events, [SoundEvent::Started, SoundEvent::Finished]);
+}
+
+/// Tests that the entries of a `SwitchSound` can be controlled
+/// from their handles.
+#[test]
+fn entries_can_be_controlled() {
+	let data = SwitchSoundData::new('a').with_entry('a', entry(1.0, 10));
+	let (mut sound, mut handle) = data.into_sound().unwrap();
+	let info = MockInfoBuilder::new().build();
+
+	sound.process_one(1.0, &info);
+	handle.entry_mut('a').unwrap().stop(Default::default());
+	sound.on_start_processing();
+	for _ in 0..3 {
+		sound.process_one(1.0, &info);
+	}
+	assert!(sound.finished());
+	assert!(handle.entry('b').is_none());
+	assert_eq!(handle.entry('a').unwrap().state(), PlaybackState::Stopped);
+}
+
+/// Tests that setting the playback rate of a `SwitchSoundHandle`
+/// only changes the playback rate of the entry that's playing.
+#[test]
+fn sets_playback_rate_of_current_entry() {
+	let data = SwitchSoundData::new('a')
+		.with_entry('a', entry(1.0, 100))
+		.with_entry('b', entry(2.0, 100));
+	let (mut sound, mut handle) = data.into_sound().unwrap();
+	let info = MockInfoBuilder::new().build();
+
+	handle.set_playback_rate(
+		2.0,
+		Tween {
+			duration: Duration::ZERO,
+			..Default::default()
+		},
+	);
+	sound.on_start_processing();
+	for _ in 0..4 {
+		sound.process_one(1.0, &info);
+	}
+	handle.set_key('b');
+	sound.on_start_processing();
+	for _ in 0..4 {
+		sound.process_one(1.0, &info);
+	}
+	sound.on_start_processing();
+	assert_eq!(handle.entry('a').unwrap().position(), 8.0);
+	assert_eq!(handle.entry('b').unwrap().position(), 4.0);
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use crate::{
+	frame::Frame,
+	info::MockInfoBuilder,
+	sound::{
+		SoundData,
+		static_sound::{StaticSoundData, StaticSoundSettings},
+	},
+};
+
+use super::{ContainerError, RandomSoundData, SequenceSoundData};
+
+fn entry(value: f32) -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 1,
+		frames: Arc::new([Frame::from_mono(value)]),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+}
+
+/// Plays a container and returns the value of the entry that was chosen.
+fn play(data: impl SoundData<Error = ContainerError<()>>) -> f32 {
+	let (mut sound, _) = data.into_sound().unwrap();
+	sound.process_one(1.0, &MockInfoBuilder::new().build()).left
+}
+
+/// Tests that a `RandomSoundData` doesn't choose entries that
+/// were played within its no-repeat window.
+#[test]
+fn random_container_does_not_repeat_entries() {
+	let data = (0..4).fold(RandomSoundData::new(), |data, i| {
+		data.with_entry(entry(i as f32), 1.0)
+	});
+	let data = data.no_repeat_window(2);
+	let values = (0..100).map(|_| play(data.clone())).collect::<Vec<_>>();
+	for window in values.windows(3) {
+		assert_ne!(window[2], window[1]);
+		assert_ne!(window[2], window[0]);
+	}
+}
+
+/// Tests that a `RandomSoundData` never chooses entries with
+/// a weight of 0.
+#[test]
+fn random_container_uses_weights() {
+	let data = RandomSoundData::new()
+		.with_entry(entry(1.0), 0.0)
+		.with_entry(entry(2.0), 1.0)
+		.with_entry(entry(3.0), 0.0);
+	for _ in 0..20 {
+		assert_eq!(play(data.clone()), 2.0);
+	}
+}
+
+/// Tests that `RandomSoundData`s with the same seed choose
+/// the same entries.
+#[test]
+fn random_container_is_seedable() {
+	let data = (0..10).fold(RandomSoundData::new(), |data, i| {
+		data.with_entry(entry(i as f32), 1.0)
+	});
+	let choices = |data: RandomSoundData<StaticSoundData>| {
+		(0..20).map(|_| play(data.clone())).collect::<Vec<_>>()
+	};
+	assert_eq!(choices(data.clone().seed(1)), choices(data.clone().seed(1)));
+	assert_ne!(choices(data.clone().seed(1)), choices(data.seed(2)));
+}
+
+/// Tests that a `SequenceSoundData` plays its entries in order.
+#[test]
+fn sequence_container_plays_entries_in_order() {
+	let data = SequenceSoundData::new()
+		.with_entry(entry(1.0))
+		.with_entry(entry(2.0))
+		.with_entry(entry(3.0));
+	let values = (0..7).map(|_| play(data.clone())).collect::<Vec<_>>();
+	assert_eq!(values, [1.0, 2.0, 3.0, 1.0, 2.0, 3.0, 1.0]);
+	assert_eq!(data.next_index(), 1);
+}
+
+/// Tests that containers without entries can't be played.
+#[test]
+fn empty_containers_return_error() {
+	assert!(matches!(
+		RandomSoundData::<StaticSoundData>::new().into_sound(),
+		Err(ContainerError::NoEntries)
+	));
+	assert!(matches!(
+		SequenceSoundData::<StaticSoundData>::new().into_sound(),
+		Err(ContainerError::NoEntries)
+	));
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod test;
+
+use crate::frame::Frame;
+
+/// The amplitude that center and surround channels are mixed
+/// into the left and right channels with (-3dB).
+const MINUS_3_DB: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// The `[left, right]` gains of each kind of speaker in the
+/// standard downmixes.
+pub(super) const L: [f32; 2] = [1.0, 0.0];
+pub(super) const R: [f32; 2] = [0.0, 1.0];
+pub(super) const C: [f32; 2] = [MINUS_3_DB, MINUS_3_DB];
+pub(super) const LFE: [f32; 2] = [0.0, 0.0];
+pub(super) const SURROUND_L: [f32; 2] = [MINUS_3_DB, 0.0];
+pub(super) const SURROUND_R: [f32; 2] = [0.0, MINUS_3_DB];
+
+/**
+How to mix audio with any number of channels down to stereo.
+
+Each input channel has a pair of gains that decide how much of it is
+mixed into the left and right output channels.
+
+Channels are expected to be in the standard WAVE (SMPTE) order:
+front left, front right, front center, low frequency, back left,
+back right, side left, side right.
+
+# Examples
+
+Keep only the front left and right channels of 5.1 audio:
+
+```
+use kira::sound::DownmixMatrix;
+
+let downmix = DownmixMatrix::new(vec![
+	[1.0, 0.0], // front left
+	[0.0, 1.0], // front right
+	[0.0, 0.0], // front center
+	[0.0, 0.0], // low frequency
+	[0.0, 0.0], // back left
+	[0.0, 0.0], // back right
+]);
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownmixMatrix {
+	coefficients: Vec<[f32; 2]>,
+}
+
+impl DownmixMatrix {
+	/// Creates a new [`DownmixMatrix`] from the `[left, right]`
+	/// gains for each input channel.
+	#[must_use]
+	pub fn new(coefficients: impl Into<Vec<[f32; 2]>>) -> Self {
+		Self {
+			coefficients: coefficients.into(),
+		}
+	}
+
+	/**
+	Returns the standard ITU-R BS.775 downmix for the given number
+	of channels, or [`None`] if there's no standard layout with
+	that many channels.
+
+	Mono audio is played equally in both channels, and stereo audio
+	is left unchanged. For audio with more channels, the center and
+	surround channels are mixed into the left and right channels at
+	-3dB, and the low frequency channel is discarded. The supported
+	layouts are:
+
+	- 3 channels: L, R, C
+	- 4 channels (quad): L, R, BL, BR
+	- 5 channels (5.0): L, R, C, BL, BR
+	- 6 channels (5.1): L, R, C, LFE, BL, BR
+	- 7 channels (6.1): L, R, C, LFE, BC, SL, SR
+	- 8 channels (7.1): L, R, C, LFE, BL, BR, SL, SR
+
+	When an audio file says which speaker each of its channels is for,
+	the channels are mixed the same way based on their speakers instead
+	of their number, so files with unusual layouts (like 3.1) are
+	mixed correctly.
+
+	The downmixed audio can be louder than the original channels,
+	so you may want to lower the volume of sounds with many channels.
+	*/
+	#[must_use]
+	pub fn itu(num_channels: usize) -> Option<Self> {
+		let coefficients: &[[f32; 2]] = match num_channels {
+			1 => &[[1.0, 1.0]],
+			2 => &[L, R],
+			3 => &[L, R, C],
+			4 => &[L, R, SURROUND_L, SURROUND_R],
+			5 => &[L, R, C, SURROUND_L, SURROUND_R],
+			6 => &[L, R, C, LFE, SURROUND_L, SURROUND_R],
+			7 => &[L, R, C, LFE, C, SURROUND_L, SURROUND_R],
+			8 => &[L, R, C, LFE, SURROUND_L, SURROUND_R, SURROUND_L, SURROUND_R],
+			_ => return None,
+		};
+		Some(Self::new(coefficients))
+	}
+
+	/// Returns the number of input channels the matrix mixes.
+	#[must_use]
+	pub fn num_channels(&self) -> usize {
+		self.coefficients.len()
+	}
+
+	/// Mixes one sample from each input channel into a stereo [`Frame`].
+	///
+	/// Any samples beyond [`DownmixMatrix::num_channels`] are ignored.
+	#[must_use]
+	pub fn apply(&self, samples: impl IntoIterator<Item = f32>) -> Frame {
+		self.coefficients
+			.iter()
+			.zip(samples)
+			.fold(Frame::ZERO, |frame, ([left, right], sample)| {
+				frame + Frame::new(sample * left, sample * right)
+			})
+	}
+}
@@ -0,0 +1,63 @@
+use crate::frame::Frame;
+
+use super::{DownmixMatrix, MINUS_3_DB};
+
+/// Tests that the ITU downmixes leave mono and stereo audio unchanged.
+#[test]
+fn itu_preserves_mono_and_stereo() {
+	assert_eq!(
+		DownmixMatrix::itu(1).unwrap().apply([0.5]),
+		Frame::new(0.5, 0.5)
+	);
+	assert_eq!(
+		DownmixMatrix::itu(2).unwrap().apply([0.25, 0.5]),
+		Frame::new(0.25, 0.5)
+	);
+}
+
+/// Tests that the ITU downmix of 5.1 audio mixes the center and
+/// surround channels into the front channels and discards the
+/// low frequency channel.
+#[test]
+fn itu_5_1() {
+	let downmix = DownmixMatrix::itu(6).unwrap();
+	assert_eq!(downmix.num_channels(), 6);
+	// front left, front right
+	assert_eq!(
+		downmix.apply([1.0, 0.5, 0.0, 0.0, 0.0, 0.0]),
+		Frame::new(1.0, 0.5)
+	);
+	// center
+	assert_eq!(
+		downmix.apply([0.0, 0.0, 1.0, 0.0, 0.0, 0.0]),
+		Frame::new(MINUS_3_DB, MINUS_3_DB)
+	);
+	// low frequency
+	assert_eq!(downmix.apply([0.0, 0.0, 0.0, 1.0, 0.0, 0.0]), Frame::ZERO);
+	// back left, back right
+	assert_eq!(
+		downmix.apply([0.0, 0.0, 0.0, 0.0, 1.0, 0.5]),
+		Frame::new(MINUS_3_DB, 0.5 * MINUS_3_DB)
+	);
+}
+
+/// Tests that there's an ITU downmix for every common channel count
+/// and none for unusual ones.
+#[test]
+fn itu_channel_counts() {
+	for num_channels in 1..=8 {
+		assert_eq!(
+			DownmixMatrix::itu(num_channels).unwrap().num_channels(),
+			num_channels
+		);
+	}
+	assert!(DownmixMatrix::itu(0).is_none());
+	assert!(DownmixMatrix::itu(9).is_none());
+}
+
+/// Tests that a custom `DownmixMatrix` applies its coefficients.
+#[test]
+fn custom() {
+	let downmix = DownmixMatrix::new([[0.0, 1.0], [1.0, 0.0], [0.5, 0.5]]);
+	assert_eq!(downmix.apply([1.0, 0.5, 1.0]), Frame::new(1.0, 1.5));
+}
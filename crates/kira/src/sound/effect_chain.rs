@@ -0,0 +1,119 @@
+use std::{
+	any::Any,
+	fmt::{Debug, Formatter},
+	sync::Arc,
+};
+
+use crate::effect::{Effect, EffectBuilder};
+
+type BuildEffect = dyn Fn() -> (Box<dyn Effect>, Box<dyn Any + Send + Sync>) + Send + Sync;
+
+/**
+A list of effects that are applied to a single instance of a sound.
+
+Each time a sound is played, new effects are created from the
+[`EffectBuilder`]s in the chain, so every instance of the sound has its
+own effects. The effect handles can be retrieved from the sound's handle
+using the index the effect was added at.
+
+# Examples
+
+```no_run
+use kira::{
+	AudioManager, AudioManagerSettings, DefaultBackend,
+	effect::filter::{FilterBuilder, FilterHandle},
+	sound::static_sound::StaticSoundData,
+};
+
+let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+let gunshot = StaticSoundData::from_file("gunshot.ogg")?
+	.with_effect(FilterBuilder::new().cutoff(2000.0));
+let mut sound = manager.play(gunshot)?;
+sound
+	.effect_mut::<FilterHandle>(0)
+	.unwrap()
+	.set_cutoff(4000.0, Default::default());
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+*/
+#[derive(Clone, Default)]
+pub struct EffectChain {
+	builders: Vec<Arc<BuildEffect>>,
+}
+
+impl EffectChain {
+	/// Creates a new [`EffectChain`] with no effects.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds an effect to the end of the chain.
+	#[must_use = "This method consumes self and returns a modified EffectChain, so the return value should be used"]
+	pub fn with_effect<B>(mut self, builder: B) -> Self
+	where
+		B: EffectBuilder + Clone + Send + Sync + 'static,
+		B::Handle: Send + Sync + 'static,
+	{
+		self.builders.push(Arc::new(move || {
+			let (effect, handle) = builder.clone().build();
+			(effect, Box::new(handle) as Box<dyn Any + Send + Sync>)
+		}));
+		self
+	}
+
+	/// Returns the number of effects in the chain.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.builders.len()
+	}
+
+	/// Returns `true` if there are no effects in the chain.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.builders.is_empty()
+	}
+
+	/// Creates the effects and their handles.
+	#[must_use]
+	pub(crate) fn build(&self) -> (Vec<Box<dyn Effect>>, EffectHandles) {
+		let (effects, handles) = self.builders.iter().map(|build| build()).unzip();
+		(effects, EffectHandles(handles))
+	}
+}
+
+impl Debug for EffectChain {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("EffectChain")
+			.field("len", &self.builders.len())
+			.finish()
+	}
+}
+
+/// Two effect chains are equal if they were cloned from the same chain.
+impl PartialEq for EffectChain {
+	fn eq(&self, other: &Self) -> bool {
+		self.builders.len() == other.builders.len()
+			&& self
+				.builders
+				.iter()
+				.zip(&other.builders)
+				.all(|(a, b)| Arc::ptr_eq(a, b))
+	}
+}
+
+/// The handles of the effects created from an [`EffectChain`].
+#[derive(Debug, Default)]
+pub(crate) struct EffectHandles(Vec<Box<dyn Any + Send + Sync>>);
+
+impl EffectHandles {
+	#[must_use]
+	pub fn get<H: 'static>(&self, index: usize) -> Option<&H> {
+		self.0.get(index)?.downcast_ref()
+	}
+
+	#[must_use]
+	pub fn get_mut<H: 'static>(&mut self, index: usize) -> Option<&mut H> {
+		self.0.get_mut(index)?.downcast_mut()
+	}
+}
@@ -10,8 +10,9 @@ pub enum FromFileError {
 	UnknownSampleRate,
 	/// Could not determine the duration of the audio.
 	UnknownDuration,
-	/// The audio uses an unsupported channel configuration. Only
-	/// mono and stereo audio is supported.
+	/// The audio has a number of channels that can't be mixed down to stereo,
+	/// either because there's no standard downmix for it or because it
+	/// doesn't match the provided [`DownmixMatrix`](super::DownmixMatrix).
 	UnsupportedChannelConfiguration,
 	/// An error occurred while reading the file from the filesystem.
 	IoError(std::io::Error),
@@ -32,7 +33,7 @@ impl Display for FromFileError {
 				f.write_str("Could not detect the duration of the audio")
 			}
 			FromFileError::UnsupportedChannelConfiguration => {
-				f.write_str("Only mono and stereo audio is supported")
+				f.write_str("Could not mix the audio's channels down to stereo")
 			}
 			FromFileError::IoError(error) => error.fmt(f),
 			FromFileError::SymphoniaError(error) => error.fmt(f),
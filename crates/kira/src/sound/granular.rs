@@ -0,0 +1,66 @@
+/*!
+Sounds that play many short, overlapping pieces of a static sound.
+
+Granular synthesis is useful for turning a short recording into a
+continuous bed of sound, like an engine, wind, or a crowd. A
+granular sound repeatedly spawns *grains*, short pieces of the
+source audio with smooth fades on both ends, and plays them over
+each other.
+
+```no_run
+use kira::{
+	AudioManager, AudioManagerSettings, DefaultBackend,
+	sound::{granular::GranularSoundData, static_sound::StaticSoundData},
+};
+
+let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+let wind = StaticSoundData::from_file("wind.ogg")?;
+manager.play(
+	GranularSoundData::new(&wind)
+		.grain_size(0.2)
+		.density(30.0)
+		.position(1.5)
+		.position_jitter(1.0)
+		.pitch_jitter(2.0)
+		.stereo_spread(0.5),
+)?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+
+Every grain control is a [`Value`](crate::Value), so it can be
+tweened from a [`GranularSoundHandle`] or linked to a
+[modulator](crate::modulator).
+
+Granular sounds share the audio of the [`StaticSoundData`](super::static_sound::StaticSoundData)
+they're created from, so playing many of them at once doesn't use
+any extra memory for the audio.
+*/
+
+mod data;
+mod handle;
+mod settings;
+mod sound;
+
+pub use data::*;
+pub use handle::*;
+pub use settings::*;
+
+use crate::{
+	Decibels, Panning, PlaybackRate, StartTime, command::ValueChangeCommand,
+	command_writers_and_readers, tween::Tween,
+};
+
+command_writers_and_readers! {
+	set_grain_size: ValueChangeCommand<f64>,
+	set_density: ValueChangeCommand<f64>,
+	set_position: ValueChangeCommand<f64>,
+	set_position_jitter: ValueChangeCommand<f64>,
+	set_pitch_jitter: ValueChangeCommand<f64>,
+	set_stereo_spread: ValueChangeCommand<f64>,
+	set_volume: ValueChangeCommand<Decibels>,
+	set_playback_rate: ValueChangeCommand<PlaybackRate>,
+	set_panning: ValueChangeCommand<Panning>,
+	pause: Tween,
+	resume: (StartTime, Tween),
+	stop: Tween,
+}
@@ -0,0 +1,203 @@
+use std::{
+	fmt::{Debug, Formatter},
+	sync::Arc,
+};
+
+use crate::{
+	Decibels, Frame, Panning, PlaybackRate, StartTime, Tween, Value,
+	event::event_queue,
+	sound::{
+		Sound, SoundData,
+		static_sound::{FramesDebug, StaticSoundData},
+	},
+};
+
+use super::{
+	GranularSoundHandle, GranularSoundSettings, command_writers_and_readers,
+	sound::{GranularSound, Shared},
+};
+
+/// A granular sound that is not playing yet.
+#[derive(Clone, PartialEq)]
+pub struct GranularSoundData {
+	/// The sample rate of the source audio (in Hz).
+	pub sample_rate: u32,
+	/// The source audio grains are taken from.
+	pub frames: Arc<[Frame]>,
+	/// The portion of the source audio grains are taken from.
+	///
+	/// This works the same way as [`StaticSoundData::slice`].
+	pub slice: Option<(usize, usize)>,
+	/// Settings for the sound.
+	pub settings: GranularSoundSettings,
+}
+
+impl GranularSoundData {
+	/// Creates a new [`GranularSoundData`] that takes grains from
+	/// the audio of a [`StaticSoundData`].
+	///
+	/// The audio is shared with the [`StaticSoundData`], not copied.
+	#[must_use]
+	pub fn new(source: &StaticSoundData) -> Self {
+		Self {
+			sample_rate: source.sample_rate,
+			frames: source.frames.clone(),
+			slice: source.slice,
+			settings: GranularSoundSettings::default(),
+		}
+	}
+
+	/// Sets when the sound should start playing.
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn start_time(mut self, start_time: impl Into<StartTime>) -> Self {
+		self.settings.start_time = start_time.into();
+		self
+	}
+
+	/// Sets how long each grain is (in seconds).
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn grain_size(mut self, grain_size: impl Into<Value<f64>>) -> Self {
+		self.settings.grain_size = grain_size.into();
+		self
+	}
+
+	/**
+	Sets how many grains are spawned per second.
+
+	The more grains overlap, the louder the sound gets. Densities
+	above 100,000 grains per second are treated as 100,000.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn density(mut self, density: impl Into<Value<f64>>) -> Self {
+		self.settings.density = density.into();
+		self
+	}
+
+	/**
+	Sets where in the source audio grains are taken from (in seconds).
+
+	Positions past the end of the audio wrap around to the beginning.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn position(mut self, position: impl Into<Value<f64>>) -> Self {
+		self.settings.position = position.into();
+		self
+	}
+
+	/**
+	Sets the maximum random offset (in seconds) added to the position
+	of each grain.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn position_jitter(mut self, position_jitter: impl Into<Value<f64>>) -> Self {
+		self.settings.position_jitter = position_jitter.into();
+		self
+	}
+
+	/**
+	Sets the maximum random change in pitch (in semitones) applied
+	to each grain.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn pitch_jitter(mut self, pitch_jitter: impl Into<Value<f64>>) -> Self {
+		self.settings.pitch_jitter = pitch_jitter.into();
+		self
+	}
+
+	/**
+	Sets how far grains can be randomly panned from the sound's panning,
+	from `0.0` (not at all) to `1.0` (anywhere from hard left to hard right).
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn stereo_spread(mut self, stereo_spread: impl Into<Value<f64>>) -> Self {
+		self.settings.stereo_spread = stereo_spread.into();
+		self
+	}
+
+	/// Sets the volume of the sound.
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn volume(mut self, volume: impl Into<Value<Decibels>>) -> Self {
+		self.settings.volume = volume.into();
+		self
+	}
+
+	/**
+	Sets the playback rate of each grain.
+
+	Changing the playback rate will change the pitch of the grains,
+	but not how often they're spawned.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn playback_rate(mut self, playback_rate: impl Into<Value<PlaybackRate>>) -> Self {
+		self.settings.playback_rate = playback_rate.into();
+		self
+	}
+
+	/**
+	Sets the panning of the sound, where -1.0 is hard left
+	and 1.0 is hard right.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn panning(mut self, panning: impl Into<Value<Panning>>) -> Self {
+		self.settings.panning = panning.into();
+		self
+	}
+
+	/// Sets the tween used to fade in the sound from silence.
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn fade_in_tween(mut self, fade_in_tween: impl Into<Option<Tween>>) -> Self {
+		self.settings.fade_in_tween = fade_in_tween.into();
+		self
+	}
+
+	/// Sets the settings for the sound.
+	#[must_use = "This method consumes self and returns a modified GranularSoundData, so the return value should be used"]
+	pub fn with_settings(mut self, settings: GranularSoundSettings) -> Self {
+		self.settings = settings;
+		self
+	}
+
+	#[must_use]
+	pub(super) fn split(self) -> (GranularSound, GranularSoundHandle) {
+		let (command_writers, command_readers) = command_writers_and_readers();
+		let (event_producer, event_consumer) = event_queue();
+		let shared = Arc::new(Shared::new());
+		let sound = GranularSound::new(self, command_readers, event_producer, shared.clone());
+		(
+			sound,
+			GranularSoundHandle {
+				command_writers,
+				shared,
+				event_consumer,
+			},
+		)
+	}
+}
+
+impl SoundData for GranularSoundData {
+	type Error = ();
+
+	type Handle = GranularSoundHandle;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+		let (sound, handle) = self.split();
+		Ok((Box::new(sound), handle))
+	}
+}
+
+impl Debug for GranularSoundData {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("GranularSoundData")
+			.field("sample_rate", &self.sample_rate)
+			.field(
+				"frames",
+				&FramesDebug {
+					len: self.frames.len(),
+				},
+			)
+			.field("slice", &self.slice)
+			.field("settings", &self.settings)
+			.finish()
+	}
+}
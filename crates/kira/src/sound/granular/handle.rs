@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use crate::{
+	Decibels, Event, Panning, PlaybackRate, StartTime, Tween,
+	command::handle_param_setters,
+	event::EventConsumer,
+	sound::{PlaybackState, SoundEvent},
+};
+
+use super::{CommandWriters, sound::Shared};
+
+/// Controls a granular sound.
+#[derive(Debug)]
+pub struct GranularSoundHandle {
+	pub(super) command_writers: CommandWriters,
+	pub(super) shared: Arc<Shared>,
+	pub(super) event_consumer: EventConsumer<SoundEvent>,
+}
+
+impl GranularSoundHandle {
+	/// Returns the current playback state of the sound.
+	#[must_use]
+	pub fn state(&self) -> PlaybackState {
+		self.shared.state()
+	}
+
+	/// Returns the number of grains that are currently playing.
+	#[must_use]
+	pub fn num_active_grains(&self) -> usize {
+		self.shared.num_active_grains()
+	}
+
+	/// Returns the next event that happened to the sound, if any.
+	///
+	/// Events are returned in the order they happened.
+	#[must_use]
+	pub fn pop_event(&mut self) -> Option<Event<SoundEvent>> {
+		self.event_consumer.pop()
+	}
+
+	handle_param_setters! {
+		/// Sets how long each grain is (in seconds).
+		///
+		/// Grains that are already playing keep their original length.
+		grain_size: f64,
+
+		/// Sets how many grains are spawned per second.
+		density: f64,
+
+		/// Sets where in the source audio grains are taken from (in seconds).
+		position: f64,
+
+		/// Sets the maximum random offset (in seconds) added to the position
+		/// of each grain.
+		position_jitter: f64,
+
+		/// Sets the maximum random change in pitch (in semitones) applied
+		/// to each grain.
+		pitch_jitter: f64,
+
+		/// Sets how far grains can be randomly panned from the sound's panning,
+		/// from `0.0` (not at all) to `1.0` (anywhere from hard left to hard right).
+		stereo_spread: f64,
+
+		/// Sets the volume of the sound.
+		volume: Decibels,
+
+		/// Sets the playback rate of each grain.
+		///
+		/// Changing the playback rate will change the pitch of the grains,
+		/// but not how often they're spawned.
+		playback_rate: PlaybackRate,
+
+		/// Sets the panning of the sound, where `-1.0` is hard left,
+		/// `0.0` is center, and `1.0` is hard right.
+		panning: Panning,
+	}
+
+	/// Fades out the sound to silence with the given tween and then
+	/// pauses playback.
+	pub fn pause(&mut self, tween: Tween) {
+		self.command_writers.pause.write(tween)
+	}
+
+	/// Resumes playback and fades in the sound from silence
+	/// with the given tween.
+	pub fn resume(&mut self, tween: Tween) {
+		self.resume_at(StartTime::Immediate, tween)
+	}
+
+	/// Resumes playback at the given start time and fades in
+	/// the sound from silence with the given tween.
+	pub fn resume_at(&mut self, start_time: StartTime, tween: Tween) {
+		self.command_writers.resume.write((start_time, tween))
+	}
+
+	/// Fades out the sound to silence with the given tween and then
+	/// stops playback.
+	///
+	/// Once the sound is stopped, it cannot be restarted.
+	pub fn stop(&mut self, tween: Tween) {
+		self.command_writers.stop.write(tween)
+	}
+}
@@ -0,0 +1,191 @@
+use crate::{Decibels, Panning, PlaybackRate, StartTime, Tween, Value};
+
+/// Settings for a granular sound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GranularSoundSettings {
+	/// When the sound should start playing.
+	pub start_time: StartTime,
+	/// How long each grain is (in seconds).
+	pub grain_size: Value<f64>,
+	/// How many grains are spawned per second.
+	///
+	/// The more grains overlap, the louder the sound gets. Densities
+	/// above 100,000 grains per second are treated as 100,000.
+	pub density: Value<f64>,
+	/// Where in the source audio grains are taken from (in seconds).
+	///
+	/// Positions past the end of the audio wrap around to the beginning.
+	pub position: Value<f64>,
+	/// The maximum random offset (in seconds) added to the position
+	/// of each grain.
+	pub position_jitter: Value<f64>,
+	/// The maximum random change in pitch (in semitones) applied
+	/// to each grain.
+	pub pitch_jitter: Value<f64>,
+	/// How far grains can be randomly panned from the sound's panning,
+	/// from `0.0` (not at all) to `1.0` (anywhere from hard left to hard right).
+	pub stereo_spread: Value<f64>,
+	/// The volume of the sound.
+	pub volume: Value<Decibels>,
+	/// The playback rate of each grain.
+	///
+	/// Changing the playback rate will change the pitch of the grains,
+	/// but not how often they're spawned.
+	pub playback_rate: Value<PlaybackRate>,
+	/// The panning of the sound, where -1.0 is hard left
+	/// and 1.0 is hard right.
+	pub panning: Value<Panning>,
+	/// An optional fade-in from silence.
+	pub fade_in_tween: Option<Tween>,
+}
+
+impl GranularSoundSettings {
+	/// Creates a new [`GranularSoundSettings`] with the default settings.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			start_time: StartTime::default(),
+			grain_size: Value::Fixed(0.1),
+			density: Value::Fixed(20.0),
+			position: Value::Fixed(0.0),
+			position_jitter: Value::Fixed(0.0),
+			pitch_jitter: Value::Fixed(0.0),
+			stereo_spread: Value::Fixed(0.0),
+			volume: Value::Fixed(Decibels::IDENTITY),
+			playback_rate: Value::Fixed(PlaybackRate(1.0)),
+			panning: Value::Fixed(Panning::CENTER),
+			fade_in_tween: None,
+		}
+	}
+
+	/// Sets when the sound should start playing.
+	#[must_use = "This method consumes self and returns a modified GranularSoundSettings, so the return value should be used"]
+	pub fn start_time(self, start_time: impl Into<StartTime>) -> Self {
+		Self {
+			start_time: start_time.into(),
+			..self
+		}
+	}
+
+	/// Sets how long each grain is (in seconds).
+	#[must_use = "This method consumes self and returns a modified GranularSoundSettings, so the return value should be used"]
+	pub fn grain_size(self, grain_size: impl Into<Value<f64>>) -> Self {
+		Self {
+			grain_size: grain_size.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets how many grains are spawned per second.
+
+	The more grains overlap, the louder the sound gets. Densities
+	above 100,000 grains per second are treated as 100,000.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundSettings, so the return value should be used"]
+	pub fn density(self, density: impl Into<Value<f64>>) -> Self {
+		Self {
+			density: density.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets where in the source audio grains are taken from (in seconds).
+
+	Positions past the end of the audio wrap around to the beginning.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundSettings, so the return value should be used"]
+	pub fn position(self, position: impl Into<Value<f64>>) -> Self {
+		Self {
+			position: position.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets the maximum random offset (in seconds) added to the position
+	of each grain.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundSettings, so the return value should be used"]
+	pub fn position_jitter(self, position_jitter: impl Into<Value<f64>>) -> Self {
+		Self {
+			position_jitter: position_jitter.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets the maximum random change in pitch (in semitones) applied
+	to each grain.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundSettings, so the return value should be used"]
+	pub fn pitch_jitter(self, pitch_jitter: impl Into<Value<f64>>) -> Self {
+		Self {
+			pitch_jitter: pitch_jitter.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets how far grains can be randomly panned from the sound's panning,
+	from `0.0` (not at all) to `1.0` (anywhere from hard left to hard right).
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundSettings, so the return value should be used"]
+	pub fn stereo_spread(self, stereo_spread: impl Into<Value<f64>>) -> Self {
+		Self {
+			stereo_spread: stereo_spread.into(),
+			..self
+		}
+	}
+
+	/// Sets the volume of the sound.
+	#[must_use = "This method consumes self and returns a modified GranularSoundSettings, so the return value should be used"]
+	pub fn volume(self, volume: impl Into<Value<Decibels>>) -> Self {
+		Self {
+			volume: volume.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets the playback rate of each grain.
+
+	Changing the playback rate will change the pitch of the grains,
+	but not how often they're spawned.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundSettings, so the return value should be used"]
+	pub fn playback_rate(self, playback_rate: impl Into<Value<PlaybackRate>>) -> Self {
+		Self {
+			playback_rate: playback_rate.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets the panning of the sound, where -1.0 is hard left
+	and 1.0 is hard right.
+	*/
+	#[must_use = "This method consumes self and returns a modified GranularSoundSettings, so the return value should be used"]
+	pub fn panning(self, panning: impl Into<Value<Panning>>) -> Self {
+		Self {
+			panning: panning.into(),
+			..self
+		}
+	}
+
+	/// Sets the tween used to fade in the sound from silence.
+	#[must_use = "This method consumes self and returns a modified GranularSoundSettings, so the return value should be used"]
+	pub fn fade_in_tween(self, fade_in_tween: impl Into<Option<Tween>>) -> Self {
+		Self {
+			fade_in_tween: fade_in_tween.into(),
+			..self
+		}
+	}
+}
+
+impl Default for GranularSoundSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
@@ -0,0 +1,357 @@
+#[cfg(test)]
+mod test;
+
+use std::{
+	f64::consts::TAU,
+	sync::{
+		Arc,
+		atomic::{AtomicU8, AtomicUsize, Ordering},
+	},
+};
+
+use crate::{
+	Decibels, Panning, Parameter, PlaybackRate, StartTime, Tween,
+	command::read_commands_into_parameters,
+	event::EventProducer,
+	frame::{Frame, interpolate_frame},
+	info::Info,
+	playback_state_manager::PlaybackStateManager,
+	random::Random,
+	sound::{
+		PlaybackState, Sound, SoundEvent,
+		static_sound::{frame_at_index, num_frames},
+	},
+	start_time::{StartTimeProgress, num_frames_before_start},
+};
+
+use super::{CommandReaders, GranularSoundData};
+
+/// The maximum number of grains that can play at once. If a grain
+/// would be spawned while this many are playing, it's skipped.
+const MAX_GRAINS: usize = 128;
+/// The maximum number of grains spawned per second. Higher
+/// densities (including infinity) spawn grains at this rate.
+const MAX_DENSITY: f64 = 100_000.0;
+
+pub(super) struct GranularSound {
+	command_readers: CommandReaders,
+	sample_rate: u32,
+	frames: Arc<[Frame]>,
+	slice: Option<(usize, usize)>,
+	grains: Vec<Grain>,
+	/// How long until the next grain should be spawned (in seconds).
+	time_until_next_grain: f64,
+	random: Random,
+	playback_state_manager: PlaybackStateManager,
+	start_time: StartTime,
+	started: bool,
+	grain_size: Parameter,
+	density: Parameter,
+	position: Parameter,
+	position_jitter: Parameter,
+	pitch_jitter: Parameter,
+	stereo_spread: Parameter,
+	volume: Parameter<Decibels>,
+	playback_rate: Parameter<PlaybackRate>,
+	panning: Parameter<Panning>,
+	shared: Arc<Shared>,
+	event_producer: EventProducer<SoundEvent>,
+}
+
+impl GranularSound {
+	#[must_use]
+	pub fn new(
+		data: GranularSoundData,
+		command_readers: CommandReaders,
+		event_producer: EventProducer<SoundEvent>,
+		shared: Arc<Shared>,
+	) -> Self {
+		let settings = data.settings;
+		Self {
+			command_readers,
+			sample_rate: data.sample_rate,
+			frames: data.frames,
+			slice: data.slice,
+			grains: Vec::with_capacity(MAX_GRAINS),
+			time_until_next_grain: 0.0,
+			random: Random::new(),
+			playback_state_manager: PlaybackStateManager::new(settings.fade_in_tween),
+			start_time: settings.start_time,
+			started: false,
+			grain_size: Parameter::new(settings.grain_size, 0.1),
+			density: Parameter::new(settings.density, 20.0),
+			position: Parameter::new(settings.position, 0.0),
+			position_jitter: Parameter::new(settings.position_jitter, 0.0),
+			pitch_jitter: Parameter::new(settings.pitch_jitter, 0.0),
+			stereo_spread: Parameter::new(settings.stereo_spread, 0.0),
+			volume: Parameter::new(settings.volume, Decibels::IDENTITY),
+			playback_rate: Parameter::new(settings.playback_rate, PlaybackRate(1.0)),
+			panning: Parameter::new(settings.panning, Panning::CENTER),
+			shared,
+			event_producer,
+		}
+	}
+
+	fn update_shared_playback_state(&mut self) {
+		self.shared
+			.set_state(self.playback_state_manager.playback_state());
+	}
+
+	fn pause(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.pause(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn resume(&mut self, start_time: StartTime, fade_in_tween: Tween) {
+		self.playback_state_manager
+			.resume(start_time, fade_in_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn stop(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.stop(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn spawn_grain(&mut self, time_in_chunk: f64) {
+		let grain_size = self.grain_size.interpolated_value(time_in_chunk);
+		if self.grains.len() >= MAX_GRAINS || grain_size <= 0.0 {
+			return;
+		}
+		let position = self.position.interpolated_value(time_in_chunk)
+			+ self.position_jitter.interpolated_value(time_in_chunk) * self.random.bipolar();
+		let pitch_offset =
+			self.pitch_jitter.interpolated_value(time_in_chunk) * self.random.bipolar();
+		let playback_rate = self.playback_rate.interpolated_value(time_in_chunk).0
+			* 2.0f64.powf(pitch_offset / 12.0);
+		let panning_offset =
+			(self.stereo_spread.interpolated_value(time_in_chunk) * self.random.bipolar()) as f32;
+		self.grains.push(Grain {
+			position: position * self.sample_rate as f64,
+			playback_rate,
+			panning_offset,
+			age: 0.0,
+			duration: grain_size,
+		});
+	}
+
+	/// Returns the frame of source audio at the given position,
+	/// wrapping around to the beginning if needed.
+	fn frame_at_position(&self, position: f64) -> Frame {
+		let num_frames = num_frames(&self.frames, self.slice);
+		if num_frames == 0 {
+			return Frame::ZERO;
+		}
+		let index = position.floor() as i64;
+		let frame = |offset: i64| {
+			let index = (index + offset).rem_euclid(num_frames as i64) as usize;
+			frame_at_index(index, &self.frames, self.slice).unwrap_or_default()
+		};
+		interpolate_frame(
+			frame(-1),
+			frame(0),
+			frame(1),
+			frame(2),
+			position.fract() as f32,
+		)
+	}
+
+	fn read_commands(&mut self) {
+		read_commands_into_parameters!(
+			self,
+			grain_size,
+			density,
+			position,
+			position_jitter,
+			pitch_jitter,
+			stereo_spread,
+			volume,
+			playback_rate,
+			panning
+		);
+		if let Some(tween) = self.command_readers.pause.read() {
+			self.pause(tween);
+		}
+		if let Some((start_time, tween)) = self.command_readers.resume.read() {
+			self.resume(start_time, tween);
+		}
+		if let Some(tween) = self.command_readers.stop.read() {
+			self.stop(tween);
+		}
+	}
+}
+
+impl Sound for GranularSound {
+	fn on_start_processing(&mut self) {
+		self.shared
+			.num_active_grains
+			.store(self.grains.len(), Ordering::SeqCst);
+		self.read_commands();
+	}
+
+	fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
+		// update parameters
+		self.grain_size.update(dt * out.len() as f64, info);
+		self.density.update(dt * out.len() as f64, info);
+		self.position.update(dt * out.len() as f64, info);
+		self.position_jitter.update(dt * out.len() as f64, info);
+		self.pitch_jitter.update(dt * out.len() as f64, info);
+		self.stereo_spread.update(dt * out.len() as f64, info);
+		self.volume.update(dt * out.len() as f64, info);
+		self.playback_rate.update(dt * out.len() as f64, info);
+		self.panning.update(dt * out.len() as f64, info);
+		let changed_playback_state = self
+			.playback_state_manager
+			.update(dt * out.len() as f64, info);
+		if changed_playback_state {
+			self.update_shared_playback_state();
+			// the fade out finishes at the end of this batch
+			if self.playback_state_manager.playback_state() == PlaybackState::Stopped {
+				self.event_producer
+					.push(SoundEvent::Stopped, info.time() + dt * out.len() as f64);
+			}
+		}
+
+		let start_delay = match self.start_time.update(dt * out.len() as f64, info) {
+			StartTimeProgress::Waiting => {
+				out.fill(Frame::ZERO);
+				return;
+			}
+			StartTimeProgress::Started { delay } => delay,
+			StartTimeProgress::WillNeverStart => {
+				self.playback_state_manager.mark_as_stopped();
+				self.update_shared_playback_state();
+				self.event_producer.push(SoundEvent::Stopped, info.time());
+				out.fill(Frame::ZERO);
+				return;
+			}
+		};
+		if !self.started {
+			self.started = true;
+			self.event_producer
+				.push(SoundEvent::Started, info.time() + start_delay);
+		}
+
+		if !self.playback_state_manager.playback_state().is_advancing() {
+			out.fill(Frame::ZERO);
+			return;
+		}
+
+		// if playback started partway through this batch, the frames
+		// before that point stay silent
+		let num_frames = out.len();
+		let start_frame = num_frames_before_start(
+			start_delay.max(self.playback_state_manager.start_delay()),
+			dt,
+			num_frames,
+		);
+		out[..start_frame].fill(Frame::ZERO);
+
+		// play back grains
+		for (i, frame) in out.iter_mut().enumerate().skip(start_frame) {
+			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
+			let density = self
+				.density
+				.interpolated_value(time_in_chunk)
+				.min(MAX_DENSITY);
+			if density > 0.0 {
+				self.time_until_next_grain -= dt;
+				let mut num_grains_spawned = 0;
+				while self.time_until_next_grain <= 0.0 {
+					// no more than the maximum number of grains can play at
+					// once, so there's no use in spawning more in one frame
+					if num_grains_spawned >= MAX_GRAINS {
+						self.time_until_next_grain = 1.0 / density;
+						break;
+					}
+					self.spawn_grain(time_in_chunk);
+					num_grains_spawned += 1;
+					self.time_until_next_grain += 1.0 / density;
+				}
+			}
+			let panning = self.panning.interpolated_value(time_in_chunk);
+			let mut grain_out = Frame::ZERO;
+			for grain in &self.grains {
+				let grain_panning = Panning((panning.0 + grain.panning_offset).clamp(-1.0, 1.0));
+				grain_out += (self.frame_at_position(grain.position) * grain.window() as f32)
+					.panned(grain_panning);
+			}
+			let sample_rate = self.sample_rate as f64;
+			self.grains.retain_mut(|grain| {
+				grain.position += grain.playback_rate * sample_rate * dt;
+				grain.age += dt;
+				grain.age < grain.duration
+			});
+			let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
+			let fade_volume = self
+				.playback_state_manager
+				.interpolated_fade_volume(time_in_chunk)
+				.as_amplitude();
+			*frame = grain_out * fade_volume * volume;
+		}
+	}
+
+	fn finished(&self) -> bool {
+		self.playback_state_manager.playback_state() == PlaybackState::Stopped
+	}
+}
+
+/// A short piece of the source audio.
+struct Grain {
+	/// The position in the source audio (in frames).
+	position: f64,
+	playback_rate: f64,
+	panning_offset: f32,
+	/// How long the grain has been playing (in seconds).
+	age: f64,
+	/// How long the grain plays for (in seconds).
+	duration: f64,
+}
+
+impl Grain {
+	/// Returns the volume of the grain at its current age.
+	///
+	/// Grains are faded in and out with a Hann window to avoid clicks.
+	fn window(&self) -> f64 {
+		0.5 - 0.5 * (TAU * self.age / self.duration).cos()
+	}
+}
+
+#[derive(Debug)]
+pub(super) struct Shared {
+	state: AtomicU8,
+	num_active_grains: AtomicUsize,
+}
+
+impl Shared {
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			state: AtomicU8::new(PlaybackState::Playing as u8),
+			num_active_grains: AtomicUsize::new(0),
+		}
+	}
+
+	#[must_use]
+	pub fn state(&self) -> PlaybackState {
+		match self.state.load(Ordering::SeqCst) {
+			0 => PlaybackState::Playing,
+			1 => PlaybackState::Pausing,
+			2 => PlaybackState::Paused,
+			3 => PlaybackState::WaitingToResume,
+			4 => PlaybackState::Resuming,
+			5 => PlaybackState::Stopping,
+			6 => PlaybackState::Stopped,
+			_ => panic!("Invalid playback state"),
+		}
+	}
+
+	pub fn set_state(&self, state: PlaybackState) {
+		self.state.store(state as u8, Ordering::SeqCst);
+	}
+
+	#[must_use]
+	pub fn num_active_grains(&self) -> usize {
+		self.num_active_grains.load(Ordering::SeqCst)
+	}
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use crate::{
+	frame::Frame,
+	info::MockInfoBuilder,
+	sound::{
+		Sound,
+		granular::GranularSoundData,
+		static_sound::{StaticSoundData, StaticSoundSettings},
+	},
+};
+
+fn constant_source() -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 100,
+		frames: vec![Frame::from_mono(1.0); 100].into(),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+}
+
+/// Tests that a `GranularSoundData` shares the audio of the
+/// `StaticSoundData` it's created from.
+#[test]
+fn shares_source_audio() {
+	let source = constant_source();
+	let data = GranularSoundData::new(&source);
+	assert!(Arc::ptr_eq(&data.frames, &source.frames));
+}
+
+/// Tests that grains are faded in and out.
+#[test]
+fn grains_are_windowed() {
+	let (mut sound, _) = GranularSoundData::new(&constant_source())
+		.grain_size(0.04)
+		.density(1.0)
+		.split();
+	let info = MockInfoBuilder::new().build();
+	for expected in [0.0, 0.5, 1.0, 0.5, 0.0, 0.0] {
+		let frame = sound.process_one(0.01, &info);
+		assert!((frame.left - expected).abs() < 0.0001);
+		assert_eq!(frame.left, frame.right);
+	}
+}
+
+/// Tests that grains are spawned at the requested density.
+#[test]
+fn spawns_grains_at_density() {
+	let (mut sound, handle) = GranularSoundData::new(&constant_source())
+		.grain_size(0.05)
+		.density(10.0)
+		.split();
+	let info = MockInfoBuilder::new().build();
+	let mut num_grains_spawned = 0;
+	let mut previous_num_active_grains = 0;
+	for _ in 0..100 {
+		sound.process_one(0.01, &info);
+		sound.on_start_processing();
+		let num_active_grains = handle.num_active_grains();
+		if num_active_grains > previous_num_active_grains {
+			num_grains_spawned += 1;
+		}
+		previous_num_active_grains = num_active_grains;
+	}
+	assert_eq!(num_grains_spawned, 10);
+}
+
+/// Tests that very high densities are limited instead of
+/// spawning grains forever.
+#[test]
+fn limits_density() {
+	for density in [f64::INFINITY, 1.0e300] {
+		let (mut sound, handle) = GranularSoundData::new(&constant_source())
+			.grain_size(10.0)
+			.density(density)
+			.split();
+		let info = MockInfoBuilder::new().build();
+		// a long frame would spawn more grains than can play at once
+		sound.process_one(1.0, &info);
+		sound.on_start_processing();
+		assert_eq!(handle.num_active_grains(), super::MAX_GRAINS);
+	}
+}
+
+/// Tests that grains are randomly panned when the stereo
+/// spread is greater than 0.
+#[test]
+fn stereo_spread() {
+	let (mut sound, _) = GranularSoundData::new(&constant_source())
+		.grain_size(0.05)
+		.density(100.0)
+		.stereo_spread(1.0)
+		.split();
+	let info = MockInfoBuilder::new().build();
+	let frames = (0..100)
+		.map(|_| sound.process_one(0.01, &info))
+		.collect::<Vec<_>>();
+	assert!(
+		frames
+			.iter()
+			.any(|frame| (frame.left - frame.right).abs() > 0.01)
+	);
+}
@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod test;
+
+#[cfg(feature = "symphonia")]
+mod read;
+
+#[cfg(feature = "symphonia")]
+pub(crate) use read::*;
+
+use std::collections::VecDeque;
+
+use super::PlaybackPosition;
+
+/**
+A named point in a sound.
+
+When playback reaches a marker, the sound emits a
+[`SoundEvent::Marker`](super::SoundEvent::Marker) event with the index
+of the marker, which can be read with the `pop_event` method on the
+sound's handle. This is useful for syncing subtitles, animations, and
+other game events to dialogue and music.
+
+Markers are loaded from cue points in WAV files and markers in AIFF
+files, and more can be added with the `with_marker` method on the
+sound data or settings.
+*/
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Marker {
+	/// The name of the marker.
+	///
+	/// Markers loaded from a file without a label have an empty name.
+	pub name: String,
+	/// Where the marker is in the sound.
+	///
+	/// This is measured from the start of the audio, even if the
+	/// sound is sliced, so markers loaded from a file stay in place.
+	/// Markers outside of the slice are never reached.
+	pub position: PlaybackPosition,
+}
+
+impl Marker {
+	/// Creates a new [`Marker`].
+	#[must_use]
+	pub fn new(name: impl Into<String>, position: impl Into<PlaybackPosition>) -> Self {
+		Self {
+			name: name.into(),
+			position: position.into(),
+		}
+	}
+}
+
+/// The frames of a sound that have markers, converted from
+/// [`Marker`]s so they can be looked up quickly on the audio thread.
+pub(crate) struct MarkerFrames {
+	/// Pairs of frame indices and marker indices, sorted by frame index.
+	frames: Vec<(usize, usize)>,
+}
+
+impl MarkerFrames {
+	/// Converts markers to frame indices relative to the start of
+	/// the slice (if any).
+	#[must_use]
+	pub fn new(markers: &[Marker], sample_rate: u32, slice: Option<(usize, usize)>) -> Self {
+		let mut frames = markers
+			.iter()
+			.enumerate()
+			.filter_map(|(marker_index, marker)| {
+				let frame_index = marker.position.into_samples(sample_rate);
+				let frame_index = match slice {
+					Some((start, end)) if (start..end).contains(&frame_index) => {
+						frame_index - start
+					}
+					Some(_) => return None,
+					None => frame_index,
+				};
+				Some((frame_index, marker_index))
+			})
+			.collect::<Vec<_>>();
+		frames.sort();
+		Self { frames }
+	}
+
+	/// Returns the indices of the markers at the given frame.
+	pub fn at(&self, frame_index: usize) -> impl Iterator<Item = usize> + '_ {
+		let start = self
+			.frames
+			.partition_point(|(marker_frame_index, _)| *marker_frame_index < frame_index);
+		self.frames[start..]
+			.iter()
+			.take_while(move |(marker_frame_index, _)| *marker_frame_index == frame_index)
+			.map(|(_, marker_index)| *marker_index)
+	}
+}
+
+/// Markers that playback has reached in the input of a time stretcher,
+/// but not in its output yet.
+pub(crate) struct PendingMarkers {
+	/// Pairs of time stretcher input positions and marker indices,
+	/// sorted by position.
+	markers: VecDeque<(usize, usize)>,
+}
+
+impl PendingMarkers {
+	#[must_use]
+	pub fn new(marker_frames: &MarkerFrames) -> Self {
+		Self {
+			markers: VecDeque::with_capacity(marker_frames.frames.len()),
+		}
+	}
+
+	/// Adds a marker that should be emitted once the output reaches
+	/// the given input position.
+	pub fn push(&mut self, position: usize, marker_index: usize) {
+		self.markers.push_back((position, marker_index));
+	}
+
+	/// Removes and returns the index of the next marker at or before
+	/// the given input position.
+	pub fn pop_reached(&mut self, position: usize) -> Option<usize> {
+		let (marker_position, marker_index) = *self.markers.front()?;
+		if marker_position > position {
+			return None;
+		}
+		self.markers.pop_front();
+		Some(marker_index)
+	}
+
+	pub fn clear(&mut self) {
+		self.markers.clear();
+	}
+}
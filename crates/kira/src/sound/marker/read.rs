@@ -0,0 +1,195 @@
+#[cfg(test)]
+mod test;
+
+use std::{
+	collections::HashMap,
+	io::{ErrorKind, Read, Seek, SeekFrom},
+};
+
+use crate::sound::PlaybackPosition;
+
+use super::Marker;
+
+/**
+Reads the cue points from a WAV file or the markers from an AIFF file,
+sorted by position.
+
+Other kinds of files (and files with malformed marker chunks) have no
+markers. The reader is returned to where it started afterwards so
+the audio can be decoded.
+*/
+pub(crate) fn read_markers(
+	source: &mut (impl Read + Seek + ?Sized),
+) -> std::io::Result<Vec<Marker>> {
+	let start = source.stream_position()?;
+	let markers = read_markers_from_chunks(source)
+		.unwrap_or_default()
+		.into_iter()
+		.map(|(position, name)| Marker {
+			name,
+			position: PlaybackPosition::Samples(position as usize),
+		})
+		.collect::<Vec<_>>();
+	source.seek(SeekFrom::Start(start))?;
+	Ok(markers)
+}
+
+/// Returns the position and name of each marker.
+fn read_markers_from_chunks(
+	source: &mut (impl Read + Seek + ?Sized),
+) -> std::io::Result<Vec<(u32, String)>> {
+	let mut header = [0; 12];
+	source.read_exact(&mut header)?;
+	let mut markers = match (&header[0..4], &header[8..12]) {
+		(b"RIFF", b"WAVE") => read_wav_markers(source)?,
+		(b"FORM", b"AIFF" | b"AIFC") => read_aiff_markers(source)?,
+		_ => vec![],
+	};
+	markers.sort_by_key(|(position, _)| *position);
+	Ok(markers)
+}
+
+/// Reads the "cue " chunk and the labels in the "adtl" list of a WAV file.
+fn read_wav_markers(
+	source: &mut (impl Read + Seek + ?Sized),
+) -> std::io::Result<Vec<(u32, String)>> {
+	let mut cue_points = vec![];
+	let mut labels = HashMap::new();
+	for_each_chunk(
+		source,
+		Endianness::Little,
+		&[b"cue ", b"LIST"],
+		|id, data| match id {
+			b"cue " => {
+				let num_cue_points = read_u32_le(data, 0).unwrap_or(0) as usize;
+				cue_points = data
+					.get(4..)
+					.unwrap_or_default()
+					.chunks_exact(24)
+					.take(num_cue_points)
+					.map(|cue_point| {
+						let id = read_u32_le(cue_point, 0).unwrap();
+						let sample_offset = read_u32_le(cue_point, 20).unwrap();
+						(id, sample_offset)
+					})
+					.collect();
+			}
+			b"LIST" if data.starts_with(b"adtl") => {
+				let mut sub_chunks = &data[4..];
+				while let (Some(sub_chunk_id), Some(size)) =
+					(sub_chunks.get(0..4), read_u32_le(sub_chunks, 4))
+				{
+					let size = size as usize;
+					let Some(sub_chunk_data) = sub_chunks.get(8..8 + size) else {
+						break;
+					};
+					if sub_chunk_id == b"labl" {
+						if let Some(cue_point_id) = read_u32_le(sub_chunk_data, 0) {
+							labels.insert(cue_point_id, read_string(&sub_chunk_data[4..]));
+						}
+					}
+					sub_chunks = sub_chunks.get(8 + size + size % 2..).unwrap_or_default();
+				}
+			}
+			_ => {}
+		},
+	)?;
+	Ok(cue_points
+		.into_iter()
+		.map(|(id, sample_offset)| (sample_offset, labels.remove(&id).unwrap_or_default()))
+		.collect())
+}
+
+/// Reads the "MARK" chunk of an AIFF file.
+fn read_aiff_markers(
+	source: &mut (impl Read + Seek + ?Sized),
+) -> std::io::Result<Vec<(u32, String)>> {
+	let mut markers = vec![];
+	for_each_chunk(source, Endianness::Big, &[b"MARK"], |_, data| {
+		let num_markers = data
+			.get(0..2)
+			.map_or(0, |bytes| u16::from_be_bytes([bytes[0], bytes[1]]));
+		let mut remaining = data.get(2..).unwrap_or_default();
+		for _ in 0..num_markers {
+			// each marker is a 2 byte ID, a 4 byte position, and a name
+			// with a 1 byte length, padded to an even number of bytes
+			let (Some(position), Some(name_length)) = (read_u32_be(remaining, 2), remaining.get(6))
+			else {
+				break;
+			};
+			let name_length = *name_length as usize;
+			let Some(name) = remaining.get(7..7 + name_length) else {
+				break;
+			};
+			markers.push((position, read_string(name)));
+			let padded_name_length = (name_length + 1).next_multiple_of(2);
+			remaining = remaining.get(6 + padded_name_length..).unwrap_or_default();
+		}
+	})?;
+	Ok(markers)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Endianness {
+	Little,
+	Big,
+}
+
+/// Calls `f` with the ID and contents of each top-level chunk of a
+/// RIFF or IFF file whose ID is in `ids`.
+///
+/// Other chunks (like the audio data) are skipped without being
+/// read into memory.
+fn for_each_chunk(
+	source: &mut (impl Read + Seek + ?Sized),
+	endianness: Endianness,
+	ids: &[&[u8; 4]],
+	mut f: impl FnMut(&[u8; 4], &[u8]),
+) -> std::io::Result<()> {
+	let mut data = vec![];
+	loop {
+		let mut header = [0; 8];
+		match source.read_exact(&mut header) {
+			Ok(()) => {}
+			Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Ok(()),
+			Err(error) => return Err(error),
+		}
+		let id = [header[0], header[1], header[2], header[3]];
+		let size_bytes = [header[4], header[5], header[6], header[7]];
+		let size = match endianness {
+			Endianness::Little => u32::from_le_bytes(size_bytes),
+			Endianness::Big => u32::from_be_bytes(size_bytes),
+		} as u64;
+		// chunks are padded to an even number of bytes
+		let padded_size = size + size % 2;
+		if ids.contains(&&id) {
+			data.clear();
+			Read::take(&mut *source, padded_size).read_to_end(&mut data)?;
+			data.truncate(size as usize);
+			f(&id, &data);
+		} else {
+			source.seek(SeekFrom::Current(padded_size as i64))?;
+		}
+	}
+}
+
+fn read_u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+	bytes
+		.get(offset..offset + 4)
+		.map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u32_be(bytes: &[u8], offset: usize) -> Option<u32> {
+	bytes
+		.get(offset..offset + 4)
+		.map(|bytes| u32::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+/// Reads a label, which may be null-terminated.
+fn read_string(bytes: &[u8]) -> String {
+	let end = bytes
+		.iter()
+		.position(|byte| *byte == 0)
+		.unwrap_or(bytes.len());
+	String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
@@ -0,0 +1,116 @@
+use std::io::{Cursor, Seek};
+
+use crate::sound::{Marker, PlaybackPosition};
+
+use super::read_markers;
+
+fn chunk(id: &[u8; 4], data: &[u8], big_endian: bool) -> Vec<u8> {
+	let size = data.len() as u32;
+	let mut bytes = id.to_vec();
+	bytes.extend(if big_endian {
+		size.to_be_bytes()
+	} else {
+		size.to_le_bytes()
+	});
+	bytes.extend(data);
+	if data.len() % 2 == 1 {
+		bytes.push(0);
+	}
+	bytes
+}
+
+fn cue_point(id: u32, sample_offset: u32) -> Vec<u8> {
+	let mut bytes = id.to_le_bytes().to_vec();
+	bytes.extend(0u32.to_le_bytes());
+	bytes.extend(b"data");
+	bytes.extend(0u32.to_le_bytes());
+	bytes.extend(0u32.to_le_bytes());
+	bytes.extend(sample_offset.to_le_bytes());
+	bytes
+}
+
+fn label(cue_point_id: u32, text: &str) -> Vec<u8> {
+	let mut data = cue_point_id.to_le_bytes().to_vec();
+	data.extend(text.as_bytes());
+	data.push(0);
+	chunk(b"labl", &data, false)
+}
+
+fn wav(chunks: &[Vec<u8>]) -> Vec<u8> {
+	let mut bytes = b"RIFF".to_vec();
+	bytes.extend(0u32.to_le_bytes());
+	bytes.extend(b"WAVE");
+	for chunk in chunks {
+		bytes.extend(chunk);
+	}
+	bytes
+}
+
+/// Tests that cue points and their labels are read from WAV files.
+#[test]
+fn reads_wav_cue_points() {
+	let mut cue = 3u32.to_le_bytes().to_vec();
+	cue.extend(cue_point(1, 500));
+	cue.extend(cue_point(2, 100));
+	cue.extend(cue_point(3, 300));
+	let mut list = b"adtl".to_vec();
+	list.extend(label(1, "end"));
+	list.extend(label(2, "hello"));
+	let bytes = wav(&[
+		chunk(b"fmt ", &[0; 16], false),
+		chunk(b"data", &[0; 7], false),
+		chunk(b"cue ", &cue, false),
+		chunk(b"LIST", &list, false),
+	]);
+	let mut cursor = Cursor::new(bytes);
+	assert_eq!(
+		read_markers(&mut cursor).unwrap(),
+		[
+			Marker::new("hello", PlaybackPosition::Samples(100)),
+			Marker::new("", PlaybackPosition::Samples(300)),
+			Marker::new("end", PlaybackPosition::Samples(500)),
+		]
+	);
+	// the reader should be back at the start so the audio can be decoded
+	assert_eq!(cursor.stream_position().unwrap(), 0);
+}
+
+/// Tests that markers are read from AIFF files.
+#[test]
+fn reads_aiff_markers() {
+	let mut mark = 2u16.to_be_bytes().to_vec();
+	mark.extend(1u16.to_be_bytes());
+	mark.extend(200u32.to_be_bytes());
+	mark.push(5);
+	mark.extend(b"verse");
+	mark.extend(2u16.to_be_bytes());
+	mark.extend(400u32.to_be_bytes());
+	mark.push(6);
+	mark.extend(b"chorus");
+	mark.push(0);
+	let mut bytes = b"FORM".to_vec();
+	bytes.extend(0u32.to_be_bytes());
+	bytes.extend(b"AIFF");
+	bytes.extend(chunk(b"COMM", &[0; 18], true));
+	bytes.extend(chunk(b"MARK", &mark, true));
+	bytes.extend(chunk(b"SSND", &[0; 9], true));
+	assert_eq!(
+		read_markers(&mut Cursor::new(bytes)).unwrap(),
+		[
+			Marker::new("verse", PlaybackPosition::Samples(200)),
+			Marker::new("chorus", PlaybackPosition::Samples(400)),
+		]
+	);
+}
+
+/// Tests that files without markers (or with malformed headers)
+/// have no markers.
+#[test]
+fn other_files_have_no_markers() {
+	let bytes = wav(&[chunk(b"data", &[0; 8], false)]);
+	assert_eq!(read_markers(&mut Cursor::new(bytes)).unwrap(), []);
+	assert_eq!(
+		read_markers(&mut Cursor::new(b"OggS".to_vec())).unwrap(),
+		[]
+	);
+}
@@ -0,0 +1,49 @@
+use super::{Marker, MarkerFrames, PendingMarkers};
+
+/// Tests that `MarkerFrames` finds every marker at a frame.
+#[test]
+fn finds_markers_at_frames() {
+	let markers = [
+		Marker::new("b", 2.0),
+		Marker::new("a", 1.0),
+		Marker::new("c", 2.0),
+	];
+	let marker_frames = MarkerFrames::new(&markers, 10, None);
+	assert_eq!(marker_frames.at(0).collect::<Vec<_>>(), []);
+	assert_eq!(marker_frames.at(10).collect::<Vec<_>>(), [1]);
+	assert_eq!(marker_frames.at(20).collect::<Vec<_>>(), [0, 2]);
+	assert_eq!(marker_frames.at(30).collect::<Vec<_>>(), []);
+}
+
+/// Tests that `MarkerFrames` offsets markers by the start of the slice
+/// and leaves out markers outside of the slice.
+#[test]
+fn offsets_markers_by_slice() {
+	let markers = [
+		Marker::new("before", 0.5),
+		Marker::new("start", 1.0),
+		Marker::new("middle", 1.5),
+		Marker::new("end", 2.0),
+	];
+	let marker_frames = MarkerFrames::new(&markers, 10, Some((10, 20)));
+	assert_eq!(marker_frames.at(0).collect::<Vec<_>>(), [1]);
+	assert_eq!(marker_frames.at(5).collect::<Vec<_>>(), [2]);
+	assert_eq!(marker_frames.at(10).collect::<Vec<_>>(), []);
+}
+
+/// Tests that `PendingMarkers` only returns markers once their
+/// position is reached.
+#[test]
+fn releases_pending_markers_in_order() {
+	let marker_frames = MarkerFrames::new(&[], 10, None);
+	let mut pending_markers = PendingMarkers::new(&marker_frames);
+	pending_markers.push(5, 1);
+	pending_markers.push(5, 0);
+	pending_markers.push(8, 2);
+	assert_eq!(pending_markers.pop_reached(4), None);
+	assert_eq!(pending_markers.pop_reached(6), Some(1));
+	assert_eq!(pending_markers.pop_reached(6), Some(0));
+	assert_eq!(pending_markers.pop_reached(6), None);
+	pending_markers.clear();
+	assert_eq!(pending_markers.pop_reached(10), None);
+}
@@ -0,0 +1,84 @@
+/*!
+Sounds that synthesize simple tones and noise.
+
+This is useful for UI beeps, debug sounds, and placeholder audio
+that would otherwise have to be baked into audio files.
+
+```no_run
+use std::time::Duration;
+
+use kira::{
+	AudioManager, AudioManagerSettings, DefaultBackend,
+	sound::oscillator::{OscillatorSoundData, OscillatorWaveform},
+};
+
+let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+// play a 440Hz sine wave for a quarter of a second
+manager.play(
+	OscillatorSoundData::new(OscillatorWaveform::Sine, 440.0).duration(Duration::from_millis(250)),
+)?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+
+The frequency is a [`Value`](crate::Value), so it can be tweened
+with [`OscillatorSoundHandle::set_frequency`] or linked to a
+[modulator](crate::modulator).
+*/
+
+mod data;
+mod handle;
+mod settings;
+mod sound;
+
+pub use data::*;
+pub use handle::*;
+pub use settings::*;
+
+use crate::{
+	Decibels, Panning, StartTime, command::ValueChangeCommand, command_writers_and_readers,
+	tween::Tween,
+};
+
+/// The shape of the sound an oscillator produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OscillatorWaveform {
+	/// A pure tone.
+	Sine,
+	/// A soft, hollow tone.
+	Triangle,
+	/// A bright, buzzy tone.
+	///
+	/// The waveform is band-limited to reduce aliasing at
+	/// high frequencies.
+	Saw,
+	/// A hollow, reedy tone.
+	///
+	/// The waveform is band-limited to reduce aliasing at
+	/// high frequencies.
+	Square,
+	/// Noise with equal energy at every frequency.
+	///
+	/// Noise ignores the oscillator's frequency.
+	WhiteNoise,
+	/// Noise with equal energy in every octave, which sounds
+	/// more balanced than white noise.
+	///
+	/// Noise ignores the oscillator's frequency.
+	PinkNoise,
+	/// Noise with most of its energy in low frequencies,
+	/// like a distant rumble.
+	///
+	/// Noise ignores the oscillator's frequency.
+	BrownNoise,
+}
+
+command_writers_and_readers! {
+	set_waveform: OscillatorWaveform,
+	set_frequency: ValueChangeCommand<f64>,
+	set_volume: ValueChangeCommand<Decibels>,
+	set_panning: ValueChangeCommand<Panning>,
+	pause: Tween,
+	resume: (StartTime, Tween),
+	stop: Tween,
+}
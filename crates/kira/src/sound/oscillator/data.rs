@@ -0,0 +1,114 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+	Decibels, Panning, StartTime, Tween, Value,
+	event::event_queue,
+	sound::{Sound, SoundData},
+};
+
+use super::{
+	OscillatorSoundHandle, OscillatorSoundSettings, OscillatorWaveform,
+	command_writers_and_readers,
+	sound::{OscillatorSound, Shared},
+};
+
+/// A synthesized tone or noise that is not playing yet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OscillatorSoundData {
+	/// The shape of the sound.
+	pub waveform: OscillatorWaveform,
+	/// The frequency of the tone (in Hz).
+	pub frequency: Value<f64>,
+	/// Settings for the sound.
+	pub settings: OscillatorSoundSettings,
+}
+
+impl OscillatorSoundData {
+	/// Creates a new [`OscillatorSoundData`] with the given waveform
+	/// and frequency (in Hz).
+	#[must_use]
+	pub fn new(waveform: OscillatorWaveform, frequency: impl Into<Value<f64>>) -> Self {
+		Self {
+			waveform,
+			frequency: frequency.into(),
+			settings: OscillatorSoundSettings::default(),
+		}
+	}
+
+	/// Sets when the sound should start playing.
+	#[must_use = "This method consumes self and returns a modified OscillatorSoundData, so the return value should be used"]
+	pub fn start_time(mut self, start_time: impl Into<StartTime>) -> Self {
+		self.settings.start_time = start_time.into();
+		self
+	}
+
+	/**
+	Sets how long the sound should play for.
+
+	If [`None`], the sound plays until it's stopped.
+	*/
+	#[must_use = "This method consumes self and returns a modified OscillatorSoundData, so the return value should be used"]
+	pub fn duration(mut self, duration: impl Into<Option<Duration>>) -> Self {
+		self.settings.duration = duration.into();
+		self
+	}
+
+	/// Sets the volume of the sound.
+	#[must_use = "This method consumes self and returns a modified OscillatorSoundData, so the return value should be used"]
+	pub fn volume(mut self, volume: impl Into<Value<Decibels>>) -> Self {
+		self.settings.volume = volume.into();
+		self
+	}
+
+	/**
+	Sets the panning of the sound, where -1.0 is hard left
+	and 1.0 is hard right.
+	*/
+	#[must_use = "This method consumes self and returns a modified OscillatorSoundData, so the return value should be used"]
+	pub fn panning(mut self, panning: impl Into<Value<Panning>>) -> Self {
+		self.settings.panning = panning.into();
+		self
+	}
+
+	/// Sets the tween used to fade in the sound from silence.
+	#[must_use = "This method consumes self and returns a modified OscillatorSoundData, so the return value should be used"]
+	pub fn fade_in_tween(mut self, fade_in_tween: impl Into<Option<Tween>>) -> Self {
+		self.settings.fade_in_tween = fade_in_tween.into();
+		self
+	}
+
+	/// Sets the settings for the sound.
+	#[must_use = "This method consumes self and returns a modified OscillatorSoundData, so the return value should be used"]
+	pub fn with_settings(mut self, settings: OscillatorSoundSettings) -> Self {
+		self.settings = settings;
+		self
+	}
+
+	#[must_use]
+	pub(super) fn split(self) -> (OscillatorSound, OscillatorSoundHandle) {
+		let (command_writers, command_readers) = command_writers_and_readers();
+		let (event_producer, event_consumer) = event_queue();
+		let shared = Arc::new(Shared::new());
+		let sound = OscillatorSound::new(self, command_readers, event_producer, shared.clone());
+		(
+			sound,
+			OscillatorSoundHandle {
+				command_writers,
+				shared,
+				event_consumer,
+			},
+		)
+	}
+}
+
+impl SoundData for OscillatorSoundData {
+	type Error = ();
+
+	type Handle = OscillatorSoundHandle;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+		let (sound, handle) = self.split();
+		Ok((Box::new(sound), handle))
+	}
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use crate::{
+	Decibels, Event, Panning, StartTime, Tween,
+	command::handle_param_setters,
+	event::EventConsumer,
+	sound::{PlaybackState, SoundEvent},
+};
+
+use super::{CommandWriters, OscillatorWaveform, sound::Shared};
+
+/// Controls an oscillator sound.
+#[derive(Debug)]
+pub struct OscillatorSoundHandle {
+	pub(super) command_writers: CommandWriters,
+	pub(super) shared: Arc<Shared>,
+	pub(super) event_consumer: EventConsumer<SoundEvent>,
+}
+
+impl OscillatorSoundHandle {
+	/// Returns the current playback state of the sound.
+	#[must_use]
+	pub fn state(&self) -> PlaybackState {
+		self.shared.state()
+	}
+
+	/// Returns the next event that happened to the sound, if any.
+	///
+	/// Events are returned in the order they happened.
+	#[must_use]
+	pub fn pop_event(&mut self) -> Option<Event<SoundEvent>> {
+		self.event_consumer.pop()
+	}
+
+	/// Sets the shape of the sound.
+	pub fn set_waveform(&mut self, waveform: OscillatorWaveform) {
+		self.command_writers.set_waveform.write(waveform)
+	}
+
+	handle_param_setters! {
+		/// Sets the frequency of the tone (in Hz).
+		frequency: f64,
+
+		/// Sets the volume of the sound.
+		volume: Decibels,
+
+		/// Sets the panning of the sound, where `-1.0` is hard left,
+		/// `0.0` is center, and `1.0` is hard right.
+		panning: Panning,
+	}
+
+	/// Fades out the sound to silence with the given tween and then
+	/// pauses playback.
+	pub fn pause(&mut self, tween: Tween) {
+		self.command_writers.pause.write(tween)
+	}
+
+	/// Resumes playback and fades in the sound from silence
+	/// with the given tween.
+	pub fn resume(&mut self, tween: Tween) {
+		self.resume_at(StartTime::Immediate, tween)
+	}
+
+	/// Resumes playback at the given start time and fades in
+	/// the sound from silence with the given tween.
+	pub fn resume_at(&mut self, start_time: StartTime, tween: Tween) {
+		self.command_writers.resume.write((start_time, tween))
+	}
+
+	/// Fades out the sound to silence with the given tween and then
+	/// stops playback.
+	///
+	/// Once the sound is stopped, it cannot be restarted.
+	pub fn stop(&mut self, tween: Tween) {
+		self.command_writers.stop.write(tween)
+	}
+}
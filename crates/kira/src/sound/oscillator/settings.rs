@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use crate::{Decibels, Panning, StartTime, Tween, Value};
+
+/// Settings for an oscillator sound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OscillatorSoundSettings {
+	/// When the sound should start playing.
+	pub start_time: StartTime,
+	/// How long the sound should play for, or [`None`] if it
+	/// should play until it's stopped.
+	pub duration: Option<Duration>,
+	/// The volume of the sound.
+	pub volume: Value<Decibels>,
+	/// The panning of the sound, where -1.0 is hard left
+	/// and 1.0 is hard right.
+	pub panning: Value<Panning>,
+	/// An optional fade-in from silence.
+	pub fade_in_tween: Option<Tween>,
+}
+
+impl OscillatorSoundSettings {
+	/// Creates a new [`OscillatorSoundSettings`] with the default settings.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			start_time: StartTime::default(),
+			duration: None,
+			volume: Value::Fixed(Decibels::IDENTITY),
+			panning: Value::Fixed(Panning::CENTER),
+			fade_in_tween: None,
+		}
+	}
+
+	/** Sets when the sound should start playing. */
+	#[must_use = "This method consumes self and returns a modified OscillatorSoundSettings, so the return value should be used"]
+	pub fn start_time(self, start_time: impl Into<StartTime>) -> Self {
+		Self {
+			start_time: start_time.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets how long the sound should play for.
+
+	If [`None`], the sound plays until it's stopped.
+	*/
+	#[must_use = "This method consumes self and returns a modified OscillatorSoundSettings, so the return value should be used"]
+	pub fn duration(self, duration: impl Into<Option<Duration>>) -> Self {
+		Self {
+			duration: duration.into(),
+			..self
+		}
+	}
+
+	/** Sets the volume of the sound. */
+	#[must_use = "This method consumes self and returns a modified OscillatorSoundSettings, so the return value should be used"]
+	pub fn volume(self, volume: impl Into<Value<Decibels>>) -> Self {
+		Self {
+			volume: volume.into(),
+			..self
+		}
+	}
+
+	/**
+	Sets the panning of the sound, where -1.0 is hard left
+	and 1.0 is hard right.
+	*/
+	#[must_use = "This method consumes self and returns a modified OscillatorSoundSettings, so the return value should be used"]
+	pub fn panning(self, panning: impl Into<Value<Panning>>) -> Self {
+		Self {
+			panning: panning.into(),
+			..self
+		}
+	}
+
+	/// Sets the tween used to fade in the sound from silence.
+	#[must_use = "This method consumes self and returns a modified OscillatorSoundSettings, so the return value should be used"]
+	pub fn fade_in_tween(self, fade_in_tween: impl Into<Option<Tween>>) -> Self {
+		Self {
+			fade_in_tween: fade_in_tween.into(),
+			..self
+		}
+	}
+}
+
+impl Default for OscillatorSoundSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
@@ -0,0 +1,309 @@
+#[cfg(test)]
+mod test;
+
+use std::{
+	f64::consts::TAU,
+	sync::{
+		Arc,
+		atomic::{AtomicU8, Ordering},
+	},
+};
+
+use crate::{
+	Decibels, Panning, Parameter, StartTime, Tween,
+	command::read_commands_into_parameters,
+	event::EventProducer,
+	frame::Frame,
+	info::Info,
+	playback_state_manager::PlaybackStateManager,
+	random::Random,
+	sound::{PlaybackState, Sound, SoundEvent},
+	start_time::{StartTimeProgress, num_frames_before_start},
+};
+
+use super::{CommandReaders, OscillatorSoundData, OscillatorWaveform};
+
+pub(super) struct OscillatorSound {
+	command_readers: CommandReaders,
+	waveform: OscillatorWaveform,
+	/// The position in the current cycle of the waveform,
+	/// from 0.0 to 1.0.
+	phase: f64,
+	noise: Noise,
+	/// How much longer the sound should play (in seconds),
+	/// or `None` if it should play until it's stopped.
+	time_remaining: Option<f64>,
+	playback_state_manager: PlaybackStateManager,
+	start_time: StartTime,
+	started: bool,
+	frequency: Parameter,
+	volume: Parameter<Decibels>,
+	panning: Parameter<Panning>,
+	shared: Arc<Shared>,
+	event_producer: EventProducer<SoundEvent>,
+}
+
+impl OscillatorSound {
+	#[must_use]
+	pub fn new(
+		data: OscillatorSoundData,
+		command_readers: CommandReaders,
+		event_producer: EventProducer<SoundEvent>,
+		shared: Arc<Shared>,
+	) -> Self {
+		let settings = data.settings;
+		Self {
+			command_readers,
+			waveform: data.waveform,
+			phase: 0.0,
+			noise: Noise::new(),
+			time_remaining: settings.duration.map(|duration| duration.as_secs_f64()),
+			playback_state_manager: PlaybackStateManager::new(settings.fade_in_tween),
+			start_time: settings.start_time,
+			started: false,
+			frequency: Parameter::new(data.frequency, 440.0),
+			volume: Parameter::new(settings.volume, Decibels::IDENTITY),
+			panning: Parameter::new(settings.panning, Panning::CENTER),
+			shared,
+			event_producer,
+		}
+	}
+
+	fn update_shared_playback_state(&mut self) {
+		self.shared
+			.set_state(self.playback_state_manager.playback_state());
+	}
+
+	fn pause(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.pause(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn resume(&mut self, start_time: StartTime, fade_in_tween: Tween) {
+		self.playback_state_manager
+			.resume(start_time, fade_in_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn stop(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.stop(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	/// Returns the next sample of the waveform and advances the phase.
+	fn next_sample(&mut self, frequency: f64, dt: f64) -> f64 {
+		let phase_increment = (frequency * dt).abs().min(0.5);
+		let phase = self.phase;
+		self.phase = (self.phase + phase_increment).fract();
+		match self.waveform {
+			OscillatorWaveform::Sine => (phase * TAU).sin(),
+			OscillatorWaveform::Triangle => ((phase + 0.75).fract() - 0.5).abs() * 4.0 - 1.0,
+			OscillatorWaveform::Saw => {
+				let phase = (phase + 0.5).fract();
+				phase * 2.0 - 1.0 - poly_blep(phase, phase_increment)
+			}
+			OscillatorWaveform::Square => {
+				let naive = if phase < 0.5 { 1.0 } else { -1.0 };
+				naive + poly_blep(phase, phase_increment)
+					- poly_blep((phase + 0.5).fract(), phase_increment)
+			}
+			OscillatorWaveform::WhiteNoise => self.noise.white(),
+			OscillatorWaveform::PinkNoise => self.noise.pink(),
+			OscillatorWaveform::BrownNoise => self.noise.brown(),
+		}
+	}
+
+	fn read_commands(&mut self) {
+		read_commands_into_parameters!(self, frequency, volume, panning);
+		if let Some(waveform) = self.command_readers.set_waveform.read() {
+			self.waveform = waveform;
+		}
+		if let Some(tween) = self.command_readers.pause.read() {
+			self.pause(tween);
+		}
+		if let Some((start_time, tween)) = self.command_readers.resume.read() {
+			self.resume(start_time, tween);
+		}
+		if let Some(tween) = self.command_readers.stop.read() {
+			self.stop(tween);
+		}
+	}
+}
+
+impl Sound for OscillatorSound {
+	fn on_start_processing(&mut self) {
+		self.read_commands();
+	}
+
+	fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
+		// update parameters
+		self.frequency.update(dt * out.len() as f64, info);
+		self.volume.update(dt * out.len() as f64, info);
+		self.panning.update(dt * out.len() as f64, info);
+		let changed_playback_state = self
+			.playback_state_manager
+			.update(dt * out.len() as f64, info);
+		if changed_playback_state {
+			self.update_shared_playback_state();
+			// the fade out finishes at the end of this batch
+			if self.playback_state_manager.playback_state() == PlaybackState::Stopped {
+				self.event_producer
+					.push(SoundEvent::Stopped, info.time() + dt * out.len() as f64);
+			}
+		}
+
+		let start_delay = match self.start_time.update(dt * out.len() as f64, info) {
+			StartTimeProgress::Waiting => {
+				out.fill(Frame::ZERO);
+				return;
+			}
+			StartTimeProgress::Started { delay } => delay,
+			StartTimeProgress::WillNeverStart => {
+				self.playback_state_manager.mark_as_stopped();
+				self.update_shared_playback_state();
+				self.event_producer.push(SoundEvent::Stopped, info.time());
+				out.fill(Frame::ZERO);
+				return;
+			}
+		};
+		if !self.started {
+			self.started = true;
+			self.event_producer
+				.push(SoundEvent::Started, info.time() + start_delay);
+		}
+
+		if !self.playback_state_manager.playback_state().is_advancing() {
+			out.fill(Frame::ZERO);
+			return;
+		}
+
+		// if playback started partway through this batch, the frames
+		// before that point stay silent
+		let num_frames = out.len();
+		let start_frame = num_frames_before_start(
+			start_delay.max(self.playback_state_manager.start_delay()),
+			dt,
+			num_frames,
+		);
+		out[..start_frame].fill(Frame::ZERO);
+
+		// synthesize audio
+		for i in start_frame..num_frames {
+			if let Some(time_remaining) = &mut self.time_remaining {
+				if *time_remaining <= 0.0 {
+					self.playback_state_manager.mark_as_stopped();
+					self.update_shared_playback_state();
+					self.event_producer
+						.push(SoundEvent::Finished, info.time() + i as f64 * dt);
+					out[i..].fill(Frame::ZERO);
+					return;
+				}
+				*time_remaining -= dt;
+			}
+			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
+			let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
+			let fade_volume = self
+				.playback_state_manager
+				.interpolated_fade_volume(time_in_chunk)
+				.as_amplitude();
+			let panning = self.panning.interpolated_value(time_in_chunk);
+			let frequency = self.frequency.interpolated_value(time_in_chunk);
+			let sample = self.next_sample(frequency, dt) as f32;
+			out[i] = (Frame::from_mono(sample) * fade_volume * volume).panned(panning);
+		}
+	}
+
+	fn finished(&self) -> bool {
+		self.playback_state_manager.playback_state() == PlaybackState::Stopped
+	}
+}
+
+/// Smooths out the discontinuity in a waveform that jumps
+/// down when `phase` wraps around, which reduces aliasing.
+///
+/// This is the polynomial band-limited step (PolyBLEP) technique.
+fn poly_blep(phase: f64, phase_increment: f64) -> f64 {
+	if phase < phase_increment {
+		let t = phase / phase_increment;
+		t + t - t * t - 1.0
+	} else if phase > 1.0 - phase_increment {
+		let t = (phase - 1.0) / phase_increment;
+		t * t + t + t + 1.0
+	} else {
+		0.0
+	}
+}
+
+/// Generates white, pink, and brown noise.
+struct Noise {
+	random: Random,
+	pink_filter: [f64; 3],
+	brown_filter: f64,
+}
+
+impl Noise {
+	#[must_use]
+	fn new() -> Self {
+		Self {
+			random: Random::new(),
+			pink_filter: [0.0; 3],
+			brown_filter: 0.0,
+		}
+	}
+
+	/// Returns a random number from -1.0 to 1.0.
+	fn white(&mut self) -> f64 {
+		self.random.bipolar()
+	}
+
+	/// Filters white noise to fall off by 3dB per octave.
+	///
+	/// Uses Paul Kellet's economy pink noise filter.
+	fn pink(&mut self) -> f64 {
+		let white = self.white();
+		let [b0, b1, b2] = &mut self.pink_filter;
+		*b0 = 0.99765 * *b0 + white * 0.0990460;
+		*b1 = 0.96300 * *b1 + white * 0.2965164;
+		*b2 = 0.57000 * *b2 + white * 1.0526913;
+		((*b0 + *b1 + *b2 + white * 0.1848) * 0.11).clamp(-1.0, 1.0)
+	}
+
+	/// Integrates white noise so it falls off by 6dB per octave.
+	fn brown(&mut self) -> f64 {
+		let white = self.white();
+		self.brown_filter = (self.brown_filter + 0.02 * white) / 1.02;
+		(self.brown_filter * 3.3).clamp(-1.0, 1.0)
+	}
+}
+
+#[derive(Debug)]
+pub(super) struct Shared {
+	state: AtomicU8,
+}
+
+impl Shared {
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			state: AtomicU8::new(PlaybackState::Playing as u8),
+		}
+	}
+
+	#[must_use]
+	pub fn state(&self) -> PlaybackState {
+		match self.state.load(Ordering::SeqCst) {
+			0 => PlaybackState::Playing,
+			1 => PlaybackState::Pausing,
+			2 => PlaybackState::Paused,
+			3 => PlaybackState::WaitingToResume,
+			4 => PlaybackState::Resuming,
+			5 => PlaybackState::Stopping,
+			6 => PlaybackState::Stopped,
+			_ => panic!("Invalid playback state"),
+		}
+	}
+
+	pub fn set_state(&self, state: PlaybackState) {
+		self.state.store(state as u8, Ordering::SeqCst);
+	}
+}
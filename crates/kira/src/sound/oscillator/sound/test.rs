@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use crate::{
+	Panning, Tween,
+	frame::Frame,
+	info::MockInfoBuilder,
+	sound::{
+		PlaybackState, Sound, SoundEvent,
+		oscillator::{OscillatorSoundData, OscillatorWaveform},
+	},
+};
+
+/// Tests that an `OscillatorSound` synthesizes the requested waveform.
+#[test]
+fn plays_sine_wave() {
+	let (mut sound, _) = OscillatorSoundData::new(OscillatorWaveform::Sine, 1.0).split();
+	let info = MockInfoBuilder::new().build();
+	for expected in [0.0, 1.0, 0.0, -1.0, 0.0] {
+		let frame = sound.process_one(0.25, &info);
+		let expected = Frame::from_mono(expected).panned(Panning::CENTER);
+		assert!((frame.left - expected.left).abs() < 0.0001);
+		assert!((frame.right - expected.right).abs() < 0.0001);
+	}
+}
+
+/// Tests that the frequency of an `OscillatorSound` can be changed
+/// from its handle.
+#[test]
+fn frequency_can_be_changed() {
+	let (mut sound, mut handle) =
+		OscillatorSoundData::new(OscillatorWaveform::Triangle, 1.0).split();
+	let info = MockInfoBuilder::new().build();
+	handle.set_frequency(
+		2.0,
+		Tween {
+			duration: Duration::ZERO,
+			..Default::default()
+		},
+	);
+	sound.on_start_processing();
+	assert_eq!(sound.process_one(0.125, &info), Frame::ZERO);
+	// at 2Hz, the triangle wave reaches its peak after an eighth of a second
+	let expected = Frame::from_mono(1.0).panned(Panning::CENTER);
+	assert_eq!(sound.process_one(0.125, &info), expected);
+}
+
+/// Tests that an `OscillatorSound` stops after its duration.
+#[test]
+fn stops_after_duration() {
+	let (mut sound, mut handle) = OscillatorSoundData::new(OscillatorWaveform::WhiteNoise, 1.0)
+		.duration(Duration::from_secs(3))
+		.split();
+	let info = MockInfoBuilder::new().build();
+	for _ in 0..3 {
+		assert_ne!(sound.process_one(1.0, &info), Frame::ZERO);
+		sound.on_start_processing();
+		assert_eq!(handle.state(), PlaybackState::Playing);
+	}
+	assert_eq!(sound.process_one(1.0, &info), Frame::ZERO);
+	assert_eq!(handle.state(), PlaybackState::Stopped);
+	assert!(sound.finished());
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.map(|event| event.kind)
+		.collect::<Vec<_>>();
+	assert_eq!(events, [SoundEvent::Started, SoundEvent::Finished]);
+}
+
+/// Tests that an `OscillatorSound` without a duration plays until
+/// it's stopped.
+#[test]
+fn plays_until_stopped() {
+	let (mut sound, mut handle) =
+		OscillatorSoundData::new(OscillatorWaveform::PinkNoise, 1.0).split();
+	let info = MockInfoBuilder::new().build();
+	for _ in 0..100 {
+		sound.process_one(1.0, &info);
+	}
+	assert!(!sound.finished());
+	handle.stop(Tween {
+		duration: Duration::ZERO,
+		..Default::default()
+	});
+	sound.on_start_processing();
+	sound.process_one(1.0, &info);
+	assert!(sound.finished());
+}
+
+/// Tests that every noise waveform stays within the range
+/// of a full-scale signal.
+#[test]
+fn noise_is_in_range() {
+	for waveform in [
+		OscillatorWaveform::WhiteNoise,
+		OscillatorWaveform::PinkNoise,
+		OscillatorWaveform::BrownNoise,
+	] {
+		let (mut sound, _) = OscillatorSoundData::new(waveform, 1.0).split();
+		let info = MockInfoBuilder::new().build();
+		let mut peak = 0.0f32;
+		for _ in 0..10_000 {
+			peak = peak.max(sound.process_one(1.0 / 48_000.0, &info).left.abs());
+		}
+		assert!(peak > 0.1, "{waveform:?} is too quiet");
+		assert!(peak <= 1.0, "{waveform:?} is too loud");
+	}
+}
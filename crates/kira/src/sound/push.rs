@@ -0,0 +1,51 @@
+/*!
+Sounds that play audio pushed to them while they're playing.
+
+This is useful for audio that's generated outside of Kira, like
+decoded voice chat, emulator output, or the audio track of a video.
+Audio is sent to the renderer through a lock-free ring buffer, so
+frames can be pushed from any thread.
+
+```no_run
+use kira::{
+	AudioManager, AudioManagerSettings, DefaultBackend, Frame,
+	sound::push::PushSoundData,
+};
+
+let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+// accept audio at 22050Hz and buffer up to half a second of it
+let mut sound = manager.play(PushSoundData::new(22050, 11025))?;
+sound.push_frames(&[Frame::from_mono(0.5), Frame::from_mono(-0.5)]);
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+
+If the renderer runs out of pushed frames, it plays silence until
+more frames are available. The number of frames of silence played
+this way can be checked with [`PushSoundHandle::num_underrun_frames`].
+
+Once the [`PushSoundHandle`] is dropped, the sound will finish after
+playing the frames that have already been pushed.
+*/
+
+mod data;
+mod handle;
+mod settings;
+mod sound;
+
+pub use data::*;
+pub use handle::*;
+pub use settings::*;
+
+use crate::{
+	Decibels, Panning, PlaybackRate, StartTime, command::ValueChangeCommand,
+	command_writers_and_readers, tween::Tween,
+};
+
+command_writers_and_readers! {
+	set_volume: ValueChangeCommand<Decibels>,
+	set_playback_rate: ValueChangeCommand<PlaybackRate>,
+	set_panning: ValueChangeCommand<Panning>,
+	pause: Tween,
+	resume: (StartTime, Tween),
+	stop: Tween,
+}
@@ -0,0 +1,117 @@
+use std::sync::{Arc, Mutex};
+
+use rtrb::RingBuffer;
+
+use crate::{
+	Decibels, Panning, PlaybackRate, StartTime, Tween, Value,
+	sound::{Sound, SoundData},
+};
+
+use super::{
+	PushSoundHandle, PushSoundSettings, command_writers_and_readers,
+	sound::{PushSound, Shared},
+};
+
+/// A sound that plays audio pushed to its handle, but is not
+/// playing yet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PushSoundData {
+	/// The sample rate of the pushed audio (in Hz).
+	pub sample_rate: u32,
+	/// The maximum number of frames that can be waiting
+	/// to be played at a time.
+	pub capacity: usize,
+	/// Settings for the sound.
+	pub settings: PushSoundSettings,
+}
+
+impl PushSoundData {
+	/// Creates a new [`PushSoundData`] that accepts audio at the given
+	/// sample rate and can buffer up to `capacity` frames.
+	#[must_use]
+	pub fn new(sample_rate: u32, capacity: usize) -> Self {
+		Self {
+			sample_rate,
+			capacity,
+			settings: PushSoundSettings::default(),
+		}
+	}
+
+	/// Sets when the sound should start playing.
+	#[must_use = "This method consumes self and returns a modified PushSoundData, so the return value should be used"]
+	pub fn start_time(mut self, start_time: impl Into<StartTime>) -> Self {
+		self.settings.start_time = start_time.into();
+		self
+	}
+
+	/// Sets the volume of the sound.
+	#[must_use = "This method consumes self and returns a modified PushSoundData, so the return value should be used"]
+	pub fn volume(mut self, volume: impl Into<Value<Decibels>>) -> Self {
+		self.settings.volume = volume.into();
+		self
+	}
+
+	/**
+	Sets the playback rate of the sound.
+
+	Changing the playback rate will change both the speed
+	and the pitch of the sound.
+	*/
+	#[must_use = "This method consumes self and returns a modified PushSoundData, so the return value should be used"]
+	pub fn playback_rate(mut self, playback_rate: impl Into<Value<PlaybackRate>>) -> Self {
+		self.settings.playback_rate = playback_rate.into();
+		self
+	}
+
+	/**
+	Sets the panning of the sound, where -1.0 is hard left
+	and 1.0 is hard right.
+	*/
+	#[must_use = "This method consumes self and returns a modified PushSoundData, so the return value should be used"]
+	pub fn panning(mut self, panning: impl Into<Value<Panning>>) -> Self {
+		self.settings.panning = panning.into();
+		self
+	}
+
+	/// Sets the tween used to fade in the sound from silence.
+	#[must_use = "This method consumes self and returns a modified PushSoundData, so the return value should be used"]
+	pub fn fade_in_tween(mut self, fade_in_tween: impl Into<Option<Tween>>) -> Self {
+		self.settings.fade_in_tween = fade_in_tween.into();
+		self
+	}
+
+	/// Sets the settings for the sound.
+	#[must_use = "This method consumes self and returns a modified PushSoundData, so the return value should be used"]
+	pub fn with_settings(mut self, settings: PushSoundSettings) -> Self {
+		self.settings = settings;
+		self
+	}
+
+	#[must_use]
+	pub(super) fn split(self) -> (PushSound, PushSoundHandle) {
+		let (command_writers, command_readers) = command_writers_and_readers();
+		let (producer, consumer) = RingBuffer::new(self.capacity);
+		let shared = Arc::new(Shared::new());
+		let sound = PushSound::new(self, command_readers, consumer, shared.clone());
+		(
+			sound,
+			PushSoundHandle {
+				command_writers,
+				shared,
+				producer: Mutex::new(producer),
+			},
+		)
+	}
+}
+
+impl SoundData for PushSoundData {
+	type Error = ();
+
+	type Handle = PushSoundHandle;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+		let (sound, handle) = self.split();
+		Ok((Box::new(sound), handle))
+	}
+}
@@ -0,0 +1,131 @@
+use std::{
+	fmt::{Debug, Formatter},
+	sync::{Arc, Mutex},
+};
+
+use rtrb::Producer;
+
+use crate::{
+	Decibels, Panning, PlaybackRate, StartTime, Tween, command::handle_param_setters, frame::Frame,
+	sound::PlaybackState,
+};
+
+use super::{CommandWriters, sound::Shared};
+
+/// Controls a push sound and sends audio to it.
+pub struct PushSoundHandle {
+	pub(super) command_writers: CommandWriters,
+	pub(super) shared: Arc<Shared>,
+	pub(super) producer: Mutex<Producer<Frame>>,
+}
+
+impl PushSoundHandle {
+	/// Returns the current playback state of the sound.
+	#[must_use]
+	pub fn state(&self) -> PlaybackState {
+		self.shared.state()
+	}
+
+	/// Returns the number of frames of silence that have been played
+	/// because no pushed frames were available.
+	#[must_use]
+	pub fn num_underrun_frames(&self) -> u64 {
+		self.shared.num_underrun_frames()
+	}
+
+	/// Returns the number of frames that can be pushed before
+	/// the buffer is full.
+	#[must_use]
+	pub fn available_capacity(&self) -> usize {
+		self.producer
+			.lock()
+			.expect("frame producer mutex poisoned")
+			.slots()
+	}
+
+	/// Queues a frame of audio to be played.
+	///
+	/// If the buffer is full, the frame is returned as an error.
+	pub fn push(&mut self, frame: Frame) -> Result<(), Frame> {
+		self.producer
+			.get_mut()
+			.expect("frame producer mutex poisoned")
+			.push(frame)
+			.map_err(|rtrb::PushError::Full(frame)| frame)
+	}
+
+	/// Queues as many of the given frames as will fit in the buffer
+	/// and returns the number of frames that were queued.
+	pub fn push_frames(&mut self, frames: &[Frame]) -> usize {
+		let producer = self
+			.producer
+			.get_mut()
+			.expect("frame producer mutex poisoned");
+		let num_frames = frames.len().min(producer.slots());
+		match producer.write_chunk_uninit(num_frames) {
+			Ok(chunk) => chunk.fill_from_iter(frames.iter().copied()),
+			Err(_) => 0,
+		}
+	}
+
+	handle_param_setters! {
+		/// Sets the volume of the sound.
+		volume: Decibels,
+
+		/// Sets the playback rate of the sound.
+		///
+		/// Changing the playback rate will change both the speed
+		/// and the pitch of the sound.
+		playback_rate: PlaybackRate,
+
+		/// Sets the panning of the sound, where `-1.0` is hard left,
+		/// `0.0` is center, and `1.0` is hard right.
+		panning: Panning,
+	}
+
+	/// Fades out the sound to silence with the given tween and then
+	/// pauses playback.
+	///
+	/// Pushed frames are kept while the sound is paused.
+	pub fn pause(&mut self, tween: Tween) {
+		self.command_writers.pause.write(tween)
+	}
+
+	/// Resumes playback and fades in the sound from silence
+	/// with the given tween.
+	pub fn resume(&mut self, tween: Tween) {
+		self.resume_at(StartTime::Immediate, tween)
+	}
+
+	/// Resumes playback at the given start time and fades in
+	/// the sound from silence with the given tween.
+	pub fn resume_at(&mut self, start_time: StartTime, tween: Tween) {
+		self.command_writers.resume.write((start_time, tween))
+	}
+
+	/// Fades out the sound to silence with the given tween and then
+	/// stops playback.
+	///
+	/// Once the sound is stopped, it cannot be restarted.
+	pub fn stop(&mut self, tween: Tween) {
+		self.command_writers.stop.write(tween)
+	}
+}
+
+impl Debug for PushSoundHandle {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("PushSoundHandle")
+			.field("command_writers", &self.command_writers)
+			.field("shared", &self.shared)
+			.field("producer", &ProducerDebug)
+			.finish()
+	}
+}
+
+struct ProducerDebug;
+
+impl Debug for ProducerDebug {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Producer").finish()
+	}
+}
@@ -0,0 +1,240 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::{
+	Arc,
+	atomic::{AtomicU8, AtomicU64, Ordering},
+};
+
+use rtrb::Consumer;
+
+use crate::{
+	Decibels, Panning, Parameter, PlaybackRate, StartTime, Tween,
+	command::read_commands_into_parameters,
+	frame::{Frame, interpolate_frame},
+	info::Info,
+	playback_state_manager::PlaybackStateManager,
+	sound::{PlaybackState, Sound},
+	start_time::{StartTimeProgress, num_frames_before_start},
+};
+
+use super::{CommandReaders, data::PushSoundData};
+
+pub(super) struct PushSound {
+	command_readers: CommandReaders,
+	sample_rate: u32,
+	consumer: Consumer<Frame>,
+	playback_state_manager: PlaybackStateManager,
+	start_time: StartTime,
+	/// The most recent frames taken from the ring buffer, used
+	/// for interpolation.
+	frames: [Frame; 4],
+	/// The number of frames left to play after the handle was dropped
+	/// and the ring buffer was emptied.
+	frames_until_empty: usize,
+	fractional_position: f64,
+	volume: Parameter<Decibels>,
+	playback_rate: Parameter<PlaybackRate>,
+	panning: Parameter<Panning>,
+	shared: Arc<Shared>,
+}
+
+impl PushSound {
+	#[must_use]
+	pub fn new(
+		data: PushSoundData,
+		command_readers: CommandReaders,
+		consumer: Consumer<Frame>,
+		shared: Arc<Shared>,
+	) -> Self {
+		let settings = data.settings;
+		Self {
+			command_readers,
+			sample_rate: data.sample_rate,
+			consumer,
+			playback_state_manager: PlaybackStateManager::new(settings.fade_in_tween),
+			start_time: settings.start_time,
+			frames: [Frame::ZERO; 4],
+			frames_until_empty: 4,
+			fractional_position: 0.0,
+			volume: Parameter::new(settings.volume, Decibels::IDENTITY),
+			playback_rate: Parameter::new(settings.playback_rate, PlaybackRate(1.0)),
+			panning: Parameter::new(settings.panning, Panning::CENTER),
+			shared,
+		}
+	}
+
+	fn update_shared_playback_state(&mut self) {
+		self.shared
+			.set_state(self.playback_state_manager.playback_state());
+	}
+
+	fn pause(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.pause(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn resume(&mut self, start_time: StartTime, fade_in_tween: Tween) {
+		self.playback_state_manager
+			.resume(start_time, fade_in_tween);
+		self.update_shared_playback_state();
+	}
+
+	fn stop(&mut self, fade_out_tween: Tween) {
+		self.playback_state_manager.stop(fade_out_tween);
+		self.update_shared_playback_state();
+	}
+
+	/// Takes the next frame from the ring buffer and pushes it
+	/// to the interpolation buffer.
+	fn next_frame(&mut self) {
+		let frame = match self.consumer.pop() {
+			Ok(frame) => frame,
+			Err(_) => {
+				if self.consumer.is_abandoned() {
+					self.frames_until_empty = self.frames_until_empty.saturating_sub(1);
+					if self.frames_until_empty == 0 {
+						self.playback_state_manager.mark_as_stopped();
+						self.update_shared_playback_state();
+					}
+				} else {
+					self.shared
+						.num_underrun_frames
+						.fetch_add(1, Ordering::SeqCst);
+				}
+				Frame::ZERO
+			}
+		};
+		self.frames.copy_within(1.., 0);
+		self.frames[self.frames.len() - 1] = frame;
+	}
+
+	fn read_commands(&mut self) {
+		read_commands_into_parameters!(self, volume, playback_rate, panning);
+		if let Some(tween) = self.command_readers.pause.read() {
+			self.pause(tween);
+		}
+		if let Some((start_time, tween)) = self.command_readers.resume.read() {
+			self.resume(start_time, tween);
+		}
+		if let Some(tween) = self.command_readers.stop.read() {
+			self.stop(tween);
+		}
+	}
+}
+
+impl Sound for PushSound {
+	fn on_start_processing(&mut self) {
+		self.read_commands();
+	}
+
+	fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
+		// update parameters
+		self.volume.update(dt * out.len() as f64, info);
+		self.playback_rate.update(dt * out.len() as f64, info);
+		self.panning.update(dt * out.len() as f64, info);
+		let changed_playback_state = self
+			.playback_state_manager
+			.update(dt * out.len() as f64, info);
+		if changed_playback_state {
+			self.update_shared_playback_state();
+		}
+
+		let start_delay = match self.start_time.update(dt * out.len() as f64, info) {
+			StartTimeProgress::Waiting => {
+				out.fill(Frame::ZERO);
+				return;
+			}
+			StartTimeProgress::Started { delay } => delay,
+			StartTimeProgress::WillNeverStart => {
+				self.playback_state_manager.mark_as_stopped();
+				self.update_shared_playback_state();
+				out.fill(Frame::ZERO);
+				return;
+			}
+		};
+
+		if !self.playback_state_manager.playback_state().is_advancing() {
+			out.fill(Frame::ZERO);
+			return;
+		}
+
+		// if playback started partway through this batch, the frames
+		// before that point stay silent
+		let num_frames = out.len();
+		let start_frame = num_frames_before_start(
+			start_delay.max(self.playback_state_manager.start_delay()),
+			dt,
+			num_frames,
+		);
+		out[..start_frame].fill(Frame::ZERO);
+
+		// play back audio
+		for (i, frame) in out.iter_mut().enumerate().skip(start_frame) {
+			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
+			let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
+			let fade_volume = self
+				.playback_state_manager
+				.interpolated_fade_volume(time_in_chunk)
+				.as_amplitude();
+			let panning = self.panning.interpolated_value(time_in_chunk);
+			let playback_rate = self.playback_rate.interpolated_value(time_in_chunk);
+			let interpolated_out = interpolate_frame(
+				self.frames[0],
+				self.frames[1],
+				self.frames[2],
+				self.frames[3],
+				self.fractional_position as f32,
+			);
+			self.fractional_position += self.sample_rate as f64 * playback_rate.0.abs() * dt;
+			while self.fractional_position >= 1.0 {
+				self.fractional_position -= 1.0;
+				self.next_frame();
+			}
+			*frame = (interpolated_out * fade_volume * volume).panned(panning);
+		}
+	}
+
+	fn finished(&self) -> bool {
+		self.playback_state_manager.playback_state() == PlaybackState::Stopped
+	}
+}
+
+#[derive(Debug)]
+pub(super) struct Shared {
+	state: AtomicU8,
+	num_underrun_frames: AtomicU64,
+}
+
+impl Shared {
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			state: AtomicU8::new(PlaybackState::Playing as u8),
+			num_underrun_frames: AtomicU64::new(0),
+		}
+	}
+
+	#[must_use]
+	pub fn state(&self) -> PlaybackState {
+		match self.state.load(Ordering::SeqCst) {
+			0 => PlaybackState::Playing,
+			1 => PlaybackState::Pausing,
+			2 => PlaybackState::Paused,
+			3 => PlaybackState::WaitingToResume,
+			4 => PlaybackState::Resuming,
+			5 => PlaybackState::Stopping,
+			6 => PlaybackState::Stopped,
+			_ => panic!("Invalid playback state"),
+		}
+	}
+
+	pub fn set_state(&self, state: PlaybackState) {
+		self.state.store(state as u8, Ordering::SeqCst);
+	}
+
+	#[must_use]
+	pub fn num_underrun_frames(&self) -> u64 {
+		self.num_underrun_frames.load(Ordering::SeqCst)
+	}
+}
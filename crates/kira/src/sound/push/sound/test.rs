@@ -0,0 +1,133 @@
+use crate::{
+	Decibels, Tween,
+	frame::Frame,
+	info::MockInfoBuilder,
+	sound::{
+		PlaybackState, Sound,
+		push::{PushSoundData, PushSoundSettings},
+	},
+};
+
+/// Tests that a `PushSound` plays the frames pushed to its handle.
+#[test]
+fn plays_pushed_frames() {
+	let (mut sound, mut handle) = PushSoundData::new(1, 10).split();
+	assert_eq!(
+		handle.push_frames(&[
+			Frame::from_mono(1.0),
+			Frame::from_mono(2.0),
+			Frame::from_mono(3.0),
+		]),
+		3
+	);
+	// the interpolation buffer has to fill up before
+	// pushed frames are heard
+	for _ in 0..3 {
+		assert_eq!(
+			sound.process_one(1.0, &MockInfoBuilder::new().build()),
+			Frame::ZERO
+		);
+	}
+	for i in 1..=3 {
+		assert_eq!(
+			sound.process_one(1.0, &MockInfoBuilder::new().build()),
+			Frame::from_mono(i as f32)
+		);
+	}
+	assert!(!sound.finished());
+}
+
+/// Tests that a `PushSound` reports how many frames of silence it
+/// had to play because the buffer was empty.
+#[test]
+fn reports_underruns() {
+	let (mut sound, mut handle) = PushSoundData::new(1, 10).split();
+	handle.push(Frame::from_mono(1.0)).unwrap();
+	for _ in 0..5 {
+		sound.process_one(1.0, &MockInfoBuilder::new().build());
+	}
+	assert_eq!(handle.num_underrun_frames(), 4);
+}
+
+/// Tests that frames pushed to a full buffer are rejected.
+#[test]
+fn rejects_frames_when_full() {
+	let (_, mut handle) = PushSoundData::new(1, 2).split();
+	assert_eq!(handle.available_capacity(), 2);
+	assert_eq!(
+		handle.push_frames(&[Frame::from_mono(1.0), Frame::from_mono(2.0)]),
+		2
+	);
+	assert_eq!(handle.available_capacity(), 0);
+	assert_eq!(
+		handle.push(Frame::from_mono(3.0)),
+		Err(Frame::from_mono(3.0))
+	);
+	assert_eq!(handle.push_frames(&[Frame::from_mono(3.0)]), 0);
+}
+
+/// Tests that a `PushSound` consumes frames at the sample rate
+/// of the pushed audio.
+#[test]
+fn resamples_pushed_audio() {
+	let (mut sound, mut handle) = PushSoundData::new(2, 10).split();
+	handle.push_frames(&[Frame::from_mono(1.0); 4]);
+	sound.process_one(1.0, &MockInfoBuilder::new().build());
+	assert_eq!(handle.available_capacity(), 8);
+	sound.process_one(1.0, &MockInfoBuilder::new().build());
+	assert_eq!(handle.available_capacity(), 10);
+	assert_eq!(handle.num_underrun_frames(), 0);
+}
+
+/// Tests that a `PushSound` finishes after its handle is dropped
+/// and all of the pushed frames have been played.
+#[test]
+fn finishes_after_handle_is_dropped() {
+	let (mut sound, mut handle) = PushSoundData::new(1, 10).split();
+	handle.push_frames(&[Frame::from_mono(1.0); 2]);
+	drop(handle);
+	for _ in 0..5 {
+		sound.process_one(1.0, &MockInfoBuilder::new().build());
+		assert!(!sound.finished());
+	}
+	sound.process_one(1.0, &MockInfoBuilder::new().build());
+	assert!(sound.finished());
+}
+
+/// Tests that a `PushSound` does not consume frames while paused.
+#[test]
+fn does_not_consume_frames_while_paused() {
+	let (mut sound, mut handle) = PushSoundData::new(1, 10).split();
+	handle.push_frames(&[Frame::from_mono(1.0); 10]);
+	handle.pause(Tween {
+		duration: std::time::Duration::ZERO,
+		..Default::default()
+	});
+	sound.on_start_processing();
+	sound.process_one(1.0, &MockInfoBuilder::new().build());
+	assert_eq!(
+		sound.playback_state_manager.playback_state(),
+		PlaybackState::Paused
+	);
+	for _ in 0..5 {
+		sound.process_one(1.0, &MockInfoBuilder::new().build());
+	}
+	assert_eq!(handle.available_capacity(), 0);
+}
+
+/// Tests that the volume, playback rate, and panning settings
+/// are applied.
+#[test]
+fn applies_settings() {
+	let (mut sound, mut handle) = PushSoundData::new(1, 10)
+		.with_settings(PushSoundSettings::new().panning(-1.0).volume(-6.0))
+		.split();
+	handle.push_frames(&[Frame::from_mono(1.0); 10]);
+	for _ in 0..3 {
+		sound.process_one(1.0, &MockInfoBuilder::new().build());
+	}
+	let frame = sound.process_one(1.0, &MockInfoBuilder::new().build());
+	let expected = Decibels(-6.0).as_amplitude() * std::f32::consts::SQRT_2;
+	assert!((frame.left - expected).abs() < 1.0e-6);
+	assert_eq!(frame.right, 0.0);
+}
@@ -39,8 +39,11 @@ use super::Region;
 command_writers_and_readers! {
 	set_volume: ValueChangeCommand<Decibels>,
 	set_playback_rate: ValueChangeCommand<PlaybackRate>,
+	set_tempo: ValueChangeCommand<f64>,
 	set_panning: ValueChangeCommand<Panning>,
 	set_loop_region: Option<Region>,
+	exit_loop: (),
+	exit_loop_at: StartTime,
 	pause: Tween,
 	resume: (StartTime, Tween),
 	stop: Tween,
@@ -12,6 +12,8 @@ use std::{
 
 use crate::{
 	Decibels, Panning, PlaybackRate, StartTime, Tween, Value,
+	effect::EffectBuilder,
+	event::event_queue,
 	frame::Frame,
 	sound::{EndPosition, IntoOptionalRegion, PlaybackPosition, Region, Sound, SoundData},
 };
@@ -124,6 +126,58 @@ impl StaticSoundData {
 		new
 	}
 
+	/**
+	Sets how long the end of the loop region crossfades into the audio
+	before the start of the loop region, which hides clicks at the loop point.
+
+	This returns a cheap clone of the [`StaticSoundData`] with the modified
+	loop crossfade.
+
+	# Examples
+
+	```
+	# use kira::sound::static_sound::StaticSoundSettings;
+	use std::time::Duration;
+
+	let settings = StaticSoundSettings::new()
+		.loop_region(3.0..)
+		.loop_crossfade(Duration::from_millis(50));
+	```
+	*/
+	#[must_use = "This method returns a modified StaticSoundData and does not mutate the original value"]
+	pub fn loop_crossfade(&self, loop_crossfade: Duration) -> Self {
+		let mut new = self.clone();
+		new.settings.loop_crossfade = loop_crossfade;
+		new
+	}
+
+	/**
+	Sets how many times the loop region should be played before
+	playback continues past the end of it. [`None`] loops forever.
+
+	This returns a cheap clone of the [`StaticSoundData`] with the modified
+	loop count.
+
+	# Examples
+
+	Play an intro, loop the middle of the sound 4 times, and then
+	play the rest of the sound:
+
+	```no_run
+	# use kira::sound::static_sound::StaticSoundData;
+	let sound = StaticSoundData::from_file("music.ogg")?
+		.loop_region(8.0..24.0)
+		.loop_count(4);
+	# Result::<(), Box<dyn std::error::Error>>::Ok(())
+	```
+	*/
+	#[must_use = "This method returns a modified StaticSoundData and does not mutate the original value"]
+	pub fn loop_count(&self, loop_count: impl Into<Option<usize>>) -> Self {
+		let mut new = self.clone();
+		new.settings.loop_count = loop_count.into();
+		new
+	}
+
 	/**
 	Sets the volume of the sound.
 
@@ -228,6 +282,30 @@ impl StaticSoundData {
 		new
 	}
 
+	/**
+	Sets how fast the sound plays relative to its normal speed.
+
+	Unlike the playback rate, changing the tempo doesn't
+	change the pitch of the sound. Tempos are limited to
+	the range `0.0..=4.0`.
+
+	# Examples
+
+	Play a sound at half speed without lowering its pitch:
+
+	```no_run
+	# use kira::sound::static_sound::StaticSoundData;
+	let sound = StaticSoundData::from_file("sound.ogg")?.tempo(0.5);
+	# Result::<(), Box<dyn std::error::Error>>::Ok(())
+	```
+	*/
+	#[must_use = "This method returns a modified StaticSoundData and does not mutate the original value"]
+	pub fn tempo(&self, tempo: impl Into<Value<f64>>) -> Self {
+		let mut new = self.clone();
+		new.settings.tempo = tempo.into();
+		new
+	}
+
 	/**
 	Sets the panning of the sound, where -1.0 is hard left
 	and 1.0 is hard right.
@@ -285,6 +363,70 @@ impl StaticSoundData {
 		new
 	}
 
+	/**
+	Adds an effect that's applied to each instance of the sound
+	before its volume and panning.
+
+	The effect's handle can be retrieved from the [`StaticSoundHandle`]
+	with [`StaticSoundHandle::effect`] and [`StaticSoundHandle::effect_mut`].
+
+	This returns a cheap clone of the [`StaticSoundData`] with the added effect.
+	*/
+	#[must_use = "This method returns a modified StaticSoundData and does not mutate the original value"]
+	pub fn with_effect<B>(&self, builder: B) -> Self
+	where
+		B: EffectBuilder + Clone + Send + Sync + 'static,
+		B::Handle: Send + Sync + 'static,
+	{
+		let mut new = self.clone();
+		new.settings = new.settings.with_effect(builder);
+		new
+	}
+
+	/**
+	Adds a named point in the sound that emits a
+	[`SoundEvent::Marker`](crate::sound::SoundEvent::Marker) event
+	when playback reaches it.
+
+	Markers are also loaded from cue points in WAV files and markers
+	in AIFF files.
+
+	This returns a cheap clone of the [`StaticSoundData`] with the added marker.
+
+	# Examples
+
+	Show a subtitle when a line of dialogue reaches a marker:
+
+	```no_run
+	use kira::{
+		AudioManager, AudioManagerSettings, DefaultBackend,
+		sound::{SoundEvent, static_sound::StaticSoundData},
+	};
+
+	let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+	let mut sound = manager.play(
+		StaticSoundData::from_file("dialogue.wav")?.with_marker("second line", 2.5),
+	)?;
+	// later...
+	while let Some(event) = sound.pop_event() {
+		if let SoundEvent::Marker(index) = event.kind {
+			println!("reached {}", sound.markers()[index].name);
+		}
+	}
+	# Result::<(), Box<dyn std::error::Error>>::Ok(())
+	```
+	*/
+	#[must_use = "This method returns a modified StaticSoundData and does not mutate the original value"]
+	pub fn with_marker(
+		&self,
+		name: impl Into<String>,
+		position: impl Into<PlaybackPosition>,
+	) -> Self {
+		let mut new = self.clone();
+		new.settings = new.settings.with_marker(name, position);
+		new
+	}
+
 	/// Returns a cheap clone of the `StaticSoundData` with the specified settings.
 	#[must_use = "This method returns a modified StaticSoundData and does not mutate the original value"]
 	pub fn with_settings(&self, settings: StaticSoundSettings) -> Self {
@@ -372,13 +514,19 @@ impl StaticSoundData {
 
 	pub(super) fn split(self) -> (StaticSound, StaticSoundHandle) {
 		let (command_writers, command_readers) = command_writers_and_readers();
-		let sound = StaticSound::new(self, command_readers);
+		let (event_producer, event_consumer) = event_queue();
+		let (effects, effect_handles) = self.settings.effects.build();
+		let markers = self.settings.markers.clone();
+		let sound = StaticSound::new(self, effects, command_readers, event_producer);
 		let shared = sound.shared();
 		(
 			sound,
 			StaticSoundHandle {
 				command_writers,
 				shared,
+				event_consumer,
+				effect_handles,
+				markers,
 			},
 		)
 	}
@@ -411,8 +559,8 @@ impl Debug for StaticSoundData {
 	}
 }
 
-struct FramesDebug {
-	len: usize,
+pub(crate) struct FramesDebug {
+	pub len: usize,
 }
 
 impl Debug for FramesDebug {
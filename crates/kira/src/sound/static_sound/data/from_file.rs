@@ -4,13 +4,18 @@ use symphonia::core::io::{MediaSource, MediaSourceStream};
 use symphonia::core::{codecs::CodecParameters, formats::TrackType};
 
 use crate::sound::{
-	FromFileError, static_sound::StaticSoundSettings, symphonia::load_frames_from_buffer_ref,
+	DownmixMatrix, FromFileError, read_markers, static_sound::StaticSoundSettings,
+	symphonia::load_frames_from_buffer_ref,
 };
 
 use super::StaticSoundData;
 
 impl StaticSoundData {
 	/// Loads an audio file into a [`StaticSoundData`].
+	///
+	/// Audio with more than two channels is mixed down to stereo
+	/// using the standard downmix for its channel layout (see
+	/// [`DownmixMatrix::itu`]).
 	#[cfg(not(target_arch = "wasm32"))]
 	#[cfg_attr(docsrs, doc(cfg(all(feature = "symphonia", not(wasm32)))))]
 	pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, FromFileError> {
@@ -31,10 +36,39 @@ impl StaticSoundData {
 	pub fn from_media_source(
 		media_source: impl MediaSource + 'static,
 	) -> Result<Self, FromFileError> {
-		Self::from_boxed_media_source(Box::new(media_source))
+		Self::from_boxed_media_source(Box::new(media_source), None)
 	}
 
-	fn from_boxed_media_source(media_source: Box<dyn MediaSource>) -> Result<Self, FromFileError> {
+	/// Loads an audio file into a [`StaticSoundData`], mixing its
+	/// channels down to stereo with the given [`DownmixMatrix`].
+	#[cfg(not(target_arch = "wasm32"))]
+	#[cfg_attr(docsrs, doc(cfg(all(feature = "symphonia", not(wasm32)))))]
+	pub fn from_file_with_downmix(
+		path: impl AsRef<std::path::Path>,
+		downmix: DownmixMatrix,
+	) -> Result<Self, FromFileError> {
+		Self::from_media_source_with_downmix(std::fs::File::open(path)?, downmix)
+	}
+
+	/// Loads audio from a type that implements Symphonia's [`MediaSource`]
+	/// trait, mixing its channels down to stereo with the given [`DownmixMatrix`].
+	#[cfg_attr(docsrs, doc(cfg(feature = "symphonia")))]
+	pub fn from_media_source_with_downmix(
+		media_source: impl MediaSource + 'static,
+		downmix: DownmixMatrix,
+	) -> Result<Self, FromFileError> {
+		Self::from_boxed_media_source(Box::new(media_source), Some(downmix))
+	}
+
+	fn from_boxed_media_source(
+		mut media_source: Box<dyn MediaSource>,
+		downmix: Option<DownmixMatrix>,
+	) -> Result<Self, FromFileError> {
+		let markers = if media_source.is_seekable() {
+			read_markers(&mut media_source)?
+		} else {
+			vec![]
+		};
 		let codecs = symphonia::default::get_codecs();
 		let probe = symphonia::default::get_probe();
 		let mss = MediaSourceStream::new(media_source, Default::default());
@@ -62,7 +96,7 @@ impl StaticSoundData {
 				Ok(Some(packet)) => {
 					if default_track_id == packet.track_id {
 						let buffer = decoder.decode(&packet)?;
-						frames.append(&mut load_frames_from_buffer_ref(&buffer)?);
+						frames.append(&mut load_frames_from_buffer_ref(&buffer, downmix.as_ref())?);
 					}
 				}
 				Ok(None) => break,
@@ -72,7 +106,10 @@ impl StaticSoundData {
 		Ok(Self {
 			sample_rate,
 			frames: frames.into(),
-			settings: StaticSoundSettings::default(),
+			settings: StaticSoundSettings {
+				markers,
+				..StaticSoundSettings::default()
+			},
 			slice: None,
 		})
 	}
@@ -1,9 +1,10 @@
 use std::sync::Arc;
 
 use crate::{
-	Decibels, Panning, PlaybackRate, StartTime, Tween,
+	Decibels, Event, Panning, PlaybackRate, StartTime, Tween,
 	command::handle_param_setters,
-	sound::{IntoOptionalRegion, PlaybackState},
+	event::EventConsumer,
+	sound::{EffectHandles, IntoOptionalRegion, Marker, PlaybackState, SoundEvent},
 };
 
 use super::{CommandWriters, sound::Shared};
@@ -13,6 +14,9 @@ use super::{CommandWriters, sound::Shared};
 pub struct StaticSoundHandle {
 	pub(super) command_writers: CommandWriters,
 	pub(super) shared: Arc<Shared>,
+	pub(super) event_consumer: EventConsumer<SoundEvent>,
+	pub(super) effect_handles: EffectHandles,
+	pub(super) markers: Vec<Marker>,
 }
 
 impl StaticSoundHandle {
@@ -28,6 +32,42 @@ impl StaticSoundHandle {
 		self.shared.position()
 	}
 
+	/// Returns the next event that happened to the sound, if any.
+	///
+	/// Events are returned in the order they happened.
+	#[must_use]
+	pub fn pop_event(&mut self) -> Option<Event<SoundEvent>> {
+		self.event_consumer.pop()
+	}
+
+	/// Returns the sound's markers.
+	///
+	/// [`SoundEvent::Marker`] events contain an index into this list.
+	#[must_use]
+	pub fn markers(&self) -> &[Marker] {
+		&self.markers
+	}
+
+	/// Returns the handle of the effect at the given index in the
+	/// sound's [`EffectChain`](crate::sound::EffectChain).
+	///
+	/// Returns `None` if there's no effect at that index or its
+	/// handle isn't an `H`.
+	#[must_use]
+	pub fn effect<H: 'static>(&self, index: usize) -> Option<&H> {
+		self.effect_handles.get(index)
+	}
+
+	/// Returns a mutable reference to the handle of the effect at the
+	/// given index in the sound's [`EffectChain`](crate::sound::EffectChain).
+	///
+	/// Returns `None` if there's no effect at that index or its
+	/// handle isn't an `H`.
+	#[must_use]
+	pub fn effect_mut<H: 'static>(&mut self, index: usize) -> Option<&mut H> {
+		self.effect_handles.get_mut(index)
+	}
+
 	handle_param_setters! {
 		/**
 		Sets the volume of the sound.
@@ -176,6 +216,36 @@ impl StaticSoundHandle {
 		*/
 		playback_rate: PlaybackRate,
 
+		/**
+		Sets how fast the sound plays relative to its normal speed.
+
+		Unlike the playback rate, changing the tempo doesn't
+		change the pitch of the sound. Tempos are limited to
+		the range `0.0..=4.0`.
+
+		# Examples
+
+		Smoothly slow the sound down to half speed:
+
+		```no_run
+		# use kira::{
+		# 	AudioManager, AudioManagerSettings, DefaultBackend,
+		# 	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+		# };
+		# let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+		# let mut sound = manager.play(StaticSoundData::from_file("sound.ogg")?)?;
+		use kira::Tween;
+		use std::time::Duration;
+
+		sound.set_tempo(0.5, Tween {
+			duration: Duration::from_secs(1),
+			..Default::default()
+		});
+		# Result::<(), Box<dyn std::error::Error>>::Ok(())
+		```
+		*/
+		tempo: f64,
+
 		/**
 		Sets the panning of the sound, where `-1.0` is hard left,
 		`0.0` is center, and `1.0` is hard right.
@@ -285,6 +355,45 @@ impl StaticSoundHandle {
 			.write(loop_region.into_optional_region())
 	}
 
+	/**
+	Stops the sound from looping after it finishes the current
+	iteration of the loop region, so playback continues past the
+	end of it.
+
+	If playback hasn't reached the loop region yet, the loop
+	region is played once.
+	*/
+	pub fn exit_loop(&mut self) {
+		self.command_writers.exit_loop.write(())
+	}
+
+	/**
+	Stops the sound from looping at the given time. If the sound
+	is in the loop region at that time, playback jumps right to
+	the end of the loop region instead of finishing the current
+	iteration.
+
+	# Examples
+
+	Exit the loop one tick of a clock from now:
+
+	```no_run
+	# use kira::{
+	# 	AudioManager, AudioManagerSettings, DefaultBackend,
+	# 	sound::static_sound::StaticSoundData,
+	# 	clock::ClockSpeed,
+	# };
+	# let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+	let clock = manager.add_clock(ClockSpeed::TicksPerMinute(120.0))?;
+	let mut sound = manager.play(StaticSoundData::from_file("music.ogg")?.loop_region(8.0..24.0))?;
+	sound.exit_loop_at(clock.time() + 1);
+	# Result::<(), Box<dyn std::error::Error>>::Ok(())
+	```
+	*/
+	pub fn exit_loop_at(&mut self, start_time: impl Into<StartTime>) {
+		self.command_writers.exit_loop_at.write(start_time.into())
+	}
+
 	/// Fades out the sound to silence with the given tween and then
 	/// pauses playback.
 	pub fn pause(&mut self, tween: Tween) {
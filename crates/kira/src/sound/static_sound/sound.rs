@@ -11,10 +11,16 @@ use std::sync::{
 use crate::{
 	Decibels, Panning, Parameter, PlaybackRate, StartTime, Tween,
 	command::read_commands_into_parameters,
+	effect::Effect,
+	event::EventProducer,
 	frame::Frame,
 	info::Info,
 	playback_state_manager::PlaybackStateManager,
-	sound::{PlaybackState, Sound, transport::Transport},
+	sound::{
+		MarkerFrames, PendingMarkers, PlaybackState, Sound, SoundEvent,
+		time_stretcher::TimeStretcher, transport::Transport,
+	},
+	start_time::{StartTimeProgress, num_frames_before_start},
 };
 
 use self::resampler::Resampler;
@@ -29,46 +35,76 @@ pub(super) struct StaticSound {
 	reverse: bool,
 	playback_state_manager: PlaybackStateManager,
 	start_time: StartTime,
+	/// When the sound should jump out of the loop region, if
+	/// [`StaticSoundHandle::exit_loop_at`](super::StaticSoundHandle::exit_loop_at)
+	/// was called.
+	loop_exit_time: Option<StartTime>,
 	resampler: Resampler,
+	time_stretcher: TimeStretcher,
 	transport: Transport,
 	fractional_position: f64,
 	volume: Parameter<Decibels>,
 	playback_rate: Parameter<PlaybackRate>,
+	tempo: Parameter<f64>,
 	panning: Parameter<Panning>,
+	effects: Vec<Box<dyn Effect>>,
 	shared: Arc<Shared>,
+	event_producer: EventProducer<SoundEvent>,
+	markers: MarkerFrames,
+	/// Markers the time stretcher has received input for but hasn't
+	/// output yet.
+	pending_markers: PendingMarkers,
+	started: bool,
 }
 
 impl StaticSound {
 	#[must_use]
-	pub fn new(data: StaticSoundData, command_readers: CommandReaders) -> Self {
+	pub fn new(
+		data: StaticSoundData,
+		effects: Vec<Box<dyn Effect>>,
+		command_readers: CommandReaders,
+		event_producer: EventProducer<SoundEvent>,
+	) -> Self {
+		let num_frames = data.num_frames();
 		let settings = data.settings;
 		let transport = Transport::new(
-			data.settings.start_position.into_samples(data.sample_rate),
-			data.settings.loop_region,
-			data.settings.reverse,
+			settings.start_position.into_samples(data.sample_rate),
+			settings.loop_region,
+			settings.reverse,
+			settings.loop_crossfade,
+			settings.loop_count,
 			data.sample_rate,
-			data.num_frames(),
+			num_frames,
 		);
 		let starting_frame_index = transport.position;
+		let markers = MarkerFrames::new(&settings.markers, data.sample_rate, data.slice);
 		let position = starting_frame_index as f64 / data.sample_rate as f64;
 		let mut sound = Self {
 			command_readers,
 			sample_rate: data.sample_rate,
 			frames: data.frames,
 			slice: data.slice,
-			reverse: data.settings.reverse,
+			reverse: settings.reverse,
 			playback_state_manager: PlaybackStateManager::new(settings.fade_in_tween),
 			start_time: settings.start_time,
+			loop_exit_time: None,
 			resampler: Resampler::new(starting_frame_index),
+			time_stretcher: TimeStretcher::new(),
 			transport,
 			fractional_position: 0.0,
 			volume: Parameter::new(settings.volume, Decibels::IDENTITY),
 			playback_rate: Parameter::new(settings.playback_rate, PlaybackRate(1.0)),
+			tempo: Parameter::new(settings.tempo, 1.0),
 			panning: Parameter::new(settings.panning, Panning::CENTER),
+			effects,
 			shared: Arc::new(Shared {
 				state: AtomicU8::new(PlaybackState::Playing as u8),
 				position: AtomicU64::new(position.to_bits()),
 			}),
+			event_producer,
+			pending_markers: PendingMarkers::new(&markers),
+			markers,
+			started: false,
 		};
 		// fill the resample buffer with 3 samples so playback can
 		// start immediately
@@ -113,23 +149,43 @@ impl StaticSound {
 	}
 
 	/// Updates the current frame index by 1 and pushes a new sample to the resampler.
-	fn update_position(&mut self) {
+	///
+	/// Returns an event if the sound looped or finished.
+	fn update_position(&mut self) -> Option<SoundEvent> {
 		self.push_frame_to_resampler();
-		if self.is_playing_backwards() {
-			self.transport.decrement_position();
+		let previous_position = self.transport.position;
+		let looped = if self.is_playing_backwards() {
+			self.transport
+				.decrement_position(num_frames(&self.frames, self.slice));
+			self.transport.position > previous_position
 		} else {
 			self.transport
 				.increment_position(num_frames(&self.frames, self.slice));
-		}
-		if !self.transport.playing && self.resampler.empty() {
+			self.transport.position < previous_position
+		};
+		// while time stretching, the sound finishes once the time
+		// stretcher runs out of audio instead
+		if self.source_finished() && !self.time_stretcher.is_active() {
+			if self.playback_state_manager.playback_state() == PlaybackState::Stopped {
+				return None;
+			}
 			self.playback_state_manager.mark_as_stopped();
 			self.update_shared_playback_state();
+			return Some(SoundEvent::Finished);
 		}
+		looped.then_some(SoundEvent::Looped)
+	}
+
+	#[must_use]
+	fn source_finished(&self) -> bool {
+		!self.transport.playing && self.resampler.empty()
 	}
 
 	fn seek_to_index(&mut self, index: usize) {
 		self.transport
 			.seek_to(index, num_frames(&self.frames, self.slice));
+		self.time_stretcher.reset();
+		self.pending_markers.clear();
 		// if the sound is playing, push a frame to the resample buffer
 		// to make sure it doesn't get skipped
 		if self.playback_state_manager.playback_state().is_advancing() {
@@ -139,11 +195,32 @@ impl StaticSound {
 
 	fn push_frame_to_resampler(&mut self) {
 		let frame = self.transport.playing.then(|| {
-			frame_at_index(self.transport.position, &self.frames, self.slice).unwrap_or_default()
+			let frame = frame_at_index(self.transport.position, &self.frames, self.slice)
+				.unwrap_or_default();
+			match self.transport.loop_crossfade(
+				self.is_playing_backwards(),
+				num_frames(&self.frames, self.slice),
+			) {
+				Some(crossfade) => crossfade.mix(
+					frame,
+					frame_at_index(crossfade.index, &self.frames, self.slice).unwrap_or_default(),
+				),
+				None => frame,
+			}
 		});
 		self.resampler.push_frame(frame, self.transport.position);
 	}
 
+	fn exit_loop_now(&mut self) {
+		// jumping out of the loop region works like jumping back to the
+		// start of it, so the frames that were already pushed to the
+		// resampler play first
+		self.transport.exit_loop_now(
+			self.is_playing_backwards(),
+			num_frames(&self.frames, self.slice),
+		);
+	}
+
 	fn seek_by(&mut self, amount: f64) {
 		let current_position = self.transport.position as f64 / self.sample_rate as f64;
 		let position = current_position + amount;
@@ -156,8 +233,79 @@ impl StaticSound {
 		self.seek_to_index(index);
 	}
 
+	/// Outputs the next frame of audio at the current playback rate.
+	#[must_use]
+	fn next_resampled_frame(&mut self, playback_rate: PlaybackRate, dt: f64, time: f64) -> Frame {
+		let out = self.resampler.get(self.fractional_position as f32);
+		self.fractional_position += self.sample_rate as f64 * playback_rate.0.abs() * dt;
+		while self.fractional_position >= 1.0 {
+			self.fractional_position -= 1.0;
+			let previous_frame_index = self.resampler.current_frame_index();
+			if let Some(event) = self.update_position() {
+				self.event_producer.push(event, time);
+			}
+			let frame_index = self.resampler.current_frame_index();
+			// the new frame is heard starting from the next output frame
+			if frame_index != previous_frame_index {
+				self.push_marker_events(frame_index, time + dt);
+			}
+		}
+		out
+	}
+
+	/// Emits an event for each marker at the given frame.
+	///
+	/// While time stretching, the frame is heard once the time stretcher
+	/// outputs it, so the events are held back until then.
+	fn push_marker_events(&mut self, frame_index: usize, time: f64) {
+		for marker_index in self.markers.at(frame_index) {
+			if self.time_stretcher.is_active() {
+				// the frame will be pushed to the time stretcher after
+				// the frame that's currently being resampled
+				self.pending_markers
+					.push(self.time_stretcher.input_position() + 1, marker_index);
+			} else {
+				self.event_producer
+					.push(SoundEvent::Marker(marker_index), time);
+			}
+		}
+	}
+
+	/// Outputs the next frame of audio at the current playback rate
+	/// after passing it through the time stretcher.
+	#[must_use]
+	fn next_time_stretched_frame(
+		&mut self,
+		playback_rate: PlaybackRate,
+		tempo: f64,
+		dt: f64,
+		time: f64,
+	) -> Frame {
+		while self.time_stretcher.needs_input() {
+			let frame = (!self.source_finished())
+				.then(|| self.next_resampled_frame(playback_rate, dt, time));
+			self.time_stretcher
+				.push_frame(frame, self.resampler.current_frame_index());
+		}
+		let out = self.time_stretcher.next_frame(tempo);
+		if let Some(position) = self.time_stretcher.output_position() {
+			while let Some(marker_index) = self.pending_markers.pop_reached(position) {
+				self.event_producer
+					.push(SoundEvent::Marker(marker_index), time);
+			}
+		}
+		if self.time_stretcher.finished()
+			&& self.playback_state_manager.playback_state() != PlaybackState::Stopped
+		{
+			self.playback_state_manager.mark_as_stopped();
+			self.update_shared_playback_state();
+			self.event_producer.push(SoundEvent::Finished, time);
+		}
+		out
+	}
+
 	fn read_commands(&mut self) {
-		read_commands_into_parameters!(self, volume, playback_rate, panning);
+		read_commands_into_parameters!(self, volume, playback_rate, tempo, panning);
 		if let Some(loop_region) = self.command_readers.set_loop_region.read() {
 			self.transport.set_loop_region(
 				loop_region,
@@ -165,6 +313,12 @@ impl StaticSound {
 				num_frames(&self.frames, self.slice),
 			);
 		}
+		if self.command_readers.exit_loop.read().is_some() {
+			self.transport.exit_loop();
+		}
+		if let Some(start_time) = self.command_readers.exit_loop_at.read() {
+			self.loop_exit_time = Some(start_time);
+		}
 		if let Some(tween) = self.command_readers.pause.read() {
 			self.pause(tween);
 		}
@@ -184,45 +338,129 @@ impl StaticSound {
 }
 
 impl Sound for StaticSound {
+	fn init(&mut self, sample_rate: u32, internal_buffer_size: usize) {
+		for effect in &mut self.effects {
+			effect.init(sample_rate, internal_buffer_size);
+		}
+	}
+
+	fn on_change_sample_rate(&mut self, sample_rate: u32) {
+		for effect in &mut self.effects {
+			effect.on_change_sample_rate(sample_rate);
+		}
+	}
+
 	fn on_start_processing(&mut self) {
-		let last_played_frame_position = self.resampler.current_frame_index();
+		let last_played_frame_position = self
+			.time_stretcher
+			.current_index()
+			.unwrap_or_else(|| self.resampler.current_frame_index());
 		self.shared.position.store(
 			(last_played_frame_position as f64 / self.sample_rate as f64).to_bits(),
 			Ordering::SeqCst,
 		);
 		self.read_commands();
+		for effect in &mut self.effects {
+			effect.on_start_processing();
+		}
 	}
 
 	fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
 		// update parameters
 		self.volume.update(dt * out.len() as f64, info);
 		self.playback_rate.update(dt * out.len() as f64, info);
+		self.tempo.update(dt * out.len() as f64, info);
 		self.panning.update(dt * out.len() as f64, info);
 		let changed_playback_state = self
 			.playback_state_manager
 			.update(dt * out.len() as f64, info);
 		if changed_playback_state {
 			self.update_shared_playback_state();
+			// the fade out finishes at the end of this batch
+			if self.playback_state_manager.playback_state() == PlaybackState::Stopped {
+				self.event_producer
+					.push(SoundEvent::Stopped, info.time() + dt * out.len() as f64);
+			}
 		}
 
-		let will_never_start = self.start_time.update(dt * out.len() as f64, info);
-		if will_never_start {
-			self.playback_state_manager.mark_as_stopped();
-			self.update_shared_playback_state();
+		let start_delay = match self.start_time.update(dt * out.len() as f64, info) {
+			StartTimeProgress::Waiting => {
+				out.fill(Frame::ZERO);
+				return;
+			}
+			StartTimeProgress::Started { delay } => delay,
+			StartTimeProgress::WillNeverStart => {
+				self.playback_state_manager.mark_as_stopped();
+				self.update_shared_playback_state();
+				self.event_producer.push(SoundEvent::Stopped, info.time());
+				out.fill(Frame::ZERO);
+				return;
+			}
+		};
+		if !self.started {
+			self.started = true;
+			self.event_producer
+				.push(SoundEvent::Started, info.time() + start_delay);
+			self.push_marker_events(
+				self.resampler.current_frame_index(),
+				info.time() + start_delay,
+			);
 		}
-		if self.start_time != StartTime::Immediate {
-			out.fill(Frame::ZERO);
-			return;
+
+		let num_frames = out.len();
+		let mut loop_exit_frame = None;
+		if let Some(loop_exit_time) = &mut self.loop_exit_time {
+			match loop_exit_time.update(dt * num_frames as f64, info) {
+				StartTimeProgress::Waiting => {}
+				StartTimeProgress::Started { delay } => {
+					loop_exit_frame = Some(num_frames_before_start(delay, dt, num_frames));
+					self.loop_exit_time = None;
+				}
+				StartTimeProgress::WillNeverStart => self.loop_exit_time = None,
+			}
 		}
 
 		if !self.playback_state_manager.playback_state().is_advancing() {
+			if loop_exit_frame.is_some() {
+				self.exit_loop_now();
+			}
 			out.fill(Frame::ZERO);
 			return;
 		}
 
+		// if playback started partway through this batch, the frames
+		// before that point stay silent
+		let start_frame = num_frames_before_start(
+			start_delay.max(self.playback_state_manager.start_delay()),
+			dt,
+			num_frames,
+		);
+		out[..start_frame].fill(Frame::ZERO);
+
 		// play back audio
-		let num_frames = out.len();
-		for (i, frame) in out.iter_mut().enumerate() {
+		for (i, frame) in out.iter_mut().enumerate().skip(start_frame) {
+			if loop_exit_frame.is_some_and(|loop_exit_frame| i >= loop_exit_frame) {
+				loop_exit_frame = None;
+				self.exit_loop_now();
+			}
+			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
+			let playback_rate = self.playback_rate.interpolated_value(time_in_chunk);
+			let tempo = self.tempo.interpolated_value(time_in_chunk);
+			let time = info.time() + i as f64 * dt;
+			*frame = if self.time_stretcher.is_active() || tempo != 1.0 {
+				self.next_time_stretched_frame(playback_rate, tempo, dt, time)
+			} else {
+				self.next_resampled_frame(playback_rate, dt, time)
+			};
+		}
+
+		// apply effects
+		for effect in &mut self.effects {
+			effect.process(&mut out[start_frame..], dt, info);
+		}
+
+		// apply volume, fades, and panning
+		for (i, frame) in out.iter_mut().enumerate().skip(start_frame) {
 			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
 			let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
 			let fade_volume = self
@@ -230,15 +468,22 @@ impl Sound for StaticSound {
 				.interpolated_fade_volume(time_in_chunk)
 				.as_amplitude();
 			let panning = self.panning.interpolated_value(time_in_chunk);
-			let playback_rate = self.playback_rate.interpolated_value(time_in_chunk);
-			let resampler_out = self.resampler.get(self.fractional_position as f32);
-			self.fractional_position += self.sample_rate as f64 * playback_rate.0.abs() * dt;
-			while self.fractional_position >= 1.0 {
-				self.fractional_position -= 1.0;
-				self.update_position();
-			}
-			*frame = (resampler_out * fade_volume * volume).panned(panning);
+			*frame = (*frame * fade_volume * volume).panned(panning);
+		}
+	}
+
+	fn remaining_duration(&self) -> Option<f64> {
+		let num_frames_remaining = self.transport.num_frames_remaining(
+			self.is_playing_backwards(),
+			num_frames(&self.frames, self.slice),
+		)? + self.resampler.num_buffered_frames();
+		let speed = self.sample_rate as f64
+			* self.playback_rate.value().0.abs()
+			* self.tempo.value().clamp(0.0, 4.0);
+		if speed == 0.0 {
+			return None;
 		}
+		Some((num_frames_remaining as f64 - self.fractional_position).max(0.0) / speed)
 	}
 
 	fn finished(&self) -> bool {
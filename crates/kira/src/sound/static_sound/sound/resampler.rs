@@ -64,6 +64,15 @@ impl Resampler {
 		self.frames[1].frame_index
 	}
 
+	/// Returns how many of the frames from the source sound that
+	/// were pushed to the resampler haven't been played yet.
+	#[must_use]
+	pub fn num_buffered_frames(&self) -> usize {
+		self.time_until_empty
+			.saturating_sub(1)
+			.min(self.frames.len() - 1)
+	}
+
 	#[must_use]
 	pub fn empty(&self) -> bool {
 		self.time_until_empty == 0
@@ -1,12 +1,21 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+	f32::consts::{FRAC_1_SQRT_2, TAU},
+	sync::Arc,
+	time::Duration,
+};
 
 use crate::{
-	Decibels, Panning, StartTime, Tween,
+	Decibels, Mix, Panning, StartTime, Tween,
 	clock::ClockTime,
+	effect::{
+		delay::{DelayBuilder, DelayHandle},
+		panning_control::{PanningControlBuilder, PanningControlHandle},
+		volume_control::{VolumeControlBuilder, VolumeControlHandle},
+	},
 	frame::Frame,
 	info::MockInfoBuilder,
 	sound::{
-		PlaybackState, Sound,
+		PlaybackState, Sound, SoundEvent,
 		static_sound::{StaticSoundData, StaticSoundSettings},
 	},
 	test_helpers::expect_frame_soon,
@@ -269,6 +278,32 @@ fn stops_with_fade_out() {
 	}
 }
 
+/// Tests that a `StaticSound` reports when it's stopped.
+#[test]
+fn reports_stopped_event() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: Arc::new([Frame::from_mono(1.0); 100]),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	};
+	let (mut sound, mut handle) = data.split();
+	sound.process_one(1.0, &MockInfoBuilder::new().build());
+	handle.stop(Tween {
+		duration: Duration::from_secs(2),
+		..Default::default()
+	});
+	sound.on_start_processing();
+	for _ in 0..5 {
+		sound.process_one(1.0, &MockInfoBuilder::new().build());
+	}
+	assert!(sound.finished());
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.map(|event| event.kind)
+		.collect::<Vec<_>>();
+	assert_eq!(events, [SoundEvent::Started, SoundEvent::Stopped]);
+}
+
 /// Tests that a `StaticSound` will wait for its start clock time
 /// when appropriate.
 #[test]
@@ -484,7 +519,7 @@ fn resume_at() {
 			fraction: 0.0,
 		}),
 		Tween {
-			duration: Duration::from_secs(1),
+			duration: Duration::from_secs(2),
 			..Default::default()
 		},
 	);
@@ -581,6 +616,115 @@ fn loops_forward() {
 	);
 }
 
+/// Tests that a `StaticSound` reports when it starts, loops,
+/// and finishes.
+#[test]
+fn reports_events() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: (0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		settings: StaticSoundSettings::new().loop_region(Some((3.0..6.0).into())),
+		slice: None,
+	};
+	let (mut sound, mut handle) = data.split();
+	assert!(handle.pop_event().is_none());
+
+	sound.process_one(1.0, &MockInfoBuilder::new().build());
+	assert_eq!(
+		handle.pop_event().map(|event| event.kind),
+		Some(SoundEvent::Started)
+	);
+	assert!(handle.pop_event().is_none());
+
+	for _ in 0..10 {
+		sound.process_one(1.0, &MockInfoBuilder::new().build());
+	}
+	let mut num_loops = 0;
+	while let Some(event) = handle.pop_event() {
+		assert_eq!(event.kind, SoundEvent::Looped);
+		num_loops += 1;
+	}
+	assert!(num_loops >= 3);
+
+	sound.transport.set_loop_region(None, 1, 10);
+	for _ in 0..20 {
+		sound.process_one(1.0, &MockInfoBuilder::new().build());
+	}
+	assert!(sound.finished());
+	// the sound may loop once more before the new loop region
+	// takes effect, but it should only finish once
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.map(|event| event.kind)
+		.filter(|kind| *kind != SoundEvent::Looped)
+		.collect::<Vec<_>>();
+	assert_eq!(events, [SoundEvent::Finished]);
+}
+
+/// Tests that a `StaticSound` reports when playback reaches
+/// each of its markers.
+#[test]
+fn reports_markers() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: (0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		settings: StaticSoundSettings::new()
+			.with_marker("end", 9.0)
+			.with_marker("start", 0.0)
+			.with_marker("middle", 3.0),
+		slice: None,
+	};
+	let (mut sound, mut handle) = data.split();
+	assert_eq!(handle.markers()[2].name, "middle");
+
+	let mut out = vec![Frame::ZERO; 20];
+	sound.process(&mut out, 1.0, &MockInfoBuilder::new().build());
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.filter(|event| matches!(event.kind, SoundEvent::Marker(_)))
+		.map(|event| (event.kind, event.time))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		events,
+		[
+			(SoundEvent::Marker(1), Duration::ZERO),
+			(SoundEvent::Marker(2), Duration::from_secs(3)),
+			(SoundEvent::Marker(0), Duration::from_secs(9)),
+		]
+	);
+}
+
+/// Tests that a `StaticSound` reports markers in its loop region
+/// each time it loops.
+#[test]
+fn reports_markers_when_looping() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: (0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		settings: StaticSoundSettings::new()
+			.loop_region(2.0..5.0)
+			.loop_count(2)
+			.with_marker("loop", 3.0)
+			.with_marker("after loop", 6.0),
+		slice: None,
+	};
+	let (mut sound, mut handle) = data.split();
+
+	for _ in 0..20 {
+		sound.process_one(1.0, &MockInfoBuilder::new().build());
+	}
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.map(|event| event.kind)
+		.filter(|kind| matches!(kind, SoundEvent::Marker(_)))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		events,
+		[
+			SoundEvent::Marker(0),
+			SoundEvent::Marker(0),
+			SoundEvent::Marker(1)
+		]
+	);
+}
+
 /// Tests that the volume of a `StaticSound` can be adjusted.
 #[test]
 #[allow(clippy::float_cmp)]
@@ -737,6 +881,83 @@ fn set_playback_rate() {
 	);
 }
 
+/// Tests that a `StaticSound` can change its speed without
+/// changing its pitch.
+#[test]
+fn tempo() {
+	let data = StaticSoundData {
+		sample_rate: 1000,
+		frames: (0..20_000)
+			.map(|i| Frame::from_mono((i as f32 / 40.0 * TAU).sin()))
+			.collect(),
+		settings: StaticSoundSettings::new().tempo(0.5),
+		slice: None,
+	};
+	let (mut sound, handle) = data.split();
+	let mut frames = vec![Frame::ZERO; 8000];
+	sound.process(&mut frames, 0.001, &MockInfoBuilder::new().build());
+	sound.on_start_processing();
+
+	// 8 seconds of audio at half speed covers 4 seconds of the sound
+	assert!((handle.position() - 4.0).abs() < 0.2);
+	// but the sine wave still has the same frequency
+	let zero_crossings = frames
+		.windows(2)
+		.filter(|frames| (frames[0].left < 0.0) != (frames[1].left < 0.0))
+		.count();
+	assert!((zero_crossings as f64 - 400.0).abs() < 10.0);
+}
+
+/// Tests that a time stretched `StaticSound` reports markers
+/// when the time stretcher outputs them.
+#[test]
+fn reports_markers_when_time_stretched() {
+	let data = StaticSoundData {
+		sample_rate: 1000,
+		frames: Arc::new([Frame::from_mono(1.0); 4000]),
+		settings: StaticSoundSettings::new()
+			.tempo(2.0)
+			.with_marker("marker", 2.0),
+		slice: None,
+	};
+	let (mut sound, mut handle) = data.split();
+	let mut frames = vec![Frame::ZERO; 1500];
+	sound.process(&mut frames, 0.001, &MockInfoBuilder::new().build());
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.filter(|event| matches!(event.kind, SoundEvent::Marker(_)))
+		.map(|event| event.time.as_secs_f64())
+		.collect::<Vec<_>>();
+	// 2 seconds into the sound is 1 second into the output
+	assert_eq!(events.len(), 1);
+	assert!((events[0] - 1.0).abs() < 0.002, "{}", events[0]);
+}
+
+/// Tests that a time stretched `StaticSound` plays all of its
+/// audio before finishing.
+#[test]
+fn finishes_after_time_stretched_audio() {
+	let data = StaticSoundData {
+		sample_rate: 1000,
+		frames: Arc::new([Frame::from_mono(1.0); 1000]),
+		settings: StaticSoundSettings::new().tempo(2.0),
+		slice: None,
+	};
+	let (mut sound, mut handle) = data.split();
+	let mut frames = vec![Frame::ZERO; 400];
+	sound.process(&mut frames, 0.001, &MockInfoBuilder::new().build());
+	for frame in &frames {
+		assert!((frame.left - 1.0).abs() < 0.0001);
+	}
+	assert!(!sound.finished());
+
+	sound.process(&mut frames, 0.001, &MockInfoBuilder::new().build());
+	assert!(sound.finished());
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.map(|event| event.kind)
+		.collect::<Vec<_>>();
+	assert_eq!(events, [SoundEvent::Started, SoundEvent::Finished]);
+}
+
 /// Tests that a `StaticSound` outputs interpolated samples when
 /// its playback position is between samples.
 #[test]
@@ -839,3 +1060,369 @@ fn reverse() {
 		);
 	}
 }
+
+/// Tests that a `StaticSound` applies its effects before
+/// its volume and panning.
+#[test]
+#[allow(clippy::float_cmp)]
+fn effects() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: Arc::new([Frame::from_mono(1.0); 10]),
+		settings: StaticSoundSettings::new()
+			.volume(-6.0)
+			.panning(Panning::RIGHT)
+			.with_effect(PanningControlBuilder(Panning::LEFT.into())),
+		slice: None,
+	};
+	let (mut sound, _) = data.split();
+
+	// the effect pans the sound all the way to the left, and then
+	// the sound's panning moves the left channel to the right
+	assert_eq!(
+		sound.process_one(1.0, &MockInfoBuilder::new().build()),
+		(Frame::from_mono(1.0).panned(Panning::LEFT) * Decibels(-6.0).as_amplitude())
+			.panned(Panning::RIGHT)
+	);
+}
+
+/// Tests that the effects of a `StaticSound` can be controlled
+/// from the sound's handle.
+#[test]
+fn effect_handles() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: Arc::new([Frame::from_mono(1.0); 100]),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+	.with_effect(VolumeControlBuilder::new(-6.0));
+	let (mut sound, mut handle) = data.split();
+	sound.init(1, 128);
+
+	assert_eq!(
+		sound.process_one(1.0, &MockInfoBuilder::new().build()),
+		Frame::from_mono(Decibels(-6.0).as_amplitude()).panned(Panning::CENTER)
+	);
+	assert!(handle.effect::<PanningControlHandle>(0).is_none());
+	assert!(handle.effect::<VolumeControlHandle>(1).is_none());
+	handle
+		.effect_mut::<VolumeControlHandle>(0)
+		.unwrap()
+		.set_volume(-12.0, Tween::default());
+	sound.on_start_processing();
+	expect_frame_soon(
+		Frame::from_mono(Decibels(-12.0).as_amplitude()).panned(Panning::CENTER),
+		&mut sound,
+	);
+}
+
+/// Tests that every instance of a `StaticSound` gets its own copy of
+/// effects that have effects of their own, like a delay's feedback loop.
+#[test]
+fn nested_effects() {
+	let mut frames = [Frame::ZERO; 10];
+	frames[0] = Frame::from_mono(1.0);
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: Arc::new(frames),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+	.with_effect(
+		DelayBuilder::new()
+			.delay_time(Duration::from_secs(2))
+			.feedback(Decibels::IDENTITY)
+			.mix(Mix::WET)
+			.with_feedback_effect(VolumeControlBuilder::new(-6.0)),
+	);
+	let (mut first, mut first_handle) = data.clone().split();
+	let (mut second, _) = data.split();
+	first.init(1, 128);
+	second.init(1, 128);
+	first_handle
+		.effect_mut::<DelayHandle>(0)
+		.unwrap()
+		.feedback_effect_mut::<VolumeControlHandle>(0)
+		.unwrap()
+		.set_volume(
+			-12.0,
+			Tween {
+				duration: Duration::ZERO,
+				..Default::default()
+			},
+		);
+	first.on_start_processing();
+	second.on_start_processing();
+
+	for (sound, volume) in [(&mut first, -12.0), (&mut second, -6.0)] {
+		let echo = Decibels(volume).as_amplitude();
+		for expected in [0.0, 0.0, echo, 0.0, echo * echo] {
+			assert_eq!(
+				sound.process_one(1.0, &MockInfoBuilder::new().build()),
+				Frame::from_mono(expected).panned(Panning::CENTER)
+			);
+		}
+	}
+}
+
+/// Tests that the end of the loop region of a `StaticSound` crossfades
+/// into the audio before the start of the loop region.
+#[test]
+fn loop_crossfade() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: (0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		settings: StaticSoundSettings::new()
+			.loop_region(4.0..)
+			.loop_crossfade(Duration::from_secs(2)),
+		slice: None,
+	};
+	let (mut sound, _) = data.split();
+
+	let expected = [
+		0.0,
+		1.0,
+		2.0,
+		3.0,
+		4.0,
+		5.0,
+		6.0,
+		7.0,
+		// frame 8 is mixed equally with frame 2
+		(8.0 + 2.0) * FRAC_1_SQRT_2,
+		// frame 9 has fully faded into frame 3
+		3.0,
+		// which continues into the start of the loop region
+		4.0,
+		5.0,
+	];
+	for value in expected {
+		let frame = sound.process_one(1.0, &MockInfoBuilder::new().build());
+		let expected = Frame::from_mono(value).panned(Panning::CENTER);
+		assert!((frame.left - expected.left).abs() < 0.0001);
+		assert!((frame.right - expected.right).abs() < 0.0001);
+	}
+}
+
+/// Tests that the start of the loop region of a reversed `StaticSound`
+/// crossfades into the audio after the end of the loop region.
+#[test]
+fn loop_crossfade_in_reverse() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: (0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		settings: StaticSoundSettings::new()
+			.reverse(true)
+			.loop_region(2.0..6.0)
+			.loop_crossfade(Duration::from_secs(2)),
+		slice: None,
+	};
+	let (mut sound, _) = data.split();
+
+	let expected = [
+		9.0,
+		8.0,
+		7.0,
+		6.0,
+		5.0,
+		4.0,
+		// frame 3 is mixed equally with frame 7
+		(3.0 + 7.0) * FRAC_1_SQRT_2,
+		// frame 2 has fully faded into frame 6
+		6.0,
+		// which continues into the end of the loop region
+		5.0,
+		4.0,
+	];
+	for value in expected {
+		let frame = sound.process_one(1.0, &MockInfoBuilder::new().build());
+		let expected = Frame::from_mono(value).panned(Panning::CENTER);
+		assert!((frame.left - expected.left).abs() < 0.0001);
+		assert!((frame.right - expected.right).abs() < 0.0001);
+	}
+}
+
+/// Tests that when the whole `StaticSound` is looped, the end of the sound
+/// crossfades into the start of the sound.
+#[test]
+fn loop_crossfade_without_audio_before_loop_region() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: (0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		settings: StaticSoundSettings::new()
+			.loop_region(..)
+			.loop_crossfade(Duration::from_secs(2)),
+		slice: None,
+	};
+	let (mut sound, _) = data.split();
+
+	let expected = [
+		0.0,
+		1.0,
+		2.0,
+		3.0,
+		4.0,
+		5.0,
+		6.0,
+		7.0,
+		// frame 8 is mixed equally with frame 0
+		(8.0 + 0.0) * FRAC_1_SQRT_2,
+		// frame 9 has fully faded into frame 1
+		1.0,
+		// which continues into the rest of the loop region
+		2.0,
+		3.0,
+	];
+	for value in expected {
+		let frame = sound.process_one(1.0, &MockInfoBuilder::new().build());
+		let expected = Frame::from_mono(value).panned(Panning::CENTER);
+		assert!((frame.left - expected.left).abs() < 0.0001);
+		assert!((frame.right - expected.right).abs() < 0.0001);
+	}
+}
+
+/// Tests that when the whole reversed `StaticSound` is looped, the
+/// start of the sound crossfades into the end of the sound.
+#[test]
+fn loop_crossfade_in_reverse_without_audio_after_loop_region() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: (0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		settings: StaticSoundSettings::new()
+			.reverse(true)
+			.loop_region(..)
+			.loop_crossfade(Duration::from_secs(2)),
+		slice: None,
+	};
+	let (mut sound, _) = data.split();
+
+	let expected = [
+		9.0,
+		8.0,
+		7.0,
+		6.0,
+		5.0,
+		4.0,
+		3.0,
+		2.0,
+		// frame 1 is mixed equally with frame 9
+		(1.0 + 9.0) * FRAC_1_SQRT_2,
+		// frame 0 has fully faded into frame 8
+		8.0,
+		// which continues into the rest of the loop region
+		7.0,
+		6.0,
+	];
+	for value in expected {
+		let frame = sound.process_one(1.0, &MockInfoBuilder::new().build());
+		let expected = Frame::from_mono(value).panned(Panning::CENTER);
+		assert!((frame.left - expected.left).abs() < 0.0001);
+		assert!((frame.right - expected.right).abs() < 0.0001);
+	}
+}
+
+/// Tests that a `StaticSound` stops looping after playing the
+/// loop region the given number of times.
+#[test]
+fn loop_count() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: (0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		settings: StaticSoundSettings::new()
+			.loop_region(3.0..6.0)
+			.loop_count(2),
+		slice: None,
+	};
+	let (mut sound, _) = data.split();
+	for i in [0, 1, 2, 3, 4, 5, 3, 4, 5, 6, 7, 8, 9] {
+		assert_eq!(
+			sound.process_one(1.0, &MockInfoBuilder::new().build()),
+			Frame::from_mono(i as f32).panned(Panning::CENTER)
+		);
+	}
+	assert!(!sound.finished());
+	sound.process_one(1.0, &MockInfoBuilder::new().build());
+	assert!(sound.finished());
+}
+
+/// Tests that a `StaticSound` finishes the current iteration of
+/// the loop region and then plays the rest of the sound after
+/// exiting the loop.
+#[test]
+fn exit_loop() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: (0..12).map(|i| Frame::from_mono(i as f32)).collect(),
+		settings: StaticSoundSettings::new().loop_region(3.0..8.0),
+		slice: None,
+	};
+	let (mut sound, mut handle) = data.split();
+	for i in [0, 1, 2, 3, 4, 5, 6, 7, 3] {
+		assert_eq!(
+			sound.process_one(1.0, &MockInfoBuilder::new().build()),
+			Frame::from_mono(i as f32).panned(Panning::CENTER)
+		);
+	}
+	handle.exit_loop();
+	sound.on_start_processing();
+	for i in 4..12 {
+		assert_eq!(
+			sound.process_one(1.0, &MockInfoBuilder::new().build()),
+			Frame::from_mono(i as f32).panned(Panning::CENTER)
+		);
+	}
+}
+
+/// Tests that a `StaticSound` jumps out of the loop region
+/// at the time given to `exit_loop_at`.
+#[test]
+fn exit_loop_at() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: (0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		settings: StaticSoundSettings::new().loop_region(3.0..6.0),
+		slice: None,
+	};
+	let (mut sound, mut handle) = data.split();
+	let mut info_builder = MockInfoBuilder::new();
+	let clock_id = info_builder.add_clock(true, 0, 0.0);
+	let info = info_builder.build();
+	handle.exit_loop_at(ClockTime {
+		clock: clock_id,
+		ticks: 1,
+		fraction: 0.0,
+	});
+	sound.on_start_processing();
+
+	// the sound should keep looping until the clock reaches tick 1
+	for i in [0, 1, 2, 3, 4, 5, 3, 4] {
+		assert_eq!(
+			sound.process_one(1.0, &info),
+			Frame::from_mono(i as f32).panned(Panning::CENTER)
+		);
+	}
+
+	let info = {
+		let mut builder = MockInfoBuilder::new();
+		builder.add_clock(true, 1, 0.0);
+		builder.build()
+	};
+	// the frames that were already buffered for interpolation
+	// are played before the jump
+	let mut frames = vec![];
+	for _ in 0..10 {
+		frames.push(sound.process_one(1.0, &info));
+	}
+	let jump = frames
+		.iter()
+		.position(|frame| *frame == Frame::from_mono(6.0).panned(Panning::CENTER))
+		.expect("the sound should have jumped to the end of the loop region");
+	assert!(jump <= 3);
+	for (i, frame) in frames[jump..].iter().take(4).enumerate() {
+		assert_eq!(
+			*frame,
+			Frame::from_mono((6 + i) as f32).panned(Panning::CENTER)
+		);
+	}
+}
@@ -18,17 +18,25 @@ manager.play(sound_data)?;
 
 Streaming sounds use less memory than static sounds, but they use more
 CPU, and they can have delays when starting or seeking.
+
+Audio for every streaming sound is decoded on a shared pool of
+threads, whose size is set by
+[`AudioManagerSettings::num_decode_threads`](crate::AudioManagerSettings::num_decode_threads).
+When there are more streams than threads, the streams with the least
+audio buffered are decoded first.
 */
 
 #![cfg_attr(docsrs, doc(cfg(not(wasm32))))]
 
 mod data;
+mod decode_pool;
 mod decoder;
 mod handle;
 mod settings;
 mod sound;
 
 pub use data::*;
+pub(crate) use decode_pool::*;
 pub use decoder::*;
 pub use handle::*;
 pub use settings::*;
@@ -44,8 +52,11 @@ use super::Region;
 pub(crate) struct CommandWriters {
 	set_volume: CommandWriter<ValueChangeCommand<Decibels>>,
 	set_playback_rate: CommandWriter<ValueChangeCommand<PlaybackRate>>,
+	set_tempo: CommandWriter<ValueChangeCommand<f64>>,
 	set_panning: CommandWriter<ValueChangeCommand<Panning>>,
 	set_loop_region: CommandWriter<Option<Region>>,
+	exit_loop: CommandWriter<()>,
+	exit_loop_at: CommandWriter<StartTime>,
 	pause: CommandWriter<Tween>,
 	resume: CommandWriter<(StartTime, Tween)>,
 	stop: CommandWriter<Tween>,
@@ -57,7 +68,10 @@ pub(crate) struct CommandWriters {
 pub(crate) struct CommandReaders {
 	set_volume: CommandReader<ValueChangeCommand<Decibels>>,
 	set_playback_rate: CommandReader<ValueChangeCommand<PlaybackRate>>,
+	set_tempo: CommandReader<ValueChangeCommand<f64>>,
 	set_panning: CommandReader<ValueChangeCommand<Panning>>,
+	exit_loop: CommandReader<()>,
+	exit_loop_at: CommandReader<StartTime>,
 	pause: CommandReader<Tween>,
 	resume: CommandReader<(StartTime, Tween)>,
 	stop: CommandReader<Tween>,
@@ -79,8 +93,11 @@ fn command_writers_and_readers() -> (
 ) {
 	let (set_volume_writer, set_volume_reader) = command_writer_and_reader();
 	let (set_playback_rate_writer, set_playback_rate_reader) = command_writer_and_reader();
+	let (set_tempo_writer, set_tempo_reader) = command_writer_and_reader();
 	let (set_panning_writer, set_panning_reader) = command_writer_and_reader();
 	let (set_loop_region_writer, set_loop_region_reader) = command_writer_and_reader();
+	let (exit_loop_writer, exit_loop_reader) = command_writer_and_reader();
+	let (exit_loop_at_writer, exit_loop_at_reader) = command_writer_and_reader();
 	let (pause_writer, pause_reader) = command_writer_and_reader();
 	let (resume_writer, resume_reader) = command_writer_and_reader();
 	let (stop_writer, stop_reader) = command_writer_and_reader();
@@ -91,8 +108,11 @@ fn command_writers_and_readers() -> (
 		CommandWriters {
 			set_volume: set_volume_writer,
 			set_playback_rate: set_playback_rate_writer,
+			set_tempo: set_tempo_writer,
 			set_panning: set_panning_writer,
 			set_loop_region: set_loop_region_writer,
+			exit_loop: exit_loop_writer,
+			exit_loop_at: exit_loop_at_writer,
 			pause: pause_writer,
 			resume: resume_writer,
 			stop: stop_writer,
@@ -103,7 +123,10 @@ fn command_writers_and_readers() -> (
 		CommandReaders {
 			set_volume: set_volume_reader,
 			set_playback_rate: set_playback_rate_reader,
+			set_tempo: set_tempo_reader,
 			set_panning: set_panning_reader,
+			exit_loop: exit_loop_reader,
+			exit_loop_at: exit_loop_at_reader,
 			pause: pause_reader,
 			resume: resume_reader,
 			stop: stop_reader,
@@ -4,13 +4,17 @@ mod test;
 use std::sync::Mutex;
 use std::{sync::Arc, time::Duration};
 
-use crate::sound::{EndPosition, IntoOptionalRegion, PlaybackPosition, Region, SoundData};
+use crate::sound::{
+	EndPosition, IntoOptionalRegion, MarkerFrames, PlaybackPosition, Region, SoundData,
+};
 use crate::{Decibels, Panning, PlaybackRate, StartTime};
-use crate::{Tween, Value};
+use crate::{Tween, Value, effect::EffectBuilder, event::event_queue};
 use rtrb::RingBuffer;
 
 use super::sound::Shared;
-use super::{StreamingSoundHandle, StreamingSoundSettings, command_writers_and_readers};
+use super::{
+	DecodePool, StreamingSoundHandle, StreamingSoundSettings, command_writers_and_readers,
+};
 
 use super::{
 	decoder::Decoder,
@@ -108,6 +112,51 @@ impl<Error: Send> StreamingSoundData<Error> {
 		self
 	}
 
+	/**
+	Sets how long the end of the loop region crossfades into the audio
+	before the start of the loop region, which hides clicks at the loop point.
+
+	# Examples
+
+	```no_run
+	# use kira::sound::streaming::StreamingSoundData;
+	use std::time::Duration;
+
+	let sound = StreamingSoundData::from_file("sound.ogg")?
+		.loop_region(3.0..)
+		.loop_crossfade(Duration::from_millis(50));
+	# Result::<(), Box<dyn std::error::Error>>::Ok(())
+	```
+	*/
+	#[must_use = "This method consumes self and returns a modified StreamingSoundData, so the return value should be used"]
+	pub fn loop_crossfade(mut self, loop_crossfade: Duration) -> Self {
+		self.settings.loop_crossfade = loop_crossfade;
+		self
+	}
+
+	/**
+	Sets how many times the loop region should be played before
+	playback continues past the end of it. [`None`] loops forever.
+
+	# Examples
+
+	Play an intro, loop the middle of the sound 4 times, and then
+	play the rest of the sound:
+
+	```no_run
+	# use kira::sound::streaming::StreamingSoundData;
+	let sound = StreamingSoundData::from_file("music.ogg")?
+		.loop_region(8.0..24.0)
+		.loop_count(4);
+	# Result::<(), Box<dyn std::error::Error>>::Ok(())
+	```
+	*/
+	#[must_use = "This method consumes self and returns a modified StreamingSoundData, so the return value should be used"]
+	pub fn loop_count(mut self, loop_count: impl Into<Option<usize>>) -> Self {
+		self.settings.loop_count = loop_count.into();
+		self
+	}
+
 	/**
 	Sets the volume of the sound.
 
@@ -210,6 +259,29 @@ impl<Error: Send> StreamingSoundData<Error> {
 		self
 	}
 
+	/**
+	Sets how fast the sound plays relative to its normal speed.
+
+	Unlike the playback rate, changing the tempo doesn't
+	change the pitch of the sound. Tempos are limited to
+	the range `0.0..=4.0`.
+
+	# Examples
+
+	Play a sound at half speed without lowering its pitch:
+
+	```no_run
+	# use kira::sound::streaming::StreamingSoundData;
+	let sound = StreamingSoundData::from_file("sound.ogg")?.tempo(0.5);
+	# Result::<(), Box<dyn std::error::Error>>::Ok(())
+	```
+	*/
+	#[must_use = "This method consumes self and returns a modified StreamingSoundData, so the return value should be used"]
+	pub fn tempo(mut self, tempo: impl Into<Value<f64>>) -> Self {
+		self.settings.tempo = tempo.into();
+		self
+	}
+
 	/**
 	Sets the panning of the sound, where -1.0 is hard left
 	and 1.0 is hard right.
@@ -263,6 +335,41 @@ impl<Error: Send> StreamingSoundData<Error> {
 		self
 	}
 
+	/**
+	Adds an effect that's applied to the sound before its
+	volume and panning.
+
+	The effect's handle can be retrieved from the [`StreamingSoundHandle`]
+	with [`StreamingSoundHandle::effect`] and [`StreamingSoundHandle::effect_mut`].
+	*/
+	#[must_use = "This method consumes self and returns a modified StreamingSoundData, so the return value should be used"]
+	pub fn with_effect<B>(mut self, builder: B) -> Self
+	where
+		B: EffectBuilder + Clone + Send + Sync + 'static,
+		B::Handle: Send + Sync + 'static,
+	{
+		self.settings = self.settings.with_effect(builder);
+		self
+	}
+
+	/**
+	Adds a named point in the sound that emits a
+	[`SoundEvent::Marker`](crate::sound::SoundEvent::Marker) event
+	when playback reaches it.
+
+	Markers are also loaded from cue points in WAV files and markers
+	in AIFF files.
+	*/
+	#[must_use = "This method consumes self and returns a modified StreamingSoundData, so the return value should be used"]
+	pub fn with_marker(
+		mut self,
+		name: impl Into<String>,
+		position: impl Into<PlaybackPosition>,
+	) -> Self {
+		self.settings = self.settings.with_marker(name, position);
+		self
+	}
+
 	/// Returns the `StreamingSoundData` with the specified settings.
 	#[must_use = "This method consumes self and returns a modified StreamingSoundData, so the return value should be used"]
 	pub fn with_settings(mut self, settings: StreamingSoundSettings) -> Self {
@@ -322,37 +429,66 @@ impl<T: Send> StreamingSoundData<T> {}
 #[cfg(feature = "symphonia")]
 impl StreamingSoundData<crate::sound::FromFileError> {
 	/// Creates a [`StreamingSoundData`] for an audio file.
+	///
+	/// Audio with more than two channels is mixed down to stereo
+	/// using the standard downmix for its channel layout (see
+	/// [`DownmixMatrix::itu`](crate::sound::DownmixMatrix::itu)).
 	pub fn from_file(
 		path: impl AsRef<std::path::Path>,
 	) -> Result<StreamingSoundData<crate::sound::FromFileError>, crate::sound::FromFileError> {
-		use std::fs::File;
-
-		use super::symphonia::SymphoniaDecoder;
-
-		Ok(Self::from_decoder(SymphoniaDecoder::new(Box::new(
-			File::open(path)?,
-		))?))
+		Self::from_boxed_media_source(Box::new(std::fs::File::open(path)?), None)
 	}
 
 	/// Creates a [`StreamingSoundData`] for a cursor wrapping audio file data.
 	pub fn from_cursor<T: AsRef<[u8]> + Send + Sync + 'static>(
 		cursor: std::io::Cursor<T>,
 	) -> Result<StreamingSoundData<crate::sound::FromFileError>, crate::sound::FromFileError> {
-		use super::symphonia::SymphoniaDecoder;
-
-		Ok(Self::from_decoder(SymphoniaDecoder::new(Box::new(cursor))?))
+		Self::from_boxed_media_source(Box::new(cursor), None)
 	}
 
 	/// Creates a [`StreamingSoundData`] for a type that implements Symphonia's
 	/// [`MediaSource`](symphonia::core::io::MediaSource) trait.
 	pub fn from_media_source(
 		media_source: impl symphonia::core::io::MediaSource + 'static,
+	) -> Result<StreamingSoundData<crate::sound::FromFileError>, crate::sound::FromFileError> {
+		Self::from_boxed_media_source(Box::new(media_source), None)
+	}
+
+	/// Creates a [`StreamingSoundData`] for an audio file, mixing its
+	/// channels down to stereo with the given
+	/// [`DownmixMatrix`](crate::sound::DownmixMatrix).
+	pub fn from_file_with_downmix(
+		path: impl AsRef<std::path::Path>,
+		downmix: crate::sound::DownmixMatrix,
+	) -> Result<StreamingSoundData<crate::sound::FromFileError>, crate::sound::FromFileError> {
+		Self::from_media_source_with_downmix(std::fs::File::open(path)?, downmix)
+	}
+
+	/// Creates a [`StreamingSoundData`] for a type that implements Symphonia's
+	/// [`MediaSource`](symphonia::core::io::MediaSource) trait, mixing its
+	/// channels down to stereo with the given
+	/// [`DownmixMatrix`](crate::sound::DownmixMatrix).
+	pub fn from_media_source_with_downmix(
+		media_source: impl symphonia::core::io::MediaSource + 'static,
+		downmix: crate::sound::DownmixMatrix,
+	) -> Result<StreamingSoundData<crate::sound::FromFileError>, crate::sound::FromFileError> {
+		Self::from_boxed_media_source(Box::new(media_source), Some(downmix))
+	}
+
+	fn from_boxed_media_source(
+		mut media_source: Box<dyn symphonia::core::io::MediaSource>,
+		downmix: Option<crate::sound::DownmixMatrix>,
 	) -> Result<StreamingSoundData<crate::sound::FromFileError>, crate::sound::FromFileError> {
 		use super::symphonia::SymphoniaDecoder;
 
-		Ok(Self::from_decoder(SymphoniaDecoder::new(Box::new(
-			media_source,
-		))?))
+		let markers = if media_source.is_seekable() {
+			crate::sound::read_markers(&mut media_source)?
+		} else {
+			vec![]
+		};
+		let mut data = Self::from_decoder(SymphoniaDecoder::new(media_source, downmix)?);
+		data.settings.markers = markers;
+		Ok(data)
 	}
 }
 
@@ -370,12 +506,16 @@ impl<Error: Send + 'static> StreamingSoundData<Error> {
 		let (command_writers, command_readers, decode_scheduler_command_readers) =
 			command_writers_and_readers();
 		let (error_producer, error_consumer) = RingBuffer::new(ERROR_BUFFER_CAPACITY);
+		let (event_producer, event_consumer) = event_queue();
 		let sample_rate = self.decoder.sample_rate();
 		let shared = Arc::new(Shared::new());
+		let (effects, effect_handles) = self.settings.effects.build();
+		let markers = self.settings.markers.clone();
+		let marker_frames = MarkerFrames::new(&markers, sample_rate, self.slice);
 		let (scheduler, frame_consumer) = DecodeScheduler::new(
 			self.decoder,
 			self.slice,
-			self.settings,
+			&self.settings,
 			shared.clone(),
 			decode_scheduler_command_readers,
 			error_producer,
@@ -383,15 +523,21 @@ impl<Error: Send + 'static> StreamingSoundData<Error> {
 		let sound = StreamingSound::new(
 			sample_rate,
 			self.settings,
+			effects,
 			shared.clone(),
 			frame_consumer,
 			command_readers,
+			event_producer,
+			marker_frames,
 			&scheduler,
 		);
 		let handle = StreamingSoundHandle {
 			shared,
 			command_writers,
 			error_consumer: Mutex::new(error_consumer),
+			event_consumer,
+			effect_handles,
+			markers,
 		};
 		Ok((sound, handle, scheduler))
 	}
@@ -405,7 +551,7 @@ impl<Error: Send + 'static> SoundData for StreamingSoundData<Error> {
 	#[allow(clippy::type_complexity)]
 	fn into_sound(self) -> Result<(Box<dyn crate::sound::Sound>, Self::Handle), Self::Error> {
 		let (sound, handle, scheduler) = self.split()?;
-		scheduler.start();
+		DecodePool::global().add(Box::new(scheduler));
 		Ok((Box::new(sound), handle))
 	}
 }
@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod test;
+
+use std::{
+	panic::{AssertUnwindSafe, catch_unwind},
+	sync::{Arc, Condvar, Mutex, OnceLock},
+	thread,
+	time::Duration,
+};
+
+use super::sound::decode_scheduler::NextStep;
+
+/// The number of decode threads used until an
+/// [`AudioManager`](crate::AudioManager) is created.
+pub(crate) const DEFAULT_NUM_DECODE_THREADS: usize = 2;
+/// How long an idle worker waits before checking the streams again
+/// when every stream's buffer is full.
+const IDLE_WAIT_DURATION: Duration = Duration::from_millis(1);
+/// How many frames a worker decodes for one stream before giving
+/// other streams a turn.
+const MAX_STEPS_PER_TURN: usize = 1024;
+
+/// Decodes audio for a streaming sound a bit at a time.
+pub(crate) trait DecodeJob: Send {
+	/// Decodes the next frame of audio.
+	fn step(&mut self) -> NextStep;
+
+	/// Returns how many more frames can be decoded before the
+	/// stream's buffer is full.
+	fn num_free_slots(&self) -> usize;
+
+	/// Returns `true` if the sound was stopped or dropped and no more
+	/// audio needs to be decoded.
+	fn finished(&self) -> bool;
+}
+
+/**
+Decodes audio for every streaming sound on a shared, bounded set of
+worker threads.
+
+Worker threads are spawned as streams are added, up to the maximum
+number of threads. Each time a worker is free, it decodes audio for
+the stream with the least audio buffered (the one closest to running
+out), then puts the stream back so other streams get a turn.
+*/
+pub(crate) struct DecodePool {
+	shared: Arc<PoolShared>,
+}
+
+impl DecodePool {
+	#[must_use]
+	pub fn new(max_threads: usize) -> Self {
+		Self {
+			shared: Arc::new(PoolShared {
+				state: Mutex::new(PoolState {
+					idle_jobs: vec![],
+					num_jobs: 0,
+					num_threads: 0,
+					max_threads: max_threads.max(1),
+					max_threads_requested: false,
+					shutting_down: false,
+				}),
+				condvar: Condvar::new(),
+			}),
+		}
+	}
+
+	/// Returns the pool shared by every streaming sound.
+	#[must_use]
+	pub fn global() -> &'static Self {
+		static GLOBAL: OnceLock<DecodePool> = OnceLock::new();
+		GLOBAL.get_or_init(|| Self::new(DEFAULT_NUM_DECODE_THREADS))
+	}
+
+	/// Requests a maximum number of worker threads.
+	///
+	/// The first request replaces the default maximum. After that,
+	/// the maximum is the largest number of threads requested, so
+	/// every [`AudioManager`](crate::AudioManager) gets at least
+	/// as many threads as it asked for. If there are more workers
+	/// than the new maximum, the extra workers exit once they're idle.
+	pub fn request_max_threads(&self, max_threads: usize) {
+		let mut state = self.shared.state.lock().unwrap();
+		let max_threads = max_threads.max(1);
+		state.max_threads = if state.max_threads_requested {
+			state.max_threads.max(max_threads)
+		} else {
+			max_threads
+		};
+		state.max_threads_requested = true;
+		drop(state);
+		self.shared.condvar.notify_all();
+	}
+
+	/// Adds a stream to decode audio for until it finishes.
+	pub fn add(&self, job: Box<dyn DecodeJob>) {
+		let mut state = self.shared.state.lock().unwrap();
+		state.idle_jobs.push(job);
+		state.num_jobs += 1;
+		// there's no use in having more workers than streams.
+		// if a thread can't be spawned, we try again when the
+		// next stream is added.
+		if state.num_threads < state.max_threads.min(state.num_jobs) {
+			let shared = self.shared.clone();
+			if thread::Builder::new()
+				.name(format!("kira decode worker {}", state.num_threads))
+				.spawn(move || run_worker(shared))
+				.is_ok()
+			{
+				state.num_threads += 1;
+			}
+		}
+		drop(state);
+		self.shared.condvar.notify_one();
+	}
+}
+
+impl Drop for DecodePool {
+	fn drop(&mut self) {
+		self.shared.state.lock().unwrap().shutting_down = true;
+		self.shared.condvar.notify_all();
+	}
+}
+
+struct PoolShared {
+	state: Mutex<PoolState>,
+	/// Notified when a stream is added or the number of workers
+	/// should change.
+	condvar: Condvar,
+}
+
+struct PoolState {
+	/// The streams that aren't being decoded by a worker right now.
+	idle_jobs: Vec<Box<dyn DecodeJob>>,
+	/// The number of streams, including the ones being decoded.
+	num_jobs: usize,
+	num_threads: usize,
+	max_threads: usize,
+	/// Whether [`DecodePool::request_max_threads`] has been called.
+	max_threads_requested: bool,
+	shutting_down: bool,
+}
+
+fn run_worker(shared: Arc<PoolShared>) {
+	let mut state = shared.state.lock().unwrap();
+	loop {
+		if state.shutting_down || state.num_threads > state.max_threads {
+			state.num_threads -= 1;
+			return;
+		}
+		let num_jobs_before = state.idle_jobs.len();
+		state.idle_jobs.retain(|job| !job.finished());
+		state.num_jobs -= num_jobs_before - state.idle_jobs.len();
+		let Some(mut job) = take_most_urgent_job(&mut state.idle_jobs) else {
+			state = if state.idle_jobs.is_empty() {
+				// the other streams (if any) are being decoded by other
+				// workers, which will keep checking them
+				shared.condvar.wait(state).unwrap()
+			} else {
+				shared
+					.condvar
+					.wait_timeout(state, IDLE_WAIT_DURATION)
+					.unwrap()
+					.0
+			};
+			continue;
+		};
+		drop(state);
+		// if decoding a stream panics, drop that stream and keep the
+		// worker running so the other streams are still decoded
+		let next_step = catch_unwind(AssertUnwindSafe(|| run_turn(job.as_mut())));
+		state = shared.state.lock().unwrap();
+		match next_step {
+			Ok(NextStep::End) | Err(_) => state.num_jobs -= 1,
+			Ok(NextStep::Continue | NextStep::Wait) => state.idle_jobs.push(job),
+		}
+	}
+}
+
+/// Removes and returns the stream with the most room in its buffer,
+/// or `None` if every stream's buffer is full.
+#[must_use]
+fn take_most_urgent_job(jobs: &mut Vec<Box<dyn DecodeJob>>) -> Option<Box<dyn DecodeJob>> {
+	let (index, _) = jobs
+		.iter()
+		.map(|job| job.num_free_slots())
+		.enumerate()
+		.filter(|(_, num_free_slots)| *num_free_slots > 0)
+		.max_by_key(|(_, num_free_slots)| *num_free_slots)?;
+	Some(jobs.swap_remove(index))
+}
+
+/// Decodes audio for a stream until its buffer is full, it ends,
+/// or it's time to give other streams a turn.
+#[must_use]
+fn run_turn(job: &mut dyn DecodeJob) -> NextStep {
+	for _ in 0..MAX_STEPS_PER_TURN {
+		match job.step() {
+			NextStep::Continue => {}
+			next_step => return next_step,
+		}
+	}
+	NextStep::Continue
+}
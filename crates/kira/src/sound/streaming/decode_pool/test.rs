@@ -0,0 +1,206 @@
+use std::{
+	sync::{
+		Arc,
+		atomic::{AtomicBool, AtomicUsize, Ordering},
+	},
+	thread,
+	time::{Duration, Instant},
+};
+
+use crate::{
+	frame::Frame,
+	info::MockInfoBuilder,
+	sound::{
+		Sound,
+		streaming::{StreamingSoundData, StreamingSoundSettings, decoder::mock::MockDecoder},
+	},
+};
+
+use super::{DecodeJob, DecodePool, NextStep, take_most_urgent_job};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+struct MockJob {
+	num_free_slots: Arc<AtomicUsize>,
+	finished: Arc<AtomicBool>,
+}
+
+impl MockJob {
+	fn new(num_free_slots: usize) -> Self {
+		Self {
+			num_free_slots: Arc::new(AtomicUsize::new(num_free_slots)),
+			finished: Arc::new(AtomicBool::new(false)),
+		}
+	}
+}
+
+impl DecodeJob for MockJob {
+	fn step(&mut self) -> NextStep {
+		match self.num_free_slots.load(Ordering::SeqCst) {
+			0 => NextStep::Wait,
+			num_free_slots => {
+				self.num_free_slots
+					.store(num_free_slots - 1, Ordering::SeqCst);
+				NextStep::Continue
+			}
+		}
+	}
+
+	fn num_free_slots(&self) -> usize {
+		self.num_free_slots.load(Ordering::SeqCst)
+	}
+
+	fn finished(&self) -> bool {
+		self.finished.load(Ordering::SeqCst)
+	}
+}
+
+struct PanickingJob;
+
+impl DecodeJob for PanickingJob {
+	fn step(&mut self) -> NextStep {
+		panic!("decoding failed");
+	}
+
+	fn num_free_slots(&self) -> usize {
+		1
+	}
+
+	fn finished(&self) -> bool {
+		false
+	}
+}
+
+fn wait_until(mut condition: impl FnMut() -> bool) {
+	let start = Instant::now();
+	while !condition() {
+		assert!(start.elapsed() < TIMEOUT, "timed out");
+		thread::sleep(Duration::from_millis(1));
+	}
+}
+
+/// Tests that the stream with the most room in its buffer
+/// is decoded first.
+#[test]
+fn decodes_most_urgent_stream_first() {
+	let mut jobs: Vec<Box<dyn DecodeJob>> = vec![
+		Box::new(MockJob::new(5)),
+		Box::new(MockJob::new(0)),
+		Box::new(MockJob::new(10)),
+	];
+	let job = take_most_urgent_job(&mut jobs).unwrap();
+	assert_eq!(job.num_free_slots(), 10);
+	let job = take_most_urgent_job(&mut jobs).unwrap();
+	assert_eq!(job.num_free_slots(), 5);
+	// streams with full buffers don't need decoding
+	assert!(take_most_urgent_job(&mut jobs).is_none());
+	assert_eq!(jobs.len(), 1);
+}
+
+/// Tests that a `DecodePool` spawns no more threads than
+/// its maximum, and drops streams once they finish.
+#[test]
+fn limits_number_of_threads() {
+	let pool = DecodePool::new(2);
+	let jobs = (0..5).map(|_| MockJob::new(100)).collect::<Vec<_>>();
+	let finished = jobs
+		.iter()
+		.map(|job| job.finished.clone())
+		.collect::<Vec<_>>();
+	let num_free_slots = jobs
+		.iter()
+		.map(|job| job.num_free_slots.clone())
+		.collect::<Vec<_>>();
+	for job in jobs {
+		pool.add(Box::new(job));
+	}
+	assert_eq!(pool.shared.state.lock().unwrap().num_threads, 2);
+
+	// every stream should be served, even with fewer threads than streams
+	wait_until(|| {
+		num_free_slots
+			.iter()
+			.all(|num_free_slots| num_free_slots.load(Ordering::SeqCst) == 0)
+	});
+
+	for finished in &finished {
+		finished.store(true, Ordering::SeqCst);
+	}
+	wait_until(|| pool.shared.state.lock().unwrap().num_jobs == 0);
+
+	pool.request_max_threads(1);
+	wait_until(|| pool.shared.state.lock().unwrap().num_threads == 1);
+}
+
+/// Tests that a `DecodePool` uses the largest number of threads
+/// requested, replacing the default with the first request.
+#[test]
+fn uses_largest_requested_number_of_threads() {
+	let pool = DecodePool::new(2);
+	pool.request_max_threads(1);
+	assert_eq!(pool.shared.state.lock().unwrap().max_threads, 1);
+	pool.request_max_threads(3);
+	assert_eq!(pool.shared.state.lock().unwrap().max_threads, 3);
+	pool.request_max_threads(2);
+	assert_eq!(pool.shared.state.lock().unwrap().max_threads, 3);
+	pool.request_max_threads(0);
+	assert_eq!(pool.shared.state.lock().unwrap().max_threads, 3);
+}
+
+/// Tests that a `DecodePool` decodes audio for a `StreamingSound`.
+#[test]
+fn decodes_streaming_sounds() {
+	let pool = DecodePool::new(1);
+	let data = StreamingSoundData {
+		decoder: Box::new(MockDecoder::new(
+			(1..=10).map(|i| Frame::from_mono(i as f32)).collect(),
+		)),
+		settings: StreamingSoundSettings::new(),
+		slice: None,
+	};
+	let (mut sound, handle, scheduler) = data.split().unwrap();
+	pool.add(Box::new(scheduler));
+	wait_until(|| handle.shared.reached_end());
+	wait_until(|| pool.shared.state.lock().unwrap().num_jobs == 0);
+
+	for i in 1..=10 {
+		assert_eq!(
+			sound.process_one(1.0, &MockInfoBuilder::new().build()),
+			Frame::from_mono(i as f32).panned(crate::Panning::CENTER)
+		);
+	}
+}
+
+/// Tests that a `DecodePool` stops decoding audio for a
+/// `StreamingSound` that was dropped without being stopped.
+#[test]
+fn drops_abandoned_streams() {
+	let pool = DecodePool::new(1);
+	let data = StreamingSoundData {
+		decoder: Box::new(MockDecoder::new(vec![Frame::ZERO; 100_000])),
+		settings: StreamingSoundSettings::new(),
+		slice: None,
+	};
+	let (sound, handle, scheduler) = data.split().unwrap();
+	pool.add(Box::new(scheduler));
+	drop(sound);
+	wait_until(|| pool.shared.state.lock().unwrap().num_jobs == 0);
+	assert!(!handle.shared.reached_end());
+}
+
+/// Tests that a `DecodePool` drops a stream that panics while
+/// decoding and keeps decoding the other streams.
+#[test]
+fn survives_panicking_streams() {
+	let pool = DecodePool::new(1);
+	for _ in 0..3 {
+		pool.add(Box::new(PanickingJob));
+	}
+	wait_until(|| pool.shared.state.lock().unwrap().num_jobs == 0);
+	assert_eq!(pool.shared.state.lock().unwrap().num_threads, 1);
+
+	let job = MockJob::new(100);
+	let num_free_slots = job.num_free_slots.clone();
+	pool.add(Box::new(job));
+	wait_until(|| num_free_slots.load(Ordering::SeqCst) == 0);
+}
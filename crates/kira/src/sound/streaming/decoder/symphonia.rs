@@ -2,7 +2,7 @@ use std::convert::TryInto;
 
 use crate::{
 	frame::Frame,
-	sound::{FromFileError, symphonia::load_frames_from_buffer_ref},
+	sound::{DownmixMatrix, FromFileError, symphonia::load_frames_from_buffer_ref},
 };
 use symphonia::core::{
 	codecs::{CodecParameters, audio::AudioDecoder},
@@ -17,10 +17,14 @@ pub(crate) struct SymphoniaDecoder {
 	sample_rate: u32,
 	num_frames: usize,
 	track_id: u32,
+	downmix: Option<DownmixMatrix>,
 }
 
 impl SymphoniaDecoder {
-	pub(crate) fn new(media_source: Box<dyn MediaSource>) -> Result<Self, FromFileError> {
+	pub(crate) fn new(
+		media_source: Box<dyn MediaSource>,
+		downmix: Option<DownmixMatrix>,
+	) -> Result<Self, FromFileError> {
 		let codecs = symphonia::default::get_codecs();
 		let probe = symphonia::default::get_probe();
 		let mss = MediaSourceStream::new(media_source, Default::default());
@@ -53,6 +57,7 @@ impl SymphoniaDecoder {
 			sample_rate,
 			num_frames,
 			track_id,
+			downmix,
 		})
 	}
 }
@@ -77,7 +82,7 @@ impl super::Decoder for SymphoniaDecoder {
 			}
 		};
 		let buffer = self.decoder.decode(&packet)?;
-		load_frames_from_buffer_ref(&buffer)
+		load_frames_from_buffer_ref(&buffer, self.downmix.as_ref())
 	}
 
 	fn seek(&mut self, index: usize) -> Result<usize, Self::Error> {
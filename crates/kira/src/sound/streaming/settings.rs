@@ -1,10 +1,13 @@
+use std::time::Duration;
+
 use crate::{
 	Decibels, Panning, PlaybackRate, StartTime, Tween, Value,
-	sound::{IntoOptionalRegion, PlaybackPosition, Region},
+	effect::EffectBuilder,
+	sound::{EffectChain, IntoOptionalRegion, Marker, PlaybackPosition, Region},
 };
 
 /// Settings for a streaming sound.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct StreamingSoundSettings {
 	/// When the sound should start playing.
 	pub start_time: StartTime,
@@ -12,6 +15,21 @@ pub struct StreamingSoundSettings {
 	pub start_position: PlaybackPosition,
 	/// The portion of the sound that should be looped.
 	pub loop_region: Option<Region>,
+	/**
+	How long the end of the loop region crossfades into the audio
+	before the start of the loop region, which hides clicks at
+	the loop point.
+
+	The crossfade is shortened if there isn't enough audio before
+	the start of the loop region (or after the end of the loop
+	region when playing in reverse).
+	*/
+	pub loop_crossfade: Duration,
+	/**
+	How many times the loop region should be played before playback
+	continues past the end of it, or [`None`] to loop forever.
+	*/
+	pub loop_count: Option<usize>,
 	/// The volume of the sound.
 	pub volume: Value<Decibels>,
 	/// The playback rate of the sound.
@@ -19,11 +37,23 @@ pub struct StreamingSoundSettings {
 	/// Changing the playback rate will change both the speed
 	/// and the pitch of the sound.
 	pub playback_rate: Value<PlaybackRate>,
+	/// How fast the sound plays relative to its normal speed.
+	///
+	/// Unlike the playback rate, changing the tempo doesn't
+	/// change the pitch of the sound. Tempos are limited to
+	/// the range `0.0..=4.0`.
+	pub tempo: Value<f64>,
 	/// The panning of the sound, where -1.0 is hard left
 	/// and 1.0 is hard right.
 	pub panning: Value<Panning>,
 	/// An optional fade-in from silence.
 	pub fade_in_tween: Option<Tween>,
+	/// Effects applied to the sound before its volume and panning.
+	pub effects: EffectChain,
+	/// Named points in the sound that emit a
+	/// [`SoundEvent::Marker`](crate::sound::SoundEvent::Marker)
+	/// event when playback reaches them.
+	pub markers: Vec<Marker>,
 }
 
 impl StreamingSoundSettings {
@@ -34,10 +64,15 @@ impl StreamingSoundSettings {
 			start_time: StartTime::Immediate,
 			start_position: PlaybackPosition::Seconds(0.0),
 			loop_region: None,
+			loop_crossfade: Duration::ZERO,
+			loop_count: None,
 			volume: Value::Fixed(Decibels::IDENTITY),
 			playback_rate: Value::Fixed(PlaybackRate(1.0)),
+			tempo: Value::Fixed(1.0),
 			panning: Value::Fixed(Panning::CENTER),
 			fade_in_tween: None,
+			effects: EffectChain::new(),
+			markers: vec![],
 		}
 	}
 
@@ -68,6 +103,30 @@ impl StreamingSoundSettings {
 		}
 	}
 
+	/**
+	Sets how long the end of the loop region crossfades into the
+	audio before the start of the loop region.
+	*/
+	#[must_use = "This method consumes self and returns a modified StreamingSoundSettings, so the return value should be used"]
+	pub fn loop_crossfade(self, loop_crossfade: Duration) -> Self {
+		Self {
+			loop_crossfade,
+			..self
+		}
+	}
+
+	/**
+	Sets how many times the loop region should be played before
+	playback continues past the end of it.
+	*/
+	#[must_use = "This method consumes self and returns a modified StreamingSoundSettings, so the return value should be used"]
+	pub fn loop_count(self, loop_count: impl Into<Option<usize>>) -> Self {
+		Self {
+			loop_count: loop_count.into(),
+			..self
+		}
+	}
+
 	/** Sets the volume of the sound. */
 	#[must_use = "This method consumes self and returns a modified StreamingSoundSettings, so the return value should be used"]
 	pub fn volume(self, volume: impl Into<Value<Decibels>>) -> Self {
@@ -91,6 +150,21 @@ impl StreamingSoundSettings {
 		}
 	}
 
+	/**
+	Sets how fast the sound plays relative to its normal speed.
+
+	Unlike the playback rate, changing the tempo doesn't
+	change the pitch of the sound. Tempos are limited to
+	the range `0.0..=4.0`.
+	*/
+	#[must_use = "This method consumes self and returns a modified StreamingSoundSettings, so the return value should be used"]
+	pub fn tempo(self, tempo: impl Into<Value<f64>>) -> Self {
+		Self {
+			tempo: tempo.into(),
+			..self
+		}
+	}
+
 	/**
 	Sets the panning of the sound, where -1.-1.0 is hard left
 	and 1.0 is hard right.
@@ -111,6 +185,33 @@ impl StreamingSoundSettings {
 			..self
 		}
 	}
+
+	/// Adds an effect that's applied to the sound before its
+	/// volume and panning.
+	#[must_use = "This method consumes self and returns a modified StreamingSoundSettings, so the return value should be used"]
+	pub fn with_effect<B>(self, builder: B) -> Self
+	where
+		B: EffectBuilder + Clone + Send + Sync + 'static,
+		B::Handle: Send + Sync + 'static,
+	{
+		Self {
+			effects: self.effects.with_effect(builder),
+			..self
+		}
+	}
+
+	/// Adds a named point in the sound that emits a
+	/// [`SoundEvent::Marker`](crate::sound::SoundEvent::Marker)
+	/// event when playback reaches it.
+	#[must_use = "This method consumes self and returns a modified StreamingSoundSettings, so the return value should be used"]
+	pub fn with_marker(
+		mut self,
+		name: impl Into<String>,
+		position: impl Into<PlaybackPosition>,
+	) -> Self {
+		self.markers.push(Marker::new(name, position));
+		self
+	}
 }
 
 impl Default for StreamingSoundSettings {
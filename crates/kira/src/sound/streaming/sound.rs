@@ -11,10 +11,16 @@ use std::sync::{
 use crate::{
 	Decibels, Panning, Parameter, PlaybackRate, StartTime, Tween,
 	command::read_commands_into_parameters,
+	effect::Effect,
+	event::EventProducer,
 	frame::{Frame, interpolate_frame},
 	info::Info,
 	playback_state_manager::PlaybackStateManager,
-	sound::{PlaybackState, Sound},
+	sound::{
+		MarkerFrames, PendingMarkers, PlaybackState, Sound, SoundEvent,
+		time_stretcher::TimeStretcher,
+	},
+	start_time::{StartTimeProgress, num_frames_before_start},
 };
 use rtrb::Consumer;
 
@@ -28,6 +34,16 @@ pub(crate) struct Shared {
 	position: AtomicU64,
 	reached_end: AtomicBool,
 	encountered_error: AtomicBool,
+	/// How many frames the decoder has left to decode, or [`u64::MAX`]
+	/// if the sound loops forever.
+	num_frames_remaining: AtomicU64,
+	/// Set by the sound when the decoder should stop looping after
+	/// the current iteration of the loop region.
+	exit_loop: AtomicBool,
+	/// Set by the sound when the decoder should jump out of the loop
+	/// region right away. The decoder unsets it once the first frame
+	/// after the jump has been pushed.
+	exit_loop_now: AtomicBool,
 }
 
 impl Shared {
@@ -38,6 +54,9 @@ impl Shared {
 			state: AtomicU8::new(PlaybackState::Playing as u8),
 			reached_end: AtomicBool::new(false),
 			encountered_error: AtomicBool::new(false),
+			num_frames_remaining: AtomicU64::new(u64::MAX),
+			exit_loop: AtomicBool::new(false),
+			exit_loop_now: AtomicBool::new(false),
 		}
 	}
 
@@ -80,23 +99,46 @@ pub(crate) struct StreamingSound {
 	sample_rate: u32,
 	frame_consumer: Consumer<TimestampedFrame>,
 	start_time: StartTime,
+	/// When the sound should jump out of the loop region, if
+	/// [`StreamingSoundHandle::exit_loop_at`](super::StreamingSoundHandle::exit_loop_at)
+	/// was called.
+	loop_exit_time: Option<StartTime>,
+	/// Whether the decoder was asked to stop looping and the sound
+	/// hasn't reached the end of the loop region since.
+	exiting_loop: bool,
+	/// Whether the decoder was asked to jump out of the loop region
+	/// and the sound is waiting to skip the frames decoded before that.
+	skipping_to_loop_exit: bool,
 	playback_state_manager: PlaybackStateManager,
 	current_frame: usize,
 	fractional_position: f64,
+	time_stretcher: TimeStretcher,
 	volume: Parameter<Decibels>,
 	playback_rate: Parameter<PlaybackRate>,
+	tempo: Parameter<f64>,
 	panning: Parameter<Panning>,
+	effects: Vec<Box<dyn Effect>>,
 	shared: Arc<Shared>,
+	event_producer: EventProducer<SoundEvent>,
+	markers: MarkerFrames,
+	/// Markers the time stretcher has received input for but hasn't
+	/// output yet.
+	pending_markers: PendingMarkers,
+	started: bool,
 }
 
 impl StreamingSound {
+	#[allow(clippy::too_many_arguments)]
 	#[must_use]
 	pub(super) fn new<Error: Send + 'static>(
 		sample_rate: u32,
 		settings: StreamingSoundSettings,
+		effects: Vec<Box<dyn Effect>>,
 		shared: Arc<Shared>,
 		frame_consumer: Consumer<TimestampedFrame>,
 		command_readers: CommandReaders,
+		event_producer: EventProducer<SoundEvent>,
+		markers: MarkerFrames,
 		scheduler: &DecodeScheduler<Error>,
 	) -> Self {
 		let current_frame = scheduler.current_frame();
@@ -109,13 +151,23 @@ impl StreamingSound {
 			sample_rate,
 			frame_consumer,
 			start_time: settings.start_time,
+			loop_exit_time: None,
+			exiting_loop: false,
+			skipping_to_loop_exit: false,
 			playback_state_manager: PlaybackStateManager::new(settings.fade_in_tween),
 			current_frame,
 			fractional_position: 0.0,
+			time_stretcher: TimeStretcher::new(),
 			volume: Parameter::new(settings.volume, Decibels::IDENTITY),
 			playback_rate: Parameter::new(settings.playback_rate, PlaybackRate(1.0)),
+			tempo: Parameter::new(settings.tempo, 1.0),
 			panning: Parameter::new(settings.panning, Panning::CENTER),
+			effects,
 			shared,
+			event_producer,
+			pending_markers: PendingMarkers::new(&markers),
+			markers,
+			started: false,
 		}
 	}
 
@@ -125,15 +177,26 @@ impl StreamingSound {
 	}
 
 	fn update_current_frame(&mut self) {
+		if let Some(index) = self
+			.time_stretcher
+			.current_index()
+			.or_else(|| self.buffered_frame_index())
+		{
+			self.current_frame = index;
+		}
+	}
+
+	/// Returns the index of the frame that's currently being played
+	/// from the ringbuffer.
+	#[must_use]
+	fn buffered_frame_index(&mut self) -> Option<usize> {
 		let chunk = self
 			.frame_consumer
 			.read_chunk(self.frame_consumer.slots().min(4))
 			.unwrap();
 		let (a, b) = chunk.as_slices();
 		let mut iter = a.iter().chain(b.iter());
-		if let Some(TimestampedFrame { index, .. }) = iter.nth(1) {
-			self.current_frame = *index;
-		}
+		iter.nth(1).map(|TimestampedFrame { index, .. }| *index)
 	}
 
 	#[must_use]
@@ -185,10 +248,154 @@ impl StreamingSound {
 		while self.frame_consumer.slots() > 1 {
 			self.frame_consumer.pop().ok();
 		}
+		self.time_stretcher.reset();
+		self.pending_markers.clear();
+	}
+
+	/// Returns `true` if the next frame that will be played is the
+	/// first frame after jumping back to the start of the loop region.
+	#[must_use]
+	fn next_frame_looped(&mut self) -> bool {
+		let chunk = self
+			.frame_consumer
+			.read_chunk(self.frame_consumer.slots().min(2))
+			.unwrap();
+		let (a, b) = chunk.as_slices();
+		let mut iter = a.iter().chain(b.iter());
+		iter.nth(1)
+			.is_some_and(|TimestampedFrame { looped, .. }| *looped)
+	}
+
+	/// Discards the buffered frames that were decoded before the
+	/// decoder jumped out of the loop region, if it did.
+	fn skip_to_loop_exit(&mut self) {
+		let chunk = self
+			.frame_consumer
+			.read_chunk(self.frame_consumer.slots())
+			.unwrap();
+		let (a, b) = chunk.as_slices();
+		// the first frame in the ringbuffer is the previous frame, so
+		// we keep one frame before the first frame after the jump
+		if let Some(num_stale_frames) = a
+			.iter()
+			.chain(b.iter())
+			.position(|TimestampedFrame { exited_loop, .. }| *exited_loop)
+			.and_then(|position| position.checked_sub(1))
+		{
+			chunk.commit(num_stale_frames);
+		}
+	}
+
+	#[must_use]
+	fn source_finished(&self) -> bool {
+		self.shared.reached_end() && self.frame_consumer.is_empty()
+	}
+
+	fn mark_as_finished(&mut self, time: f64) {
+		if self.playback_state_manager.playback_state() == PlaybackState::Stopped {
+			return;
+		}
+		self.playback_state_manager.mark_as_stopped();
+		self.update_shared_playback_state();
+		self.event_producer.push(SoundEvent::Finished, time);
+	}
+
+	/// Outputs the next frame of audio at the current playback rate.
+	#[must_use]
+	fn next_resampled_frame(&mut self, playback_rate: PlaybackRate, dt: f64, time: f64) -> Frame {
+		let next_frames = self.next_frames();
+		let out = interpolate_frame(
+			next_frames[0],
+			next_frames[1],
+			next_frames[2],
+			next_frames[3],
+			self.fractional_position as f32,
+		);
+		self.fractional_position += self.sample_rate as f64 * playback_rate.0.max(0.0) * dt;
+		while self.fractional_position >= 1.0 {
+			self.fractional_position -= 1.0;
+			let previous_frame_index = self.buffered_frame_index();
+			self.frame_consumer.pop().ok();
+			if self
+				.frame_consumer
+				.peek()
+				.is_ok_and(|TimestampedFrame { looped, .. }| *looped)
+			{
+				self.event_producer.push(SoundEvent::Looped, time);
+			}
+			if self.exiting_loop && self.next_frame_looped() {
+				// if the decoder already looped again before it was asked
+				// to stop looping, skip to where it left the loop region
+				self.exiting_loop = false;
+				self.skip_to_loop_exit();
+			}
+			// the new frame is heard starting from the next output frame
+			if let Some(frame_index) = self
+				.buffered_frame_index()
+				.filter(|frame_index| Some(*frame_index) != previous_frame_index)
+			{
+				self.push_marker_events(frame_index, time + dt);
+			}
+		}
+		out
+	}
+
+	/// Emits an event for each marker at the given frame.
+	///
+	/// While time stretching, the frame is heard once the time stretcher
+	/// outputs it, so the events are held back until then.
+	fn push_marker_events(&mut self, frame_index: usize, time: f64) {
+		for marker_index in self.markers.at(frame_index) {
+			if self.time_stretcher.is_active() {
+				// the frame will be pushed to the time stretcher after
+				// the frame that's currently being resampled
+				self.pending_markers
+					.push(self.time_stretcher.input_position() + 1, marker_index);
+			} else {
+				self.event_producer
+					.push(SoundEvent::Marker(marker_index), time);
+			}
+		}
+	}
+
+	/// Outputs the next frame of audio at the current playback rate
+	/// after passing it through the time stretcher.
+	#[must_use]
+	fn next_time_stretched_frame(
+		&mut self,
+		playback_rate: PlaybackRate,
+		tempo: f64,
+		dt: f64,
+		time: f64,
+	) -> Frame {
+		while self.time_stretcher.needs_input() {
+			let frame = (!self.source_finished())
+				.then(|| self.next_resampled_frame(playback_rate, dt, time));
+			let index = self.buffered_frame_index().unwrap_or(self.current_frame);
+			self.time_stretcher.push_frame(frame, index);
+		}
+		let out = self.time_stretcher.next_frame(tempo);
+		if let Some(position) = self.time_stretcher.output_position() {
+			while let Some(marker_index) = self.pending_markers.pop_reached(position) {
+				self.event_producer
+					.push(SoundEvent::Marker(marker_index), time);
+			}
+		}
+		if self.time_stretcher.finished() {
+			self.mark_as_finished(time);
+		}
+		out
 	}
 
 	fn read_commands(&mut self) {
-		read_commands_into_parameters!(self, volume, playback_rate, panning);
+		read_commands_into_parameters!(self, volume, playback_rate, tempo, panning);
+		if self.command_readers.exit_loop.read().is_some() {
+			self.shared.exit_loop.store(true, Ordering::SeqCst);
+			self.exiting_loop = true;
+		}
+		if let Some(start_time) = self.command_readers.exit_loop_at.read() {
+			self.loop_exit_time = Some(start_time);
+		}
 		if let Some(tween) = self.command_readers.pause.read() {
 			self.pause(tween);
 		}
@@ -205,16 +412,34 @@ impl StreamingSound {
 }
 
 impl Sound for StreamingSound {
+	fn init(&mut self, sample_rate: u32, internal_buffer_size: usize) {
+		for effect in &mut self.effects {
+			effect.init(sample_rate, internal_buffer_size);
+		}
+	}
+
+	fn on_change_sample_rate(&mut self, sample_rate: u32) {
+		for effect in &mut self.effects {
+			effect.on_change_sample_rate(sample_rate);
+		}
+	}
+
 	fn on_start_processing(&mut self) {
 		self.update_current_frame();
 		self.shared
 			.position
 			.store(self.position().to_bits(), Ordering::SeqCst);
 		self.read_commands();
+		for effect in &mut self.effects {
+			effect.on_start_processing();
+		}
 	}
 
 	fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
 		if self.shared.encountered_error() {
+			if self.playback_state_manager.playback_state() != PlaybackState::Stopped {
+				self.event_producer.push(SoundEvent::Error, info.time());
+			}
 			self.playback_state_manager.mark_as_stopped();
 			self.update_shared_playback_state();
 			out.fill(Frame::ZERO);
@@ -224,22 +449,58 @@ impl Sound for StreamingSound {
 		// update parameters
 		self.volume.update(dt * out.len() as f64, info);
 		self.playback_rate.update(dt * out.len() as f64, info);
+		self.tempo.update(dt * out.len() as f64, info);
 		self.panning.update(dt * out.len() as f64, info);
 		let changed_playback_state = self
 			.playback_state_manager
 			.update(dt * out.len() as f64, info);
 		if changed_playback_state {
 			self.update_shared_playback_state();
+			// the fade out finishes at the end of this batch
+			if self.playback_state_manager.playback_state() == PlaybackState::Stopped {
+				self.event_producer
+					.push(SoundEvent::Stopped, info.time() + dt * out.len() as f64);
+			}
 		}
 
-		let will_never_start = self.start_time.update(dt * out.len() as f64, info);
-		if will_never_start {
-			self.playback_state_manager.mark_as_stopped();
-			self.update_shared_playback_state();
+		let start_delay = match self.start_time.update(dt * out.len() as f64, info) {
+			StartTimeProgress::Waiting => {
+				out.fill(Frame::ZERO);
+				return;
+			}
+			StartTimeProgress::Started { delay } => delay,
+			StartTimeProgress::WillNeverStart => {
+				self.playback_state_manager.mark_as_stopped();
+				self.update_shared_playback_state();
+				self.event_producer.push(SoundEvent::Stopped, info.time());
+				out.fill(Frame::ZERO);
+				return;
+			}
+		};
+		if !self.started {
+			self.started = true;
+			self.event_producer
+				.push(SoundEvent::Started, info.time() + start_delay);
+			self.push_marker_events(self.current_frame, info.time() + start_delay);
 		}
-		if self.start_time != StartTime::Immediate {
-			out.fill(Frame::ZERO);
-			return;
+
+		// the decoder unsets this flag after pushing the first frame
+		// after the loop region, so once it's unset, that frame
+		// (if any) is in the ringbuffer
+		if self.skipping_to_loop_exit && !self.shared.exit_loop_now.load(Ordering::SeqCst) {
+			self.skipping_to_loop_exit = false;
+			self.skip_to_loop_exit();
+		}
+		if let Some(loop_exit_time) = &mut self.loop_exit_time {
+			match loop_exit_time.update(dt * out.len() as f64, info) {
+				StartTimeProgress::Waiting => {}
+				StartTimeProgress::Started { .. } => {
+					self.shared.exit_loop_now.store(true, Ordering::SeqCst);
+					self.skipping_to_loop_exit = true;
+					self.loop_exit_time = None;
+				}
+				StartTimeProgress::WillNeverStart => self.loop_exit_time = None,
+			}
 		}
 
 		if !self.playback_state_manager.playback_state().is_advancing() {
@@ -254,8 +515,40 @@ impl Sound for StreamingSound {
 			return;
 		}
 
+		// if playback started partway through this batch, the frames
+		// before that point stay silent
 		let num_frames = out.len();
-		for (i, frame) in out.iter_mut().enumerate() {
+		let start_frame = num_frames_before_start(
+			start_delay.max(self.playback_state_manager.start_delay()),
+			dt,
+			num_frames,
+		);
+		out[..start_frame].fill(Frame::ZERO);
+
+		// play back audio
+		for (i, frame) in out.iter_mut().enumerate().skip(start_frame) {
+			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
+			let playback_rate = self.playback_rate.interpolated_value(time_in_chunk);
+			let tempo = self.tempo.interpolated_value(time_in_chunk);
+			let time = info.time() + i as f64 * dt;
+			*frame = if self.time_stretcher.is_active() || tempo != 1.0 {
+				self.next_time_stretched_frame(playback_rate, tempo, dt, time)
+			} else {
+				let out = self.next_resampled_frame(playback_rate, dt, time);
+				if self.source_finished() {
+					self.mark_as_finished(time);
+				}
+				out
+			};
+		}
+
+		// apply effects
+		for effect in &mut self.effects {
+			effect.process(&mut out[start_frame..], dt, info);
+		}
+
+		// apply volume, fades, and panning
+		for (i, frame) in out.iter_mut().enumerate().skip(start_frame) {
 			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
 			let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
 			let fade_volume = self
@@ -263,26 +556,26 @@ impl Sound for StreamingSound {
 				.interpolated_fade_volume(time_in_chunk)
 				.as_amplitude();
 			let panning = self.panning.interpolated_value(time_in_chunk);
-			let playback_rate = self.playback_rate.interpolated_value(time_in_chunk);
-			let next_frames = self.next_frames();
-			let interpolated_out = interpolate_frame(
-				next_frames[0],
-				next_frames[1],
-				next_frames[2],
-				next_frames[3],
-				self.fractional_position as f32,
-			);
-			self.fractional_position += self.sample_rate as f64 * playback_rate.0.max(0.0) * dt;
-			while self.fractional_position >= 1.0 {
-				self.fractional_position -= 1.0;
-				self.frame_consumer.pop().ok();
-			}
-			if self.shared.reached_end() && self.frame_consumer.is_empty() {
-				self.playback_state_manager.mark_as_stopped();
-				self.update_shared_playback_state();
-			}
-			*frame = (interpolated_out * fade_volume * volume).panned(panning);
+			*frame = (*frame * fade_volume * volume).panned(panning);
+		}
+	}
+
+	fn remaining_duration(&self) -> Option<f64> {
+		let num_decoded_frames_remaining =
+			match self.shared.num_frames_remaining.load(Ordering::SeqCst) {
+				u64::MAX => return None,
+				num_frames_remaining => num_frames_remaining as usize,
+			};
+		// the first frame in the ringbuffer is the previous frame
+		let num_frames_remaining =
+			num_decoded_frames_remaining + self.frame_consumer.slots().saturating_sub(1);
+		let speed = self.sample_rate as f64
+			* self.playback_rate.value().0.max(0.0)
+			* self.tempo.value().clamp(0.0, 4.0);
+		if speed == 0.0 {
+			return None;
 		}
+		Some((num_frames_remaining as f64 - self.fractional_position).max(0.0) / speed)
 	}
 
 	fn finished(&self) -> bool {
@@ -294,4 +587,10 @@ impl Sound for StreamingSound {
 pub(crate) struct TimestampedFrame {
 	frame: Frame,
 	index: usize,
+	/// Whether this is the first frame after jumping back to
+	/// the start of the loop region.
+	looped: bool,
+	/// Whether this is the first frame after jumping out of
+	/// the loop region early.
+	exited_loop: bool,
 }
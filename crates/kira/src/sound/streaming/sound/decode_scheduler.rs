@@ -1,13 +1,12 @@
-use std::{
-	sync::{Arc, atomic::Ordering},
-	time::Duration,
-};
+use std::sync::{Arc, atomic::Ordering};
 
 use crate::{
 	frame::Frame,
 	sound::{
 		PlaybackState,
-		streaming::{DecodeSchedulerCommandReaders, StreamingSoundSettings, decoder::Decoder},
+		streaming::{
+			DecodeJob, DecodeSchedulerCommandReaders, StreamingSoundSettings, decoder::Decoder,
+		},
 		transport::Transport,
 	},
 };
@@ -16,7 +15,6 @@ use rtrb::{Consumer, Producer, RingBuffer};
 use super::{Shared, TimestampedFrame};
 
 const BUFFER_SIZE: usize = 16_384;
-const DECODER_THREAD_SLEEP_DURATION: Duration = Duration::from_millis(1);
 
 pub(crate) enum NextStep {
 	Continue,
@@ -36,13 +34,28 @@ pub(crate) struct DecodeScheduler<Error: Send + 'static> {
 	frame_producer: Producer<TimestampedFrame>,
 	error_producer: Producer<Error>,
 	shared: Arc<Shared>,
+	/// Whether the next frame will be the first frame after
+	/// jumping back to the start of the loop region.
+	looped: bool,
+	/// How many frames have been pushed since the last frame with
+	/// `looped` set, or [`None`] if the decoder hasn't looped yet.
+	frames_since_loop: Option<usize>,
+	/// Whether the next frame will be the first frame after
+	/// jumping out of the loop region early.
+	exited_loop: bool,
+	/// The audio before the start of the loop region that the end
+	/// of the loop region crossfades into, so it doesn't have to be
+	/// decoded again for every frame of the crossfade.
+	loop_crossfade_frames: Vec<Frame>,
+	/// The index of the first frame in `loop_crossfade_frames`.
+	loop_crossfade_frames_start: Option<usize>,
 }
 
 impl<Error: Send + 'static> DecodeScheduler<Error> {
 	pub(crate) fn new(
 		mut decoder: Box<dyn Decoder<Error = Error>>,
 		slice: Option<(usize, usize)>,
-		settings: StreamingSoundSettings,
+		settings: &StreamingSoundSettings,
 		shared: Arc<Shared>,
 		command_readers: DecodeSchedulerCommandReaders,
 		error_producer: Producer<Error>,
@@ -54,6 +67,8 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 			.push(TimestampedFrame {
 				frame: Frame::ZERO,
 				index: 0,
+				looped: false,
+				exited_loop: false,
 			})
 			.expect("The frame producer shouldn't be full because we just created it");
 		let sample_rate = decoder.sample_rate();
@@ -73,6 +88,8 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 				start_position,
 				settings.loop_region,
 				false,
+				settings.loop_crossfade,
+				settings.loop_count,
 				sample_rate,
 				num_frames,
 			),
@@ -82,6 +99,11 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 			frame_producer,
 			error_producer,
 			shared,
+			looped: false,
+			frames_since_loop: None,
+			exited_loop: false,
+			loop_crossfade_frames: vec![],
+			loop_crossfade_frames_start: None,
 		};
 		Ok((scheduler, frame_consumer))
 	}
@@ -91,24 +113,6 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 		self.transport.position
 	}
 
-	pub fn start(mut self) {
-		std::thread::spawn(move || {
-			loop {
-				match self.run() {
-					Ok(result) => match result {
-						NextStep::Continue => {}
-						NextStep::Wait => std::thread::sleep(DECODER_THREAD_SLEEP_DURATION),
-						NextStep::End => break,
-					},
-					Err(error) => {
-						self.error_producer.push(error).ok();
-						self.shared.encountered_error.store(true, Ordering::SeqCst);
-					}
-				}
-			}
-		});
-	}
-
 	pub fn run(&mut self) -> Result<NextStep, Error> {
 		// if the sound was manually stopped, end the thread
 		if self.shared.state() == PlaybackState::Stopped {
@@ -122,6 +126,7 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 		if let Some(loop_region) = self.command_readers.set_loop_region.read() {
 			self.transport
 				.set_loop_region(loop_region, self.sample_rate, self.num_frames);
+			self.loop_crossfade_frames_start = None;
 		}
 		if let Some(amount) = self.command_readers.seek_by.read() {
 			self.seek_by(amount)?;
@@ -129,14 +134,56 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 		if let Some(position) = self.command_readers.seek_to.read() {
 			self.seek_to(position)?;
 		}
-		let frame = self.frame_at_index(self.transport.position)?;
+		if self.shared.exit_loop.swap(false, Ordering::SeqCst) {
+			self.transport.exit_loop();
+			// if the decoder already jumped back to the start of the loop
+			// region and the sound hasn't played that frame yet, jump out
+			// of the loop region instead. the sound will skip ahead to here
+			// when it reaches the end of the loop region.
+			let num_buffered_frames = BUFFER_SIZE - self.frame_producer.slots();
+			if self
+				.frames_since_loop
+				.is_some_and(|frames_since_loop| frames_since_loop < num_buffered_frames)
+			{
+				self.exited_loop |= self.transport.exit_loop_now(false, self.num_frames);
+			}
+		}
+		let exit_loop_now = self.shared.exit_loop_now.load(Ordering::SeqCst);
+		if exit_loop_now {
+			self.exited_loop |= self.transport.exit_loop_now(false, self.num_frames);
+		}
+		let mut frame = self.frame_at_index(self.transport.position)?;
+		if let Some(crossfade) = self.transport.loop_crossfade(false, self.num_frames) {
+			let other = self.loop_crossfade_frame(crossfade.index)?;
+			frame = crossfade.mix(frame, other);
+		}
 		self.frame_producer
 			.push(TimestampedFrame {
 				frame,
 				index: self.transport.position,
+				looped: self.looped,
+				exited_loop: self.exited_loop,
 			})
 			.expect("could not push frame to frame producer");
+		self.frames_since_loop = if self.looped {
+			Some(1)
+		} else {
+			self.frames_since_loop
+				.map(|frames_since_loop| frames_since_loop + 1)
+		};
+		self.exited_loop = false;
+		if exit_loop_now {
+			self.shared.exit_loop_now.store(false, Ordering::SeqCst);
+		}
+		let previous_position = self.transport.position;
 		self.transport.increment_position(self.num_frames);
+		self.looped = self.transport.position <= previous_position;
+		self.shared.num_frames_remaining.store(
+			self.transport
+				.num_frames_remaining(false, self.num_frames)
+				.map_or(u64::MAX, |num_frames_remaining| num_frames_remaining as u64),
+			Ordering::SeqCst,
+		);
 		if !self.transport.playing {
 			self.shared.reached_end.store(true, Ordering::SeqCst);
 			return Ok(NextStep::End);
@@ -182,6 +229,42 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 		}
 	}
 
+	/// Returns a frame that the end of the loop region crossfades into,
+	/// decoding the rest of the frames used by the crossfade if they
+	/// aren't loaded yet.
+	///
+	/// Depending on how much audio there is before the loop region,
+	/// these frames may be before the start of the loop region or
+	/// inside it.
+	fn loop_crossfade_frame(&mut self, index: usize) -> Result<Frame, Error> {
+		if let Some(start) = self.loop_crossfade_frames_start {
+			if let Some(frame) = index
+				.checked_sub(start)
+				.and_then(|index| self.loop_crossfade_frames.get(index))
+			{
+				return Ok(*frame);
+			}
+		}
+		let (_, loop_end) = self
+			.transport
+			.loop_region
+			.expect("a loop crossfade requires a loop region");
+		// each remaining frame of the crossfade uses the next frame
+		let num_frames = loop_end - self.transport.position;
+		// decode the frames without losing our place in the audio
+		let decoded_chunk = self.decoded_chunk.take();
+		let decoder_current_frame_index = self.decoder_current_frame_index;
+		self.loop_crossfade_frames.clear();
+		for i in index..index + num_frames {
+			let frame = self.frame_at_index(i)?;
+			self.loop_crossfade_frames.push(frame);
+		}
+		self.loop_crossfade_frames_start = Some(index);
+		self.decoder_current_frame_index = self.decoder.seek(decoder_current_frame_index)?;
+		self.decoded_chunk = decoded_chunk;
+		Ok(self.loop_crossfade_frames[0])
+	}
+
 	fn seek_to(&mut self, position: f64) -> Result<(), Error> {
 		let index = (position * self.sample_rate as f64).round() as usize;
 		self.seek_to_index(index)?;
@@ -201,6 +284,32 @@ impl<Error: Send + 'static> DecodeScheduler<Error> {
 	}
 }
 
+impl<Error: Send + 'static> DecodeJob for DecodeScheduler<Error> {
+	fn step(&mut self) -> NextStep {
+		match self.run() {
+			Ok(next_step) => next_step,
+			Err(error) => {
+				self.error_producer.push(error).ok();
+				self.shared.encountered_error.store(true, Ordering::SeqCst);
+				// the sound will stop once it sees the error, and then
+				// the decode pool will drop this scheduler
+				NextStep::Wait
+			}
+		}
+	}
+
+	fn num_free_slots(&self) -> usize {
+		self.frame_producer.slots()
+	}
+
+	fn finished(&self) -> bool {
+		// if the sound was dropped without being stopped (for example,
+		// because the mixer track it was playing on was removed), its
+		// buffer will stay full forever, so there's no use in polling it
+		self.shared.state() == PlaybackState::Stopped || self.frame_producer.is_abandoned()
+	}
+}
+
 struct DecodedChunk {
 	pub start_index: usize,
 	pub frames: Vec<Frame>,
@@ -1,12 +1,16 @@
-use std::time::Duration;
+use std::{
+	f32::consts::{FRAC_1_SQRT_2, TAU},
+	time::Duration,
+};
 
 use crate::{
 	Decibels, Panning, StartTime, Tween,
 	clock::ClockTime,
+	effect::volume_control::{VolumeControlBuilder, VolumeControlHandle},
 	frame::Frame,
 	info::MockInfoBuilder,
 	sound::{
-		PlaybackState, Sound,
+		PlaybackPosition, PlaybackState, Sound, SoundEvent,
 		streaming::{StreamingSoundData, StreamingSoundSettings, decoder::mock::MockDecoder},
 	},
 	test_helpers::expect_frame_soon,
@@ -541,7 +545,7 @@ fn resume_at() {
 			fraction: 0.0,
 		}),
 		Tween {
-			duration: Duration::from_secs(1),
+			duration: Duration::from_secs(2),
 			..Default::default()
 		},
 	);
@@ -627,6 +631,64 @@ fn loops_forward() {
 	);
 }
 
+/// Tests that a `StreamingSound` reports when playback reaches
+/// each of its markers.
+#[test]
+fn reports_markers() {
+	let data = StreamingSoundData {
+		decoder: Box::new(MockDecoder::new(
+			(0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		)),
+		settings: StreamingSoundSettings::new()
+			.with_marker("before slice", 1.0)
+			.with_marker("start", 2.0)
+			.with_marker("middle", PlaybackPosition::Samples(5)),
+		slice: Some((2, 8)),
+	};
+	let (mut sound, mut handle, mut scheduler) = data.split().unwrap();
+	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+	assert_eq!(handle.markers().len(), 3);
+
+	let mut out = vec![Frame::ZERO; 10];
+	sound.process(&mut out, 1.0, &MockInfoBuilder::new().build());
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.filter(|event| matches!(event.kind, SoundEvent::Marker(_)))
+		.map(|event| (event.kind, event.time))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		events,
+		[
+			(SoundEvent::Marker(1), Duration::ZERO),
+			(SoundEvent::Marker(2), Duration::from_secs(3)),
+		]
+	);
+}
+
+/// Tests that a time stretched `StreamingSound` reports markers
+/// when the time stretcher outputs them.
+#[test]
+fn reports_markers_when_time_stretched() {
+	let data = StreamingSoundData {
+		decoder: Box::new(MockDecoder::new(vec![Frame::from_mono(1.0); 4000])),
+		settings: StreamingSoundSettings::new()
+			.tempo(2.0)
+			.with_marker("marker", PlaybackPosition::Samples(2000)),
+		slice: None,
+	};
+	let (mut sound, mut handle, mut scheduler) = data.split().unwrap();
+	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+
+	let mut out = vec![Frame::ZERO; 1500];
+	sound.process(&mut out, 1.0, &MockInfoBuilder::new().build());
+	let events = std::iter::from_fn(|| handle.pop_event())
+		.filter(|event| matches!(event.kind, SoundEvent::Marker(_)))
+		.map(|event| event.time.as_secs_f64())
+		.collect::<Vec<_>>();
+	// 2000 frames into the sound is 1000 frames into the output
+	assert_eq!(events.len(), 1);
+	assert!((events[0] - 1000.0).abs() < 2.0, "{}", events[0]);
+}
+
 /// Tests that the volume of a `StreamingSound` can be adjusted.
 #[test]
 #[allow(clippy::float_cmp)]
@@ -787,6 +849,35 @@ fn set_playback_rate() {
 	);
 }
 
+/// Tests that a `StreamingSound` can change its speed without
+/// changing its pitch.
+#[test]
+fn tempo() {
+	let data = StreamingSoundData {
+		decoder: Box::new(MockDecoder::new(
+			(0..10_000)
+				.map(|i| Frame::from_mono((i as f32 / 40.0 * TAU).sin()))
+				.collect(),
+		)),
+		settings: StreamingSoundSettings::new().tempo(0.5),
+		slice: None,
+	};
+	let (mut sound, handle, mut scheduler) = data.split().unwrap();
+	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+	let mut frames = vec![Frame::ZERO; 6000];
+	sound.process(&mut frames, 1.0, &MockInfoBuilder::new().build());
+	sound.on_start_processing();
+
+	// 6000 frames of audio at half speed covers 3000 frames of the sound
+	assert!((handle.position() - 3000.0).abs() < 200.0);
+	// but the sine wave still has the same frequency
+	let zero_crossings = frames
+		.windows(2)
+		.filter(|frames| (frames[0].left < 0.0) != (frames[1].left < 0.0))
+		.count();
+	assert!((zero_crossings as f64 - 300.0).abs() < 10.0);
+}
+
 /// Tests that a `StreamingSound` outputs interpolated samples when
 /// its playback position is between samples.
 #[test]
@@ -963,3 +1054,232 @@ fn seek_by() {
 	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
 	expect_frame_soon(Frame::from_mono(20.0).panned(Panning::CENTER), &mut sound);
 }
+
+/// Tests that a `StreamingSound` applies its effects and that they
+/// can be controlled from the sound's handle.
+#[test]
+fn effects() {
+	let data = StreamingSoundData {
+		decoder: Box::new(MockDecoder::new(vec![Frame::from_mono(1.0); 100])),
+		settings: StreamingSoundSettings::new(),
+		slice: None,
+	}
+	.with_effect(VolumeControlBuilder::new(-6.0));
+	let (mut sound, mut handle, mut scheduler) = data.split().unwrap();
+	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+	sound.init(1, 128);
+
+	assert_eq!(
+		sound.process_one(1.0, &MockInfoBuilder::new().build()),
+		Frame::from_mono(Decibels(-6.0).as_amplitude()).panned(Panning::CENTER)
+	);
+	handle
+		.effect_mut::<VolumeControlHandle>(0)
+		.unwrap()
+		.set_volume(-12.0, Tween::default());
+	sound.on_start_processing();
+	expect_frame_soon(
+		Frame::from_mono(Decibels(-12.0).as_amplitude()).panned(Panning::CENTER),
+		&mut sound,
+	);
+}
+
+/// Tests that the end of the loop region of a `StreamingSound` crossfades
+/// into the audio before the start of the loop region.
+#[test]
+fn loop_crossfade() {
+	let data = StreamingSoundData {
+		decoder: Box::new(MockDecoder::new(
+			(0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		)),
+		settings: StreamingSoundSettings::new()
+			.loop_region(4.0..)
+			.loop_crossfade(Duration::from_secs(2)),
+		slice: None,
+	};
+	let (mut sound, _, mut scheduler) = data.split().unwrap();
+	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+
+	let expected = [
+		0.0,
+		1.0,
+		2.0,
+		3.0,
+		4.0,
+		5.0,
+		6.0,
+		7.0,
+		// frame 8 is mixed equally with frame 2
+		(8.0 + 2.0) * FRAC_1_SQRT_2,
+		// frame 9 has fully faded into frame 3
+		3.0,
+		// which continues into the start of the loop region
+		4.0,
+		5.0,
+		6.0,
+		7.0,
+		(8.0 + 2.0) * FRAC_1_SQRT_2,
+		3.0,
+	];
+	for value in expected {
+		let frame = sound.process_one(1.0, &MockInfoBuilder::new().build());
+		let expected = Frame::from_mono(value).panned(Panning::CENTER);
+		assert!((frame.left - expected.left).abs() < 0.0001);
+		assert!((frame.right - expected.right).abs() < 0.0001);
+	}
+}
+
+/// Tests that when the whole `StreamingSound` is looped, the end of the
+/// sound crossfades into the start of the sound.
+#[test]
+fn loop_crossfade_without_audio_before_loop_region() {
+	let data = StreamingSoundData {
+		decoder: Box::new(MockDecoder::new(
+			(0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		)),
+		settings: StreamingSoundSettings::new()
+			.loop_region(..)
+			.loop_crossfade(Duration::from_secs(2)),
+		slice: None,
+	};
+	let (mut sound, _, mut scheduler) = data.split().unwrap();
+	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+
+	let expected = [
+		0.0,
+		1.0,
+		2.0,
+		3.0,
+		4.0,
+		5.0,
+		6.0,
+		7.0,
+		// frame 8 is mixed equally with frame 0
+		(8.0 + 0.0) * FRAC_1_SQRT_2,
+		// frame 9 has fully faded into frame 1
+		1.0,
+		// which continues into the rest of the loop region
+		2.0,
+		3.0,
+		4.0,
+		5.0,
+		6.0,
+		7.0,
+		(8.0 + 0.0) * FRAC_1_SQRT_2,
+		1.0,
+	];
+	for value in expected {
+		let frame = sound.process_one(1.0, &MockInfoBuilder::new().build());
+		let expected = Frame::from_mono(value).panned(Panning::CENTER);
+		assert!((frame.left - expected.left).abs() < 0.0001);
+		assert!((frame.right - expected.right).abs() < 0.0001);
+		while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+	}
+}
+
+/// Tests that a `StreamingSound` stops looping after playing the
+/// loop region the given number of times.
+#[test]
+fn loop_count() {
+	let data = StreamingSoundData {
+		decoder: Box::new(MockDecoder::new(
+			(0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		)),
+		settings: StreamingSoundSettings::new()
+			.loop_region(3.0..6.0)
+			.loop_count(2),
+		slice: None,
+	};
+	let (mut sound, _, mut scheduler) = data.split().unwrap();
+	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+
+	for i in [0, 1, 2, 3, 4, 5, 3, 4, 5, 6, 7, 8, 9] {
+		assert_eq!(
+			sound.process_one(1.0, &MockInfoBuilder::new().build()),
+			Frame::from_mono(i as f32).panned(Panning::CENTER)
+		);
+	}
+}
+
+/// Tests that a `StreamingSound` finishes the current iteration of
+/// the loop region and then plays the rest of the sound after
+/// exiting the loop, even if the decoder has already looped again.
+#[test]
+fn exit_loop() {
+	let data = StreamingSoundData {
+		decoder: Box::new(MockDecoder::new(
+			(0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		)),
+		settings: StreamingSoundSettings::new().loop_region(3.0..6.0),
+		slice: None,
+	};
+	let (mut sound, mut handle, mut scheduler) = data.split().unwrap();
+	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+
+	for i in [0, 1, 2, 3, 4, 5, 3] {
+		assert_eq!(
+			sound.process_one(1.0, &MockInfoBuilder::new().build()),
+			Frame::from_mono(i as f32).panned(Panning::CENTER)
+		);
+	}
+	handle.exit_loop();
+	sound.on_start_processing();
+	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+	for i in 4..10 {
+		assert_eq!(
+			sound.process_one(1.0, &MockInfoBuilder::new().build()),
+			Frame::from_mono(i as f32).panned(Panning::CENTER)
+		);
+	}
+}
+
+/// Tests that a `StreamingSound` jumps out of the loop region
+/// at the time given to `exit_loop_at`.
+#[test]
+fn exit_loop_at() {
+	let data = StreamingSoundData {
+		decoder: Box::new(MockDecoder::new(
+			(0..10).map(|i| Frame::from_mono(i as f32)).collect(),
+		)),
+		settings: StreamingSoundSettings::new().loop_region(3.0..6.0),
+		slice: None,
+	};
+	let (mut sound, mut handle, mut scheduler) = data.split().unwrap();
+	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+	let mut info_builder = MockInfoBuilder::new();
+	let clock_id = info_builder.add_clock(true, 0, 0.0);
+	let info = info_builder.build();
+	handle.exit_loop_at(ClockTime {
+		clock: clock_id,
+		ticks: 1,
+		fraction: 0.0,
+	});
+	sound.on_start_processing();
+
+	// the sound should keep looping until the clock reaches tick 1
+	for i in [0, 1, 2, 3, 4, 5, 3] {
+		assert_eq!(
+			sound.process_one(1.0, &info),
+			Frame::from_mono(i as f32).panned(Panning::CENTER)
+		);
+	}
+
+	let info = {
+		let mut builder = MockInfoBuilder::new();
+		builder.add_clock(true, 1, 0.0);
+		builder.build()
+	};
+	// the frame that was already decoded plays while the
+	// decoder jumps out of the loop region
+	assert_eq!(
+		sound.process_one(1.0, &info),
+		Frame::from_mono(4.0).panned(Panning::CENTER)
+	);
+	while matches!(scheduler.run().unwrap(), NextStep::Continue) {}
+	for i in 6..10 {
+		assert_eq!(
+			sound.process_one(1.0, &info),
+			Frame::from_mono(i as f32).panned(Panning::CENTER)
+		);
+	}
+}
@@ -1,50 +1,119 @@
+#[cfg(test)]
+mod test;
+
 use symphonia::core::audio::{
-	Audio, AudioBuffer, GenericAudioBufferRef,
+	Audio, AudioBuffer, Channels, GenericAudioBufferRef, Position,
 	conv::{FromSample, IntoSample},
 	sample::Sample,
 };
 
 use crate::frame::Frame;
 
-use super::FromFileError;
+use super::{
+	DownmixMatrix, FromFileError,
+	downmix::{C, L, LFE, R, SURROUND_L, SURROUND_R},
+};
 
 pub fn load_frames_from_buffer_ref(
 	buffer: &GenericAudioBufferRef,
+	downmix: Option<&DownmixMatrix>,
 ) -> Result<Vec<Frame>, FromFileError> {
 	match buffer {
-		GenericAudioBufferRef::U8(buffer) => load_frames_from_buffer(buffer),
-		GenericAudioBufferRef::U16(buffer) => load_frames_from_buffer(buffer),
-		GenericAudioBufferRef::U24(buffer) => load_frames_from_buffer(buffer),
-		GenericAudioBufferRef::U32(buffer) => load_frames_from_buffer(buffer),
-		GenericAudioBufferRef::S8(buffer) => load_frames_from_buffer(buffer),
-		GenericAudioBufferRef::S16(buffer) => load_frames_from_buffer(buffer),
-		GenericAudioBufferRef::S24(buffer) => load_frames_from_buffer(buffer),
-		GenericAudioBufferRef::S32(buffer) => load_frames_from_buffer(buffer),
-		GenericAudioBufferRef::F32(buffer) => load_frames_from_buffer(buffer),
-		GenericAudioBufferRef::F64(buffer) => load_frames_from_buffer(buffer),
+		GenericAudioBufferRef::U8(buffer) => load_frames_from_buffer(buffer, downmix),
+		GenericAudioBufferRef::U16(buffer) => load_frames_from_buffer(buffer, downmix),
+		GenericAudioBufferRef::U24(buffer) => load_frames_from_buffer(buffer, downmix),
+		GenericAudioBufferRef::U32(buffer) => load_frames_from_buffer(buffer, downmix),
+		GenericAudioBufferRef::S8(buffer) => load_frames_from_buffer(buffer, downmix),
+		GenericAudioBufferRef::S16(buffer) => load_frames_from_buffer(buffer, downmix),
+		GenericAudioBufferRef::S24(buffer) => load_frames_from_buffer(buffer, downmix),
+		GenericAudioBufferRef::S32(buffer) => load_frames_from_buffer(buffer, downmix),
+		GenericAudioBufferRef::F32(buffer) => load_frames_from_buffer(buffer, downmix),
+		GenericAudioBufferRef::F64(buffer) => load_frames_from_buffer(buffer, downmix),
 	}
 }
 
+/// Converts a buffer of decoded audio into stereo [`Frame`]s.
+///
+/// Audio with more than two channels is mixed down to stereo
+/// using the given [`DownmixMatrix`], or the standard downmix for
+/// its channel layout if no matrix is given.
 pub fn load_frames_from_buffer<S: Sample>(
 	buffer: &AudioBuffer<S>,
+	downmix: Option<&DownmixMatrix>,
 ) -> Result<Vec<Frame>, FromFileError>
 where
 	f32: FromSample<S>,
 {
-	match buffer.num_planes() {
-		1 => Ok(buffer
+	match (buffer.num_planes(), downmix) {
+		(1, None) => Ok(buffer
 			.plane(0)
 			.unwrap()
 			.iter()
 			.map(|sample| Frame::from_mono((*sample).into_sample()))
 			.collect()),
-		2 => Ok(buffer
+		(2, None) => Ok(buffer
 			.plane(0)
 			.unwrap()
 			.iter()
 			.zip(buffer.plane(1).unwrap().iter())
 			.map(|(left, right)| Frame::new((*left).into_sample(), (*right).into_sample()))
 			.collect()),
-		_ => Err(FromFileError::UnsupportedChannelConfiguration),
+		(num_channels, downmix) => {
+			let standard_downmix;
+			let downmix = match downmix {
+				Some(downmix) => downmix,
+				None => {
+					standard_downmix = default_downmix(buffer.spec().channels(), num_channels)
+						.ok_or(FromFileError::UnsupportedChannelConfiguration)?;
+					&standard_downmix
+				}
+			};
+			if num_channels == 0 || downmix.num_channels() != num_channels {
+				return Err(FromFileError::UnsupportedChannelConfiguration);
+			}
+			let planes = (0..num_channels)
+				.map(|channel| buffer.plane(channel).unwrap())
+				.collect::<Vec<_>>();
+			Ok((0..planes[0].len())
+				.map(|i| downmix.apply(planes.iter().map(|plane| plane[i].into_sample())))
+				.collect())
+		}
 	}
 }
+
+/**
+Returns the standard downmix for audio with the given channels.
+
+Audio that says which speaker each channel is for is mixed according
+to those speakers, so for example 3.1 audio (L, R, C, LFE) isn't
+mixed like quad audio (L, R, BL, BR). Otherwise, the
+[ITU downmix](DownmixMatrix::itu) for the number of channels is used.
+*/
+#[must_use]
+fn default_downmix(channels: &Channels, num_channels: usize) -> Option<DownmixMatrix> {
+	let Channels::Positioned(positions) = channels else {
+		return DownmixMatrix::itu(num_channels);
+	};
+	// the planes of the buffer are in the same order as the position flags
+	let coefficients = positions
+		.iter()
+		.map(|position| {
+			if position.intersects(Position::FRONT_LEFT | Position::FRONT_LEFT_CENTER) {
+				L
+			} else if position.intersects(Position::FRONT_RIGHT | Position::FRONT_RIGHT_CENTER) {
+				R
+			} else if position.intersects(Position::REAR_LEFT | Position::SIDE_LEFT) {
+				SURROUND_L
+			} else if position.intersects(Position::REAR_RIGHT | Position::SIDE_RIGHT) {
+				SURROUND_R
+			} else if position.intersects(Position::LFE1) {
+				LFE
+			} else {
+				// the front center, rear center, and any speakers that
+				// aren't on either side are split between both channels
+				C
+			}
+		})
+		.collect::<Vec<_>>();
+	Some(DownmixMatrix::new(coefficients))
+}
@@ -0,0 +1,83 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use symphonia::core::audio::{AudioBuffer, AudioMut, AudioSpec, Channels, Position};
+
+use crate::{frame::Frame, sound::DownmixMatrix};
+
+use super::load_frames_from_buffer;
+
+/// Creates a buffer with one frame that has the given sample
+/// in each channel.
+fn buffer(channels: Channels, samples: &[f32]) -> AudioBuffer<f32> {
+	let mut buffer = AudioBuffer::new(AudioSpec::new(48_000, channels), 1);
+	buffer.render_silence(Some(1));
+	for (channel, sample) in samples.iter().enumerate() {
+		buffer.plane_mut(channel).unwrap()[0] = *sample;
+	}
+	buffer
+}
+
+/// Tests that audio with four channels is mixed down based on
+/// which speakers the channels are for.
+#[test]
+fn downmixes_by_channel_layout() {
+	// 3.1: the third channel is the front center, and the
+	// low frequency channel is discarded
+	let surround_3_1 = buffer(
+		Channels::Positioned(
+			Position::FRONT_LEFT | Position::FRONT_RIGHT | Position::FRONT_CENTER | Position::LFE1,
+		),
+		&[0.0, 0.0, 1.0, 1.0],
+	);
+	assert_eq!(
+		load_frames_from_buffer(&surround_3_1, None).unwrap(),
+		[Frame::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)]
+	);
+
+	// quad: the third channel is the back left
+	let quad = buffer(
+		Channels::Positioned(
+			Position::FRONT_LEFT
+				| Position::FRONT_RIGHT
+				| Position::REAR_LEFT
+				| Position::REAR_RIGHT,
+		),
+		&[0.0, 0.0, 1.0, 0.0],
+	);
+	assert_eq!(
+		load_frames_from_buffer(&quad, None).unwrap(),
+		[Frame::new(FRAC_1_SQRT_2, 0.0)]
+	);
+}
+
+/// Tests that audio without speaker positions is mixed down using
+/// the ITU downmix for its number of channels.
+#[test]
+fn downmixes_discrete_channels_by_count() {
+	let discrete = buffer(Channels::Discrete(6), &[1.0, 0.5, 0.0, 1.0, 0.0, 0.0]);
+	assert_eq!(
+		load_frames_from_buffer(&discrete, None).unwrap(),
+		[DownmixMatrix::itu(6)
+			.unwrap()
+			.apply([1.0, 0.5, 0.0, 1.0, 0.0, 0.0])]
+	);
+}
+
+/// Tests that a custom downmix is used instead of the standard one.
+#[test]
+fn uses_custom_downmix() {
+	let surround_3_1 = buffer(
+		Channels::Positioned(
+			Position::FRONT_LEFT | Position::FRONT_RIGHT | Position::FRONT_CENTER | Position::LFE1,
+		),
+		&[0.25, 0.5, 1.0, 1.0],
+	);
+	let downmix = DownmixMatrix::new(vec![[1.0, 0.0], [0.0, 1.0], [0.0, 0.0], [1.0, 1.0]]);
+	assert_eq!(
+		load_frames_from_buffer(&surround_3_1, Some(&downmix)).unwrap(),
+		[Frame::new(1.25, 1.5)]
+	);
+	// the downmix has to match the number of channels
+	let downmix = DownmixMatrix::itu(6).unwrap();
+	assert!(load_frames_from_buffer(&surround_3_1, Some(&downmix)).is_err());
+}
@@ -0,0 +1,293 @@
+#[cfg(test)]
+mod test;
+
+use std::f32::consts::PI;
+
+use crate::frame::Frame;
+
+/// The number of frames in each segment of audio that's overlapped
+/// with its neighbors.
+const SEGMENT_LENGTH: usize = 1024;
+/// The number of frames between the starts of consecutive segments
+/// in the output.
+const HOP_LENGTH: usize = SEGMENT_LENGTH / 2;
+/// How far (in frames) a segment can be moved from its ideal
+/// position to line up better with the previous segment.
+const TOLERANCE: usize = 128;
+/// How far apart the candidate positions are in the first,
+/// rough pass of the search for the best segment position.
+const COARSE_SEARCH_STEP: usize = 4;
+/// The fastest tempo supported. This limits how much audio
+/// needs to be buffered.
+const MAX_TEMPO: f64 = 4.0;
+/// The maximum number of input frames that are ever buffered at once.
+const INPUT_CAPACITY: usize =
+	(MAX_TEMPO as usize + 1) * HOP_LENGTH + 2 * TOLERANCE + SEGMENT_LENGTH;
+
+/**
+Changes the speed of a stream of audio without changing its pitch.
+
+This uses WSOLA (waveform similarity overlap-add): the output is made
+of overlapping segments of the input, and each segment is taken from
+wherever in the input lines up best with the end of the previous segment,
+as long as that's close to where the segment would ideally start.
+
+The time stretcher starts out inactive, and it becomes active the first
+time a frame is pushed to it. After that, it keeps processing audio,
+even at a tempo of 1, to avoid a jump in the audio when switching back to
+the normal tempo. [`TimeStretcher::reset`] makes it inactive again.
+*/
+pub(crate) struct TimeStretcher {
+	/// Frames of input audio along with the index in the source
+	/// audio they came from.
+	input: Vec<(Frame, usize)>,
+	/// How many input frames were discarded before the first
+	/// frame in `input`.
+	input_offset: usize,
+	/// The input frames that followed the previous segment, which
+	/// the current segment is crossfaded with.
+	overlap: Vec<Frame>,
+	/// The position of the input frame the current segment starts at, or
+	/// [`None`] if no segment has been started.
+	segment_start: Option<usize>,
+	/// Whether the current segment fades in over the previous one.
+	/// This is `false` for the first segment after a reset, so the
+	/// audio continues seamlessly from before the time stretcher
+	/// became active.
+	crossfade: bool,
+	/// How many frames of the current segment have been output.
+	segment_position: usize,
+	/// Where in the input the current segment should ideally have started.
+	segment_ideal_start: f64,
+	/// The tempo the current segment was started with.
+	segment_tempo: f64,
+	/// Where in the input the next segment should ideally start.
+	analysis_position: f64,
+	/// The position of the first input frame after the source audio ended.
+	end: Option<usize>,
+	/// The source index of the input frame that the most recently
+	/// output frame would have come from if there was no need to line
+	/// up segments.
+	current_index: Option<usize>,
+	/// The position of the input frame that [`TimeStretcher::current_index`]
+	/// came from.
+	output_position: Option<usize>,
+	finished: bool,
+}
+
+impl TimeStretcher {
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			input: Vec::with_capacity(INPUT_CAPACITY),
+			input_offset: 0,
+			overlap: vec![Frame::ZERO; HOP_LENGTH],
+			segment_start: None,
+			crossfade: false,
+			segment_position: 0,
+			segment_ideal_start: 0.0,
+			segment_tempo: 1.0,
+			analysis_position: 0.0,
+			end: None,
+			current_index: None,
+			output_position: None,
+			finished: false,
+		}
+	}
+
+	/// Returns `true` if any frames have been pushed to the time
+	/// stretcher since it was created or reset.
+	#[must_use]
+	pub fn is_active(&self) -> bool {
+		self.segment_start.is_some() || !self.input.is_empty()
+	}
+
+	/// Returns the index in the source audio of the most recently
+	/// output frame.
+	#[must_use]
+	pub fn current_index(&self) -> Option<usize> {
+		self.current_index
+	}
+
+	/// Returns the position of the input frame that the most recently
+	/// output frame would have come from if there was no need to line
+	/// up segments.
+	///
+	/// Input frames are numbered in the order they were pushed, starting
+	/// from `0` after the time stretcher is created or reset.
+	#[must_use]
+	pub fn output_position(&self) -> Option<usize> {
+		self.output_position
+	}
+
+	/// Returns the position the next frame pushed to the time
+	/// stretcher will have.
+	#[must_use]
+	pub fn input_position(&self) -> usize {
+		self.input_offset + self.input.len()
+	}
+
+	/// Returns `true` if every frame from before the source audio
+	/// ended has been output.
+	#[must_use]
+	pub fn finished(&self) -> bool {
+		self.finished
+	}
+
+	/// Discards all buffered audio and makes the time stretcher inactive.
+	pub fn reset(&mut self) {
+		self.input.clear();
+		self.input_offset = 0;
+		self.segment_start = None;
+		self.crossfade = false;
+		self.segment_position = 0;
+		self.segment_ideal_start = 0.0;
+		self.segment_tempo = 1.0;
+		self.analysis_position = 0.0;
+		self.end = None;
+		self.current_index = None;
+		self.output_position = None;
+		self.finished = false;
+	}
+
+	/// Returns `true` if more input frames need to be pushed before
+	/// the next frame can be output.
+	#[must_use]
+	pub fn needs_input(&self) -> bool {
+		if self.segment_start.is_some() && self.segment_position < HOP_LENGTH {
+			return false;
+		}
+		let required_end = self.analysis_position.round() as usize + TOLERANCE + SEGMENT_LENGTH;
+		self.input_offset + self.input.len() < required_end
+	}
+
+	/// Adds a frame of input audio that came from the given index
+	/// in the source audio. [`None`] means the source audio has ended.
+	pub fn push_frame(&mut self, frame: Option<Frame>, index: usize) {
+		debug_assert!(self.input.len() < INPUT_CAPACITY);
+		if frame.is_none() && self.end.is_none() {
+			self.end = Some(self.input_position());
+		}
+		self.input.push((frame.unwrap_or(Frame::ZERO), index));
+	}
+
+	/// Outputs the next frame of audio.
+	///
+	/// [`TimeStretcher::needs_input`] should return `false` before
+	/// this is called.
+	#[must_use]
+	pub fn next_frame(&mut self, tempo: f64) -> Frame {
+		if self.segment_start.is_none() || self.segment_position >= HOP_LENGTH {
+			self.start_segment(tempo);
+		}
+		let segment_start = self
+			.segment_start
+			.expect("a segment should have been started");
+		let position = segment_start + self.segment_position;
+		let frame = self.input[position - self.input_offset].0;
+		let out = if self.crossfade {
+			let fade_in = (PI * self.segment_position as f32 / SEGMENT_LENGTH as f32)
+				.sin()
+				.powi(2);
+			self.overlap[self.segment_position] * (1.0 - fade_in) + frame * fade_in
+		} else {
+			frame
+		};
+		// the frames within a segment always advance at the normal speed,
+		// so the position in the source audio is based on where the segment
+		// should have been rather than where it actually is
+		let ideal_position = (self.segment_ideal_start
+			+ self.segment_position as f64 * self.segment_tempo)
+			.round() as usize;
+		let ideal_position =
+			ideal_position.clamp(self.input_offset, self.input_offset + self.input.len() - 1);
+		self.current_index = Some(self.input[ideal_position - self.input_offset].1);
+		self.output_position = Some(ideal_position);
+		self.segment_position += 1;
+		if self.end.is_some_and(|end| position >= end) {
+			self.finished = true;
+		}
+		out
+	}
+
+	fn start_segment(&mut self, tempo: f64) {
+		let ideal_start = self.analysis_position.round() as usize;
+		let start = match self.segment_start {
+			None => ideal_start,
+			Some(previous_start) => {
+				// remember what would have come after the previous segment
+				let overlap_start = previous_start + HOP_LENGTH - self.input_offset;
+				for (overlap_frame, (frame, _)) in self
+					.overlap
+					.iter_mut()
+					.zip(&self.input[overlap_start..overlap_start + HOP_LENGTH])
+				{
+					*overlap_frame = *frame;
+				}
+				self.crossfade = true;
+				// if the ideal start is exactly where the previous segment
+				// continues, crossfading the two is seamless
+				if ideal_start == previous_start + HOP_LENGTH {
+					ideal_start
+				} else {
+					self.find_best_segment_start(ideal_start)
+				}
+			}
+		};
+		self.input.drain(..start - self.input_offset);
+		self.input_offset = start;
+		self.segment_start = Some(start);
+		self.segment_position = 0;
+		self.segment_ideal_start = self.analysis_position;
+		self.segment_tempo = tempo.clamp(0.0, MAX_TEMPO);
+		self.analysis_position += self.segment_tempo * HOP_LENGTH as f64;
+	}
+
+	/// Finds the segment start near the ideal position that's most similar
+	/// to the audio that would have followed the previous segment.
+	#[must_use]
+	fn find_best_segment_start(&self, ideal_start: usize) -> usize {
+		let min_start = ideal_start.saturating_sub(TOLERANCE).max(self.input_offset);
+		let max_start = ideal_start + TOLERANCE;
+		let mut best_start = ideal_start.max(min_start);
+		let mut best_similarity = self.similarity(best_start, 2);
+		for start in (min_start..=max_start).step_by(COARSE_SEARCH_STEP) {
+			let similarity = self.similarity(start, 2);
+			if similarity > best_similarity {
+				best_start = start;
+				best_similarity = similarity;
+			}
+		}
+		let coarse_best_start = best_start;
+		best_similarity = self.similarity(best_start, 1);
+		let refine_min = coarse_best_start
+			.saturating_sub(COARSE_SEARCH_STEP - 1)
+			.max(min_start);
+		let refine_max = (coarse_best_start + COARSE_SEARCH_STEP - 1).min(max_start);
+		for start in refine_min..=refine_max {
+			let similarity = self.similarity(start, 1);
+			if similarity > best_similarity {
+				best_start = start;
+				best_similarity = similarity;
+			}
+		}
+		best_start
+	}
+
+	/// Returns the normalized cross-correlation between the overlap
+	/// buffer and the input starting at `start`, looking at every
+	/// `step`th frame.
+	#[must_use]
+	fn similarity(&self, start: usize, step: usize) -> f32 {
+		let candidate = &self.input[start - self.input_offset..][..HOP_LENGTH];
+		let mut correlation = 0.0;
+		let mut energy = 0.0;
+		for (overlap_frame, (frame, _)) in self.overlap.iter().zip(candidate).step_by(step) {
+			let a = overlap_frame.left + overlap_frame.right;
+			let b = frame.left + frame.right;
+			correlation += a * b;
+			energy += b * b;
+		}
+		correlation / (energy + f32::EPSILON).sqrt()
+	}
+}
@@ -0,0 +1,103 @@
+use std::f32::consts::TAU;
+
+use crate::frame::Frame;
+
+use super::TimeStretcher;
+
+/// The length of one cycle of the test sine wave in frames.
+const PERIOD: usize = 40;
+
+fn sine(index: usize) -> Frame {
+	Frame::from_mono((index as f32 / PERIOD as f32 * TAU).sin())
+}
+
+/// Outputs `num_frames` frames from a time stretcher fed with a sine wave,
+/// returning the output and the number of input frames that were pushed.
+fn stretch_sine(
+	time_stretcher: &mut TimeStretcher,
+	tempo: f64,
+	num_frames: usize,
+	input_length: Option<usize>,
+) -> (Vec<Frame>, usize) {
+	let mut output = vec![];
+	let mut num_input_frames = 0;
+	for _ in 0..num_frames {
+		while time_stretcher.needs_input() {
+			let frame = match input_length {
+				Some(length) if num_input_frames >= length => None,
+				_ => Some(sine(num_input_frames)),
+			};
+			time_stretcher.push_frame(frame, num_input_frames);
+			num_input_frames += 1;
+		}
+		output.push(time_stretcher.next_frame(tempo));
+	}
+	(output, num_input_frames)
+}
+
+fn count_zero_crossings(frames: &[Frame]) -> usize {
+	frames
+		.windows(2)
+		.filter(|frames| (frames[0].left < 0.0) != (frames[1].left < 0.0))
+		.count()
+}
+
+/// Tests that a `TimeStretcher` passes audio through unchanged
+/// at a tempo of 1.
+#[test]
+fn passes_audio_through_at_normal_tempo() {
+	let mut time_stretcher = TimeStretcher::new();
+	let (output, _) = stretch_sine(&mut time_stretcher, 1.0, 10_000, None);
+	for (i, frame) in output.iter().enumerate() {
+		assert!((frame.left - sine(i).left).abs() < 0.0001);
+	}
+	assert_eq!(time_stretcher.current_index(), Some(9999));
+}
+
+/// Tests that a `TimeStretcher` changes the speed of audio
+/// without changing its pitch.
+#[test]
+fn changes_speed_without_changing_pitch() {
+	for tempo in [0.5, 2.0] {
+		let mut time_stretcher = TimeStretcher::new();
+		let (output, num_input_frames) = stretch_sine(&mut time_stretcher, tempo, 40_000, None);
+		// the input is consumed at the new tempo (plus a little
+		// that's buffered ahead)
+		let expected_input_frames = 40_000.0 * tempo;
+		assert!((num_input_frames as f64 - expected_input_frames).abs() < 2000.0);
+		// but the output has the same frequency as the input
+		let zero_crossings = count_zero_crossings(&output);
+		assert!((zero_crossings as f64 - 2000.0).abs() < 20.0);
+		// and doesn't have any big jumps between segments
+		for frames in output.windows(2) {
+			assert!((frames[1].left - frames[0].left).abs() < 0.2);
+		}
+	}
+}
+
+/// Tests that a `TimeStretcher` reports when it has output all of the
+/// audio from before the source audio ended.
+#[test]
+fn reports_finished() {
+	let mut time_stretcher = TimeStretcher::new();
+	let (output, _) = stretch_sine(&mut time_stretcher, 0.5, 3400, Some(2000));
+	assert!(!time_stretcher.finished());
+	assert!(count_zero_crossings(&output) > 160);
+	let (_, _) = stretch_sine(&mut time_stretcher, 0.5, 1000, Some(0));
+	assert!(time_stretcher.finished());
+}
+
+/// Tests that resetting a `TimeStretcher` makes it inactive
+/// and discards buffered audio.
+#[test]
+fn resets() {
+	let mut time_stretcher = TimeStretcher::new();
+	assert!(!time_stretcher.is_active());
+	let _ = stretch_sine(&mut time_stretcher, 0.5, 100, None);
+	assert!(time_stretcher.is_active());
+	time_stretcher.reset();
+	assert!(!time_stretcher.is_active());
+	assert_eq!(time_stretcher.current_index(), None);
+	let (output, _) = stretch_sine(&mut time_stretcher, 1.0, 100, None);
+	assert_eq!(output[0], sine(0));
+}
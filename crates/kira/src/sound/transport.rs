@@ -1,3 +1,7 @@
+use std::{f32::consts::FRAC_PI_2, time::Duration};
+
+use crate::frame::Frame;
+
 use super::{EndPosition, Region};
 
 #[cfg(test)]
@@ -8,6 +12,12 @@ pub struct Transport {
 	/// The start and end frames of the sound that should be looped. The upper bound
 	/// is *exclusive*.
 	pub loop_region: Option<(usize, usize)>,
+	/// How many frames at the edge of the loop region should be crossfaded
+	/// with the audio on the other side of the loop point.
+	pub loop_crossfade_length: usize,
+	/// How many more times playback should jump back to the start of
+	/// the loop region, or [`None`] if it should loop forever.
+	pub loops_remaining: Option<usize>,
 	pub playing: bool,
 }
 
@@ -17,6 +27,8 @@ impl Transport {
 		start_position: usize,
 		loop_region: Option<Region>,
 		reverse: bool,
+		loop_crossfade: Duration,
+		loop_count: Option<usize>,
 		sample_rate: u32,
 		num_frames: usize,
 	) -> Self {
@@ -35,6 +47,9 @@ impl Transport {
 				start_position
 			},
 			loop_region,
+			loop_crossfade_length: (loop_crossfade.as_secs_f64() * sample_rate as f64).round()
+				as usize,
+			loops_remaining: loop_count.map(|loop_count| loop_count.saturating_sub(1)),
 			playing: true,
 		}
 	}
@@ -55,14 +70,65 @@ impl Transport {
 		});
 	}
 
+	/**
+	Returns how the frame at the current position should be crossfaded
+	with the audio on the other side of the loop point, if at all.
+
+	When playing forward, the end of the loop region fades into the audio
+	right before the start of the loop region, so jumping back to the
+	start of the loop region continues that audio seamlessly. When playing
+	backwards, the start of the loop region fades into the audio right
+	after the end of the loop region instead.
+
+	If there isn't enough audio outside of the loop region to fade into
+	(for example, when the whole sound is looped), the end of the loop
+	region fades into the start of the loop region instead (or vice versa
+	when playing backwards), and playback resumes right after the frames
+	that were faded into.
+	*/
+	#[must_use]
+	pub fn loop_crossfade(&self, backwards: bool, num_frames: usize) -> Option<LoopCrossfade> {
+		if self.loops_remaining == Some(0) {
+			return None;
+		}
+		let (loop_start, loop_end) = self.loop_region?;
+		let loop_length = loop_end.checked_sub(loop_start)?;
+		let (length, resume_offset) =
+			self.loop_crossfade_length_and_resume_offset(backwards, num_frames);
+		if length == 0 {
+			return None;
+		}
+		if backwards {
+			let fade_end = loop_start + length;
+			if self.position < loop_start || self.position >= fade_end {
+				return None;
+			}
+			Some(LoopCrossfade {
+				index: self.position + loop_length - resume_offset,
+				progress: (fade_end - self.position) as f32 / length as f32,
+			})
+		} else {
+			let fade_start = loop_end - length;
+			if self.position < fade_start || self.position >= loop_end {
+				return None;
+			}
+			Some(LoopCrossfade {
+				index: self.position + resume_offset - loop_length,
+				progress: (self.position + 1 - fade_start) as f32 / length as f32,
+			})
+		}
+	}
+
 	pub fn increment_position(&mut self, num_frames: usize) {
 		if !self.playing {
 			return;
 		}
 		self.position += 1;
 		if let Some((loop_start, loop_end)) = self.loop_region {
-			while self.position >= loop_end {
-				self.position -= loop_end - loop_start;
+			let (_, resume_offset) =
+				self.loop_crossfade_length_and_resume_offset(false, num_frames);
+			while self.position >= loop_end && self.take_loop() {
+				self.position -= loop_end - loop_start - resume_offset;
 			}
 		}
 		if self.position >= num_frames {
@@ -70,13 +136,14 @@ impl Transport {
 		}
 	}
 
-	pub fn decrement_position(&mut self) {
+	pub fn decrement_position(&mut self, num_frames: usize) {
 		if !self.playing {
 			return;
 		}
 		if let Some((loop_start, loop_end)) = self.loop_region {
-			while self.position <= loop_start {
-				self.position += loop_end - loop_start;
+			let (_, resume_offset) = self.loop_crossfade_length_and_resume_offset(true, num_frames);
+			while self.position <= loop_start && self.take_loop() {
+				self.position += loop_end - loop_start - resume_offset;
 			}
 		}
 		if self.position == 0 {
@@ -87,7 +154,9 @@ impl Transport {
 	}
 
 	pub fn seek_to(&mut self, mut position: usize, num_frames: usize) {
-		if let Some((loop_start, loop_end)) = self.loop_region {
+		if let Some((loop_start, loop_end)) =
+			self.loop_region.filter(|_| self.loops_remaining != Some(0))
+		{
 			if position > self.position {
 				while position >= loop_end {
 					position -= loop_end - loop_start;
@@ -103,4 +172,138 @@ impl Transport {
 			self.playing = false;
 		}
 	}
+
+	/**
+	Returns how many frames are left to play, including the remaining
+	iterations of the loop region, or [`None`] if the sound loops forever.
+	*/
+	#[must_use]
+	pub fn num_frames_remaining(&self, backwards: bool, num_frames: usize) -> Option<usize> {
+		if !self.playing {
+			return Some(0);
+		}
+		let mut num_frames_remaining = if backwards {
+			self.position + 1
+		} else {
+			num_frames.saturating_sub(self.position)
+		};
+		if let Some((loop_start, loop_end)) = self.loop_region {
+			let will_loop = if backwards {
+				self.position >= loop_start
+			} else {
+				self.position < loop_end
+			};
+			if will_loop {
+				let (_, resume_offset) =
+					self.loop_crossfade_length_and_resume_offset(backwards, num_frames);
+				num_frames_remaining += self.loops_remaining?
+					* loop_end
+						.saturating_sub(loop_start)
+						.saturating_sub(resume_offset);
+			}
+		}
+		Some(num_frames_remaining)
+	}
+
+	/// Lets playback continue past the loop region the next
+	/// time it reaches the end of it.
+	pub fn exit_loop(&mut self) {
+		self.loops_remaining = Some(0);
+	}
+
+	/**
+	Stops looping and, if the current position is in the loop region,
+	jumps to the first frame after it (or before it when playing backwards).
+
+	Returns `true` if the position changed.
+	*/
+	pub fn exit_loop_now(&mut self, backwards: bool, num_frames: usize) -> bool {
+		self.exit_loop();
+		let Some((loop_start, loop_end)) = self.loop_region else {
+			return false;
+		};
+		if self.position < loop_start || self.position >= loop_end {
+			return false;
+		}
+		if backwards {
+			match loop_start.checked_sub(1) {
+				Some(position) => self.position = position,
+				None => self.playing = false,
+			}
+		} else {
+			self.position = loop_end;
+			if self.position >= num_frames {
+				self.playing = false;
+			}
+		}
+		true
+	}
+
+	/**
+	Returns how many frames at the edge of the loop region should be
+	crossfaded, and how many frames into the loop region playback
+	resumes after looping.
+
+	Playback resumes at the edge of the loop region when the crossfade
+	fades into audio outside of the loop region. Otherwise, the crossfade
+	fades into the other edge of the loop region, so those frames are
+	skipped after looping.
+	*/
+	#[must_use]
+	fn loop_crossfade_length_and_resume_offset(
+		&self,
+		backwards: bool,
+		num_frames: usize,
+	) -> (usize, usize) {
+		let Some((loop_start, loop_end)) = self.loop_region else {
+			return (0, 0);
+		};
+		let loop_length = loop_end.saturating_sub(loop_start);
+		let num_frames_outside_loop_region = if backwards {
+			num_frames.saturating_sub(loop_end)
+		} else {
+			loop_start
+		};
+		let length = self.loop_crossfade_length.min(loop_length);
+		if num_frames_outside_loop_region >= length {
+			return (length, 0);
+		}
+		// the frames faded out of and the frames faded into
+		// can't overlap
+		let length = self.loop_crossfade_length.min(loop_length / 2);
+		(length, length)
+	}
+
+	/// Uses up one of the remaining loops, returning `false`
+	/// if there are none left.
+	fn take_loop(&mut self) -> bool {
+		match &mut self.loops_remaining {
+			None => true,
+			Some(0) => false,
+			Some(loops_remaining) => {
+				*loops_remaining -= 1;
+				true
+			}
+		}
+	}
+}
+
+/// How a frame near the edge of a loop region should be crossfaded
+/// with the audio on the other side of the loop region.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoopCrossfade {
+	/// The index of the frame to fade into.
+	pub index: usize,
+	/// How far through the crossfade the current frame is, from 0.0 to 1.0.
+	pub progress: f32,
+}
+
+impl LoopCrossfade {
+	/// Mixes the frame at the current position with the frame
+	/// it's fading into using an equal-power curve.
+	#[must_use]
+	pub fn mix(self, frame: Frame, other: Frame) -> Frame {
+		let angle = self.progress * FRAC_PI_2;
+		frame * angle.cos() + other * angle.sin()
+	}
 }
@@ -1,10 +1,14 @@
-use super::Transport;
+use std::time::Duration;
+
+use super::{LoopCrossfade, Transport};
 
 #[test]
 fn stops_at_end() {
 	let mut transport = Transport {
 		position: 2,
 		loop_region: None,
+		loop_crossfade_length: 0,
+		loops_remaining: None,
 		playing: true,
 	};
 	for i in 2..4 {
@@ -21,12 +25,14 @@ fn stops_at_start_when_playing_backwards() {
 	let mut transport = Transport {
 		position: 2,
 		loop_region: None,
+		loop_crossfade_length: 0,
+		loops_remaining: None,
 		playing: true,
 	};
 	for i in (0..=2).rev() {
 		assert_eq!(transport.position, i);
 		assert!(transport.playing);
-		transport.decrement_position();
+		transport.decrement_position(10);
 	}
 	assert_eq!(transport.position, 0);
 	assert!(!transport.playing);
@@ -37,6 +43,8 @@ fn loops() {
 	let mut transport = Transport {
 		position: 0,
 		loop_region: Some((2, 5)),
+		loop_crossfade_length: 0,
+		loops_remaining: None,
 		playing: true,
 	};
 	for i in 0..5 {
@@ -56,18 +64,20 @@ fn loops_when_playing_backward() {
 	let mut transport = Transport {
 		position: 0,
 		loop_region: Some((2, 5)),
+		loop_crossfade_length: 0,
+		loops_remaining: None,
 		playing: true,
 	};
 	transport.position = 10;
 	for i in (2..=10).rev() {
 		assert_eq!(transport.position, i);
 		assert!(transport.playing);
-		transport.decrement_position();
+		transport.decrement_position(10);
 	}
 	for i in (2..5).rev() {
 		assert_eq!(transport.position, i);
 		assert!(transport.playing);
-		transport.decrement_position();
+		transport.decrement_position(10);
 	}
 }
 
@@ -76,13 +86,15 @@ fn loop_wrapping() {
 	let mut transport = Transport {
 		position: 0,
 		loop_region: Some((2, 5)),
+		loop_crossfade_length: 0,
+		loops_remaining: None,
 		playing: true,
 	};
 	transport.position = 6;
 	transport.increment_position(10);
 	assert_eq!(transport.position, 4);
 	transport.position = 1;
-	transport.decrement_position();
+	transport.decrement_position(10);
 	assert_eq!(transport.position, 3);
 }
 
@@ -91,6 +103,8 @@ fn seek_loop_wrapping() {
 	let mut transport = Transport {
 		position: 0,
 		loop_region: Some((2, 5)),
+		loop_crossfade_length: 0,
+		loops_remaining: None,
 		playing: true,
 	};
 	transport.seek_to(7, 10);
@@ -101,7 +115,283 @@ fn seek_loop_wrapping() {
 
 #[test]
 fn seek_out_of_bounds() {
-	let mut transport = Transport::new(5, None, false, 1, 10);
+	let mut transport = Transport::new(5, None, false, Duration::ZERO, None, 1, 10);
 	transport.seek_to(10, 10);
 	assert!(!transport.playing);
 }
+
+/// Tests that the end of a loop region is crossfaded with the audio
+/// before the start of the loop region.
+#[test]
+fn loop_crossfade() {
+	let mut transport = Transport {
+		position: 0,
+		loop_region: Some((4, 10)),
+		loop_crossfade_length: 2,
+		loops_remaining: None,
+		playing: true,
+	};
+	let mut crossfades = vec![];
+	for _ in 0..10 {
+		crossfades.push(transport.loop_crossfade(false, 20));
+		transport.increment_position(20);
+	}
+	assert_eq!(
+		crossfades,
+		[
+			None,
+			None,
+			None,
+			None,
+			None,
+			None,
+			None,
+			None,
+			Some(LoopCrossfade {
+				index: 2,
+				progress: 0.5
+			}),
+			Some(LoopCrossfade {
+				index: 3,
+				progress: 1.0
+			}),
+		]
+	);
+	assert_eq!(transport.position, 4);
+}
+
+/// Tests that the start of a loop region is crossfaded with the audio
+/// after the end of the loop region when playing backwards.
+#[test]
+fn loop_crossfade_backwards() {
+	let mut transport = Transport {
+		position: 6,
+		loop_region: Some((4, 10)),
+		loop_crossfade_length: 2,
+		loops_remaining: None,
+		playing: true,
+	};
+	let mut crossfades = vec![];
+	for _ in 0..3 {
+		crossfades.push(transport.loop_crossfade(true, 20));
+		transport.decrement_position(20);
+	}
+	assert_eq!(
+		crossfades,
+		[
+			None,
+			Some(LoopCrossfade {
+				index: 11,
+				progress: 0.5
+			}),
+			Some(LoopCrossfade {
+				index: 10,
+				progress: 1.0
+			}),
+		]
+	);
+	assert_eq!(transport.position, 9);
+}
+
+/// Tests that when there isn't enough audio outside of the loop region
+/// to fade into, the end of the loop region is crossfaded with the start
+/// of the loop region, and playback resumes after the frames that were
+/// faded into.
+#[test]
+fn loop_crossfade_into_loop_region() {
+	let mut transport = Transport {
+		position: 0,
+		loop_region: Some((0, 6)),
+		loop_crossfade_length: 2,
+		loops_remaining: None,
+		playing: true,
+	};
+	let mut crossfades = vec![];
+	for _ in 0..6 {
+		crossfades.push(transport.loop_crossfade(false, 6));
+		transport.increment_position(6);
+	}
+	assert_eq!(
+		crossfades,
+		[
+			None,
+			None,
+			None,
+			None,
+			Some(LoopCrossfade {
+				index: 0,
+				progress: 0.5
+			}),
+			Some(LoopCrossfade {
+				index: 1,
+				progress: 1.0
+			}),
+		]
+	);
+	assert_eq!(transport.position, 2);
+	assert!(transport.playing);
+}
+
+/// Tests that when there isn't enough audio outside of the loop region
+/// to fade into while playing backwards, the start of the loop region is
+/// crossfaded with the end of the loop region, and playback resumes
+/// before the frames that were faded into.
+#[test]
+fn loop_crossfade_into_loop_region_backwards() {
+	let mut transport = Transport {
+		position: 5,
+		loop_region: Some((0, 6)),
+		loop_crossfade_length: 2,
+		loops_remaining: None,
+		playing: true,
+	};
+	let mut crossfades = vec![];
+	for _ in 0..6 {
+		crossfades.push(transport.loop_crossfade(true, 6));
+		transport.decrement_position(6);
+	}
+	assert_eq!(
+		crossfades,
+		[
+			None,
+			None,
+			None,
+			None,
+			Some(LoopCrossfade {
+				index: 5,
+				progress: 0.5
+			}),
+			Some(LoopCrossfade {
+				index: 4,
+				progress: 1.0
+			}),
+		]
+	);
+	assert_eq!(transport.position, 3);
+	assert!(transport.playing);
+}
+
+/// Tests that loop crossfades into the loop region are shortened so the
+/// frames faded out of and the frames faded into don't overlap, and
+/// that the remaining duration accounts for the skipped frames.
+#[test]
+fn loop_crossfade_into_loop_region_limited_by_loop_length() {
+	let transport = Transport {
+		position: 5,
+		loop_region: Some((1, 10)),
+		loop_crossfade_length: 8,
+		loops_remaining: Some(1),
+		playing: true,
+	};
+	// the crossfade is limited to half of the loop region
+	assert_eq!(transport.loop_crossfade(false, 10), None);
+	assert_eq!(
+		transport.loop_crossfade_length_and_resume_offset(false, 10),
+		(4, 4)
+	);
+	assert_eq!(
+		Transport {
+			position: 6,
+			..transport
+		}
+		.loop_crossfade(false, 10),
+		Some(LoopCrossfade {
+			index: 1,
+			progress: 0.25
+		})
+	);
+	assert_eq!(transport.num_frames_remaining(false, 10), Some(5 + (9 - 4)));
+	// there's enough audio before the loop region for a shorter crossfade
+	let transport = Transport {
+		loop_crossfade_length: 1,
+		..transport
+	};
+	assert_eq!(
+		transport.loop_crossfade_length_and_resume_offset(false, 10),
+		(1, 0)
+	);
+}
+
+/// Tests that a transport stops looping after the loop region has
+/// been played the given number of times.
+#[test]
+fn finite_loop_count() {
+	let mut transport = Transport::new(
+		0,
+		Some((2.0..5.0).into()),
+		false,
+		Duration::ZERO,
+		Some(2),
+		1,
+		10,
+	);
+	for i in [0, 1, 2, 3, 4, 2, 3, 4, 5, 6, 7, 8, 9] {
+		assert_eq!(transport.position, i);
+		assert!(transport.playing);
+		transport.increment_position(10);
+	}
+	assert!(!transport.playing);
+}
+
+/// Tests that a transport finishes the current iteration of the
+/// loop region and then continues past it after exiting the loop.
+#[test]
+fn exit_loop() {
+	let mut transport = Transport {
+		position: 3,
+		loop_region: Some((2, 5)),
+		loop_crossfade_length: 2,
+		loops_remaining: None,
+		playing: true,
+	};
+	transport.exit_loop();
+	for i in 3..10 {
+		assert_eq!(transport.position, i);
+		assert_eq!(transport.loop_crossfade(false, 10), None);
+		transport.increment_position(10);
+	}
+	transport.seek_to(3, 10);
+	transport.seek_to(7, 10);
+	assert_eq!(transport.position, 7);
+}
+
+/// Tests that a transport jumps out of the loop region right away
+/// when exiting the loop immediately.
+#[test]
+fn exit_loop_now() {
+	let mut transport = Transport {
+		position: 3,
+		loop_region: Some((2, 5)),
+		loop_crossfade_length: 0,
+		loops_remaining: None,
+		playing: true,
+	};
+	assert!(transport.exit_loop_now(false, 10));
+	assert_eq!(transport.position, 5);
+	assert!(!transport.exit_loop_now(false, 10));
+	assert_eq!(transport.position, 5);
+	transport.position = 3;
+	assert!(transport.exit_loop_now(true, 10));
+	assert_eq!(transport.position, 1);
+	transport.decrement_position(10);
+	assert_eq!(transport.position, 0);
+}
+
+#[test]
+fn num_frames_remaining() {
+	let mut transport = Transport {
+		position: 3,
+		loop_region: Some((2, 5)),
+		loop_crossfade_length: 0,
+		loops_remaining: Some(2),
+		playing: true,
+	};
+	assert_eq!(transport.num_frames_remaining(false, 10), Some(7 + 2 * 3));
+	assert_eq!(transport.num_frames_remaining(true, 10), Some(4 + 2 * 3));
+	transport.position = 6;
+	assert_eq!(transport.num_frames_remaining(false, 10), Some(4));
+	transport.loops_remaining = None;
+	assert_eq!(transport.num_frames_remaining(true, 10), None);
+	transport.playing = false;
+	assert_eq!(transport.num_frames_remaining(false, 10), Some(0));
+}
@@ -1,11 +1,14 @@
 use std::time::Duration;
 
 use crate::{
-	clock::ClockTime,
+	clock::{ClockTime, QuantizedClockTime},
 	info::{Info, WhenToStart},
 };
 
 /// Describes when an action should occur.
+///
+/// Actions occur on the exact sample the start time is reached,
+/// even if that's partway through a batch of audio.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum StartTime {
 	/// The action should occur immediately.
@@ -16,31 +19,70 @@ pub enum StartTime {
 	/// The action should occur when a clock reaches a
 	/// specific time.
 	ClockTime(ClockTime),
+	/// The action should occur on a tick of a clock that's
+	/// chosen by the audio thread when the start time is first used.
+	///
+	/// See [`QuantizedClockTime`] for more information.
+	Quantized(QuantizedClockTime),
 }
 
 impl StartTime {
-	pub(crate) fn update(&mut self, dt: f64, info: &Info) -> WillNeverStart {
+	/// Advances the start time by a batch of audio lasting `dt` seconds.
+	pub(crate) fn update(&mut self, dt: f64, info: &Info) -> StartTimeProgress {
 		match self {
-			StartTime::Immediate => {}
+			StartTime::Immediate => StartTimeProgress::Started { delay: 0.0 },
 			StartTime::Delayed(time_remaining) => {
-				*time_remaining = time_remaining.saturating_sub(Duration::from_secs_f64(dt));
-				if time_remaining.is_zero() {
+				let delay = time_remaining.as_secs_f64();
+				if delay <= dt {
 					*self = StartTime::Immediate;
+					StartTimeProgress::Started { delay }
+				} else {
+					*time_remaining = time_remaining.saturating_sub(Duration::from_secs_f64(dt));
+					StartTimeProgress::Waiting
 				}
 			}
 			StartTime::ClockTime(clock_time) => match info.when_to_start(*clock_time) {
 				WhenToStart::Now => {
+					let delay = info.clock_time_delay(*clock_time, dt);
 					*self = StartTime::Immediate;
+					StartTimeProgress::Started { delay }
 				}
-				WhenToStart::Later => {}
-				WhenToStart::Never => return true,
+				WhenToStart::Later => StartTimeProgress::Waiting,
+				WhenToStart::Never => StartTimeProgress::WillNeverStart,
+			},
+			StartTime::Quantized(quantized_time) => match quantized_time.resolve(dt, info) {
+				Some(clock_time) => {
+					*self = StartTime::ClockTime(clock_time);
+					self.update(dt, info)
+				}
+				None => StartTimeProgress::WillNeverStart,
 			},
 		}
-		false
 	}
 }
 
-pub(crate) type WillNeverStart = bool;
+/// The state of a [`StartTime`] after an update.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum StartTimeProgress {
+	/// The start time hasn't been reached yet.
+	Waiting,
+	/// The start time has been reached.
+	Started {
+		/// How many seconds into the batch of audio the start time
+		/// was reached. This is 0 if it was reached before the batch.
+		delay: f64,
+	},
+	/// The start time will never be reached because the clock it
+	/// depends on no longer exists.
+	WillNeverStart,
+}
+
+/// Returns how many frames at the beginning of a batch should be
+/// skipped if playback starts `delay` seconds into the batch.
+#[must_use]
+pub(crate) fn num_frames_before_start(delay: f64, dt: f64, num_frames: usize) -> usize {
+	((delay / dt).round() as usize).min(num_frames)
+}
 
 impl From<Duration> for StartTime {
 	fn from(v: Duration) -> Self {
@@ -53,3 +95,9 @@ impl From<ClockTime> for StartTime {
 		Self::ClockTime(v)
 	}
 }
+
+impl From<QuantizedClockTime> for StartTime {
+	fn from(v: QuantizedClockTime) -> Self {
+		Self::Quantized(v)
+	}
+}
@@ -265,15 +265,42 @@ manager.add_spatial_sub_track(
 )?;
 # Result::<(), Box<dyn Error>>::Ok(())
 ```
+
+## Taps
+
+A [`TrackTap`] receives the audio a track outputs after its effects
+and volume have been applied, which is useful for recording or
+analyzing the mix. Every kind of track handle has an `add_tap` method.
+
+```no_run
+# use std::error::Error;
+# use kira::{
+# 	AudioManager, AudioManagerSettings, DefaultBackend,
+# 	track::TrackBuilder,
+# };
+use kira::Frame;
+
+# let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())?;
+# let mut track = manager.add_sub_track(TrackBuilder::default())?;
+let mut tap = track.add_tap(4096)?;
+// later...
+let mut frames = [Frame::ZERO; 1024];
+let num_frames = tap.read(&mut frames);
+# Result::<(), Box<dyn Error>>::Ok(())
+```
 */
 
 mod main;
+mod profiling;
 mod send;
 mod sub;
+mod tap;
 
 pub use main::*;
+pub use profiling::TrackProfile;
 pub use send::*;
 pub use sub::*;
+pub use tap::*;
 
 use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
@@ -4,20 +4,32 @@ mod handle;
 pub use builder::*;
 pub use handle::*;
 
+use std::sync::Arc;
+
 use crate::{
 	Decibels, Frame, Parameter,
-	backend::resources::ResourceStorage,
+	backend::{
+		ChannelFrame, RendererShared, mix_down_bus, resources::ResourceStorage, with_bus_mixed_down,
+	},
 	command::{CommandReader, ValueChangeCommand},
 	effect::Effect,
 	info::Info,
 	sound::Sound,
 };
 
+use super::{
+	Taps,
+	profiling::{Stopwatch, TrackTimings},
+};
+
 pub(crate) struct MainTrack {
+	renderer_shared: Arc<RendererShared>,
+	timings: Arc<TrackTimings>,
 	volume: Parameter<Decibels>,
 	set_volume_command_reader: CommandReader<ValueChangeCommand<Decibels>>,
 	sounds: ResourceStorage<Box<dyn Sound>>,
 	effects: Vec<Box<dyn Effect>>,
+	taps: Taps,
 	temp_buffer: Vec<Frame>,
 	internal_buffer_size: usize,
 }
@@ -30,6 +42,9 @@ impl MainTrack {
 	}
 
 	pub fn on_change_sample_rate(&mut self, sample_rate: u32) {
+		for (_, sound) in &mut self.sounds {
+			sound.on_change_sample_rate(sample_rate);
+		}
 		for effect in &mut self.effects {
 			effect.on_change_sample_rate(sample_rate);
 		}
@@ -45,9 +60,16 @@ impl MainTrack {
 		for effect in &mut self.effects {
 			effect.on_start_processing();
 		}
+		self.taps.on_start_processing();
 	}
 
-	pub fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
+	/// Processes the main track. `bus` holds audio that sub-tracks
+	/// have already panned across the speakers of a surround layout.
+	/// If the track has effects, that audio is mixed down to stereo
+	/// first so the effects can process it.
+	pub fn process(&mut self, out: &mut [Frame], bus: &mut [ChannelFrame], dt: f64, info: &Info) {
+		let profiling = self.renderer_shared.profiling_enabled();
+		let stopwatch = Stopwatch::start(profiling);
 		self.volume.update(dt * out.len() as f64, info);
 		for (_, sound) in &mut self.sounds {
 			sound.process(&mut self.temp_buffer[..out.len()], dt, info);
@@ -56,14 +78,29 @@ impl MainTrack {
 			}
 			self.temp_buffer.fill(Frame::ZERO);
 		}
-		for effect in &mut self.effects {
+		let channel_layout = self.renderer_shared.channel_layout;
+		if !self.effects.is_empty() {
+			mix_down_bus(out, bus, channel_layout);
+		}
+		for (i, effect) in self.effects.iter_mut().enumerate() {
+			let effect_stopwatch = Stopwatch::start(profiling);
 			effect.process(out, dt, info);
+			effect_stopwatch.record(self.timings.effect_processing_time(i));
 		}
 		let num_frames = out.len();
 		for (i, frame) in out.iter_mut().enumerate() {
 			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
 			let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
 			*frame *= volume;
+			bus[i] *= volume;
 		}
+		self.taps.write(with_bus_mixed_down(
+			out,
+			bus,
+			channel_layout,
+			&mut self.temp_buffer,
+		));
+		self.temp_buffer.fill(Frame::ZERO);
+		self.timings.record_batch(stopwatch);
 	}
 }
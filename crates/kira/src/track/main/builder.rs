@@ -1,6 +1,11 @@
+use std::sync::Arc;
+
 use crate::{
-	Decibels, Frame, Parameter, Value, backend::resources::ResourceStorage,
-	command::command_writer_and_reader, effect::EffectBuilder,
+	Decibels, Frame, Parameter, Value,
+	backend::{RendererShared, resources::ResourceStorage},
+	command::command_writer_and_reader,
+	effect::EffectBuilder,
+	track::{Taps, profiling::TrackTimings},
 };
 
 use super::{Effect, MainTrack, MainTrackHandle};
@@ -14,6 +19,8 @@ pub struct MainTrackBuilder {
 	pub(crate) effects: Vec<Box<dyn Effect>>,
 	/// The maximum number of sounds that can be played simultaneously on this track.
 	pub(crate) sound_capacity: usize,
+	/// The maximum number of taps that can be attached to this track.
+	pub(crate) tap_capacity: usize,
 }
 
 impl MainTrackBuilder {
@@ -24,6 +31,7 @@ impl MainTrackBuilder {
 			volume: Value::Fixed(Decibels::IDENTITY),
 			effects: vec![],
 			sound_capacity: 128,
+			tap_capacity: 4,
 		}
 	}
 
@@ -45,6 +53,16 @@ impl MainTrackBuilder {
 		}
 	}
 
+	/// Sets the maximum number of [`TrackTap`](crate::track::TrackTap)s that can be attached
+	/// to this track at a time.
+	#[must_use = "This method consumes self and returns a modified MainTrackBuilder, so the return value should be used"]
+	pub fn tap_capacity(self, capacity: usize) -> Self {
+		Self {
+			tap_capacity: capacity,
+			..self
+		}
+	}
+
 	/**
 	Adds an effect to the track.
 
@@ -142,20 +160,33 @@ impl MainTrackBuilder {
 	}
 
 	#[must_use]
-	pub(crate) fn build(self, internal_buffer_size: usize) -> (MainTrack, MainTrackHandle) {
+	pub(crate) fn build(
+		self,
+		renderer_shared: Arc<RendererShared>,
+		internal_buffer_size: usize,
+	) -> (MainTrack, MainTrackHandle) {
 		let (set_volume_command_writer, set_volume_command_reader) = command_writer_and_reader();
 		let (sounds, sound_controller) = ResourceStorage::new(self.sound_capacity);
+		let (taps, tap_controller) = Taps::new(self.tap_capacity);
+		let timings = Arc::new(TrackTimings::new(self.effects.len()));
 		let track = MainTrack {
+			renderer_shared: renderer_shared.clone(),
+			timings: timings.clone(),
 			volume: Parameter::new(self.volume, Decibels::IDENTITY),
 			set_volume_command_reader,
 			sounds,
 			effects: self.effects,
+			taps,
 			temp_buffer: vec![Frame::ZERO; internal_buffer_size],
 			internal_buffer_size,
 		};
 		let handle = MainTrackHandle {
+			renderer_shared,
 			set_volume_command_writer,
 			sound_controller,
+			tap_controller,
+			timings,
+			internal_buffer_size,
 		};
 		(track, handle)
 	}
@@ -1,15 +1,22 @@
+use std::sync::{Arc, atomic::Ordering};
+
 use crate::{
-	Decibels, PlaySoundError, Tween, Value,
-	backend::resources::ResourceController,
+	Decibels, PlaySoundError, ResourceLimitReached, Tween, Value,
+	backend::{RendererShared, resources::ResourceController},
 	command::{CommandWriter, ValueChangeCommand},
 	sound::{Sound, SoundData},
+	track::{TapController, TrackProfile, TrackTap, profiling::TrackTimings},
 };
 
 /// Controls the main mixer track.
 #[derive(Debug)]
 pub struct MainTrackHandle {
+	pub(crate) renderer_shared: Arc<RendererShared>,
 	pub(crate) set_volume_command_writer: CommandWriter<ValueChangeCommand<Decibels>>,
 	pub(crate) sound_controller: ResourceController<Box<dyn Sound>>,
+	pub(crate) tap_controller: TapController,
+	pub(crate) timings: Arc<TrackTimings>,
+	pub(crate) internal_buffer_size: usize,
 }
 
 impl MainTrackHandle {
@@ -18,9 +25,13 @@ impl MainTrackHandle {
 		&mut self,
 		sound_data: D,
 	) -> Result<D::Handle, PlaySoundError<D::Error>> {
-		let (sound, handle) = sound_data
+		let (mut sound, handle) = sound_data
 			.into_sound()
 			.map_err(PlaySoundError::IntoSoundError)?;
+		sound.init(
+			self.renderer_shared.sample_rate.load(Ordering::SeqCst),
+			self.internal_buffer_size,
+		);
 		self.sound_controller
 			.insert(sound)
 			.map_err(|_| PlaySoundError::SoundLimitReached)?;
@@ -46,4 +57,39 @@ impl MainTrackHandle {
 	pub fn num_sounds(&self) -> usize {
 		self.sound_controller.len()
 	}
+
+	/**
+	Adds a tap to the track, which receives the audio the track
+	outputs after its effects and volume have been applied.
+
+	`buffer_capacity` is the number of frames the tap can hold before
+	new frames are dropped.
+	*/
+	pub fn add_tap(&mut self, buffer_capacity: usize) -> Result<TrackTap, ResourceLimitReached> {
+		self.tap_controller.add_tap(buffer_capacity)
+	}
+
+	/// Returns the maximum number of taps this track can have.
+	#[must_use]
+	pub fn tap_capacity(&self) -> usize {
+		self.tap_controller.capacity()
+	}
+
+	/// Returns the number of taps this track has.
+	#[must_use]
+	pub fn num_taps(&self) -> usize {
+		self.tap_controller.len()
+	}
+
+	/**
+	Returns how much time the audio thread spent processing this
+	track since the last time this was called.
+
+	Timings are only recorded while profiling is enabled with
+	[`AudioManager::set_profiling_enabled`](crate::AudioManager::set_profiling_enabled).
+	*/
+	#[must_use]
+	pub fn pop_profile(&mut self) -> TrackProfile {
+		self.timings.pop()
+	}
 }
@@ -0,0 +1,113 @@
+use std::{
+	sync::atomic::{AtomicU64, Ordering},
+	time::Duration,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/**
+How much time the audio thread spent processing a mixer track.
+
+Timings are only recorded while profiling is enabled with
+[`AudioManager::set_profiling_enabled`](crate::AudioManager::set_profiling_enabled).
+They aren't recorded on WebAssembly.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct TrackProfile {
+	/// The number of batches of audio the track processed while
+	/// these timings were recorded.
+	pub num_batches: u64,
+	/// The total time spent processing the track, including its
+	/// sounds, sub-tracks, and effects.
+	pub processing_time: Duration,
+	/// The time spent processing each effect on the track, in the
+	/// order the effects were added.
+	pub effect_processing_times: Vec<Duration>,
+}
+
+impl TrackProfile {
+	/// Returns the average time spent processing the track
+	/// per batch of audio.
+	#[must_use]
+	pub fn average_processing_time(&self) -> Duration {
+		if self.num_batches == 0 {
+			return Duration::ZERO;
+		}
+		Duration::from_nanos(self.processing_time.as_nanos() as u64 / self.num_batches)
+	}
+}
+
+/// Timings shared between a mixer track and its handle.
+#[derive(Debug)]
+pub(crate) struct TrackTimings {
+	num_batches: AtomicU64,
+	processing_time: AtomicU64,
+	effect_processing_times: Box<[AtomicU64]>,
+}
+
+impl TrackTimings {
+	#[must_use]
+	pub fn new(num_effects: usize) -> Self {
+		Self {
+			num_batches: AtomicU64::new(0),
+			processing_time: AtomicU64::new(0),
+			effect_processing_times: (0..num_effects).map(|_| AtomicU64::new(0)).collect(),
+		}
+	}
+
+	#[must_use]
+	pub fn effect_processing_time(&self, index: usize) -> &AtomicU64 {
+		&self.effect_processing_times[index]
+	}
+
+	pub fn record_batch(&self, stopwatch: Stopwatch) {
+		if stopwatch.record(&self.processing_time) {
+			self.num_batches.fetch_add(1, Ordering::Relaxed);
+		}
+	}
+
+	/// Returns the timings recorded since the last time this
+	/// was called and resets them.
+	#[must_use]
+	pub fn pop(&self) -> TrackProfile {
+		TrackProfile {
+			num_batches: self.num_batches.swap(0, Ordering::Relaxed),
+			processing_time: Duration::from_nanos(self.processing_time.swap(0, Ordering::Relaxed)),
+			effect_processing_times: self
+				.effect_processing_times
+				.iter()
+				.map(|time| Duration::from_nanos(time.swap(0, Ordering::Relaxed)))
+				.collect(),
+		}
+	}
+}
+
+/// Measures how long something takes if profiling is enabled.
+#[must_use]
+pub(crate) struct Stopwatch {
+	#[cfg(not(target_arch = "wasm32"))]
+	start_time: Option<Instant>,
+}
+
+impl Stopwatch {
+	#[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+	pub fn start(enabled: bool) -> Self {
+		Self {
+			#[cfg(not(target_arch = "wasm32"))]
+			start_time: enabled.then(Instant::now),
+		}
+	}
+
+	/// Adds the elapsed time in nanoseconds to `total`. Returns
+	/// `false` if profiling was disabled when the stopwatch started.
+	#[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+	pub fn record(self, total: &AtomicU64) -> bool {
+		#[cfg(not(target_arch = "wasm32"))]
+		if let Some(start_time) = self.start_time {
+			total.fetch_add(start_time.elapsed().as_nanos() as u64, Ordering::Relaxed);
+			return true;
+		}
+		false
+	}
+}
@@ -9,12 +9,16 @@ pub use handle::*;
 
 use crate::{
 	Decibels, Frame, Parameter,
+	backend::{RendererShared, resources::ResourceStorage},
 	command::{CommandReader, ValueChangeCommand},
 	effect::Effect,
 	info::Info,
 };
 
-use super::TrackShared;
+use super::{
+	Taps, TrackShared,
+	profiling::{Stopwatch, TrackTimings},
+};
 
 /// A unique identifier for a mixer send track.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -27,10 +31,13 @@ impl From<&SendTrackHandle> for SendTrackId {
 }
 
 pub(crate) struct SendTrack {
+	renderer_shared: Arc<RendererShared>,
 	shared: Arc<TrackShared>,
+	timings: Arc<TrackTimings>,
 	volume: Parameter<Decibels>,
 	set_volume_command_reader: CommandReader<ValueChangeCommand<Decibels>>,
 	effects: Vec<Box<dyn Effect>>,
+	taps: Taps,
 	input: Vec<Frame>,
 	internal_buffer_size: usize,
 }
@@ -65,16 +72,21 @@ impl SendTrack {
 		for effect in &mut self.effects {
 			effect.on_start_processing();
 		}
+		self.taps.on_start_processing();
 	}
 
 	pub fn process(&mut self, out: &mut [Frame], dt: f64, info: &Info) {
+		let profiling = self.renderer_shared.profiling_enabled();
+		let stopwatch = Stopwatch::start(profiling);
 		self.volume.update(dt * out.len() as f64, info);
 		for (out_frame, input_frame) in out.iter_mut().zip(self.input.iter().copied()) {
 			*out_frame += input_frame;
 		}
 		self.input.fill(Frame::ZERO);
-		for effect in &mut self.effects {
+		for (i, effect) in self.effects.iter_mut().enumerate() {
+			let effect_stopwatch = Stopwatch::start(profiling);
 			effect.process(out, dt, info);
+			effect_stopwatch.record(self.timings.effect_processing_time(i));
 		}
 		let num_frames = out.len();
 		for (i, frame) in out.iter_mut().enumerate() {
@@ -82,6 +94,21 @@ impl SendTrack {
 			let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
 			*frame *= volume;
 		}
+		self.taps.write(out);
+		self.timings.record_batch(stopwatch);
+	}
+}
+
+/// Receives the audio that mixer tracks route to send tracks.
+pub(crate) trait SendTrackInputs {
+	fn add_input(&mut self, id: SendTrackId, input: &[Frame], volume: Decibels);
+}
+
+impl SendTrackInputs for ResourceStorage<SendTrack> {
+	fn add_input(&mut self, id: SendTrackId, input: &[Frame], volume: Decibels) {
+		if let Some(send_track) = self.get_mut(id.0) {
+			send_track.add_input(input, volume);
+		}
 	}
 }
 
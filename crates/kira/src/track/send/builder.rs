@@ -1,11 +1,11 @@
 use std::sync::Arc;
 
 use crate::{
-	Decibels, Parameter, Value, command::command_writer_and_reader, effect::EffectBuilder,
-	frame::Frame,
+	Decibels, Parameter, Value, backend::RendererShared, command::command_writer_and_reader,
+	effect::EffectBuilder, frame::Frame, track::profiling::TrackTimings,
 };
 
-use super::{Effect, SendTrack, SendTrackHandle, SendTrackId, TrackShared};
+use super::{Effect, SendTrack, SendTrackHandle, SendTrackId, Taps, TrackShared};
 
 /// Configures a mixer track.
 pub struct SendTrackBuilder {
@@ -14,6 +14,8 @@ pub struct SendTrackBuilder {
 	/// The effects that should be applied to the input audio
 	/// for this track.
 	pub(crate) effects: Vec<Box<dyn Effect>>,
+	/// The maximum number of taps that can be attached to this track.
+	pub(crate) tap_capacity: usize,
 }
 
 impl SendTrackBuilder {
@@ -23,6 +25,7 @@ impl SendTrackBuilder {
 		Self {
 			volume: Value::Fixed(Decibels::IDENTITY),
 			effects: vec![],
+			tap_capacity: 4,
 		}
 	}
 
@@ -72,6 +75,16 @@ impl SendTrackBuilder {
 		}
 	}
 
+	/// Sets the maximum number of [`TrackTap`](crate::track::TrackTap)s that can be attached
+	/// to this track at a time.
+	#[must_use = "This method consumes self and returns a modified SendTrackBuilder, so the return value should be used"]
+	pub fn tap_capacity(self, capacity: usize) -> Self {
+		Self {
+			tap_capacity: capacity,
+			..self
+		}
+	}
+
 	/**
 	Adds an effect to the send track.
 
@@ -172,15 +185,21 @@ impl SendTrackBuilder {
 	pub(crate) fn build(
 		self,
 		id: SendTrackId,
+		renderer_shared: Arc<RendererShared>,
 		internal_buffer_size: usize,
 	) -> (SendTrack, SendTrackHandle) {
 		let (set_volume_command_writer, set_volume_command_reader) = command_writer_and_reader();
 		let shared = Arc::new(TrackShared::new());
+		let (taps, tap_controller) = Taps::new(self.tap_capacity);
+		let timings = Arc::new(TrackTimings::new(self.effects.len()));
 		let track = SendTrack {
+			renderer_shared,
 			shared: shared.clone(),
+			timings: timings.clone(),
 			volume: Parameter::new(self.volume, Decibels::IDENTITY),
 			set_volume_command_reader,
 			effects: self.effects,
+			taps,
 			input: vec![Frame::ZERO; internal_buffer_size],
 			internal_buffer_size,
 		};
@@ -188,6 +207,8 @@ impl SendTrackBuilder {
 			id,
 			shared,
 			set_volume_command_writer,
+			tap_controller,
+			timings,
 		};
 		(track, handle)
 	}
@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
 use crate::{
-	Decibels, Tween, Value,
+	Decibels, ResourceLimitReached, Tween, Value,
 	command::{CommandWriter, ValueChangeCommand},
+	track::{TapController, TrackProfile, TrackTap, profiling::TrackTimings},
 };
 
 use super::{SendTrackId, TrackShared};
@@ -16,6 +17,8 @@ pub struct SendTrackHandle {
 	pub(crate) id: SendTrackId,
 	pub(crate) shared: Arc<TrackShared>,
 	pub(crate) set_volume_command_writer: CommandWriter<ValueChangeCommand<Decibels>>,
+	pub(crate) tap_controller: TapController,
+	pub(crate) timings: Arc<TrackTimings>,
 }
 
 impl SendTrackHandle {
@@ -31,6 +34,41 @@ impl SendTrackHandle {
 			tween,
 		})
 	}
+
+	/**
+	Adds a tap to the track, which receives the audio the track
+	outputs after its effects and volume have been applied.
+
+	`buffer_capacity` is the number of frames the tap can hold before
+	new frames are dropped.
+	*/
+	pub fn add_tap(&mut self, buffer_capacity: usize) -> Result<TrackTap, ResourceLimitReached> {
+		self.tap_controller.add_tap(buffer_capacity)
+	}
+
+	/// Returns the maximum number of taps this track can have.
+	#[must_use]
+	pub fn tap_capacity(&self) -> usize {
+		self.tap_controller.capacity()
+	}
+
+	/// Returns the number of taps this track has.
+	#[must_use]
+	pub fn num_taps(&self) -> usize {
+		self.tap_controller.len()
+	}
+
+	/**
+	Returns how much time the audio thread spent processing this
+	track since the last time this was called.
+
+	Timings are only recorded while profiling is enabled with
+	[`AudioManager::set_profiling_enabled`](crate::AudioManager::set_profiling_enabled).
+	*/
+	#[must_use]
+	pub fn pop_profile(&mut self) -> TrackProfile {
+		self.timings.pop()
+	}
 }
 
 impl Drop for SendTrackHandle {
@@ -10,23 +10,31 @@ pub use spatial_handle::*;
 
 use std::{error::Error, f32::consts::FRAC_PI_8, fmt::Display, sync::Arc};
 
+use atomic_arena::Arena;
 use glam::{Quat, Vec3};
 
 use crate::{
 	Decibels, Easing, Frame, Parameter, StartTime, Tween, Tweenable,
-	backend::resources::{
-		ResourceStorage, clocks::Clocks, listeners::Listeners, modulators::Modulators,
+	backend::{
+		ChannelFrame, ChannelLayout, RendererShared, mix_down_bus, resources::ResourceStorage,
+		with_bus_mixed_down,
 	},
+	clock::Clock,
 	command::ValueChangeCommand,
 	command_writers_and_readers,
 	effect::Effect,
-	info::{Info, SpatialTrackInfo},
-	listener::ListenerId,
+	event::EventProducer,
+	info::{Info, ModulatorValues, SpatialTrackInfo},
+	listener::{Listener, ListenerId},
 	playback_state_manager::PlaybackStateManager,
-	sound::Sound,
+	sound::{PlaybackState, Sound},
+	start_time::num_frames_before_start,
 };
 
-use super::{SendTrack, SendTrackId, SendTrackRoute, TrackShared};
+use super::{
+	SendTrackId, SendTrackInputs, SendTrackRoute, Taps, TrackShared,
+	profiling::{Stopwatch, TrackTimings},
+};
 
 /// An error that's returned when trying to change the volume of a track route
 /// that did not exist originally.
@@ -41,8 +49,25 @@ impl Display for NonexistentRoute {
 
 impl Error for NonexistentRoute {}
 
+/// Something that happened to a mixer track on the audio thread.
+///
+/// Events can be retrieved with the `pop_event` method on
+/// a track's handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrackEvent {
+	/// The track finished fading out and is now paused.
+	Paused,
+	/// The track started playing again after being paused.
+	Resumed,
+}
+
 pub(crate) struct Track {
+	renderer_shared: Arc<RendererShared>,
 	shared: Arc<TrackShared>,
+	timings: Arc<TrackTimings>,
+	event_producer: EventProducer<TrackEvent>,
+	/// The playback state the last time events were checked for.
+	previous_playback_state: PlaybackState,
 	command_readers: CommandReaders,
 	volume: Parameter<Decibels>,
 	sounds: ResourceStorage<Box<dyn Sound>>,
@@ -52,7 +77,9 @@ pub(crate) struct Track {
 	persist_until_sounds_finish: bool,
 	spatial_data: Option<SpatialData>,
 	playback_state_manager: PlaybackStateManager,
+	taps: Taps,
 	temp_buffer: Vec<Frame>,
+	temp_bus_buffer: Vec<ChannelFrame>,
 	internal_buffer_size: usize,
 }
 
@@ -83,6 +110,9 @@ impl Track {
 	}
 
 	pub fn on_change_sample_rate(&mut self, sample_rate: u32) {
+		for (_, sound) in &mut self.sounds {
+			sound.on_change_sample_rate(sample_rate);
+		}
 		for effect in &mut self.effects {
 			effect.on_change_sample_rate(sample_rate);
 		}
@@ -125,19 +155,33 @@ impl Track {
 		for effect in &mut self.effects {
 			effect.on_start_processing();
 		}
+		self.taps.on_start_processing();
 	}
 
+	/**
+	Processes the track and its sub-tracks.
+
+	When the mix uses a surround [`ChannelLayout`], spatial tracks pan
+	their output across every speaker and write it to `bus` instead of
+	`out`. Tracks with effects or spatialization mix the surround audio
+	from their sub-tracks down to stereo before processing it, and taps
+	and send tracks always receive a stereo version of the output.
+	*/
 	#[allow(clippy::too_many_arguments)]
 	pub fn process(
 		&mut self,
 		out: &mut [Frame],
+		bus: &mut [ChannelFrame],
 		dt: f64,
-		clocks: &Clocks,
-		modulators: &Modulators,
-		listeners: &Listeners,
+		clocks: &Arena<Clock>,
+		modulators: ModulatorValues<'_>,
+		listeners: &Arena<Listener>,
 		parent_spatial_track_info: Option<SpatialTrackInfo>,
-		send_tracks: &mut ResourceStorage<SendTrack>,
+		send_tracks: &mut impl SendTrackInputs,
 	) {
+		let profiling = self.renderer_shared.profiling_enabled();
+		let stopwatch = Stopwatch::start(profiling);
+
 		// get info
 		let spatial_track_info = self
 			.spatial_data
@@ -148,10 +192,11 @@ impl Track {
 			})
 			.or(parent_spatial_track_info);
 		let info = Info::new(
-			&clocks.0.resources,
-			&modulators.0.resources,
-			&listeners.0.resources,
+			clocks,
+			modulators,
+			listeners,
 			spatial_track_info,
+			self.renderer_shared.time(),
 		);
 
 		// update volume parameters
@@ -167,87 +212,162 @@ impl Track {
 		if changed_playback_state {
 			self.update_shared_playback_state();
 		}
+		self.push_playback_state_events(info.time(), dt * out.len() as f64);
 		if !self.playback_state_manager.playback_state().is_advancing() {
 			out.fill(Frame::ZERO);
+			bus.fill(ChannelFrame::ZERO);
+			self.taps.write(out);
+			self.timings.record_batch(stopwatch);
 			return;
 		}
 
+		// if playback resumed partway through this batch, the frames
+		// before that point stay silent
 		let num_frames = out.len();
+		let start_frame =
+			num_frames_before_start(self.playback_state_manager.start_delay(), dt, num_frames);
 
-		// process sub tracks
-		for (_, sub_track) in &mut self.sub_tracks {
-			sub_track.process(
-				&mut self.temp_buffer[..out.len()],
-				dt,
-				clocks,
-				modulators,
-				listeners,
-				spatial_track_info,
-				send_tracks,
-			);
-			for (summed_out, track_out) in out.iter_mut().zip(self.temp_buffer.iter().copied()) {
-				*summed_out += track_out;
+		let channel_layout = self.renderer_shared.channel_layout;
+		let pan_to_bus = self.spatial_data.is_some() && channel_layout.is_surround();
+		{
+			let out = &mut out[start_frame..];
+			let bus = &mut bus[start_frame..];
+
+			// process sub tracks
+			for (_, sub_track) in &mut self.sub_tracks {
+				sub_track.process(
+					&mut self.temp_buffer[..out.len()],
+					&mut self.temp_bus_buffer[..out.len()],
+					dt,
+					clocks,
+					modulators,
+					listeners,
+					spatial_track_info,
+					send_tracks,
+				);
+				for (summed_out, track_out) in out.iter_mut().zip(self.temp_buffer.iter().copied())
+				{
+					*summed_out += track_out;
+				}
+				for (summed_bus, track_bus) in
+					bus.iter_mut().zip(self.temp_bus_buffer.iter().copied())
+				{
+					*summed_bus += track_bus;
+				}
+				self.temp_buffer.fill(Frame::ZERO);
+				self.temp_bus_buffer.fill(ChannelFrame::ZERO);
 			}
-			self.temp_buffer.fill(Frame::ZERO);
-		}
 
-		// process sounds
-		for (_, sound) in &mut self.sounds {
-			sound.process(&mut self.temp_buffer[..out.len()], dt, &info);
-			for (summed_out, sound_out) in out.iter_mut().zip(self.temp_buffer.iter().copied()) {
-				*summed_out += sound_out;
+			// process sounds
+			for (_, sound) in &mut self.sounds {
+				sound.process(&mut self.temp_buffer[..out.len()], dt, &info);
+				for (summed_out, sound_out) in out.iter_mut().zip(self.temp_buffer.iter().copied())
+				{
+					*summed_out += sound_out;
+				}
+				self.temp_buffer.fill(Frame::ZERO);
 			}
-			self.temp_buffer.fill(Frame::ZERO);
-		}
 
-		// apply effects
-		for effect in &mut self.effects {
-			effect.process(out, dt, &info);
-		}
+			// effects and spatialization only work with stereo audio,
+			// so surround audio from sub-tracks is mixed down first
+			if !self.effects.is_empty() || self.spatial_data.is_some() {
+				mix_down_bus(out, bus, channel_layout);
+			}
 
-		// apply spatialization
-		if let Some(spatial_data) = &mut self.spatial_data {
-			spatial_data.position.update(dt * out.len() as f64, &info);
-			spatial_data
-				.spatialization_strength
-				.update(dt * out.len() as f64, &info);
-			for (i, frame) in out.iter_mut().enumerate() {
-				let time_in_chunk = i as f64 / num_frames as f64;
-				if let Some(listener_info) = info.listener_info() {
-					let interpolated_position =
-						listener_info.interpolated_position(time_in_chunk as f32);
-					let interpolated_orientation =
-						listener_info.interpolated_orientation(time_in_chunk as f32);
-					*frame = spatial_data.spatialize(
-						*frame,
-						interpolated_position.into(),
-						interpolated_orientation.into(),
-						time_in_chunk,
-					);
-				} else {
-					*frame = Frame::ZERO;
+			// apply effects
+			for (i, effect) in self.effects.iter_mut().enumerate() {
+				let effect_stopwatch = Stopwatch::start(profiling);
+				effect.process(out, dt, &info);
+				effect_stopwatch.record(self.timings.effect_processing_time(i));
+			}
+
+			// apply spatialization
+			if let Some(spatial_data) = &mut self.spatial_data {
+				spatial_data.position.update(dt * out.len() as f64, &info);
+				spatial_data
+					.spatialization_strength
+					.update(dt * out.len() as f64, &info);
+				for (i, (frame, bus_frame)) in out.iter_mut().zip(bus.iter_mut()).enumerate() {
+					let time_in_chunk = (start_frame + i) as f64 / num_frames as f64;
+					if let Some(listener_info) = info.listener_info() {
+						let interpolated_position =
+							listener_info.interpolated_position(time_in_chunk as f32);
+						let interpolated_orientation =
+							listener_info.interpolated_orientation(time_in_chunk as f32);
+						if pan_to_bus {
+							*bus_frame += spatial_data.pan(
+								*frame,
+								interpolated_position.into(),
+								interpolated_orientation.into(),
+								time_in_chunk,
+								channel_layout,
+							);
+						}
+						*frame = spatial_data.spatialize(
+							*frame,
+							interpolated_position.into(),
+							interpolated_orientation.into(),
+							time_in_chunk,
+						);
+					} else {
+						*frame = Frame::ZERO;
+					}
 				}
 			}
-		}
 
-		// apply volume fade
-		for (i, frame) in out.iter_mut().enumerate() {
-			let time_in_chunk = (i + 1) as f64 / num_frames as f64;
-			let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
-			let fade_volume = self
-				.playback_state_manager
-				.interpolated_fade_volume(time_in_chunk)
-				.as_amplitude();
-			*frame *= volume * fade_volume;
+			// apply volume fade
+			for (i, (frame, bus_frame)) in out.iter_mut().zip(bus.iter_mut()).enumerate() {
+				let time_in_chunk = (start_frame + i + 1) as f64 / num_frames as f64;
+				let volume = self.volume.interpolated_value(time_in_chunk).as_amplitude();
+				let fade_volume = self
+					.playback_state_manager
+					.interpolated_fade_volume(time_in_chunk)
+					.as_amplitude();
+				*frame *= volume * fade_volume;
+				*bus_frame *= volume * fade_volume;
+			}
 		}
+		// taps and send tracks receive the stereo version of the output.
+		// spatial tracks already have one in `out`.
+		let stereo_out = if pan_to_bus {
+			&*out
+		} else {
+			with_bus_mixed_down(out, bus, channel_layout, &mut self.temp_buffer)
+		};
+		self.taps.write(stereo_out);
 
 		// output to send tracks
 		for (send_track_id, SendTrackRoute { volume, .. }) in &self.sends {
-			let Some(send_track) = send_tracks.get_mut(send_track_id.0) else {
-				continue;
-			};
-			send_track.add_input(out, volume.value());
+			send_tracks.add_input(*send_track_id, stereo_out, volume.value());
 		}
+		self.temp_buffer.fill(Frame::ZERO);
+
+		// the surround version of the output is already in the bus
+		if pan_to_bus {
+			out.fill(Frame::ZERO);
+		}
+
+		self.timings.record_batch(stopwatch);
+	}
+
+	fn push_playback_state_events(&mut self, time: f64, batch_duration: f64) {
+		let playback_state = self.playback_state_manager.playback_state();
+		match (self.previous_playback_state, playback_state) {
+			// the fade out finishes at the end of this batch
+			(previous, PlaybackState::Paused) if previous != PlaybackState::Paused => {
+				self.event_producer
+					.push(TrackEvent::Paused, time + batch_duration);
+			}
+			(
+				PlaybackState::Paused | PlaybackState::WaitingToResume,
+				PlaybackState::Resuming | PlaybackState::Playing,
+			) => self.event_producer.push(
+				TrackEvent::Resumed,
+				time + self.playback_state_manager.start_delay(),
+			),
+			_ => {}
+		}
+		self.previous_playback_state = playback_state;
 	}
 
 	fn read_commands(&mut self) {
@@ -288,7 +408,8 @@ struct SpatialData {
 	/// direction from the listener.
 	///
 	/// This value should be between `0.0` and `1.0`. `0.0` disables spatialization
-	/// entirely.
+	/// entirely. With surround layouts, it controls how focused the output is on the
+	/// speakers nearest to the track's direction.
 	spatialization_strength: Parameter<f32>,
 }
 
@@ -307,21 +428,8 @@ impl SpatialData {
 			.clamp(0.0, 1.0);
 		let min_ear_amplitude = 1.0 - spatialization_strength;
 
-		let mut output = input;
 		// attenuate volume
-		if let Some(attenuation_function) = self.attenuation_function {
-			let distance = (listener_position - position).length();
-			let relative_distance = self.distances.relative_distance(distance);
-			let relative_volume =
-				attenuation_function.apply((1.0 - relative_distance).into()) as f32;
-			let amplitude = Tweenable::interpolate(
-				Decibels::SILENCE,
-				Decibels::IDENTITY,
-				relative_volume.into(),
-			)
-			.as_amplitude();
-			output *= amplitude;
-		}
+		let mut output = input * self.attenuation(listener_position, position);
 		// apply spatialization
 		if spatialization_strength != 0.0 {
 			output = output.as_mono();
@@ -342,6 +450,54 @@ impl SpatialData {
 		}
 		output
 	}
+
+	/// Pans a frame across the speakers of a surround layout
+	/// depending on its direction from the listener.
+	fn pan(
+		&self,
+		input: Frame,
+		listener_position: Vec3,
+		listener_orientation: Quat,
+		time_in_chunk: f64,
+		channel_layout: ChannelLayout,
+	) -> ChannelFrame {
+		let position = self.position.interpolated_value(time_in_chunk);
+		let spatialization_strength = self
+			.spatialization_strength
+			.interpolated_value(time_in_chunk)
+			.clamp(0.0, 1.0);
+		// the listener faces -Z, with +X to their right
+		let relative_position = listener_orientation.inverse() * (position - listener_position);
+		let (azimuth, spread) = if relative_position.length_squared() == 0.0 {
+			(0.0, 1.0)
+		} else {
+			(
+				relative_position.x.atan2(-relative_position.z),
+				1.0 - spatialization_strength,
+			)
+		};
+		channel_layout.pan(azimuth, spread)
+			* input.as_mono().left
+			* self.attenuation(listener_position, position)
+	}
+
+	/// Returns the amplitude of the track at the given distance
+	/// from the listener.
+	#[must_use]
+	fn attenuation(&self, listener_position: Vec3, position: Vec3) -> f32 {
+		let Some(attenuation_function) = self.attenuation_function else {
+			return 1.0;
+		};
+		let distance = (listener_position - position).length();
+		let relative_distance = self.distances.relative_distance(distance);
+		let relative_volume = attenuation_function.apply((1.0 - relative_distance).into()) as f32;
+		Tweenable::interpolate(
+			Decibels::SILENCE,
+			Decibels::IDENTITY,
+			relative_volume.into(),
+		)
+		.as_amplitude()
+	}
 }
 
 #[must_use]
@@ -2,14 +2,16 @@ use std::{collections::HashMap, sync::Arc};
 
 use crate::{
 	Decibels, Frame, Parameter, Value,
-	backend::{RendererShared, resources::ResourceStorage},
+	backend::{ChannelFrame, RendererShared, resources::ResourceStorage},
 	command::command_writer_and_reader,
 	effect::EffectBuilder,
+	event::event_queue,
 	playback_state_manager::PlaybackStateManager,
+	sound::PlaybackState,
 };
 
 use super::{
-	Effect, SendTrackId, SendTrackRoute, Track, TrackHandle, TrackShared,
+	Effect, SendTrackId, SendTrackRoute, Taps, Track, TrackHandle, TrackShared, TrackTimings,
 	command_writers_and_readers,
 };
 
@@ -24,6 +26,7 @@ pub struct TrackBuilder {
 	pub(crate) sub_track_capacity: usize,
 	/// The maximum number of sounds that can be played simultaneously on this track.
 	pub(crate) sound_capacity: usize,
+	pub(crate) tap_capacity: usize,
 	pub(crate) sends: HashMap<SendTrackId, Value<Decibels>>,
 	pub(crate) persist_until_sounds_finish: bool,
 }
@@ -37,6 +40,7 @@ impl TrackBuilder {
 			effects: vec![],
 			sub_track_capacity: 128,
 			sound_capacity: 128,
+			tap_capacity: 4,
 			sends: HashMap::new(),
 			persist_until_sounds_finish: false,
 		}
@@ -106,6 +110,16 @@ impl TrackBuilder {
 		}
 	}
 
+	/// Sets the maximum number of [`TrackTap`](crate::track::TrackTap)s that can be attached
+	/// to this track at a time.
+	#[must_use = "This method consumes self and returns a modified TrackBuilder, so the return value should be used"]
+	pub fn tap_capacity(self, capacity: usize) -> Self {
+		Self {
+			tap_capacity: capacity,
+			..self
+		}
+	}
+
 	/// Routes this track to the given send track with the given volume.
 	pub fn with_send(
 		mut self,
@@ -232,8 +246,11 @@ impl TrackBuilder {
 	) -> (Track, TrackHandle) {
 		let (command_writers, command_readers) = command_writers_and_readers();
 		let shared = Arc::new(TrackShared::new());
+		let timings = Arc::new(TrackTimings::new(self.effects.len()));
+		let (event_producer, event_consumer) = event_queue();
 		let (sounds, sound_controller) = ResourceStorage::new(self.sound_capacity);
 		let (sub_tracks, sub_track_controller) = ResourceStorage::new(self.sub_track_capacity);
+		let (taps, tap_controller) = Taps::new(self.tap_capacity);
 		let mut sends = vec![];
 		let mut send_volume_command_writers = HashMap::new();
 		for (send_track_id, volume) in self.sends {
@@ -249,7 +266,11 @@ impl TrackBuilder {
 			send_volume_command_writers.insert(send_track_id, set_volume_command_writer);
 		}
 		let track = Track {
+			renderer_shared: renderer_shared.clone(),
 			shared: shared.clone(),
+			timings: timings.clone(),
+			event_producer,
+			previous_playback_state: PlaybackState::Playing,
 			command_readers,
 			volume: Parameter::new(self.volume, Decibels::IDENTITY),
 			sounds,
@@ -259,15 +280,20 @@ impl TrackBuilder {
 			persist_until_sounds_finish: self.persist_until_sounds_finish,
 			spatial_data: None,
 			playback_state_manager: PlaybackStateManager::new(None),
+			taps,
 			temp_buffer: vec![Frame::ZERO; internal_buffer_size],
+			temp_bus_buffer: vec![ChannelFrame::ZERO; internal_buffer_size],
 			internal_buffer_size,
 		};
 		let handle = TrackHandle {
 			renderer_shared,
 			shared,
+			timings,
+			event_consumer,
 			command_writers,
 			sound_controller,
 			sub_track_controller,
+			tap_controller,
 			send_volume_command_writers,
 			internal_buffer_size,
 		};
@@ -4,17 +4,19 @@ use glam::Vec3;
 
 use crate::{
 	Decibels, Frame, Parameter, Value,
-	backend::{RendererShared, resources::ResourceStorage},
+	backend::{ChannelFrame, RendererShared, resources::ResourceStorage},
 	command::command_writer_and_reader,
 	effect::EffectBuilder,
+	event::event_queue,
 	listener::ListenerId,
 	playback_state_manager::PlaybackStateManager,
+	sound::PlaybackState,
 	tween::Easing,
 };
 
 use super::{
-	Effect, SendTrackId, SendTrackRoute, SpatialData, SpatialTrackHandle, Track, TrackShared,
-	command_writers_and_readers,
+	Effect, SendTrackId, SendTrackRoute, SpatialData, SpatialTrackHandle, Taps, Track, TrackShared,
+	TrackTimings, command_writers_and_readers,
 };
 
 /// Configures a spatial mixer track.
@@ -28,6 +30,7 @@ pub struct SpatialTrackBuilder {
 	pub(crate) sub_track_capacity: usize,
 	/// The maximum number of sounds that can be played simultaneously on this track.
 	pub(crate) sound_capacity: usize,
+	pub(crate) tap_capacity: usize,
 	pub(crate) sends: HashMap<SendTrackId, Value<Decibels>>,
 	pub(crate) persist_until_sounds_finish: bool,
 	/// The distances from a listener at which the track is loudest and quietest.
@@ -53,6 +56,7 @@ impl SpatialTrackBuilder {
 			effects: vec![],
 			sub_track_capacity: 128,
 			sound_capacity: 128,
+			tap_capacity: 4,
 			sends: HashMap::new(),
 			persist_until_sounds_finish: false,
 			distances: SpatialTrackDistances::default(),
@@ -125,6 +129,16 @@ impl SpatialTrackBuilder {
 		}
 	}
 
+	/// Sets the maximum number of [`TrackTap`](crate::track::TrackTap)s that can be attached
+	/// to this track at a time.
+	#[must_use = "This method consumes self and returns a modified SpatialTrackBuilder, so the return value should be used"]
+	pub fn tap_capacity(self, capacity: usize) -> Self {
+		Self {
+			tap_capacity: capacity,
+			..self
+		}
+	}
+
 	/// Routes this track to the given send track with the given volume.
 	pub fn with_send(
 		mut self,
@@ -268,6 +282,10 @@ impl SpatialTrackBuilder {
 	///
 	/// This value should be between `0.0` and `1.0`. `0.0` disables spatialization
 	/// entirely.
+	///
+	/// With a surround [`ChannelLayout`](crate::backend::ChannelLayout), the output is
+	/// panned across every speaker around the listener instead, and lower values spread
+	/// it more evenly across the speakers.
 	#[must_use = "This method consumes self and returns a modified SpatialTrackBuilder, so the return value should be used"]
 	pub fn spatialization_strength(self, spatialization_strength: impl Into<Value<f32>>) -> Self {
 		Self {
@@ -286,8 +304,11 @@ impl SpatialTrackBuilder {
 	) -> (Track, SpatialTrackHandle) {
 		let (command_writers, command_readers) = command_writers_and_readers();
 		let shared = Arc::new(TrackShared::new());
+		let timings = Arc::new(TrackTimings::new(self.effects.len()));
+		let (event_producer, event_consumer) = event_queue();
 		let (sounds, sound_controller) = ResourceStorage::new(self.sound_capacity);
 		let (sub_tracks, sub_track_controller) = ResourceStorage::new(self.sub_track_capacity);
+		let (taps, tap_controller) = Taps::new(self.tap_capacity);
 		let mut sends = vec![];
 		let mut send_volume_command_writers = HashMap::new();
 		for (send_track_id, volume) in self.sends {
@@ -303,7 +324,11 @@ impl SpatialTrackBuilder {
 			send_volume_command_writers.insert(send_track_id, set_volume_command_writer);
 		}
 		let track = Track {
+			renderer_shared: renderer_shared.clone(),
 			shared: shared.clone(),
+			timings: timings.clone(),
+			event_producer,
+			previous_playback_state: PlaybackState::Playing,
 			command_readers,
 			volume: Parameter::new(self.volume, Decibels::IDENTITY),
 			sounds,
@@ -319,15 +344,20 @@ impl SpatialTrackBuilder {
 				spatialization_strength: Parameter::new(self.spatialization_strength, 0.75),
 			}),
 			playback_state_manager: PlaybackStateManager::new(None),
+			taps,
 			temp_buffer: vec![Frame::ZERO; internal_buffer_size],
+			temp_bus_buffer: vec![ChannelFrame::ZERO; internal_buffer_size],
 			internal_buffer_size,
 		};
 		let handle = SpatialTrackHandle {
 			renderer_shared,
 			shared,
+			timings,
+			event_consumer,
 			command_writers,
 			sound_controller,
 			sub_track_controller,
+			tap_controller,
 			send_volume_command_writers,
 			internal_buffer_size,
 		};
@@ -4,17 +4,18 @@ use std::{
 };
 
 use crate::{
-	Decibels, PlaySoundError, ResourceLimitReached, StartTime, Tween, Value,
+	Decibels, Event, PlaySoundError, ResourceLimitReached, StartTime, Tween, Value,
 	backend::{RendererShared, resources::ResourceController},
 	command::{CommandWriter, ValueChangeCommand},
+	event::EventConsumer,
 	listener::ListenerId,
 	sound::{Sound, SoundData},
-	track::TrackPlaybackState,
+	track::{TapController, TrackPlaybackState, TrackProfile, TrackTap, profiling::TrackTimings},
 };
 
 use super::{
 	CommandWriters, NonexistentRoute, SendTrackId, SpatialTrackBuilder, Track, TrackBuilder,
-	TrackHandle, TrackShared,
+	TrackEvent, TrackHandle, TrackShared,
 };
 
 /// Controls a mixer track.
@@ -25,9 +26,12 @@ use super::{
 pub struct SpatialTrackHandle {
 	pub(crate) renderer_shared: Arc<RendererShared>,
 	pub(crate) shared: Arc<TrackShared>,
+	pub(crate) timings: Arc<TrackTimings>,
+	pub(crate) event_consumer: EventConsumer<TrackEvent>,
 	pub(crate) command_writers: CommandWriters,
 	pub(crate) sound_controller: ResourceController<Box<dyn Sound>>,
 	pub(crate) sub_track_controller: ResourceController<Track>,
+	pub(crate) tap_controller: TapController,
 	pub(crate) send_volume_command_writers:
 		HashMap<SendTrackId, CommandWriter<ValueChangeCommand<Decibels>>>,
 	pub(crate) internal_buffer_size: usize,
@@ -40,14 +44,26 @@ impl SpatialTrackHandle {
 		self.shared.state()
 	}
 
+	/// Returns the next event that happened to the track, if any.
+	///
+	/// Events are returned in the order they happened.
+	#[must_use]
+	pub fn pop_event(&mut self) -> Option<Event<TrackEvent>> {
+		self.event_consumer.pop()
+	}
+
 	/// Plays a sound.
 	pub fn play<D: SoundData>(
 		&mut self,
 		sound_data: D,
 	) -> Result<D::Handle, PlaySoundError<D::Error>> {
-		let (sound, handle) = sound_data
+		let (mut sound, handle) = sound_data
 			.into_sound()
 			.map_err(PlaySoundError::IntoSoundError)?;
+		sound.init(
+			self.renderer_shared.sample_rate.load(Ordering::SeqCst),
+			self.internal_buffer_size,
+		);
 		self.sound_controller
 			.insert(sound)
 			.map_err(|_| PlaySoundError::SoundLimitReached)?;
@@ -183,6 +199,41 @@ impl SpatialTrackHandle {
 	pub fn num_sub_tracks(&self) -> usize {
 		self.sub_track_controller.len()
 	}
+
+	/**
+	Adds a tap to the track, which receives the audio the track
+	outputs after its effects and volume have been applied.
+
+	`buffer_capacity` is the number of frames the tap can hold before
+	new frames are dropped.
+	*/
+	pub fn add_tap(&mut self, buffer_capacity: usize) -> Result<TrackTap, ResourceLimitReached> {
+		self.tap_controller.add_tap(buffer_capacity)
+	}
+
+	/// Returns the maximum number of taps this track can have.
+	#[must_use]
+	pub fn tap_capacity(&self) -> usize {
+		self.tap_controller.capacity()
+	}
+
+	/// Returns the number of taps this track has.
+	#[must_use]
+	pub fn num_taps(&self) -> usize {
+		self.tap_controller.len()
+	}
+
+	/**
+	Returns how much time the audio thread spent processing this
+	track since the last time this was called.
+
+	Timings are only recorded while profiling is enabled with
+	[`AudioManager::set_profiling_enabled`](crate::AudioManager::set_profiling_enabled).
+	*/
+	#[must_use]
+	pub fn pop_profile(&mut self) -> TrackProfile {
+		self.timings.pop()
+	}
 }
 
 impl Drop for SpatialTrackHandle {
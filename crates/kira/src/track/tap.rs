@@ -0,0 +1,169 @@
+use std::{
+	fmt::{Debug, Formatter},
+	sync::{
+		Arc, Mutex,
+		atomic::{AtomicBool, AtomicU64, Ordering},
+	},
+};
+
+use rtrb::{Consumer, Producer, RingBuffer};
+
+use crate::{
+	Frame, ResourceLimitReached,
+	backend::resources::{ResourceController, ResourceStorage},
+};
+
+/**
+Receives the output of a mixer track.
+
+Taps receive the audio a track outputs after its effects and volume
+have been applied. If the tap isn't read often enough and its buffer
+fills up, new frames will be dropped, and the number of dropped frames
+can be checked with [`TrackTap::num_dropped_frames`].
+
+When a [`TrackTap`] is dropped, it's removed from the mixer track.
+*/
+pub struct TrackTap {
+	consumer: Mutex<Consumer<Frame>>,
+	shared: Arc<TapShared>,
+}
+
+impl TrackTap {
+	/// Returns the next frame output by the track, if there is one.
+	pub fn pop(&mut self) -> Option<Frame> {
+		self.consumer
+			.get_mut()
+			.expect("tap consumer mutex poisoned")
+			.pop()
+			.ok()
+	}
+
+	/// Fills `out` with as many frames output by the track as are available
+	/// and returns the number of frames that were written.
+	pub fn read(&mut self, out: &mut [Frame]) -> usize {
+		let consumer = self
+			.consumer
+			.get_mut()
+			.expect("tap consumer mutex poisoned");
+		let num_frames = out.len().min(consumer.slots());
+		let Ok(chunk) = consumer.read_chunk(num_frames) else {
+			return 0;
+		};
+		let (first, second) = chunk.as_slices();
+		out[..first.len()].copy_from_slice(first);
+		out[first.len()..num_frames].copy_from_slice(second);
+		chunk.commit_all();
+		num_frames
+	}
+
+	/// Returns the number of frames that are ready to be read.
+	#[must_use]
+	pub fn num_available_frames(&self) -> usize {
+		self.consumer
+			.lock()
+			.expect("tap consumer mutex poisoned")
+			.slots()
+	}
+
+	/// Returns the number of frames that were dropped because
+	/// the tap's buffer was full.
+	#[must_use]
+	pub fn num_dropped_frames(&self) -> u64 {
+		self.shared.num_dropped_frames.load(Ordering::SeqCst)
+	}
+}
+
+impl Drop for TrackTap {
+	fn drop(&mut self) {
+		self.shared.removed.store(true, Ordering::SeqCst);
+	}
+}
+
+impl Debug for TrackTap {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("TrackTap")
+			.field("shared", &self.shared)
+			.finish_non_exhaustive()
+	}
+}
+
+#[derive(Debug)]
+struct TapShared {
+	removed: AtomicBool,
+	num_dropped_frames: AtomicU64,
+}
+
+pub(crate) struct Tap {
+	producer: Producer<Frame>,
+	shared: Arc<TapShared>,
+}
+
+impl Tap {
+	fn write(&mut self, frames: &[Frame]) {
+		let num_frames = frames.len().min(self.producer.slots());
+		if let Ok(chunk) = self.producer.write_chunk_uninit(num_frames) {
+			chunk.fill_from_iter(frames.iter().copied());
+		}
+		let num_dropped_frames = frames.len() - num_frames;
+		if num_dropped_frames > 0 {
+			self.shared
+				.num_dropped_frames
+				.fetch_add(num_dropped_frames as u64, Ordering::SeqCst);
+		}
+	}
+}
+
+/// The taps attached to a mixer track.
+pub(crate) struct Taps(ResourceStorage<Tap>);
+
+impl Taps {
+	#[must_use]
+	pub fn new(capacity: usize) -> (Self, TapController) {
+		let (taps, controller) = ResourceStorage::new(capacity);
+		(Self(taps), TapController(controller))
+	}
+
+	pub fn on_start_processing(&mut self) {
+		self.0
+			.remove_and_add(|tap| tap.shared.removed.load(Ordering::SeqCst));
+	}
+
+	/// Sends a track's output to every tap.
+	pub fn write(&mut self, frames: &[Frame]) {
+		for (_, tap) in &mut self.0 {
+			tap.write(frames);
+		}
+	}
+}
+
+/// Adds taps to a mixer track from gameplay code.
+#[derive(Debug)]
+pub(crate) struct TapController(ResourceController<Tap>);
+
+impl TapController {
+	pub fn add_tap(&mut self, buffer_capacity: usize) -> Result<TrackTap, ResourceLimitReached> {
+		let (producer, consumer) = RingBuffer::new(buffer_capacity);
+		let shared = Arc::new(TapShared {
+			removed: AtomicBool::new(false),
+			num_dropped_frames: AtomicU64::new(0),
+		});
+		self.0.insert(Tap {
+			producer,
+			shared: shared.clone(),
+		})?;
+		Ok(TrackTap {
+			consumer: Mutex::new(consumer),
+			shared,
+		})
+	}
+
+	#[must_use]
+	pub fn capacity(&self) -> usize {
+		self.0.capacity()
+	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use kira::{
+	AudioManager, AudioManagerSettings, Event, Frame, StartTime, Tween,
+	backend::offline::{OfflineBackend, OfflineBackendSettings},
+	clock::{ClockEvent, ClockSpeed, ClockTime},
+	sound::{
+		SoundEvent,
+		static_sound::{StaticSoundData, StaticSoundSettings},
+	},
+	track::{MainTrackBuilder, TrackBuilder, TrackEvent},
+};
+
+fn create_manager() -> AudioManager<OfflineBackend> {
+	AudioManager::<OfflineBackend>::new(AudioManagerSettings {
+		backend_settings: OfflineBackendSettings {
+			sample_rate: 10,
+			num_channels: 2,
+		},
+		main_track_builder: MainTrackBuilder::new(),
+		internal_buffer_size: 4,
+		..Default::default()
+	})
+	.unwrap()
+}
+
+fn test_sound(num_frames: usize) -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 10,
+		frames: vec![Frame::from_mono(0.5); num_frames].into(),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+}
+
+#[test]
+fn sound_events_have_audio_timestamps() {
+	let mut manager = create_manager();
+	let mut clock = manager.add_clock(ClockSpeed::TicksPerSecond(1.0)).unwrap();
+	let mut sound = manager
+		.play(test_sound(10).start_time(StartTime::ClockTime(ClockTime {
+			clock: clock.id(),
+			ticks: 1,
+			fraction: 0.0,
+		})))
+		.unwrap();
+	clock.start();
+	manager.backend_mut().render_frames(40);
+
+	let events = std::iter::from_fn(|| sound.pop_event()).collect::<Vec<_>>();
+	assert_eq!(events.len(), 2);
+	assert_eq!(events[0].kind, SoundEvent::Started);
+	assert_eq!(events[1].kind, SoundEvent::Finished);
+	// the sound starts around when the clock ticks and
+	// finishes about a second later
+	assert!(events[0].time >= Duration::from_millis(500));
+	assert!(events[0].time <= Duration::from_millis(1500));
+	let play_time = events[1].time - events[0].time;
+	assert!(play_time >= Duration::from_millis(900));
+	assert!(play_time <= Duration::from_millis(1500));
+
+	assert_eq!(
+		clock.pop_event(),
+		Some(Event {
+			kind: ClockEvent::Ticked { ticks: 0 },
+			time: Duration::ZERO,
+		})
+	);
+	let event = clock.pop_event().unwrap();
+	assert_eq!(event.kind, ClockEvent::Ticked { ticks: 1 });
+	assert!((event.time.as_secs_f64() - 1.0).abs() < 0.000001);
+}
+
+#[test]
+fn track_events() {
+	let mut manager = create_manager();
+	let mut track = manager.add_sub_track(TrackBuilder::new()).unwrap();
+	manager.backend_mut().render_frames(4);
+	assert!(track.pop_event().is_none());
+
+	track.pause(Tween {
+		duration: Duration::from_millis(200),
+		..Default::default()
+	});
+	manager.backend_mut().render_frames(8);
+	let event = track.pop_event().unwrap();
+	assert_eq!(event.kind, TrackEvent::Paused);
+	assert!(event.time >= Duration::from_millis(600));
+	assert!(track.pop_event().is_none());
+
+	track.resume(Tween::default());
+	manager.backend_mut().render_frames(8);
+	assert_eq!(
+		track.pop_event().map(|event| event.kind),
+		Some(TrackEvent::Resumed)
+	);
+	assert!(track.pop_event().is_none());
+}
@@ -0,0 +1,82 @@
+use std::{
+	sync::{
+		Arc,
+		atomic::{AtomicUsize, Ordering},
+	},
+	thread,
+	time::{Duration, Instant},
+};
+
+use kira::{
+	AudioManager, AudioManagerSettings,
+	backend::headless::{HeadlessBackend, HeadlessBackendSettings},
+	clock::ClockSpeed,
+};
+
+#[test]
+fn renders_at_realtime_speed() {
+	let num_samples = Arc::new(AtomicUsize::new(0));
+	let mut manager = AudioManager::<HeadlessBackend>::new(AudioManagerSettings {
+		backend_settings: HeadlessBackendSettings {
+			sample_rate: 1000,
+			num_channels: 2,
+			buffer_size: 10,
+			output: Some(Box::new({
+				let num_samples = num_samples.clone();
+				move |samples: &[f32]| {
+					num_samples.fetch_add(samples.len(), Ordering::SeqCst);
+				}
+			})),
+		},
+		internal_buffer_size: 10,
+		..Default::default()
+	})
+	.unwrap();
+	let mut clock = manager
+		.add_clock(ClockSpeed::TicksPerSecond(100.0))
+		.unwrap();
+	clock.start();
+
+	let start_time = Instant::now();
+	thread::sleep(Duration::from_millis(300));
+	let elapsed = start_time.elapsed().as_secs_f64();
+	let num_frames = manager.backend_mut().num_frames_rendered();
+	// the backend renders one buffer ahead of realtime
+	assert!(num_frames as f64 <= elapsed * 1000.0 + 20.0);
+	assert!(num_frames >= 100);
+	assert!(clock.time().ticks >= 10);
+	assert!(clock.time().ticks as f64 <= elapsed * 100.0 + 2.0);
+	let num_samples = num_samples.load(Ordering::SeqCst);
+	assert!(num_samples >= num_frames as usize * 2);
+	assert!(num_samples <= (num_frames as usize + 10) * 2);
+}
+
+#[test]
+fn renders_with_at_least_one_channel_and_hertz() {
+	let num_samples = Arc::new(AtomicUsize::new(0));
+	let mut manager = AudioManager::<HeadlessBackend>::new(AudioManagerSettings {
+		backend_settings: HeadlessBackendSettings {
+			sample_rate: 0,
+			num_channels: 0,
+			buffer_size: 10,
+			output: Some(Box::new({
+				let num_samples = num_samples.clone();
+				move |samples: &[f32]| {
+					num_samples.fetch_add(samples.len(), Ordering::SeqCst);
+				}
+			})),
+		},
+		internal_buffer_size: 10,
+		..Default::default()
+	})
+	.unwrap();
+	let backend = manager.backend_mut();
+	assert_eq!(backend.sample_rate(), 1);
+	assert_eq!(backend.num_channels(), 1);
+	let start_time = Instant::now();
+	while backend.num_frames_rendered() == 0 {
+		assert!(start_time.elapsed() < Duration::from_secs(5));
+		thread::sleep(Duration::from_millis(1));
+	}
+	assert!(num_samples.load(Ordering::SeqCst) >= 10);
+}
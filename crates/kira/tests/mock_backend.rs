@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use kira::{
+	AudioManager, AudioManagerSettings, Frame, Panning,
+	backend::mock::{MockBackend, MockBackendSettings},
+	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+};
+
+fn create_manager() -> AudioManager<MockBackend> {
+	AudioManager::<MockBackend>::new(AudioManagerSettings {
+		backend_settings: MockBackendSettings { sample_rate: 10 },
+		internal_buffer_size: 4,
+		..Default::default()
+	})
+	.unwrap()
+}
+
+fn test_sound() -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 10,
+		frames: (0..20).map(|i| Frame::from_mono(i as f32 / 20.0)).collect(),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+}
+
+#[test]
+fn renders_frames() {
+	let mut manager = create_manager();
+	manager.play(test_sound()).unwrap();
+	let frames = manager.backend_mut().render(25);
+	assert_eq!(frames.len(), 25);
+	for (i, frame) in frames.iter().enumerate().take(20) {
+		assert_eq!(
+			*frame,
+			Frame::from_mono(i as f32 / 20.0).panned(Panning::CENTER)
+		);
+	}
+	assert!(frames[20..].iter().all(|frame| *frame == Frame::ZERO));
+}
+
+#[test]
+fn output_does_not_depend_on_chunk_size() {
+	let render = |chunk_size| {
+		let mut manager = create_manager();
+		manager.backend_mut().set_chunk_size(chunk_size);
+		assert_eq!(manager.backend_mut().chunk_size(), chunk_size);
+		manager.play(test_sound()).unwrap();
+		manager.backend_mut().render(30)
+	};
+	let expected = render(4);
+	for chunk_size in [1, 3, 7, 16] {
+		assert_eq!(render(chunk_size), expected);
+	}
+}
+
+#[test]
+fn advances_by_duration() {
+	let mut manager = create_manager();
+	manager.play(test_sound()).unwrap();
+	manager.backend_mut().advance(Duration::from_millis(500));
+	let frames = manager.backend_mut().render(3);
+	for (i, frame) in frames.into_iter().enumerate() {
+		assert_eq!(
+			frame,
+			Frame::from_mono((i + 5) as f32 / 20.0).panned(Panning::CENTER)
+		);
+	}
+	manager.backend_mut().advance(Duration::from_secs(2));
+	assert!(
+		manager
+			.backend_mut()
+			.render(4)
+			.iter()
+			.all(|frame| *frame == Frame::ZERO)
+	);
+}
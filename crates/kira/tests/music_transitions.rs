@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use kira::{
+	AudioManager, AudioManagerSettings, Frame,
+	backend::mock::{MockBackend, MockBackendSettings},
+	clock::ClockSpeed,
+	music::{MusicController, MusicSegment, MusicTransition, Quantization},
+	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+	track::TrackBuilder,
+};
+
+fn create_manager() -> AudioManager<MockBackend> {
+	AudioManager::<MockBackend>::new(AudioManagerSettings {
+		backend_settings: MockBackendSettings { sample_rate: 1000 },
+		internal_buffer_size: 128,
+		..Default::default()
+	})
+	.unwrap()
+}
+
+fn constant_sound(value: f32, num_frames: usize) -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 1000,
+		frames: vec![Frame::from_mono(value); num_frames].into(),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+}
+
+fn create_controller(manager: &mut AudioManager<MockBackend>) -> MusicController {
+	let track = manager.add_sub_track(TrackBuilder::new()).unwrap();
+	let clock = manager.add_clock(ClockSpeed::TicksPerSecond(10.0)).unwrap();
+	MusicController::new(track, clock)
+		.with_segment(
+			"explore",
+			MusicSegment::new(constant_sound(0.25, 800).loop_region(..)).num_beats(8),
+		)
+		.with_segment(
+			"combat",
+			MusicSegment::new(constant_sound(0.5, 800).loop_region(..)).num_beats(8),
+		)
+}
+
+fn expected(value: f32) -> Frame {
+	Frame::from_mono(value).panned(Default::default())
+}
+
+fn first_frame_matching(frames: &[Frame], value: f32) -> Option<usize> {
+	frames.iter().position(|frame| *frame == expected(value))
+}
+
+#[test]
+fn transitions_at_the_next_bar() {
+	let mut manager = create_manager();
+	let mut music = create_controller(&mut manager);
+	music.play("explore").unwrap();
+	music.clock_mut().start();
+	manager.backend_mut().render(150);
+	let transition_time = music.transition_to("combat").unwrap();
+	assert_eq!(transition_time.ticks, 4);
+	assert_eq!(music.current_segment(), Some("combat"));
+	let frames = manager.backend_mut().render(500);
+	// the transition happens at tick 4, which is 400 frames
+	// after the clock started
+	assert_eq!(first_frame_matching(&frames, 0.5), Some(250));
+	assert!(frames[..250].iter().all(|frame| *frame == expected(0.25)));
+	assert!(frames[250..].iter().all(|frame| *frame == expected(0.5)));
+}
+
+#[test]
+fn counts_bars_from_the_start_of_the_segment() {
+	let mut manager = create_manager();
+	let mut music = create_controller(&mut manager);
+	music.clock_mut().start();
+	manager.backend_mut().render(50);
+	// the clock ticks before the audio thread sees the new segment,
+	// so it starts on the next beat
+	music.play("explore").unwrap();
+	manager.backend_mut().render(200);
+	assert_eq!(music.current_segment_start_time().unwrap().ticks, 1);
+	let transition_time = music.transition_to("combat").unwrap();
+	assert_eq!(transition_time.ticks, 5);
+	let frames = manager.backend_mut().render(500);
+	assert_eq!(first_frame_matching(&frames, 0.5), Some(250));
+	assert!(frames[..250].iter().all(|frame| *frame == expected(0.25)));
+}
+
+#[test]
+fn transitions_at_the_end_of_the_segment() {
+	let mut manager = create_manager();
+	let mut music = create_controller(&mut manager).with_transition(
+		"explore",
+		"combat",
+		MusicTransition::new(Quantization::EndOfSegment),
+	);
+	music.play("explore").unwrap();
+	music.clock_mut().start();
+	manager.backend_mut().render(150);
+	let transition_time = music.transition_to("combat").unwrap();
+	assert_eq!(transition_time.ticks, 8);
+	let frames = manager.backend_mut().render(800);
+	assert_eq!(first_frame_matching(&frames, 0.5), Some(650));
+	assert!(frames[..500].iter().all(|frame| *frame == expected(0.25)));
+}
+
+#[test]
+fn plays_stingers_at_the_transition() {
+	let mut manager = create_manager();
+	let mut music = create_controller(&mut manager).with_transition_to(
+		"combat",
+		MusicTransition::new(Quantization::NextBeat).stinger(constant_sound(0.125, 10)),
+	);
+	music.play("explore").unwrap();
+	music.clock_mut().start();
+	manager.backend_mut().render(150);
+	let transition_time = music.transition_to("combat").unwrap();
+	assert_eq!(transition_time.ticks, 2);
+	let frames = manager.backend_mut().render(300);
+	assert_eq!(first_frame_matching(&frames, 0.625), Some(50));
+	assert!(frames[50..60].iter().all(|frame| *frame == expected(0.625)));
+	assert!(frames[60..].iter().all(|frame| *frame == expected(0.5)));
+}
+
+#[test]
+fn crossfades_between_segments() {
+	let mut manager = create_manager();
+	let mut music = create_controller(&mut manager).default_transition(
+		MusicTransition::new(Quantization::NextBeat).crossfade(Duration::from_millis(100)),
+	);
+	music.play("explore").unwrap();
+	music.clock_mut().start();
+	manager.backend_mut().render(150);
+	music.transition_to("combat").unwrap();
+	let frames = manager.backend_mut().render(400);
+	// the segments crossfade over the 100 frames after the
+	// transition at frame 50
+	assert!(
+		frames[60..140]
+			.iter()
+			.all(|frame| *frame != expected(0.25) && *frame != expected(0.5))
+	);
+	assert!(frames[300..].iter().all(|frame| *frame == expected(0.5)));
+}
+
+#[test]
+fn transitioning_to_the_current_segment_does_nothing() {
+	let mut manager = create_manager();
+	let mut music = create_controller(&mut manager);
+	let start_time = music.play("explore").unwrap();
+	music.clock_mut().start();
+	manager.backend_mut().render(150);
+	assert_eq!(music.transition_to("explore").unwrap(), start_time);
+	assert!(music.transition_to("boss").is_err());
+}
@@ -0,0 +1,184 @@
+use std::{sync::Arc, time::Duration};
+
+use kira::{
+	AudioManager, AudioManagerSettings, Decibels, Frame,
+	backend::{
+		OutputStage, StereoUpmix,
+		offline::{OfflineBackend, OfflineBackendSettings},
+	},
+	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+};
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+	u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+	u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn play_frame(manager: &mut AudioManager<OfflineBackend>, frame: Frame) {
+	let sample_rate = manager.backend_mut().sample_rate();
+	manager
+		.play(StaticSoundData {
+			sample_rate,
+			frames: Arc::new([frame]),
+			settings: StaticSoundSettings::new(),
+			slice: None,
+		})
+		.unwrap();
+}
+
+#[test]
+fn renders_audio_on_demand() {
+	let mut manager = AudioManager::<OfflineBackend>::new(AudioManagerSettings {
+		backend_settings: OfflineBackendSettings {
+			sample_rate: 10,
+			num_channels: 2,
+		},
+		internal_buffer_size: 4,
+		..Default::default()
+	})
+	.unwrap();
+	manager
+		.play(StaticSoundData {
+			sample_rate: 10,
+			frames: Arc::new([
+				Frame::from_mono(0.5),
+				Frame::from_mono(0.25),
+				Frame::from_mono(-0.5),
+			]),
+			settings: StaticSoundSettings::new(),
+			slice: None,
+		})
+		.unwrap();
+	let backend = manager.backend_mut();
+	assert_eq!(backend.num_frames(), 0);
+	backend.render(Duration::from_secs(1));
+	assert_eq!(backend.num_frames(), 10);
+	let samples = backend.samples();
+	assert_eq!(samples.len(), 20);
+	for (i, expected) in [0.5, 0.25, -0.5].into_iter().enumerate() {
+		assert_eq!(samples[i * 2], expected);
+		assert_eq!(samples[i * 2 + 1], expected);
+	}
+	assert!(samples[6..].iter().all(|sample| *sample == 0.0));
+	backend.clear();
+	assert_eq!(backend.num_frames(), 0);
+}
+
+#[test]
+fn writes_wav() {
+	let mut manager = AudioManager::<OfflineBackend>::new(AudioManagerSettings {
+		backend_settings: OfflineBackendSettings {
+			sample_rate: 100,
+			num_channels: 1,
+		},
+		..Default::default()
+	})
+	.unwrap();
+	let backend = manager.backend_mut();
+	backend.render_frames(3);
+	let mut bytes = vec![];
+	backend.write_wav(&mut bytes).unwrap();
+	assert_eq!(bytes.len(), 58 + 3 * 4);
+	assert_eq!(&bytes[0..4], b"RIFF");
+	assert_eq!(read_u32(&bytes, 4), bytes.len() as u32 - 8);
+	assert_eq!(&bytes[8..12], b"WAVE");
+	assert_eq!(&bytes[12..16], b"fmt ");
+	assert_eq!(read_u16(&bytes, 20), 3);
+	assert_eq!(read_u16(&bytes, 22), 1);
+	assert_eq!(read_u32(&bytes, 24), 100);
+	assert_eq!(read_u32(&bytes, 28), 400);
+	assert_eq!(read_u16(&bytes, 32), 4);
+	assert_eq!(read_u16(&bytes, 34), 32);
+	assert_eq!(&bytes[38..42], b"fact");
+	assert_eq!(read_u32(&bytes, 46), 3);
+	assert_eq!(&bytes[50..54], b"data");
+	assert_eq!(read_u32(&bytes, 54), 12);
+}
+
+#[test]
+fn renders_at_least_one_channel() {
+	let mut manager = AudioManager::<OfflineBackend>::new(AudioManagerSettings {
+		backend_settings: OfflineBackendSettings {
+			sample_rate: 10,
+			num_channels: 0,
+		},
+		..Default::default()
+	})
+	.unwrap();
+	play_frame(&mut manager, Frame::from_mono(0.5));
+	let backend = manager.backend_mut();
+	assert_eq!(backend.num_channels(), 1);
+	backend.render_frames(2);
+	assert_eq!(backend.num_frames(), 2);
+	assert_eq!(backend.samples(), [0.5, 0.0]);
+}
+
+#[test]
+fn maps_stereo_mix_to_channel_layout() {
+	let render = |num_channels, stereo_upmix| {
+		let mut manager = AudioManager::<OfflineBackend>::new(AudioManagerSettings {
+			backend_settings: OfflineBackendSettings {
+				sample_rate: 10,
+				num_channels,
+			},
+			stereo_upmix,
+			..Default::default()
+		})
+		.unwrap();
+		play_frame(&mut manager, Frame::new(0.5, 0.25));
+		let backend = manager.backend_mut();
+		backend.render_frames(1);
+		backend.samples().to_vec()
+	};
+	let attenuation = std::f32::consts::FRAC_1_SQRT_2;
+	assert_eq!(render(1, StereoUpmix::FrontOnly), [0.375]);
+	assert_eq!(render(2, StereoUpmix::Surround), [0.5, 0.25]);
+	assert_eq!(render(3, StereoUpmix::Surround), [0.5, 0.25, 0.0]);
+	assert_eq!(
+		render(6, StereoUpmix::FrontOnly),
+		[0.5, 0.25, 0.0, 0.0, 0.0, 0.0]
+	);
+	assert_eq!(
+		render(6, StereoUpmix::Surround),
+		[
+			0.5,
+			0.25,
+			0.375 * attenuation,
+			0.0,
+			0.5 * attenuation,
+			0.25 * attenuation
+		]
+	);
+}
+
+#[test]
+fn reports_output_gain_reduction() {
+	let render = |output_stage| {
+		let mut manager = AudioManager::<OfflineBackend>::new(AudioManagerSettings {
+			backend_settings: OfflineBackendSettings {
+				sample_rate: 10,
+				num_channels: 2,
+			},
+			output_stage,
+			..Default::default()
+		})
+		.unwrap();
+		assert_eq!(manager.output_gain_reduction(), Decibels::IDENTITY);
+		play_frame(&mut manager, Frame::new(2.0, -0.5));
+		manager.backend_mut().render_frames(1);
+		(
+			manager.backend_mut().samples().to_vec(),
+			manager.output_gain_reduction(),
+		)
+	};
+	let (samples, gain_reduction) = render(OutputStage::Clamp);
+	assert_eq!(samples, [1.0, -0.5]);
+	assert!((gain_reduction.0 - 6.0206).abs() < 0.001);
+	let (samples, gain_reduction) = render(OutputStage::SoftClip);
+	assert!(samples[0] > 0.5 && samples[0] < 1.0);
+	assert_eq!(samples[1], -0.5);
+	assert!(gain_reduction.0 > 6.0206);
+}
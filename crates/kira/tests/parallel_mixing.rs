@@ -0,0 +1,157 @@
+use std::{cell::Cell, sync::Arc};
+
+use kira::{
+	AudioManager, AudioManagerSettings, Decibels, Easing, Frame, Mapping, Value,
+	backend::offline::{OfflineBackend, OfflineBackendSettings},
+	info::Info,
+	modulator::{Modulator, ModulatorBuilder, ModulatorId},
+	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+	track::{MainTrackBuilder, SendTrackBuilder, TrackBuilder},
+};
+
+const NUM_TRACKS: usize = 8;
+const NUM_FRAMES: usize = 1000;
+
+fn test_sound(seed: usize) -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 100,
+		frames: (0..NUM_FRAMES)
+			.map(|i| {
+				Frame::new(
+					((i * (seed + 1)) as f32 * 0.01).sin(),
+					(i as f32 * 0.03).cos(),
+				)
+			})
+			.collect::<Arc<[Frame]>>(),
+		settings: StaticSoundSettings::new().volume(Decibels(-12.0)),
+		slice: None,
+	}
+}
+
+/// A modulator that counts the seconds that have passed. It keeps
+/// its state in a `Cell`, so it isn't `Sync`.
+struct TimerBuilder;
+
+impl ModulatorBuilder for TimerBuilder {
+	type Handle = ModulatorId;
+
+	fn build(self, id: ModulatorId) -> (Box<dyn Modulator>, Self::Handle) {
+		(
+			Box::new(Timer {
+				time: Cell::new(0.0),
+			}),
+			id,
+		)
+	}
+}
+
+struct Timer {
+	time: Cell<f64>,
+}
+
+impl Modulator for Timer {
+	fn update(&mut self, dt: f64, _info: &Info) {
+		self.time.set(self.time.get() + dt);
+	}
+
+	fn value(&self) -> f64 {
+		self.time.get()
+	}
+
+	fn finished(&self) -> bool {
+		false
+	}
+}
+
+fn create_manager(num_mixer_threads: usize) -> AudioManager<OfflineBackend> {
+	AudioManager::<OfflineBackend>::new(AudioManagerSettings {
+		backend_settings: OfflineBackendSettings {
+			sample_rate: 100,
+			num_channels: 2,
+		},
+		main_track_builder: MainTrackBuilder::new(),
+		num_mixer_threads,
+		..Default::default()
+	})
+	.unwrap()
+}
+
+/// Renders a mix of sub-tracks, nested sub-tracks, and a send track.
+fn render(num_mixer_threads: usize) -> Vec<f32> {
+	let mut manager = create_manager(num_mixer_threads);
+	let send_track = manager.add_send_track(SendTrackBuilder::new()).unwrap();
+	let mut tracks = vec![];
+	for i in 0..NUM_TRACKS {
+		let mut track = manager
+			.add_sub_track(
+				TrackBuilder::new()
+					.volume(Decibels(-(i as f32)))
+					.with_send(&send_track, Decibels(-6.0)),
+			)
+			.unwrap();
+		track.play(test_sound(i)).unwrap();
+		let mut sub_track = track.add_sub_track(TrackBuilder::new()).unwrap();
+		sub_track.play(test_sound(i + NUM_TRACKS)).unwrap();
+		tracks.push((track, sub_track));
+	}
+	let backend = manager.backend_mut();
+	backend.render_frames(NUM_FRAMES);
+	backend.samples().to_vec()
+}
+
+#[test]
+fn matches_serial_mixing() {
+	let serial = render(0);
+	assert!(serial.iter().any(|sample| *sample != 0.0));
+	for num_mixer_threads in [1, 3] {
+		let parallel = render(num_mixer_threads);
+		assert_eq!(parallel.len(), serial.len());
+		for (parallel, serial) in parallel.iter().zip(&serial) {
+			assert!((parallel - serial).abs() < 0.00001);
+		}
+	}
+}
+
+#[test]
+fn is_deterministic() {
+	let first = render(3);
+	for _ in 0..5 {
+		assert_eq!(render(3), first);
+	}
+}
+
+/// Renders sub-tracks whose volume is controlled by a modulator
+/// that isn't `Sync`.
+fn render_modulated(num_mixer_threads: usize) -> Vec<f32> {
+	let mut manager = create_manager(num_mixer_threads);
+	let timer = manager.add_modulator(TimerBuilder).unwrap();
+	let mut tracks = vec![];
+	for i in 0..NUM_TRACKS {
+		let mut track = manager
+			.add_sub_track(TrackBuilder::new().volume(Value::from_modulator(
+				timer,
+				Mapping {
+					input_range: (0.0, 10.0),
+					output_range: (Decibels(-30.0), Decibels::IDENTITY),
+					easing: Easing::Linear,
+				},
+			)))
+			.unwrap();
+		track.play(test_sound(i)).unwrap();
+		tracks.push(track);
+	}
+	let backend = manager.backend_mut();
+	backend.render_frames(NUM_FRAMES);
+	backend.samples().to_vec()
+}
+
+#[test]
+fn reads_modulators_on_worker_threads() {
+	let serial = render_modulated(0);
+	assert!(serial.iter().any(|sample| *sample != 0.0));
+	let parallel = render_modulated(3);
+	assert_eq!(parallel.len(), serial.len());
+	for (parallel, serial) in parallel.iter().zip(&serial) {
+		assert!((parallel - serial).abs() < 0.00001);
+	}
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use kira::{
+	AudioManager, AudioManagerSettings, Decibels,
+	backend::offline::{OfflineBackend, OfflineBackendSettings},
+	effect::{filter::FilterBuilder, panning_control::PanningControlBuilder},
+	track::{MainTrackBuilder, SendTrackBuilder, TrackBuilder, TrackProfile},
+};
+
+fn create_manager() -> AudioManager<OfflineBackend> {
+	AudioManager::<OfflineBackend>::new(AudioManagerSettings {
+		backend_settings: OfflineBackendSettings {
+			sample_rate: 100,
+			num_channels: 2,
+		},
+		main_track_builder: MainTrackBuilder::new().with_effect(FilterBuilder::new()),
+		internal_buffer_size: 10,
+		..Default::default()
+	})
+	.unwrap()
+}
+
+#[test]
+fn records_track_and_effect_timings() {
+	let mut manager = create_manager();
+	manager.set_profiling_enabled(true);
+	assert!(manager.profiling_enabled());
+	let mut send_track = manager
+		.add_send_track(SendTrackBuilder::new().with_effect(PanningControlBuilder::default()))
+		.unwrap();
+	let mut track = manager
+		.add_sub_track(
+			TrackBuilder::new()
+				.with_effect(FilterBuilder::new())
+				.with_effect(PanningControlBuilder::default())
+				.with_send(&send_track, Decibels::IDENTITY),
+		)
+		.unwrap();
+	manager.backend_mut().render_frames(100);
+
+	let profile = track.pop_profile();
+	assert!(profile.num_batches > 0);
+	assert_eq!(profile.effect_processing_times.len(), 2);
+	assert!(profile.processing_time >= profile.effect_processing_times.iter().sum());
+	let profile = send_track.pop_profile();
+	assert!(profile.num_batches > 0);
+	assert_eq!(profile.effect_processing_times.len(), 1);
+	let profile = manager.main_track().pop_profile();
+	assert!(profile.num_batches > 0);
+	assert_eq!(profile.effect_processing_times.len(), 1);
+
+	// popping the timings resets them
+	let profile = track.pop_profile();
+	assert_eq!(profile.num_batches, 0);
+	assert_eq!(profile.average_processing_time(), Duration::ZERO);
+}
+
+#[test]
+fn does_not_record_timings_when_disabled() {
+	let mut manager = create_manager();
+	let mut track = manager
+		.add_sub_track(TrackBuilder::new().with_effect(FilterBuilder::new()))
+		.unwrap();
+	manager.backend_mut().render_frames(100);
+	assert_eq!(
+		track.pop_profile(),
+		TrackProfile {
+			num_batches: 0,
+			processing_time: Default::default(),
+			effect_processing_times: vec![Default::default()],
+		}
+	);
+}
@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use kira::{
+	AudioManager, AudioManagerSettings, Decibels, Easing, Frame, Mapping, Panning, StartTime,
+	Tween, Value,
+	backend::mock::{MockBackend, MockBackendSettings},
+	clock::{ClockId, ClockSpeed, ClockTime, QuantizedClockTime},
+	modulator::tweener::TweenerBuilder,
+	sound::{
+		container::{BlendCurve, BlendSoundData, QueueSoundData},
+		static_sound::{StaticSoundData, StaticSoundSettings},
+	},
+};
+
+fn create_manager() -> AudioManager<MockBackend> {
+	create_manager_with_buffer_size(128)
+}
+
+fn create_manager_with_buffer_size(internal_buffer_size: usize) -> AudioManager<MockBackend> {
+	AudioManager::<MockBackend>::new(AudioManagerSettings {
+		backend_settings: MockBackendSettings { sample_rate: 1000 },
+		internal_buffer_size,
+		..Default::default()
+	})
+	.unwrap()
+}
+
+fn constant_sound(settings: StaticSoundSettings) -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 1000,
+		frames: vec![Frame::from_mono(1.0); 1000].into(),
+		settings,
+		slice: None,
+	}
+}
+
+fn first_audible_frame(frames: &[Frame]) -> Option<usize> {
+	frames.iter().position(|frame| *frame != Frame::ZERO)
+}
+
+#[test]
+fn sounds_start_on_the_exact_clock_tick() {
+	for chunk_size in [1, 64, 128] {
+		let mut manager = create_manager();
+		manager.backend_mut().set_chunk_size(chunk_size);
+		let mut clock = manager.add_clock(ClockSpeed::TicksPerSecond(10.0)).unwrap();
+		manager
+			.play(constant_sound(
+				StaticSoundSettings::new().start_time(clock.time() + 3),
+			))
+			.unwrap();
+		clock.start();
+		let frames = manager.backend_mut().render(500);
+		assert_eq!(first_audible_frame(&frames), Some(300));
+	}
+}
+
+#[test]
+fn sounds_start_after_the_exact_delay() {
+	let mut manager = create_manager();
+	manager
+		.play(constant_sound(
+			StaticSoundSettings::new().start_time(Duration::from_millis(250)),
+		))
+		.unwrap();
+	let frames = manager.backend_mut().render(500);
+	assert_eq!(first_audible_frame(&frames), Some(250));
+}
+
+#[test]
+fn sounds_resume_on_the_exact_clock_tick() {
+	let mut manager = create_manager();
+	let mut clock = manager.add_clock(ClockSpeed::TicksPerSecond(10.0)).unwrap();
+	let mut sound = manager
+		.play(constant_sound(StaticSoundSettings::new()))
+		.unwrap();
+	sound.pause(Tween {
+		duration: Duration::ZERO,
+		..Default::default()
+	});
+	manager.backend_mut().render(128);
+	sound.resume_at(
+		StartTime::ClockTime(clock.time() + 2),
+		Tween {
+			duration: Duration::ZERO,
+			..Default::default()
+		},
+	);
+	clock.start();
+	let frames = manager.backend_mut().render(500);
+	assert_eq!(first_audible_frame(&frames), Some(200));
+}
+
+#[test]
+fn quantized_start_times_land_on_the_clock_grid() {
+	for (quantized_time, expected_tick) in [
+		(
+			QuantizedClockTime::next_tick as fn(ClockId) -> QuantizedClockTime,
+			2,
+		),
+		(|clock| QuantizedClockTime::next_multiple(clock, 4), 4),
+		(
+			|clock| QuantizedClockTime::next_multiple(clock, 4).offset(1),
+			5,
+		),
+	] {
+		for chunk_size in [1, 64, 128] {
+			let mut manager = create_manager();
+			manager.backend_mut().set_chunk_size(chunk_size);
+			let mut clock = manager.add_clock(ClockSpeed::TicksPerSecond(10.0)).unwrap();
+			clock.start();
+			manager.backend_mut().render(150);
+			manager
+				.play(constant_sound(
+					StaticSoundSettings::new().start_time(quantized_time(clock.id())),
+				))
+				.unwrap();
+			let frames = manager.backend_mut().render(500);
+			assert_eq!(
+				first_audible_frame(&frames),
+				Some(expected_tick * 100 - 150),
+				"chunk size {chunk_size}, expected tick {expected_tick}"
+			);
+		}
+	}
+}
+
+#[test]
+fn quantized_start_times_wait_for_stopped_clocks() {
+	let mut manager = create_manager();
+	let mut clock = manager.add_clock(ClockSpeed::TicksPerSecond(10.0)).unwrap();
+	manager
+		.play(constant_sound(
+			StaticSoundSettings::new().start_time(QuantizedClockTime::next_tick(&clock)),
+		))
+		.unwrap();
+	manager.backend_mut().render(150);
+	clock.start();
+	let frames = manager.backend_mut().render(500);
+	assert_eq!(first_audible_frame(&frames), Some(0));
+}
+
+#[test]
+fn tweens_start_on_the_exact_clock_tick() {
+	for start_time in [
+		(|clock| StartTime::ClockTime(ClockTime::from_ticks_u64(clock, 4)))
+			as fn(ClockId) -> StartTime,
+		|clock| StartTime::Quantized(QuantizedClockTime::next_multiple(clock, 4)),
+	] {
+		for chunk_size in [1, 64, 128] {
+			let mut manager = create_manager();
+			manager.backend_mut().set_chunk_size(chunk_size);
+			let mut clock = manager.add_clock(ClockSpeed::TicksPerSecond(10.0)).unwrap();
+			let mut tweener = manager
+				.add_modulator(TweenerBuilder { initial_value: 0.0 })
+				.unwrap();
+			clock.start();
+			manager.backend_mut().render(200);
+			// the tween starts on tick 4, 200 frames from now
+			tweener.set(
+				1.0,
+				Tween {
+					start_time: start_time(clock.id()),
+					duration: Duration::from_secs(1),
+					easing: Easing::Linear,
+				},
+			);
+			manager
+				.play(constant_sound(StaticSoundSettings::new().volume(
+					Value::from_modulator(
+						&tweener,
+						Mapping {
+							input_range: (0.0, 1.0),
+							output_range: (Decibels(-20.0), Decibels::IDENTITY),
+							easing: Easing::Linear,
+						},
+					),
+				)))
+				.unwrap();
+			let frames = manager.backend_mut().render(384);
+			// parameters reach the modulator's value at the end of each chunk
+			for (index, tween_progress) in [(255, 0.056), (383, 0.184)] {
+				let expected = Decibels(-20.0 + 20.0 * tween_progress).as_amplitude();
+				let actual =
+					frames[index].left / Frame::from_mono(1.0).panned(Panning::CENTER).left;
+				assert!(
+					(actual - expected).abs() < 0.001,
+					"chunk size {chunk_size}, frame {index}: expected {expected}, got {actual}"
+				);
+			}
+		}
+	}
+}
+
+#[test]
+fn container_children_start_on_the_exact_clock_tick() {
+	// containers render their children in chunks of 512 frames,
+	// so tick 8 is in the second chunk of a 1024 frame batch
+	let mut manager = create_manager_with_buffer_size(1024);
+	let mut clock = manager.add_clock(ClockSpeed::TicksPerSecond(10.0)).unwrap();
+	let sound = constant_sound(StaticSoundSettings::new().start_time(clock.time() + 8));
+	manager
+		.play(BlendSoundData::new(0.0).with_layer(sound, BlendCurve::FULL))
+		.unwrap();
+	clock.start();
+	let frames = manager.backend_mut().render(1024);
+	assert_eq!(first_audible_frame(&frames), Some(800), "blend");
+
+	let mut manager = create_manager_with_buffer_size(1024);
+	let mut clock = manager.add_clock(ClockSpeed::TicksPerSecond(10.0)).unwrap();
+	let sound = constant_sound(StaticSoundSettings::new().start_time(clock.time() + 8));
+	manager
+		.play(QueueSoundData::new().with_entry(sound))
+		.unwrap();
+	clock.start();
+	let frames = manager.backend_mut().render(1024);
+	assert_eq!(first_audible_frame(&frames), Some(800), "queue");
+}
@@ -4,7 +4,7 @@ use kira::{
 	AudioManager, AudioManagerSettings, Frame,
 	backend::mock::MockBackend,
 	sound::{
-		PlaybackState,
+		PlaybackState, SoundEvent,
 		streaming::{Decoder, StreamingSoundData},
 	},
 };
@@ -45,5 +45,9 @@ fn streaming_sound_stops_on_error() {
 	manager.backend_mut().on_start_processing();
 	assert_eq!(sound.state(), PlaybackState::Stopped);
 	assert_eq!(sound.pop_error(), Some(MockDecoderError));
+	assert_eq!(
+		sound.pop_event().map(|event| event.kind),
+		Some(SoundEvent::Error)
+	);
 	assert_eq!(manager.main_track().num_sounds(), 0);
 }
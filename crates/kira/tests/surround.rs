@@ -0,0 +1,134 @@
+use std::{f32::consts::FRAC_1_SQRT_2, sync::Arc, time::Duration};
+
+use approx::assert_relative_eq;
+use kira::{
+	AudioManager, AudioManagerSettings, Decibels, Frame,
+	backend::{
+		ChannelLayout,
+		offline::{OfflineBackend, OfflineBackendSettings},
+	},
+	effect::volume_control::VolumeControlBuilder,
+	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+	track::{MainTrackBuilder, SpatialTrackBuilder, SpatialTrackHandle},
+};
+
+fn create_manager(
+	channel_layout: ChannelLayout,
+	num_channels: u16,
+	main_track_builder: MainTrackBuilder,
+) -> AudioManager<OfflineBackend> {
+	AudioManager::<OfflineBackend>::new(AudioManagerSettings {
+		main_track_builder,
+		backend_settings: OfflineBackendSettings {
+			sample_rate: 10,
+			num_channels,
+		},
+		internal_buffer_size: 4,
+		channel_layout,
+		..Default::default()
+	})
+	.unwrap()
+}
+
+fn sound(frame: Frame) -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 10,
+		frames: Arc::new([frame]),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+}
+
+/// Plays a sound on a spatial track directly behind the listener.
+fn play_sound_behind_listener(manager: &mut AudioManager<OfflineBackend>) -> SpatialTrackHandle {
+	let listener = manager
+		.add_listener(glam::Vec3::ZERO, glam::Quat::IDENTITY)
+		.unwrap();
+	let mut track = manager
+		.add_spatial_sub_track(
+			&listener,
+			glam::vec3(0.0, 0.0, 1.0),
+			SpatialTrackBuilder::new()
+				.attenuation_function(None)
+				.spatialization_strength(1.0),
+		)
+		.unwrap();
+	track.play(sound(Frame::from_mono(1.0))).unwrap();
+	track
+}
+
+/// Plays a sound on a spatial track directly behind the listener
+/// and returns the first rendered frame.
+fn render_sound_behind_listener(channel_layout: ChannelLayout, num_channels: u16) -> Vec<f32> {
+	let mut manager = create_manager(channel_layout, num_channels, MainTrackBuilder::new());
+	let _track = play_sound_behind_listener(&mut manager);
+	let backend = manager.backend_mut();
+	backend.render(Duration::from_secs(1));
+	backend.samples()[..num_channels.into()].to_vec()
+}
+
+#[test]
+fn pans_spatial_tracks_across_surround_speakers() {
+	let frame = render_sound_behind_listener(ChannelLayout::Surround51, 6);
+	for sample in &frame[..4] {
+		assert_relative_eq!(*sample, 0.0, epsilon = 0.0001);
+	}
+	assert_relative_eq!(frame[4], FRAC_1_SQRT_2, epsilon = 0.0001);
+	assert_relative_eq!(frame[5], FRAC_1_SQRT_2, epsilon = 0.0001);
+}
+
+#[test]
+fn downmixes_surround_mix_to_stereo_device() {
+	let frame = render_sound_behind_listener(ChannelLayout::Surround51, 2);
+	// the back speakers are folded into the front speakers at -3dB
+	assert_relative_eq!(frame[0], 0.5, epsilon = 0.0001);
+	assert_relative_eq!(frame[1], 0.5, epsilon = 0.0001);
+}
+
+#[test]
+fn plays_non_spatial_audio_on_front_speakers() {
+	let mut manager = create_manager(ChannelLayout::Surround51, 6, MainTrackBuilder::new());
+	manager.play(sound(Frame::new(0.5, -0.25))).unwrap();
+	let backend = manager.backend_mut();
+	backend.render(Duration::from_secs(1));
+	assert_eq!(backend.samples()[..6], [0.5, -0.25, 0.0, 0.0, 0.0, 0.0]);
+}
+
+#[test]
+fn taps_receive_stereo_downmix_of_spatial_tracks() {
+	let mut manager = create_manager(ChannelLayout::Surround51, 6, MainTrackBuilder::new());
+	let mut tap = manager.main_track().add_tap(16).unwrap();
+	let _track = play_sound_behind_listener(&mut manager);
+	let backend = manager.backend_mut();
+	backend.render_frames(1);
+	// the output keeps the surround panning
+	assert_relative_eq!(backend.samples()[4], FRAC_1_SQRT_2, epsilon = 0.0001);
+	let frame = tap.pop().unwrap();
+	assert_relative_eq!(frame.left, 0.5, epsilon = 0.0001);
+	assert_relative_eq!(frame.right, 0.5, epsilon = 0.0001);
+}
+
+#[test]
+fn main_track_effects_process_spatial_tracks() {
+	let volume = Decibels(-6.0);
+	let mut manager = create_manager(
+		ChannelLayout::Surround51,
+		6,
+		MainTrackBuilder::new().with_effect(VolumeControlBuilder::new(volume)),
+	);
+	let mut tap = manager.main_track().add_tap(16).unwrap();
+	let _track = play_sound_behind_listener(&mut manager);
+	let backend = manager.backend_mut();
+	backend.render_frames(1);
+	// the spatial track is mixed down to stereo so the effect can process it
+	let expected = 0.5 * volume.as_amplitude();
+	let samples = backend.samples();
+	assert_relative_eq!(samples[0], expected, epsilon = 0.0001);
+	assert_relative_eq!(samples[1], expected, epsilon = 0.0001);
+	for sample in &samples[2..6] {
+		assert_relative_eq!(*sample, 0.0, epsilon = 0.0001);
+	}
+	let frame = tap.pop().unwrap();
+	assert_relative_eq!(frame.left, expected, epsilon = 0.0001);
+	assert_relative_eq!(frame.right, expected, epsilon = 0.0001);
+}
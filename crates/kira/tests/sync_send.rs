@@ -1,6 +1,6 @@
 use kira::{
 	AudioManager,
-	backend::cpal::CpalBackend,
+	backend::{cpal::CpalBackend, headless::HeadlessBackend},
 	clock::ClockHandle,
 	effect::{
 		compressor::CompressorHandle, delay::DelayHandle, distortion::DistortionHandle,
@@ -9,16 +9,27 @@ use kira::{
 	},
 	listener::ListenerHandle,
 	modulator::{lfo::LfoHandle, tweener::TweenerHandle},
-	sound::{FromFileError, static_sound::StaticSoundHandle, streaming::StreamingSoundHandle},
-	track::{MainTrackHandle, SendTrackHandle, SpatialTrackHandle, TrackHandle},
+	music::MusicController,
+	sound::{
+		FromFileError,
+		container::{BlendSoundHandle, QueueSoundHandle, SwitchSoundHandle},
+		granular::GranularSoundHandle,
+		oscillator::OscillatorSoundHandle,
+		push::PushSoundHandle,
+		static_sound::{StaticSoundData, StaticSoundHandle},
+		streaming::StreamingSoundHandle,
+	},
+	track::{MainTrackHandle, SendTrackHandle, SpatialTrackHandle, TrackHandle, TrackTap},
 };
 
 fn main() {
 	sync_send::<AudioManager<CpalBackend>>();
+	sync_send::<AudioManager<HeadlessBackend>>();
 	sync_send::<LfoHandle>();
 	sync_send::<ClockHandle>();
 	sync_send::<DelayHandle>();
 	sync_send::<TrackHandle>();
+	sync_send::<TrackTap>();
 	sync_send::<FilterHandle>();
 	sync_send::<ReverbHandle>();
 	sync_send::<TweenerHandle>();
@@ -28,8 +39,15 @@ fn main() {
 	sync_send::<SendTrackHandle>();
 	sync_send::<CompressorHandle>();
 	sync_send::<DistortionHandle>();
+	sync_send::<PushSoundHandle>();
+	sync_send::<OscillatorSoundHandle>();
+	sync_send::<GranularSoundHandle>();
 	sync_send::<StaticSoundHandle>();
+	sync_send::<SwitchSoundHandle<u8, StaticSoundHandle>>();
+	sync_send::<BlendSoundHandle<StaticSoundHandle>>();
+	sync_send::<QueueSoundHandle<StaticSoundData>>();
 	sync_send::<SpatialTrackHandle>();
+	sync_send::<MusicController>();
 	sync_send::<VolumeControlHandle>();
 	sync_send::<PanningControlHandle>();
 	sync_send::<StreamingSoundHandle<FromFileError>>();
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use kira::{
+	AudioManager, AudioManagerSettings, Decibels, Frame,
+	backend::offline::{OfflineBackend, OfflineBackendSettings},
+	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+	track::{MainTrackBuilder, TrackBuilder},
+};
+
+fn create_manager() -> AudioManager<OfflineBackend> {
+	AudioManager::<OfflineBackend>::new(AudioManagerSettings {
+		backend_settings: OfflineBackendSettings {
+			sample_rate: 10,
+			num_channels: 2,
+		},
+		main_track_builder: MainTrackBuilder::new(),
+		internal_buffer_size: 4,
+		..Default::default()
+	})
+	.unwrap()
+}
+
+fn test_sound() -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 10,
+		frames: Arc::new([
+			Frame::from_mono(1.0),
+			Frame::from_mono(0.5),
+			Frame::from_mono(-0.5),
+		]),
+		settings: StaticSoundSettings::new(),
+		slice: None,
+	}
+}
+
+#[test]
+fn receives_post_volume_output() {
+	let mut manager = create_manager();
+	let mut track = manager
+		.add_sub_track(TrackBuilder::new().volume(Decibels(-6.0)))
+		.unwrap();
+	let mut track_tap = track.add_tap(16).unwrap();
+	let mut main_tap = manager.main_track().add_tap(16).unwrap();
+	track.play(test_sound()).unwrap();
+	manager.backend_mut().render_frames(8);
+	let amplitude = Decibels(-6.0).as_amplitude();
+	let mut frames = [Frame::ZERO; 16];
+	assert_eq!(track_tap.num_available_frames(), 8);
+	assert_eq!(track_tap.read(&mut frames), 8);
+	assert_eq!(frames[0], Frame::from_mono(amplitude));
+	assert_eq!(frames[1], Frame::from_mono(0.5 * amplitude));
+	assert_eq!(frames[2], Frame::from_mono(-0.5 * amplitude));
+	assert!(frames[3..8].iter().all(|frame| *frame == Frame::ZERO));
+	assert_eq!(track_tap.pop(), None);
+	assert_eq!(main_tap.pop(), Some(Frame::from_mono(amplitude)));
+	assert_eq!(main_tap.num_available_frames(), 7);
+}
+
+#[test]
+fn reports_dropped_frames() {
+	let mut manager = create_manager();
+	let mut tap = manager.main_track().add_tap(6).unwrap();
+	manager.backend_mut().render_frames(8);
+	assert_eq!(tap.num_dropped_frames(), 2);
+	let mut frames = [Frame::ZERO; 6];
+	assert_eq!(tap.read(&mut frames), 6);
+	manager.backend_mut().render_frames(4);
+	assert_eq!(tap.num_available_frames(), 4);
+	assert_eq!(tap.num_dropped_frames(), 2);
+}
+
+#[test]
+fn removes_dropped_taps() {
+	let mut manager = create_manager();
+	let mut track = manager
+		.add_sub_track(TrackBuilder::new().tap_capacity(1))
+		.unwrap();
+	assert_eq!(track.tap_capacity(), 1);
+	let tap = track.add_tap(4).unwrap();
+	assert_eq!(track.num_taps(), 1);
+	assert!(track.add_tap(4).is_err());
+	manager.backend_mut().render_frames(1);
+	drop(tap);
+	manager.backend_mut().render_frames(1);
+	assert_eq!(track.num_taps(), 0);
+	assert!(track.add_tap(4).is_ok());
+}